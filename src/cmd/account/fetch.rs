@@ -0,0 +1,85 @@
+use super::variant::AccountVariant;
+use crate::opts::account::descriptor::AccountDescriptor;
+use crate::probe::abi::Abi;
+use crate::probe::utils::send_rpc_request;
+
+use eyre::{eyre, Result};
+use reqwest::Url;
+use serde_json::{json, Value};
+use starknet::core::types::FieldElement;
+use starknet::core::utils::{get_selector_from_name, get_storage_var_address};
+use starknet::providers::jsonrpc::models::{BlockId, FunctionCall};
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+
+/// Rebuilds the descriptor of a deployed account from the chain state.
+pub async fn fetch_account(
+    rpc_url: Url,
+    address: FieldElement,
+    block_id: &BlockId,
+) -> Result<AccountDescriptor> {
+    let provider = JsonRpcClient::new(HttpTransport::new(rpc_url.clone()));
+
+    let class_hash = provider.get_class_hash_at(block_id, address).await?;
+    let (variant, public_key) =
+        detect_account(&provider, &rpc_url, address, class_hash, block_id).await?;
+
+    Ok(AccountDescriptor {
+        address,
+        class_hash,
+        variant,
+        public_key,
+    })
+}
+
+/// Detects the kind of the account deployed with the given class, and reads its public key. The
+/// kind of account is detected from the abi of its class, or of the implementation class if the
+/// account is a proxy.
+pub async fn detect_account(
+    provider: &JsonRpcClient<HttpTransport>,
+    rpc_url: &Url,
+    address: FieldElement,
+    class_hash: FieldElement,
+    block_id: &BlockId,
+) -> Result<(AccountVariant, FieldElement)> {
+    let mut abi = get_class_abi(rpc_url, class_hash, block_id).await?;
+
+    // Argent and Braavos accounts used to be deployed behind a proxy
+    if abi.function("get_implementation").is_ok() {
+        let implementation = provider
+            .call(
+                &FunctionCall {
+                    contract_address: address,
+                    entry_point_selector: get_selector_from_name("get_implementation")?,
+                    calldata: vec![],
+                },
+                block_id,
+            )
+            .await?;
+
+        let implementation = implementation
+            .first()
+            .ok_or_else(|| eyre!("the proxy at {address:#x} has no implementation"))?;
+        abi = get_class_abi(rpc_url, *implementation, block_id).await?;
+    }
+
+    let variant = AccountVariant::detect(&abi)
+        .ok_or_else(|| eyre!("unable to detect the kind of account deployed at {address:#x}"))?;
+
+    let (name, keys) = variant.public_key_storage();
+    let public_key = provider
+        .get_storage_at(address, get_storage_var_address(name, keys)?, block_id)
+        .await?;
+
+    Ok((variant, public_key))
+}
+
+async fn get_class_abi(url: &Url, class_hash: FieldElement, block_id: &BlockId) -> Result<Abi> {
+    let class: Value = send_rpc_request(
+        url,
+        "starknet_getClass",
+        json!({ "block_id": block_id, "class_hash": class_hash }),
+    )
+    .await?;
+
+    Abi::parse(&class)
+}
@@ -0,0 +1,123 @@
+use super::fetch::detect_account;
+use super::variant::AccountVariant;
+use crate::cmd::deploy_account::fee_token_balance;
+use crate::opts::transaction::FeeToken;
+use crate::probe::utils::fmt::{format_units, Pretty, ValueFormat};
+
+use comfy_table::modifiers::UTF8_SOLID_INNER_BORDERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::Table;
+use eyre::Result;
+use reqwest::Url;
+use serde_json::{json, Value};
+use starknet::core::types::FieldElement;
+use starknet::providers::jsonrpc::models::BlockId;
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+
+/// An overview of an account. An address that has no contract deployed yet may still hold
+/// tokens, to pay for the deployment of its account.
+#[derive(Debug)]
+pub struct AccountInfo {
+    address: FieldElement,
+    class_hash: Option<FieldElement>,
+    variant: Option<AccountVariant>,
+    public_key: Option<FieldElement>,
+    nonce: Option<FieldElement>,
+    eth_balance: u128,
+    strk_balance: u128,
+}
+
+impl AccountInfo {
+    /// Gathers the state of the account, with the calls to the node made concurrently.
+    pub async fn fetch(rpc_url: Url, address: FieldElement, block_id: &BlockId) -> Result<Self> {
+        let provider = JsonRpcClient::new(HttpTransport::new(rpc_url.clone()));
+
+        let account = async {
+            let class_hash = provider.get_class_hash_at(block_id, address).await.ok()?;
+            let detected = detect_account(&provider, &rpc_url, address, class_hash, block_id)
+                .await
+                .ok();
+            Some((class_hash, detected))
+        };
+
+        let (account, nonce, eth_balance, strk_balance) = tokio::join!(
+            account,
+            provider.get_nonce(block_id, address),
+            fee_token_balance(&provider, FeeToken::Eth, address, block_id),
+            fee_token_balance(&provider, FeeToken::Strk, address, block_id),
+        );
+
+        let (class_hash, detected) = account.unzip();
+        let (variant, public_key) = detected.flatten().unzip();
+
+        Ok(Self {
+            address,
+            class_hash,
+            variant,
+            public_key,
+            // an address without a contract has no nonce
+            nonce: class_hash.and(nonce.ok()),
+            eth_balance: eth_balance?,
+            strk_balance: strk_balance?,
+        })
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "address": self.address,
+            "deployed": self.class_hash.is_some(),
+            "class_hash": self.class_hash,
+            "variant": self.variant,
+            "public_key": self.public_key,
+            "nonce": self.nonce,
+            "eth_balance": self.eth_balance.to_string(),
+            "strk_balance": self.strk_balance.to_string(),
+        })
+    }
+}
+
+impl Pretty for AccountInfo {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
+        let unknown = || "-".to_string();
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .add_row(vec!["ADDRESS", &self.address.prettify(fmt)])
+            .add_row(vec![
+                "DEPLOYED",
+                if self.class_hash.is_some() {
+                    "yes"
+                } else {
+                    "no"
+                },
+            ])
+            .add_row(vec![
+                "CLASS HASH",
+                &self.class_hash.map_or_else(unknown, |c| c.prettify(fmt)),
+            ])
+            .add_row(vec![
+                "ACCOUNT TYPE",
+                &self.variant.map_or_else(unknown, |v| format!("{v:?}")),
+            ])
+            .add_row(vec![
+                "NONCE",
+                &self.nonce.map_or_else(unknown, |n| n.to_string()),
+            ])
+            .add_row(vec![
+                "ETH BALANCE",
+                &format!("{} ETH", format_units(self.eth_balance, 18)),
+            ])
+            .add_row(vec![
+                "STRK BALANCE",
+                &format!("{} STRK", format_units(self.strk_balance, 18)),
+            ])
+            .add_row(vec![
+                "PUBLIC KEY",
+                &self.public_key.map_or_else(unknown, |k| k.prettify(fmt)),
+            ]);
+
+        format!("{table}")
+    }
+}
@@ -0,0 +1,62 @@
+use crate::opts::account::keychain::{remove_key, store_key};
+
+use std::io::Write;
+
+use clap::Subcommand;
+use eyre::Result;
+use inquire::Password;
+use starknet::core::types::FieldElement;
+use starknet::signers::SigningKey;
+
+#[derive(Debug, Subcommand)]
+pub enum KeychainCommands {
+    #[clap(about = "Store a private key in the OS keychain.")]
+    Add {
+        #[clap(value_name = "NAME")]
+        #[clap(help = "The name to store the key under, to be used with --keychain.")]
+        name: String,
+
+        #[clap(long)]
+        #[clap(value_name = "PRIVATE_KEY")]
+        #[clap(help = "The private key to store. Prompted for if not provided.")]
+        private_key: Option<FieldElement>,
+    },
+
+    #[clap(about = "Remove a private key from the OS keychain.")]
+    Remove {
+        #[clap(value_name = "NAME")]
+        name: String,
+    },
+}
+
+impl KeychainCommands {
+    pub fn run(self, out: &mut dyn Write) -> Result<()> {
+        match self {
+            Self::Add { name, private_key } => {
+                let private_key = match private_key {
+                    Some(private_key) => private_key,
+                    None => {
+                        FieldElement::from_hex_be(&Password::new("Enter private key : ").prompt()?)?
+                    }
+                };
+
+                store_key(&name, private_key)?;
+
+                let public_key = SigningKey::from_secret_scalar(private_key)
+                    .verifying_key()
+                    .scalar();
+                writeln!(
+                    out,
+                    "Stored key `{name}` in the keychain.\nPublic key : {public_key:#x}"
+                )?;
+            }
+
+            Self::Remove { name } => {
+                remove_key(&name)?;
+                writeln!(out, "Removed key `{name}` from the keychain.")?;
+            }
+        }
+
+        Ok(())
+    }
+}
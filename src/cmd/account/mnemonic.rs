@@ -0,0 +1,81 @@
+//! Derivation of Stark keys from a BIP-39 mnemonic, as specified by EIP-2645: a secp256k1 key is
+//! derived from the seed along a BIP-32 path, and is then ground into a valid Stark private key.
+
+use bip32::{DerivationPath, XPrv};
+use bip39::Mnemonic;
+use crypto_bigint::{Encoding, U256};
+use eyre::{eyre, Result};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use starknet::core::types::FieldElement;
+
+/// The path used by wallets to derive the key of their first account.
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/9004'/0'/0/0";
+
+/// The order of the Stark curve, which private keys must be below.
+const EC_ORDER: U256 =
+    U256::from_be_hex("0800000000000010ffffffffffffffffb781126dcae7b2321e66a241adc64d2f");
+
+/// Generates a new random mnemonic of 24 words.
+pub fn generate_mnemonic() -> Result<String> {
+    let mut entropy = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut entropy);
+    Ok(Mnemonic::from_entropy(&entropy)?.to_string())
+}
+
+/// Derives the Stark private key at the given path from a mnemonic.
+pub fn derive_private_key(mnemonic: &str, path: &str) -> Result<FieldElement> {
+    let mnemonic = Mnemonic::parse(mnemonic)?;
+    let path = path
+        .parse::<DerivationPath>()
+        .map_err(|_| eyre!("invalid derivation path `{path}`"))?;
+
+    let key = XPrv::derive_from_path(mnemonic.to_seed(""), &path)?;
+    grind_key(key.to_bytes())
+}
+
+/// Turns a 256 bits seed into a key below the order of the Stark curve, without biasing it. The
+/// seed is hashed along with an increasing index until the hash falls into a range which is a
+/// multiple of the order.
+fn grind_key(seed: [u8; 32]) -> Result<FieldElement> {
+    // 2^256 - (2^256 mod n)
+    let remainder = U256::MAX
+        .wrapping_rem(&EC_ORDER)
+        .wrapping_add(&U256::ONE)
+        .wrapping_rem(&EC_ORDER);
+    let max_allowed = U256::MAX.wrapping_sub(&remainder).wrapping_add(&U256::ONE);
+
+    for index in 0..=u8::MAX {
+        let hash = Sha256::new()
+            .chain_update(seed)
+            .chain_update([index])
+            .finalize();
+        let key = U256::from_be_slice(&hash);
+
+        if key < max_allowed {
+            let key = key.wrapping_rem(&EC_ORDER);
+            return Ok(FieldElement::from_bytes_be(&key.to_be_bytes())?);
+        }
+    }
+
+    Err(eyre!("unable to grind the key"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grind_stark_key() {
+        let seed = hex::decode("86F3E7293141F20A8BAFF320E8EE4ACCB9D4A4BF2B4D295E8CEE784DB46E0519")
+            .unwrap();
+
+        assert_eq!(
+            grind_key(seed.try_into().unwrap()).unwrap(),
+            FieldElement::from_hex_be(
+                "0x5c8c8683596c732541a59e03007b2d30dbbbb873556fe65b5fb63c16688f941"
+            )
+            .unwrap()
+        );
+    }
+}
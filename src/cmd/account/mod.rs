@@ -1,23 +1,51 @@
+pub mod fetch;
+pub mod info;
+pub mod keychain;
+pub mod mnemonic;
 pub mod simple_account;
+pub mod v3;
+pub mod vanity;
+pub mod variant;
 
-use super::{account::simple_account::SimpleAccount, parser::PathParser};
+use super::account::simple_account::SimpleAccount;
+use super::deploy_account::{DeployAccountArgs, Funding};
+use super::parser::{AddressParser, BlockIdParser, PathParser};
+use crate::opts::account::descriptor::AccountDescriptor;
+use crate::opts::account::keychain::load_key;
 use crate::opts::account::{utils::get_main_keystore_dir, WalletOptions};
-use crate::opts::starknet::StarknetChain;
-use crate::probe::utils::parse_hex_or_str_as_felt;
+use crate::opts::display::DisplayOptions;
+use crate::opts::starknet::{StarkNetOptions, StarknetChain};
+use crate::opts::transaction::TransactionOptions;
+use crate::probe::addressbook::AddressOrName;
+use crate::probe::utils::fmt::Pretty;
+use crate::probe::utils::{parse_hex_or_str_as_felt, random_private_key};
 
+use std::io::Write;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
+
+use self::fetch::fetch_account;
+use self::info::AccountInfo;
+use self::keychain::KeychainCommands;
+use self::mnemonic::{derive_private_key, generate_mnemonic, DEFAULT_DERIVATION_PATH};
+use self::vanity::{grind_salt, VanityPattern};
+use self::variant::AccountVariant;
 
 use clap::{ArgGroup, Subcommand};
-use eyre::Result;
+use eyre::{eyre, Result};
 use inquire::{Password, Select, Text};
-use starknet::{core::types::FieldElement, signers::Signer};
+use starknet::core::types::FieldElement;
+use starknet::providers::jsonrpc::models::BlockId;
+use starknet::signers::{Signer, SigningKey};
 use walkdir::WalkDir;
 
+// the variants are only built once, when parsing the command line
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Subcommand)]
 pub enum WalletCommands {
     #[clap(about = "Create a keystore for a StarkNet account.")]
-    #[clap(group(ArgGroup::new("new-raw").args(&["path"]).requires_all(&["account", "privatekey", "password", "chain"])))]
+    #[clap(group(ArgGroup::new("new-raw").args(&["path"]).requires_all(&["account", "password", "chain"])))]
     New {
         #[clap(long)]
         #[clap(value_name = "PATH")]
@@ -27,10 +55,11 @@ pub enum WalletCommands {
 
         #[clap(long)]
         #[clap(value_name = "ACCOUNT_ADDRESS")]
+        #[clap(value_parser(AddressParser))]
         #[clap(
             help = "Address of the StarkNet account contract you want to create a keystore for."
         )]
-        account: Option<FieldElement>,
+        account: Option<AddressOrName>,
 
         #[clap(long)]
         #[clap(requires = "path")]
@@ -38,6 +67,17 @@ pub enum WalletCommands {
         #[clap(help = "The raw private key associated with the account contract.")]
         privatekey: Option<FieldElement>,
 
+        #[clap(long)]
+        #[clap(conflicts_with = "privatekey")]
+        #[clap(help = "Generate a new mnemonic and derive the private key from it.")]
+        mnemonic: bool,
+
+        #[clap(long)]
+        #[clap(value_name = "DERIVATION_PATH")]
+        #[clap(default_value = DEFAULT_DERIVATION_PATH)]
+        #[clap(help = "The path along which the private key is derived from the mnemonic.")]
+        derivation_path: String,
+
         #[clap(long)]
         #[clap(requires = "path")]
         #[clap(value_name = "CHAIN")]
@@ -56,6 +96,182 @@ pub enum WalletCommands {
         password: Option<String>,
     },
 
+    #[clap(about = "Generate random private keys, along with their public keys.")]
+    NewKey {
+        #[clap(long)]
+        #[clap(value_name = "N")]
+        #[clap(default_value_t = 1)]
+        #[clap(help = "The number of keys to generate.")]
+        count: usize,
+    },
+
+    #[clap(about = "Derive a private key from a mnemonic.")]
+    Derive {
+        #[clap(long)]
+        #[clap(value_name = "WORDS")]
+        #[clap(help = "The mnemonic to derive the private key from.")]
+        mnemonic: String,
+
+        #[clap(long)]
+        #[clap(value_name = "DERIVATION_PATH")]
+        #[clap(default_value = DEFAULT_DERIVATION_PATH)]
+        #[clap(help = "The path along which the private key is derived.")]
+        path: String,
+    },
+
+    #[clap(about = "Deploy an account controlled by a local key, once it has been funded.")]
+    #[clap(group(ArgGroup::new("signer").args(&["keystore", "private_key", "keychain"]).required(true)))]
+    Deploy {
+        #[clap(long)]
+        #[clap(value_name = "PATH")]
+        #[clap(value_parser(PathParser))]
+        #[clap(help = "The keystore holding the key that will control the account.")]
+        keystore: Option<PathBuf>,
+
+        #[clap(long)]
+        #[clap(requires = "keystore")]
+        #[clap(value_name = "KEYSTORE_PASSWORD")]
+        #[clap(help = "Provide the password for the JSON keystore in cleartext.")]
+        password: Option<String>,
+
+        #[clap(long)]
+        #[clap(value_name = "PRIVATE_KEY")]
+        #[clap(help = "The private key that will control the account.")]
+        private_key: Option<FieldElement>,
+
+        #[clap(long)]
+        #[clap(value_name = "NAME")]
+        #[clap(help = "The name of the key in the OS keychain that will control the account.")]
+        keychain: Option<String>,
+
+        #[clap(long)]
+        #[clap(value_name = "CLASS_HASH")]
+        #[clap(help = "The class hash of the account contract.")]
+        class_hash: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_enum)]
+        #[clap(default_value_t = AccountVariant::OpenZeppelin)]
+        #[clap(help = "The kind of account contract the class hash is for.")]
+        variant: AccountVariant,
+
+        #[clap(long)]
+        #[clap(value_name = "CLASS_HASH")]
+        #[clap(required_if_eq("variant", "braavos"))]
+        #[clap(help = "The class a Braavos account upgrades itself to when it is deployed.")]
+        implementation: Option<FieldElement>,
+
+        #[clap(long)]
+        #[clap(
+            help = "The salt used to compute the account address. Randomly generated if not provided."
+        )]
+        salt: Option<FieldElement>,
+
+        #[clap(long)]
+        #[clap(value_name = "SECONDS")]
+        #[clap(default_value_t = 5)]
+        #[clap(help = "How often to check whether the account has been funded.")]
+        poll_interval: u64,
+
+        #[clap(long = "out")]
+        #[clap(value_name = "PATH")]
+        #[clap(help = "Write the descriptor of the deployed account to the given file.")]
+        out: Option<PathBuf>,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "TRANSACTION OPTIONS")]
+        transaction: TransactionOptions,
+    },
+
+    #[clap(about = "Rebuild the descriptor of a deployed account from its address.")]
+    Fetch {
+        #[clap(value_name = "ADDRESS")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address of the account.")]
+        address: AddressOrName,
+
+        #[clap(next_line_help = true)]
+        #[clap(short, long = "block")]
+        #[clap(default_value = "latest")]
+        #[clap(value_parser(BlockIdParser))]
+        #[clap(
+            help = "The hash of the requested block, or number (height) of the requested block, or a block tag (e.g. latest, pending)."
+        )]
+        block_id: BlockId,
+
+        #[clap(long = "out")]
+        #[clap(value_name = "PATH")]
+        #[clap(help = "Write the descriptor to the given file instead of printing it.")]
+        out: Option<PathBuf>,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(about = "Show the deployment status, class, nonce, balances and key of an account.")]
+    Info {
+        #[clap(value_name = "ADDRESS")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address of the account.")]
+        address: AddressOrName,
+
+        #[clap(next_line_help = true)]
+        #[clap(short, long = "block")]
+        #[clap(default_value = "latest")]
+        #[clap(value_parser(BlockIdParser))]
+        #[clap(
+            help = "The hash of the requested block, or number (height) of the requested block, or a block tag (e.g. latest, pending)."
+        )]
+        block_id: BlockId,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(about = "Manage the private keys stored in the OS keychain.")]
+    Keychain {
+        #[clap(subcommand)]
+        commands: KeychainCommands,
+    },
+
+    #[clap(about = "Grind a salt that deploys an account to an address matching a pattern.")]
+    #[clap(group(ArgGroup::new("pattern").args(&["starts_with", "ends_with"]).required(true).multiple(true)))]
+    Vanity {
+        #[clap(long)]
+        #[clap(value_name = "HEX")]
+        #[clap(help = "The hex digits the address must start with.")]
+        starts_with: Option<String>,
+
+        #[clap(long)]
+        #[clap(value_name = "HEX")]
+        #[clap(help = "The hex digits the address must end with.")]
+        ends_with: Option<String>,
+
+        #[clap(long)]
+        #[clap(value_name = "CLASS_HASH")]
+        #[clap(help = "The class hash of the account contract.")]
+        class_hash: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_enum)]
+        #[clap(default_value_t = AccountVariant::OpenZeppelin)]
+        #[clap(help = "The kind of account contract the class hash is for.")]
+        variant: AccountVariant,
+
+        #[clap(long)]
+        #[clap(value_name = "PRIVATE_KEY")]
+        #[clap(
+            help = "The private key that will control the account. Randomly generated if not provided."
+        )]
+        private_key: Option<FieldElement>,
+    },
+
     #[clap(about = "Sign a message using an account's signing key.")]
     #[clap(group(ArgGroup::new("sign-raw").args(&["keystore"]).requires_all(&["password", "message"])))]
     Sign {
@@ -79,16 +295,31 @@ pub enum WalletCommands {
 }
 
 impl WalletCommands {
-    pub async fn run(self) -> Result<()> {
+    pub async fn run(self, display: &DisplayOptions, out: &mut dyn Write) -> Result<()> {
         match self {
             Self::New {
                 path,
                 account,
                 privatekey,
+                mnemonic,
+                derivation_path,
                 chain,
                 name,
                 password,
             } => {
+                let privatekey = if mnemonic {
+                    let phrase = generate_mnemonic()?;
+                    let privatekey = derive_private_key(&phrase, &derivation_path)?;
+
+                    writeln!(
+                        out,
+                        "Mnemonic : {phrase}\nDerivation path : {derivation_path}\n"
+                    )?;
+                    Some(privatekey)
+                } else {
+                    privatekey
+                };
+
                 if let Some(path) = path {
                     if !path.is_dir() {
                         // we require path to be an existing directory
@@ -96,17 +327,25 @@ impl WalletCommands {
                         std::process::exit(1)
                     }
 
-                    let account =
-                        SimpleAccount::new(None, account.unwrap(), privatekey.unwrap(), chain);
+                    let privatekey = privatekey.ok_or_else(|| {
+                        eyre!("the private key must be given, or derived with --mnemonic")
+                    })?;
+                    // the chain is required along with the path
+                    let chain = chain.unwrap();
+                    let address = account.unwrap().resolve(chain.get_id())?;
+                    let account = SimpleAccount::new(None, address, privatekey, Some(chain));
                     account.encrypt_keystore(&path, password.unwrap(), name)?;
 
-                    println!(
+                    writeln!(
+                        out,
                         "\n🎉 Successfully created new encrypted keystore at {}.\n\nAccount: {:#X}\nPrivate key: {:#X}\nChain: {}",
                         path.display(),
                         account.account,
                         account.get_signing_key(),
                         account.chain.map_or_else(|| "".to_string(), |c| c.to_string())
-                    );
+                    )?;
+                } else if let Some(privatekey) = privatekey {
+                    write_key_pair(out, privatekey)?;
                 } else {
                     let wallet = WalletOptions {
                         interactive: true,
@@ -125,17 +364,179 @@ impl WalletCommands {
 
                     account.encrypt_keystore(get_main_keystore_dir(), password, Some(name))?;
 
-                    println!(
+                    writeln!(
+                        out,
                         "\n🎉 Created new encrypted keystore.\n\nAccount: {:#X}\nPrivate key: {:#X}\nChain: {}",
                         account.account,
                         account.get_signing_key(),
                         account.chain.map_or_else(|| "".to_string(), |c| c.to_string())
-                    );
+                    )?;
                 }
 
                 Ok(())
             }
 
+            Self::NewKey { count } => {
+                for i in 0..count {
+                    if i > 0 {
+                        writeln!(out)?;
+                    }
+                    write_key_pair(out, random_private_key())?;
+                }
+                Ok(())
+            }
+
+            Self::Derive { mnemonic, path } => {
+                write_key_pair(out, derive_private_key(&mnemonic, &path)?)?;
+                Ok(())
+            }
+
+            Self::Deploy {
+                keystore,
+                password,
+                private_key,
+                keychain,
+                class_hash,
+                variant,
+                implementation,
+                salt,
+                poll_interval,
+                out: output,
+                starknet,
+                transaction,
+            } => {
+                let private_key = match (keystore, private_key, keychain) {
+                    (_, Some(private_key), _) => private_key,
+                    (_, None, Some(name)) => load_key(&name)?,
+                    (Some(keystore), None, None) => {
+                        let password = match password {
+                            Some(password) => password,
+                            None => Password::new("Enter keystore password : ").prompt()?,
+                        };
+                        SimpleAccount::decrypt_keystore(keystore, password)?.get_signing_key()
+                    }
+                    (None, None, None) => unreachable!("a signer is required"),
+                };
+
+                let (res, receipt) = DeployAccountArgs {
+                    class_hash,
+                    variant,
+                    implementation,
+                    private_key,
+                    salt,
+                    starknet,
+                    transaction,
+                }
+                .deploy(
+                    Funding::Wait(Duration::from_secs(poll_interval)),
+                    display.quiet,
+                )
+                .await?;
+
+                if display.quiet {
+                    writeln!(out, "{:#x}", res.contract_address)?;
+                } else {
+                    writeln!(out, "Transaction hash : {:#x}", res.transaction_hash)?;
+
+                    if let Some(receipt) = receipt {
+                        writeln!(
+                            out,
+                            "\n{}",
+                            display.colorize(receipt.prettify(&display.value_format()))
+                        )?;
+                    }
+                }
+
+                if let Some(output) = output {
+                    let descriptor = AccountDescriptor {
+                        address: res.contract_address,
+                        class_hash,
+                        variant,
+                        public_key: SigningKey::from_secret_scalar(private_key)
+                            .verifying_key()
+                            .scalar(),
+                    };
+                    descriptor.save(&output)?;
+                    if !display.quiet {
+                        writeln!(out, "Account descriptor written to {}", output.display())?;
+                    }
+                }
+
+                Ok(())
+            }
+
+            Self::Fetch {
+                address,
+                block_id,
+                out: output,
+                starknet,
+            } => {
+                let address = starknet.resolve_address(&address).await?;
+                let descriptor = fetch_account(starknet.rpc_url(), address, &block_id).await?;
+
+                match output {
+                    Some(output) => {
+                        descriptor.save(&output)?;
+                        writeln!(out, "Account descriptor written to {}", output.display())?;
+                    }
+                    None => writeln!(
+                        out,
+                        "{}",
+                        display.render(serde_json::to_string_pretty(&descriptor)?)?
+                    )?,
+                }
+
+                Ok(())
+            }
+
+            Self::Info {
+                address,
+                block_id,
+                starknet,
+            } => {
+                let address = starknet.resolve_address(&address).await?;
+                let info = AccountInfo::fetch(starknet.rpc_url(), address, &block_id).await?;
+
+                if display.is_json() {
+                    let json = serde_json::to_string_pretty(&info.to_json())?;
+                    writeln!(out, "{}", display.render(json)?)?;
+                } else {
+                    writeln!(
+                        out,
+                        "{}",
+                        display.colorize(info.prettify(&display.value_format()))
+                    )?;
+                }
+
+                Ok(())
+            }
+
+            Self::Keychain { commands } => commands.run(out),
+
+            Self::Vanity {
+                starts_with,
+                ends_with,
+                class_hash,
+                variant,
+                private_key,
+            } => {
+                let pattern = VanityPattern::new(starts_with.as_deref(), ends_with.as_deref())?;
+                let private_key =
+                    private_key.unwrap_or_else(|| SigningKey::from_random().secret_scalar());
+                let public_key = SigningKey::from_secret_scalar(private_key)
+                    .verifying_key()
+                    .scalar();
+
+                let calldata = variant.constructor_calldata(public_key);
+                let (salt, address) = grind_salt(class_hash, &calldata, &pattern);
+
+                writeln!(out, "Salt : {salt:#x}")?;
+                write_key_pair(out, private_key)?;
+                writeln!(out, "Address : {address:#x}")?;
+
+                Ok(())
+            }
+
             Self::Sign {
                 keystore: path,
                 password,
@@ -147,7 +548,7 @@ impl WalletCommands {
                     let account = SimpleAccount::decrypt_keystore(path, password.unwrap())?;
                     let hash = parse_hex_or_str_as_felt(message.as_ref().unwrap())?;
                     let sig = account.sign_hash(&hash).await?;
-                    println!("{:#x} {:#x}", sig.r, sig.s);
+                    writeln!(out, "{:#x} {:#x}", sig.r, sig.s)?;
                 } else {
                     let chain = Select::new("Select chain", vec!["mainnet", "testnet", "testnet2"])
                         .prompt()?;
@@ -173,7 +574,7 @@ impl WalletCommands {
                     let hash = parse_hex_or_str_as_felt(&message)?;
                     let sig = account.sign_hash(&hash).await?;
 
-                    println!("\n{:#x} {:#x}", sig.r, sig.s);
+                    writeln!(out, "\n{:#x} {:#x}", sig.r, sig.s)?;
                 }
 
                 Ok(())
@@ -181,3 +582,14 @@ impl WalletCommands {
         }
     }
 }
+
+fn write_key_pair(out: &mut dyn Write, private_key: FieldElement) -> Result<()> {
+    let public_key = SigningKey::from_secret_scalar(private_key)
+        .verifying_key()
+        .scalar();
+    writeln!(
+        out,
+        "Private key : {private_key:#x}\nPublic key : {public_key:#x}"
+    )?;
+    Ok(())
+}
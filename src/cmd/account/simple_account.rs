@@ -1,4 +1,6 @@
+use super::variant::AccountVariant;
 use crate::opts::{account::utils::read_json_file, starknet::StarknetChain};
+use crate::probe::utils::poseidon_hash_many;
 use crate::probe::SimpleProbe;
 
 use std::fs::DirBuilder;
@@ -8,24 +10,22 @@ use std::{convert::Infallible, path::Path};
 use async_trait::async_trait;
 use eyre::Result;
 use rand::thread_rng;
+use reqwest::Url;
 use starknet::accounts::Call;
-use starknet::core::crypto::compute_hash_on_elements;
 use starknet::core::{crypto::Signature, types::FieldElement};
+use starknet::providers::jsonrpc::models::BroadcastedDeployAccountTransaction;
 use starknet::providers::jsonrpc::models::BroadcastedInvokeTransaction;
 use starknet::providers::jsonrpc::models::BroadcastedInvokeTransactionV1;
+use starknet::providers::jsonrpc::models::DeclareTransactionResult;
+use starknet::providers::jsonrpc::models::DeployAccountTransactionResult;
+use starknet::providers::jsonrpc::models::FeeEstimate;
 use starknet::providers::jsonrpc::models::{BlockId, BlockTag};
+use starknet::providers::jsonrpc::models::{BroadcastedDeclareTransaction, ContractClass};
 use starknet::providers::jsonrpc::models::{BroadcastedTransaction, InvokeTransactionResult};
 use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient, JsonRpcClientError};
 use starknet::signers::{local_wallet::SignError, Signer, SigningKey, VerifyingKey};
 use starknet_keystore::Keystore;
 
-const PREFIX_INVOKE: FieldElement = FieldElement::from_mont([
-    18443034532770911073,
-    18446744073709551615,
-    18446744073709551615,
-    513398556346534256,
-]);
-
 #[derive(Debug, thiserror::Error)]
 pub enum AccountError {
     #[error(transparent)]
@@ -36,6 +36,9 @@ pub enum AccountError {
 
     #[error(transparent)]
     SignError(SignError),
+
+    #[error("the implementation class hash is required to deploy a Braavos account")]
+    MissingImplementation,
 }
 
 #[derive(Debug)]
@@ -44,6 +47,10 @@ pub struct SimpleAccount {
     pub account: FieldElement,
     pub chain: Option<StarknetChain>,
     pub provider: Option<JsonRpcClient<HttpTransport>>,
+    pub rpc_url: Option<Url>,
+    pub variant: AccountVariant,
+    /// The class a Braavos account upgrades itself to when it is deployed.
+    pub implementation: Option<FieldElement>,
 }
 
 impl SimpleAccount {
@@ -57,10 +64,58 @@ impl SimpleAccount {
             chain,
             account,
             provider,
+            rpc_url: None,
+            variant: AccountVariant::OpenZeppelin,
+            implementation: None,
             signing_key: SigningKey::from_secret_scalar(signing_key),
         }
     }
 
+    /// Signs the hash of a deploy account transaction, followed by the data the account expects to
+    /// be signed along with its deployment, if any, and by the signature of that data.
+    pub async fn sign_deployment(
+        &self,
+        tx_hash: FieldElement,
+        chain: FieldElement,
+    ) -> Result<Vec<FieldElement>, AccountError> {
+        let signature = self
+            .sign_hash(&tx_hash)
+            .await
+            .map_err(AccountError::SignError)?;
+        let mut signature = vec![signature.r, signature.s];
+
+        let data = self.variant.deployment_data(self.implementation, chain)?;
+        if !data.is_empty() {
+            let data_signature = self
+                .sign_hash(&poseidon_hash_many(&data))
+                .await
+                .map_err(AccountError::SignError)?;
+
+            signature.extend(data);
+            signature.extend([data_signature.r, data_signature.s]);
+        }
+
+        Ok(signature)
+    }
+
+    /// Returns the chain id from the provider, falling back to the account's chain when the
+    /// account is used offline.
+    pub async fn get_chain_id(&self) -> Result<FieldElement, AccountError> {
+        match (&self.provider, &self.chain) {
+            (Some(provider), _) => provider
+                .chain_id()
+                .await
+                .map_err(AccountError::ProviderError),
+            (None, Some(chain)) => Ok(chain.get_id()),
+            (None, None) => Err(AccountError::MissingProvider),
+        }
+    }
+
+    /// The url of the RPC endpoint, for requests which are not supported by the provider.
+    pub fn get_rpc_url(&self) -> Result<&Url, AccountError> {
+        self.rpc_url.as_ref().ok_or(AccountError::MissingProvider)
+    }
+
     pub fn get_signing_key(&self) -> FieldElement {
         self.signing_key.secret_scalar()
     }
@@ -76,8 +131,8 @@ impl SimpleAccount {
         U: AsRef<str>,
     {
         let mut path = path.as_ref().to_path_buf();
-        if self.chain.is_some() {
-            path = path.join(self.chain.as_ref().unwrap().to_string());
+        if let Some(ref chain) = self.chain {
+            path = path.join(chain.to_string());
         }
         DirBuilder::new().recursive(true).create(&path)?;
 
@@ -140,7 +195,10 @@ pub trait Account: Signer {
 
     async fn get_max_fee(&self, request: &BroadcastedTransaction) -> Result<u64, Self::Error>;
 
-    async fn get_nonce(&self) -> Result<FieldElement, Self::Error>;
+    async fn estimate_fee(
+        &self,
+        request: &BroadcastedTransaction,
+    ) -> Result<FeeEstimate, Self::Error>;
 
     async fn send_invoke_transaction(
         &self,
@@ -153,6 +211,40 @@ pub trait Account: Signer {
         nonce: FieldElement,
         max_fee: FieldElement,
     ) -> Result<BroadcastedInvokeTransaction, Self::Error>;
+
+    async fn prepare_invoke_transaction_with_calldata(
+        &self,
+        calldata: Vec<FieldElement>,
+        nonce: FieldElement,
+        max_fee: FieldElement,
+    ) -> Result<BroadcastedInvokeTransaction, Self::Error>;
+
+    async fn send_declare_transaction(
+        &self,
+        request: &BroadcastedDeclareTransaction,
+    ) -> Result<DeclareTransactionResult, Self::Error>;
+
+    async fn prepare_declare_transaction(
+        &self,
+        contract_class: &ContractClass,
+        class_hash: FieldElement,
+        nonce: FieldElement,
+        max_fee: FieldElement,
+    ) -> Result<BroadcastedDeclareTransaction, Self::Error>;
+
+    async fn send_deploy_account_transaction(
+        &self,
+        request: &BroadcastedDeployAccountTransaction,
+    ) -> Result<DeployAccountTransactionResult, Self::Error>;
+
+    async fn prepare_deploy_account_transaction(
+        &self,
+        class_hash: FieldElement,
+        salt: FieldElement,
+        constructor_calldata: &[FieldElement],
+        nonce: FieldElement,
+        max_fee: FieldElement,
+    ) -> Result<BroadcastedDeployAccountTransaction, Self::Error>;
 }
 
 #[async_trait]
@@ -163,39 +255,34 @@ impl Account for SimpleAccount {
         self.provider.as_ref().ok_or(AccountError::MissingProvider)
     }
 
-    async fn get_nonce(&self) -> Result<FieldElement, Self::Error> {
-        let provider = self.get_provider()?;
-
-        provider
-            .get_nonce(&BlockId::Tag(BlockTag::Latest), self.account)
-            .await
-            .map_err(AccountError::ProviderError)
-    }
-
     async fn prepare_invoke_transaction(
         &self,
         calls: &[Call],
         nonce: FieldElement,
         max_fee: FieldElement,
     ) -> Result<BroadcastedInvokeTransaction, Self::Error> {
-        let provider = self.get_provider()?;
-        let chain = provider
-            .chain_id()
+        let calldata = self.variant.encode_calls(calls);
+        self.prepare_invoke_transaction_with_calldata(calldata, nonce, max_fee)
             .await
-            .map_err(AccountError::ProviderError)?;
+    }
 
-        let calldata = SimpleProbe::generate_calldata_for_multicall_account(calls);
+    // `calldata` is the raw calldata of the account's `__execute__` function
+    async fn prepare_invoke_transaction_with_calldata(
+        &self,
+        calldata: Vec<FieldElement>,
+        nonce: FieldElement,
+        max_fee: FieldElement,
+    ) -> Result<BroadcastedInvokeTransaction, Self::Error> {
+        let chain = self.get_chain_id().await?;
 
-        let tx_hash = compute_hash_on_elements(&[
-            PREFIX_INVOKE,
-            FieldElement::ONE, // version
+        let tx_hash = SimpleProbe::compute_invoke_tx_hash(
             self.account,
-            FieldElement::ZERO, // entry_point_selector
-            compute_hash_on_elements(&calldata),
+            &calldata,
             max_fee,
             chain,
             nonce,
-        ]);
+            FieldElement::ONE,
+        );
 
         let signature = self
             .sign_hash(&tx_hash)
@@ -215,13 +302,20 @@ impl Account for SimpleAccount {
 
     // must be called after prepare_transaction
     async fn get_max_fee(&self, request: &BroadcastedTransaction) -> Result<u64, Self::Error> {
+        self.estimate_fee(request).await.map(|fee| fee.overall_fee)
+    }
+
+    // must be called after prepare_transaction
+    async fn estimate_fee(
+        &self,
+        request: &BroadcastedTransaction,
+    ) -> Result<FeeEstimate, Self::Error> {
         let provider = self.get_provider()?;
-        let res = provider
+
+        provider
             .estimate_fee(request, &BlockId::Tag(BlockTag::Latest))
             .await
-            .map_err(AccountError::ProviderError)?;
-
-        Ok(res.overall_fee)
+            .map_err(AccountError::ProviderError)
     }
 
     // must be called after setting the max fee
@@ -236,6 +330,99 @@ impl Account for SimpleAccount {
             .await
             .map_err(AccountError::ProviderError)
     }
+
+    async fn prepare_declare_transaction(
+        &self,
+        contract_class: &ContractClass,
+        class_hash: FieldElement,
+        nonce: FieldElement,
+        max_fee: FieldElement,
+    ) -> Result<BroadcastedDeclareTransaction, Self::Error> {
+        let chain = self.get_chain_id().await?;
+
+        let tx_hash = SimpleProbe::compute_declare_tx_hash(
+            self.account,
+            class_hash,
+            max_fee,
+            chain,
+            nonce,
+            FieldElement::ONE,
+        );
+
+        let signature = self
+            .sign_hash(&tx_hash)
+            .await
+            .map_err(AccountError::SignError)?;
+
+        Ok(BroadcastedDeclareTransaction {
+            max_fee,
+            nonce,
+            version: 1,
+            sender_address: self.account,
+            contract_class: contract_class.to_owned(),
+            signature: vec![signature.r, signature.s],
+        })
+    }
+
+    // must be called after setting the max fee
+    async fn send_declare_transaction(
+        &self,
+        request: &BroadcastedDeclareTransaction,
+    ) -> Result<DeclareTransactionResult, Self::Error> {
+        let provider = self.get_provider()?;
+
+        provider
+            .add_declare_transaction(request)
+            .await
+            .map_err(AccountError::ProviderError)
+    }
+
+    // `self.account` must be the counterfactual address of the account being deployed
+    async fn prepare_deploy_account_transaction(
+        &self,
+        class_hash: FieldElement,
+        salt: FieldElement,
+        constructor_calldata: &[FieldElement],
+        nonce: FieldElement,
+        max_fee: FieldElement,
+    ) -> Result<BroadcastedDeployAccountTransaction, Self::Error> {
+        let chain = self.get_chain_id().await?;
+
+        let tx_hash = SimpleProbe::compute_deploy_account_tx_hash(
+            class_hash,
+            salt,
+            constructor_calldata,
+            max_fee,
+            chain,
+            nonce,
+            FieldElement::ONE,
+        );
+
+        let signature = self.sign_deployment(tx_hash, chain).await?;
+
+        Ok(BroadcastedDeployAccountTransaction {
+            max_fee,
+            nonce,
+            class_hash,
+            version: 1,
+            contract_address_salt: salt,
+            constructor_calldata: constructor_calldata.to_vec(),
+            signature,
+        })
+    }
+
+    // must be called after setting the max fee
+    async fn send_deploy_account_transaction(
+        &self,
+        request: &BroadcastedDeployAccountTransaction,
+    ) -> Result<DeployAccountTransactionResult, Self::Error> {
+        let provider = self.get_provider()?;
+
+        provider
+            .add_deploy_account_transaction(request)
+            .await
+            .map_err(AccountError::ProviderError)
+    }
 }
 
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
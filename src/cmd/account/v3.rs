@@ -0,0 +1,342 @@
+//! v3 transactions pay their fee in STRK and are bounded by resources instead of a max fee. They
+//! are not supported by the provider, so they are built and sent as raw JSON-RPC requests.
+
+use super::simple_account::{AccountError, SimpleAccount};
+use crate::opts::transaction::{DataAvailabilityMode, TransactionOptions};
+use crate::probe::utils::{poseidon_hash_many, send_rpc_request};
+
+use std::future::Future;
+
+use eyre::{eyre, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use starknet::accounts::Call;
+use starknet::core::types::FieldElement;
+use starknet::core::utils::{cairo_short_string_to_felt, get_contract_address};
+use starknet::providers::jsonrpc::models::{
+    DeployAccountTransactionResult, FeeEstimate, InvokeTransactionResult,
+};
+use starknet::signers::Signer;
+
+// 2^128 + 3, the version used to sign transactions that are only meant for fee estimation
+const QUERY_VERSION_THREE: FieldElement = FieldElement::from_mont([
+    18446744073700081569,
+    17407,
+    18446744073709551584,
+    576460752142432688,
+]);
+
+#[derive(Debug, Clone, Copy)]
+pub struct V3Fees {
+    pub l1_gas: u64,
+    pub l1_gas_price: u128,
+    pub tip: u64,
+    pub nonce_da_mode: DataAvailabilityMode,
+    pub fee_da_mode: DataAvailabilityMode,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct V3FeeEstimate {
+    pub gas_consumed: FieldElement,
+    pub gas_price: FieldElement,
+    pub overall_fee: FieldElement,
+}
+
+impl From<&TransactionOptions> for V3Fees {
+    fn from(transaction: &TransactionOptions) -> Self {
+        Self {
+            l1_gas: transaction.l1_gas.unwrap_or_default(),
+            l1_gas_price: transaction.l1_gas_price.unwrap_or_default(),
+            tip: transaction.tip,
+            nonce_da_mode: transaction.nonce_da_mode,
+            fee_da_mode: transaction.fee_da_mode,
+        }
+    }
+}
+
+impl TryFrom<V3FeeEstimate> for FeeEstimate {
+    type Error = eyre::Report;
+
+    fn try_from(estimate: V3FeeEstimate) -> Result<Self> {
+        Ok(FeeEstimate {
+            gas_consumed: estimate.gas_consumed.to_string().parse()?,
+            gas_price: estimate.gas_price.to_string().parse()?,
+            overall_fee: estimate.overall_fee.to_string().parse()?,
+        })
+    }
+}
+
+impl V3Fees {
+    fn hash(&self) -> Result<FieldElement> {
+        Ok(poseidon_hash_many(&[
+            FieldElement::from(self.tip),
+            resource_bound("L1_GAS", self.l1_gas, self.l1_gas_price)?,
+            resource_bound("L2_GAS", 0, 0)?,
+        ]))
+    }
+
+    fn data_availability_modes(&self) -> FieldElement {
+        FieldElement::from((self.nonce_da_mode.as_u64() << 32) + self.fee_da_mode.as_u64())
+    }
+
+    fn to_json(self) -> Value {
+        json!({
+            "resource_bounds": {
+                "l1_gas": {
+                    "max_amount": format!("{:#x}", self.l1_gas),
+                    "max_price_per_unit": format!("{:#x}", self.l1_gas_price),
+                },
+                "l2_gas": {
+                    "max_amount": "0x0",
+                    "max_price_per_unit": "0x0",
+                },
+            },
+            "tip": format!("{:#x}", self.tip),
+            "paymaster_data": [],
+            "nonce_data_availability_mode": self.nonce_da_mode,
+            "fee_data_availability_mode": self.fee_da_mode,
+        })
+    }
+}
+
+impl SimpleAccount {
+    /// Signs and sends a v3 invoke transaction, estimating the L1 gas bounds if they are not
+    /// specified in the transaction options.
+    pub async fn execute_v3(
+        &self,
+        calls: &[Call],
+        nonce: FieldElement,
+        transaction: &TransactionOptions,
+    ) -> Result<InvokeTransactionResult> {
+        let request = self.build_invoke_v3(calls, nonce, transaction).await?;
+
+        send_rpc_request(
+            self.get_rpc_url()?,
+            "starknet_addInvokeTransaction",
+            json!({ "invoke_transaction": request }),
+        )
+        .await
+    }
+
+    /// Returns the signed v3 invoke transaction, ready to be broadcasted.
+    pub async fn build_invoke_v3(
+        &self,
+        calls: &[Call],
+        nonce: FieldElement,
+        transaction: &TransactionOptions,
+    ) -> Result<Value> {
+        let calldata = self.variant.encode_calls(calls);
+
+        let fees = self
+            .resolve_v3_fees(transaction, |fees| {
+                self.prepare_invoke_transaction_v3(&calldata, nonce, fees, true)
+            })
+            .await?;
+
+        self.prepare_invoke_transaction_v3(&calldata, nonce, fees, false)
+            .await
+    }
+
+    pub async fn send_deploy_account_transaction_v3(
+        &self,
+        request: &Value,
+    ) -> Result<DeployAccountTransactionResult> {
+        send_rpc_request(
+            self.get_rpc_url()?,
+            "starknet_addDeployAccountTransaction",
+            json!({ "deploy_account_transaction": request }),
+        )
+        .await
+    }
+
+    pub async fn prepare_invoke_transaction_v3(
+        &self,
+        calldata: &[FieldElement],
+        nonce: FieldElement,
+        fees: V3Fees,
+        query: bool,
+    ) -> Result<Value> {
+        let chain = self.get_chain_id().await?;
+        let version = if query {
+            QUERY_VERSION_THREE
+        } else {
+            FieldElement::THREE
+        };
+
+        let tx_hash = poseidon_hash_many(&[
+            cairo_short_string_to_felt("invoke")?,
+            version,
+            self.account,
+            fees.hash()?,
+            poseidon_hash_many(&[]), // paymaster_data
+            chain,
+            nonce,
+            fees.data_availability_modes(),
+            poseidon_hash_many(&[]), // account_deployment_data
+            poseidon_hash_many(calldata),
+        ]);
+
+        let signature = self
+            .sign_hash(&tx_hash)
+            .await
+            .map_err(AccountError::SignError)?;
+
+        let mut request = json!({
+            "type": "INVOKE",
+            "version": version,
+            "sender_address": self.account,
+            "calldata": calldata,
+            "signature": [signature.r, signature.s],
+            "nonce": nonce,
+            "account_deployment_data": [],
+        });
+        merge(&mut request, fees.to_json());
+
+        Ok(request)
+    }
+
+    // `self.account` must be the counterfactual address of the account being deployed
+    pub async fn prepare_deploy_account_transaction_v3(
+        &self,
+        class_hash: FieldElement,
+        salt: FieldElement,
+        constructor_calldata: &[FieldElement],
+        nonce: FieldElement,
+        fees: V3Fees,
+        query: bool,
+    ) -> Result<Value> {
+        let chain = self.get_chain_id().await?;
+        let version = if query {
+            QUERY_VERSION_THREE
+        } else {
+            FieldElement::THREE
+        };
+
+        let contract_address =
+            get_contract_address(salt, class_hash, constructor_calldata, FieldElement::ZERO);
+
+        let tx_hash = poseidon_hash_many(&[
+            cairo_short_string_to_felt("deploy_account")?,
+            version,
+            contract_address,
+            fees.hash()?,
+            poseidon_hash_many(&[]), // paymaster_data
+            chain,
+            nonce,
+            fees.data_availability_modes(),
+            poseidon_hash_many(constructor_calldata),
+            class_hash,
+            salt,
+        ]);
+
+        let signature = self.sign_deployment(tx_hash, chain).await?;
+
+        let mut request = json!({
+            "type": "DEPLOY_ACCOUNT",
+            "version": version,
+            "class_hash": class_hash,
+            "contract_address_salt": salt,
+            "constructor_calldata": constructor_calldata,
+            "signature": signature,
+            "nonce": nonce,
+        });
+        merge(&mut request, fees.to_json());
+
+        Ok(request)
+    }
+
+    // must be called with a transaction signed with the query version
+    pub async fn estimate_fee_v3(&self, request: &Value) -> Result<V3FeeEstimate> {
+        let mut estimates: Vec<V3FeeEstimate> = send_rpc_request(
+            self.get_rpc_url()?,
+            "starknet_estimateFee",
+            json!({
+                "request": [request],
+                "simulation_flags": [],
+                "block_id": "pending",
+            }),
+        )
+        .await?;
+
+        estimates
+            .pop()
+            .ok_or_else(|| eyre!("no fee estimate returned"))
+    }
+
+    /// Returns the fees of the transaction, where the L1 gas bounds that are not specified in
+    /// the transaction options are taken from the fee estimate of the request built by `prepare`.
+    pub async fn resolve_v3_fees<F, Fut>(
+        &self,
+        transaction: &TransactionOptions,
+        prepare: F,
+    ) -> Result<V3Fees>
+    where
+        F: FnOnce(V3Fees) -> Fut,
+        Fut: Future<Output = Result<Value>>,
+    {
+        let mut fees = V3Fees::from(transaction);
+
+        if transaction.l1_gas.is_none() || transaction.l1_gas_price.is_none() {
+            let request = prepare(fees).await?;
+            let estimate = self.estimate_fee_v3(&request).await?;
+            let max_fee =
+                transaction.scale_estimated_fee(estimate.overall_fee.to_string().parse()?)?;
+
+            if transaction.l1_gas_price.is_none() {
+                fees.l1_gas_price = estimate.gas_price.to_string().parse()?;
+            }
+            if transaction.l1_gas.is_none() {
+                // the amount is derived from the scaled fee so that it stays within the cap
+                fees.l1_gas = match max_fee.checked_div(fees.l1_gas_price) {
+                    Some(amount) => u64::try_from(amount).unwrap_or(u64::MAX),
+                    None => estimate.gas_consumed.to_string().parse()?,
+                };
+            }
+        }
+
+        Ok(fees)
+    }
+}
+
+/// Encodes the bounds of a resource as `name (60 bits) | max_amount (64 bits) | max_price (128 bits)`.
+fn resource_bound(name: &str, max_amount: u64, max_price_per_unit: u128) -> Result<FieldElement> {
+    let name = cairo_short_string_to_felt(name)?.to_bytes_be();
+
+    let mut bytes = [0u8; 32];
+    bytes[..8].copy_from_slice(&name[24..]);
+    bytes[8..16].copy_from_slice(&max_amount.to_be_bytes());
+    bytes[16..].copy_from_slice(&max_price_per_unit.to_be_bytes());
+
+    Ok(FieldElement::from_bytes_be(&bytes)?)
+}
+
+fn merge(a: &mut Value, b: Value) {
+    if let (Value::Object(a), Value::Object(b)) = (a, b) {
+        a.extend(b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn query_version_three() {
+        assert_eq!(
+            QUERY_VERSION_THREE,
+            FieldElement::from_str("0x100000000000000000000000000000003").unwrap()
+        );
+    }
+
+    #[test]
+    fn encode_resource_bound() {
+        assert_eq!(
+            resource_bound("L1_GAS", 0x100, 0x200).unwrap(),
+            FieldElement::from_str(
+                "0x4c315f474153000000000000010000000000000000000000000000000200"
+            )
+            .unwrap()
+        );
+    }
+}
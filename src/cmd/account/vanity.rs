@@ -0,0 +1,86 @@
+use eyre::{bail, Result};
+use rand::RngCore;
+use rayon::iter::{self, ParallelIterator};
+use starknet::core::types::FieldElement;
+use starknet::core::utils::get_contract_address;
+
+/// The hex digits an address must start and end with.
+#[derive(Debug, Clone, Default)]
+pub struct VanityPattern {
+    prefix: String,
+    suffix: String,
+}
+
+impl VanityPattern {
+    pub fn new(prefix: Option<&str>, suffix: Option<&str>) -> Result<Self> {
+        let parse = |pattern: Option<&str>| {
+            let pattern = pattern.unwrap_or_default().to_lowercase();
+            let pattern = pattern.trim_start_matches("0x");
+
+            if !pattern.chars().all(|c| c.is_ascii_hexdigit()) {
+                bail!("`{pattern}` is not a hex pattern")
+            }
+
+            Ok(pattern.to_string())
+        };
+
+        Ok(Self {
+            prefix: parse(prefix)?,
+            suffix: parse(suffix)?,
+        })
+    }
+
+    /// Whether the address, as printed without leading zeros, matches the pattern.
+    pub fn matches(&self, address: FieldElement) -> bool {
+        let address = format!("{address:x}");
+        address.starts_with(&self.prefix) && address.ends_with(&self.suffix)
+    }
+}
+
+/// Grinds random salts on all cores until one deploys the account to an address matching the
+/// pattern. Returns the salt along with the address. Each hex digit of the pattern makes the
+/// search 16 times longer.
+pub fn grind_salt(
+    class_hash: FieldElement,
+    constructor_calldata: &[FieldElement],
+    pattern: &VanityPattern,
+) -> (FieldElement, FieldElement) {
+    iter::repeat(())
+        .map_init(rand::thread_rng, |rng, _| {
+            let mut bytes = [0u8; 32];
+            rng.fill_bytes(&mut bytes);
+            // keep the salt below 2^251 so that it is a valid felt
+            bytes[0] &= 0x07;
+
+            let salt = FieldElement::from_bytes_be(&bytes).unwrap();
+            let address =
+                get_contract_address(salt, class_hash, constructor_calldata, FieldElement::ZERO);
+            (salt, address)
+        })
+        .find_any(|(_, address)| pattern.matches(*address))
+        .expect("the salts are infinite")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grind_vanity_address() {
+        let pattern = VanityPattern::new(Some("0xA"), Some("b")).unwrap();
+        assert!(pattern.matches(FieldElement::from_hex_be("0xa12b").unwrap()));
+        assert!(!pattern.matches(FieldElement::from_hex_be("0xa12c").unwrap()));
+        assert!(VanityPattern::new(Some("xyz"), None).is_err());
+
+        let class_hash = FieldElement::from_hex_be("0x123").unwrap();
+        let calldata = [FieldElement::from_hex_be("0x456").unwrap()];
+
+        let pattern = VanityPattern::new(None, Some("b")).unwrap();
+        let (salt, address) = grind_salt(class_hash, &calldata, &pattern);
+        assert!(format!("{address:#x}").ends_with('b'));
+        assert_eq!(
+            address,
+            get_contract_address(salt, class_hash, &calldata, FieldElement::ZERO)
+        );
+    }
+}
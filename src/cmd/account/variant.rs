@@ -0,0 +1,176 @@
+use super::simple_account::AccountError;
+use crate::probe::abi::Abi;
+use crate::probe::SimpleProbe;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use starknet::accounts::Call;
+use starknet::core::types::FieldElement;
+
+/// The account contracts supported by probe. They differ in the arguments they are deployed with,
+/// in how they take the calls to execute and in what they expect to be signed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountVariant {
+    #[clap(name = "oz")]
+    #[serde(rename = "oz")]
+    OpenZeppelin,
+    Argent,
+    Braavos,
+}
+
+impl AccountVariant {
+    /// Detects the kind of account from the functions declared in the abi of its class. Braavos
+    /// accounts are checked first as they also expose `get_public_key`.
+    pub fn detect(abi: &Abi) -> Option<Self> {
+        let declares = |names: &[&str]| names.iter().any(|name| abi.function(name).is_ok());
+
+        if declares(&["get_signers", "getSigners"]) {
+            Some(Self::Braavos)
+        } else if declares(&["get_guardian", "getGuardian"]) {
+            Some(Self::Argent)
+        } else if declares(&["get_public_key", "getPublicKey"]) {
+            Some(Self::OpenZeppelin)
+        } else {
+            None
+        }
+    }
+
+    /// The storage variable holding the public key of the account, along with its keys.
+    pub fn public_key_storage(&self) -> (&'static str, &'static [FieldElement]) {
+        match self {
+            Self::OpenZeppelin => ("Account_public_key", &[]),
+            Self::Argent => ("_signer", &[]),
+            // the stark signer is the first member of the signer with id 0
+            Self::Braavos => ("Account_signers", &[FieldElement::ZERO]),
+        }
+    }
+
+    /// The constructor calldata of an account controlled by the given public key.
+    pub fn constructor_calldata(&self, public_key: FieldElement) -> Vec<FieldElement> {
+        match self {
+            Self::OpenZeppelin | Self::Braavos => vec![public_key],
+            // the owner, then the guardian which is left unset
+            Self::Argent => vec![public_key, FieldElement::ZERO],
+        }
+    }
+
+    /// Encodes the calls as the calldata of the `__execute__` function of the account. Argent and
+    /// Braavos accounts are Cairo 1 contracts, which take the calldata of each call inlined.
+    pub fn encode_calls(&self, calls: &[Call]) -> Vec<FieldElement> {
+        match self {
+            Self::OpenZeppelin => SimpleProbe::generate_calldata_for_multicall_account(calls),
+            Self::Argent | Self::Braavos => {
+                SimpleProbe::generate_calldata_for_cairo_1_account(calls)
+            }
+        }
+    }
+
+    /// The data to sign along with a deploy account transaction, which is appended to its
+    /// signature. Braavos accounts are deployed as a base account that upgrades itself to the
+    /// given implementation, with the parameters of the upgrade taken from this data.
+    pub fn deployment_data(
+        &self,
+        implementation: Option<FieldElement>,
+        chain_id: FieldElement,
+    ) -> Result<Vec<FieldElement>, AccountError> {
+        match self {
+            Self::OpenZeppelin | Self::Argent => Ok(Vec::new()),
+            Self::Braavos => {
+                let implementation = implementation.ok_or(AccountError::MissingImplementation)?;
+
+                let mut data = vec![implementation];
+                // the signer type, then the coordinates of the secp256r1 signer which is not set
+                data.extend([FieldElement::ZERO; 5]);
+                // the multisig threshold, the withdrawal limit and the fee rates
+                data.extend([FieldElement::ZERO; 4]);
+                data.push(chain_id);
+
+                Ok(data)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn detect_account_variant() {
+        let abi = |names: &[&str]| {
+            let entries = names
+                .iter()
+                .map(
+                    |name| json!({ "type": "function", "name": name, "inputs": [], "outputs": [] }),
+                )
+                .collect::<Vec<_>>();
+            Abi::parse(&json!(entries)).unwrap()
+        };
+
+        assert_eq!(
+            AccountVariant::detect(&abi(&["getPublicKey", "__execute__"])),
+            Some(AccountVariant::OpenZeppelin)
+        );
+        assert_eq!(
+            AccountVariant::detect(&abi(&["get_signer", "get_guardian"])),
+            Some(AccountVariant::Argent)
+        );
+        assert_eq!(
+            AccountVariant::detect(&abi(&["get_public_key", "get_signers"])),
+            Some(AccountVariant::Braavos)
+        );
+        assert_eq!(AccountVariant::detect(&abi(&["transfer"])), None);
+    }
+
+    #[test]
+    fn cairo_1_accounts_calldata() {
+        let calls = [
+            Call {
+                to: FieldElement::from(0x1_u64),
+                selector: FieldElement::from(0xa_u64),
+                calldata: vec![FieldElement::from(5_u64), FieldElement::from(6_u64)],
+            },
+            Call {
+                to: FieldElement::from(0x2_u64),
+                selector: FieldElement::from(0xb_u64),
+                calldata: vec![],
+            },
+        ];
+
+        assert_eq!(
+            AccountVariant::Argent.encode_calls(&calls),
+            [2_u64, 0x1, 0xa, 2, 5, 6, 0x2, 0xb, 0]
+                .map(FieldElement::from)
+                .to_vec()
+        );
+        assert_eq!(
+            AccountVariant::OpenZeppelin.encode_calls(&calls),
+            [2_u64, 0x1, 0xa, 0, 2, 0x2, 0xb, 2, 0, 2, 5, 6]
+                .map(FieldElement::from)
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn braavos_deployment_data() {
+        let implementation = FieldElement::from(0x123_u64);
+        let chain_id = FieldElement::from(0x534e_u64);
+
+        let data = AccountVariant::Braavos
+            .deployment_data(Some(implementation), chain_id)
+            .unwrap();
+
+        assert_eq!(data.len(), 11);
+        assert_eq!(data[0], implementation);
+        assert_eq!(data[10], chain_id);
+        assert!(AccountVariant::Braavos
+            .deployment_data(None, chain_id)
+            .is_err());
+        assert!(AccountVariant::Argent
+            .deployment_data(None, chain_id)
+            .unwrap()
+            .is_empty());
+    }
+}
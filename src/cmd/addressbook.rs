@@ -0,0 +1,84 @@
+use crate::opts::display::DisplayOptions;
+use crate::opts::starknet::StarkNetOptions;
+use crate::probe::addressbook::AddressBook;
+use crate::probe::utils::fmt::pretty_contacts;
+
+use std::io::Write;
+
+use clap::Subcommand;
+use eyre::{bail, Result};
+use starknet::core::types::FieldElement;
+
+#[derive(Debug, Subcommand)]
+pub enum AddressBookCommands {
+    #[clap(about = "Save an address under a name, for the chain of the network.")]
+    Add {
+        #[clap(value_name = "NAME")]
+        name: String,
+
+        #[clap(value_name = "ADDRESS")]
+        address: FieldElement,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(visible_alias = "remove")]
+    #[clap(about = "Remove a saved name from the chain of the network.")]
+    Rm {
+        #[clap(value_name = "NAME")]
+        name: String,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(visible_alias = "list")]
+    #[clap(about = "List the saved names of every chain.")]
+    Ls,
+}
+
+impl AddressBookCommands {
+    pub async fn run(self, display: &DisplayOptions, out: &mut dyn Write) -> Result<()> {
+        let book = AddressBook::from_home_dir()?;
+
+        match self {
+            Self::Add {
+                name,
+                address,
+                starknet,
+            } => {
+                let chain = starknet.chain_id().await?;
+                book.add(chain, &name, address)?;
+            }
+
+            Self::Rm { name, starknet } => {
+                let chain = starknet.chain_id().await?;
+                if !book.remove(chain, &name)? {
+                    bail!("`{name}` is not saved for the chain {chain:#x}")
+                }
+            }
+
+            Self::Ls => {
+                let contacts = book.load()?;
+
+                if display.is_json() {
+                    let json = serde_json::to_string_pretty(&contacts)?;
+                    writeln!(out, "{}", display.render(json)?)?;
+                } else if contacts.is_empty() {
+                    writeln!(out, "No contacts saved.")?;
+                } else {
+                    writeln!(
+                        out,
+                        "{}",
+                        display.colorize(pretty_contacts(&contacts, &display.value_format()))
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
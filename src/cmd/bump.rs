@@ -0,0 +1,120 @@
+use super::account::simple_account::Account;
+use super::send::build_account;
+use crate::opts::account::WalletOptions;
+use crate::opts::starknet::StarkNetOptions;
+
+use std::str::FromStr;
+
+use clap::Parser;
+use eyre::{bail, eyre, Result};
+use starknet::core::types::FieldElement;
+use starknet::providers::jsonrpc::models::{
+    ErrorCode, InvokeTransaction, InvokeTransactionResult, MaybePendingTransactionReceipt,
+    Transaction, TransactionReceipt, TransactionStatus,
+};
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient, JsonRpcClientError, RpcError};
+
+#[derive(Debug, Parser)]
+pub struct TxBumpArgs {
+    #[clap(value_name = "TX_HASH")]
+    #[clap(help = "The hash of the pending transaction to resubmit")]
+    pub hash: FieldElement,
+
+    #[clap(long)]
+    #[clap(value_name = "MULTIPLIER")]
+    #[clap(default_value = "1.5")]
+    #[clap(help = "The factor by which the max fee of the transaction is increased")]
+    pub fee_multiplier: f64,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "STARKNET OPTIONS")]
+    pub starknet: StarkNetOptions,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "Wallet OPTIONS")]
+    pub wallet: WalletOptions,
+}
+
+impl TxBumpArgs {
+    /// Resubmits the invoke transaction with the same nonce and calldata but a higher max fee.
+    /// Returns the new max fee along with the result of the replacing transaction.
+    pub async fn run(self) -> Result<(FieldElement, InvokeTransactionResult)> {
+        let TxBumpArgs {
+            hash,
+            fee_multiplier,
+            starknet,
+            wallet,
+        } = self;
+
+        if fee_multiplier <= 1.0 {
+            bail!("fee multiplier must be greater than 1")
+        }
+
+        let account = build_account(&wallet, starknet, false).await?;
+        let provider = account.get_provider()?;
+
+        ensure_not_included(provider, hash).await?;
+
+        let tx = match provider.get_transaction_by_hash(hash).await? {
+            Transaction::Invoke(InvokeTransaction::V1(tx)) => tx,
+            _ => bail!("only invoke v1 transactions can be bumped"),
+        };
+
+        if tx.sender_address != account.account {
+            bail!(
+                "transaction {hash:#x} was not sent by account {:#x}",
+                account.account
+            )
+        }
+
+        let max_fee = tx.max_fee.to_string().parse::<u128>()?;
+        let max_fee =
+            FieldElement::from_str(&((max_fee as f64 * fee_multiplier) as u128).to_string())?;
+
+        let request = account
+            .prepare_invoke_transaction_with_calldata(tx.calldata, tx.nonce, max_fee)
+            .await?;
+
+        let res = account
+            .send_invoke_transaction(&request)
+            .await
+            .map_err(|e| eyre!(e))?;
+
+        Ok((max_fee, res))
+    }
+}
+
+/// Fails unless the transaction was received by the node but is not yet part of a block, as a
+/// transaction can't be replaced once it has been executed.
+async fn ensure_not_included(
+    provider: &JsonRpcClient<HttpTransport>,
+    hash: FieldElement,
+) -> Result<()> {
+    match provider.get_transaction_receipt(hash).await {
+        Ok(MaybePendingTransactionReceipt::Receipt(receipt)) => {
+            let (status, block_number) = match receipt {
+                TransactionReceipt::Invoke(r) => (r.status, r.block_number),
+                TransactionReceipt::Declare(r) => (r.status, r.block_number),
+                TransactionReceipt::Deploy(r) => (r.status, r.block_number),
+                TransactionReceipt::DeployAccount(r) => (r.status, r.block_number),
+                TransactionReceipt::L1Handler(r) => (r.status, r.block_number),
+            };
+
+            match status {
+                TransactionStatus::Rejected => bail!("transaction {hash:#x} was rejected"),
+                _ => bail!("transaction {hash:#x} is already included in block {block_number}"),
+            }
+        }
+
+        Ok(MaybePendingTransactionReceipt::PendingReceipt(_)) => {
+            bail!("transaction {hash:#x} is already included in the pending block")
+        }
+
+        // the node knows of the transaction but hasn't executed it yet
+        Err(JsonRpcClientError::RpcError(RpcError::Code(ErrorCode::TransactionHashNotFound))) => {
+            Ok(())
+        }
+
+        Err(e) => Err(e.into()),
+    }
+}
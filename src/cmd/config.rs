@@ -0,0 +1,252 @@
+use crate::opts::config::{config_path, Config, CONFIG_TEMPLATE};
+use crate::opts::display::DisplayOptions;
+use crate::opts::starknet::StarkNetOptions;
+use crate::probe::utils::fmt::{Pretty, ValueFormat};
+
+use std::fs;
+use std::io::Write;
+
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Subcommand};
+use comfy_table::modifiers::UTF8_SOLID_INNER_BORDERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::Table;
+use eyre::{bail, Result};
+use serde::Serialize;
+use serde_json::json;
+use toml::Value;
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommands {
+    #[clap(about = "Write a starter config file.")]
+    Init {
+        #[clap(long)]
+        #[clap(help = "Overwrite the config file if it already exists.")]
+        force: bool,
+    },
+}
+
+/// A value of the effective configuration, along with where it comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Setting {
+    pub name: String,
+    pub value: String,
+    pub source: String,
+}
+
+impl Setting {
+    fn new(name: impl ToString, value: impl ToString, source: impl ToString) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.to_string(),
+            source: source.to_string(),
+        }
+    }
+}
+
+impl ConfigCommands {
+    pub fn run(self, out: &mut dyn Write) -> Result<()> {
+        match self {
+            Self::Init { force } => {
+                let path = config_path()?;
+                if path.exists() && !force {
+                    bail!(
+                        "{} already exists, use --force to overwrite it",
+                        path.display()
+                    )
+                }
+
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::write(&path, CONFIG_TEMPLATE)?;
+                writeln!(out, "Config file written to {}", path.display())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints the configuration the other commands would run with given the same options. The
+/// matches of the command tell whether each option comes from a flag, the environment or its
+/// default.
+pub fn show_config(
+    starknet: &StarkNetOptions,
+    matches: &ArgMatches,
+    display: &DisplayOptions,
+) -> Result<String> {
+    let path = config_path()?;
+    let config = Config::load()?;
+    let settings = effective_settings(starknet, matches, &config, &display.value_format());
+
+    if display.is_json() {
+        let json = json!({
+            "config_file": path,
+            "exists": path.exists(),
+            "settings": settings,
+        });
+        return display.render(serde_json::to_string_pretty(&json)?);
+    }
+
+    let missing = if path.exists() { "" } else { " (not found)" };
+    Ok(format!(
+        "Config file : {}{missing}\n{}",
+        path.display(),
+        display.colorize(pretty_settings(&settings))
+    ))
+}
+
+fn effective_settings(
+    starknet: &StarkNetOptions,
+    matches: &ArgMatches,
+    config: &Config,
+    fmt: &ValueFormat,
+) -> Vec<Setting> {
+    let mut settings = Vec::new();
+
+    // the network replaces the RPC endpoint, and gives the chain id unless `--chain` is set
+    let network = matches
+        .get_raw("network")
+        .and_then(|mut values| values.next())
+        .map(|name| {
+            let name = name.to_string_lossy().to_string();
+            let origin = if config.networks.contains_key(&name) {
+                "config file"
+            } else {
+                "public"
+            };
+            format!("network `{name}` ({origin})")
+        });
+
+    match &network {
+        Some(network) => settings.push(Setting::new("rpc_url", starknet.rpc_url(), network)),
+        None => settings.push(Setting::new(
+            "rpc_url",
+            starknet.rpc_url(),
+            source(matches, "rpc_url", "STARKNET_RPC_URL"),
+        )),
+    }
+
+    let chain = match (starknet.chain, starknet.chain(), &network) {
+        (Some(chain), ..) => Setting::new(
+            "chain",
+            chain.prettify(fmt),
+            source(matches, "chain", "STARKNET_CHAIN"),
+        ),
+        (None, Some(chain), Some(network)) => Setting::new("chain", chain.prettify(fmt), network),
+        _ => Setting::new("chain", "-", "asked to the node"),
+    };
+    settings.push(chain);
+
+    settings.push(
+        match (&starknet.account_descriptor, config.account.descriptor()) {
+            (Some(path), _) => Setting::new(
+                "account",
+                path.display(),
+                source(matches, "account_descriptor", "STARKNET_ACCOUNT"),
+            ),
+            (None, Some(path)) => Setting::new("account", path.display(), "config file"),
+            (None, None) => Setting::new("account", "-", "unset"),
+        },
+    );
+
+    // the keystore isn't an option of this command, so only the env and the file are looked up
+    let keystore = std::env::var_os("STARKNET_KEYSTORE").filter(|path| !path.is_empty());
+    settings.push(match (keystore, config.account.keystore()) {
+        (Some(path), _) => {
+            Setting::new("keystore", path.to_string_lossy(), "env STARKNET_KEYSTORE")
+        }
+        (None, Some(path)) => Setting::new("keystore", path.display(), "config file"),
+        (None, None) => Setting::new("keystore", "-", "unset"),
+    });
+
+    for (name, network) in &config.networks {
+        settings.push(Setting::new(
+            format!("networks.{name}.rpc_url"),
+            &network.rpc_url,
+            "config file",
+        ));
+        if let Some(chain_id) = &network.chain_id {
+            settings.push(Setting::new(
+                format!("networks.{name}.chain_id"),
+                chain_id,
+                "config file",
+            ));
+        }
+    }
+
+    for (command, options) in &config.defaults {
+        for (option, value) in options {
+            let value = match value {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            settings.push(Setting::new(
+                format!("defaults.{command}.{option}"),
+                value,
+                "config file",
+            ));
+        }
+    }
+
+    settings
+}
+
+fn pretty_settings(settings: &[Setting]) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+        .set_header(vec!["SETTING", "VALUE", "SOURCE"]);
+
+    for setting in settings {
+        table.add_row(vec![&setting.name, &setting.value, &setting.source]);
+    }
+
+    format!("{table}")
+}
+
+/// Where the value of the argument comes from.
+fn source(matches: &ArgMatches, id: &str, env: &str) -> String {
+    match matches.value_source(id) {
+        Some(ValueSource::CommandLine) => "flag".to_string(),
+        Some(ValueSource::EnvVariable) => format!("env {env}"),
+        Some(ValueSource::DefaultValue) => "default".to_string(),
+        _ => "unset".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::probe::App;
+
+    use clap::{CommandFactory, FromArgMatches};
+
+    #[test]
+    fn settings_sources() {
+        let matches = App::command()
+            .try_get_matches_from(["probe", "config", "--rpc-url", "http://localhost:1234"])
+            .unwrap();
+        let matches = matches.subcommand_matches("config").unwrap();
+        let starknet = StarkNetOptions::from_arg_matches(matches).unwrap();
+        let config = Config::default();
+
+        let settings = effective_settings(&starknet, matches, &config, &ValueFormat::default());
+        assert_eq!(
+            settings[0],
+            Setting::new("rpc_url", "http://localhost:1234/", "flag")
+        );
+
+        let matches = App::command()
+            .try_get_matches_from(["probe", "config", "--network", "mainnet"])
+            .unwrap();
+        let matches = matches.subcommand_matches("config").unwrap();
+        let starknet = StarkNetOptions::from_arg_matches(matches).unwrap();
+
+        let settings = effective_settings(&starknet, matches, &config, &ValueFormat::default());
+        assert_eq!(settings[0].source, "network `mainnet` (public)");
+        assert_eq!(settings[1].source, "network `mainnet` (public)");
+    }
+}
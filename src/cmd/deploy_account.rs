@@ -0,0 +1,341 @@
+use super::account::simple_account::{Account, SimpleAccount};
+use super::account::v3::V3Fees;
+use super::account::variant::AccountVariant;
+use super::send::wait_if_requested;
+use crate::opts::starknet::StarkNetOptions;
+use crate::opts::transaction::{FeeToken, TransactionOptions};
+
+use std::time::Duration;
+
+use clap::Parser;
+use eyre::{bail, eyre, Result};
+use inquire::Confirm;
+use starknet::core::types::FieldElement;
+use starknet::core::utils::{get_contract_address, get_selector_from_name};
+use starknet::providers::jsonrpc::models::{
+    BlockId, BlockTag, BroadcastedTransaction, DeployAccountTransactionResult, FeeEstimate,
+    FunctionCall, MaybePendingTransactionReceipt,
+};
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+use starknet::signers::SigningKey;
+
+#[derive(Debug, Parser)]
+pub struct DeployAccountArgs {
+    #[clap(long)]
+    #[clap(value_name = "CLASS_HASH")]
+    #[clap(help = "The class hash of the account contract")]
+    pub class_hash: FieldElement,
+
+    #[clap(long)]
+    #[clap(value_enum)]
+    #[clap(default_value_t = AccountVariant::OpenZeppelin)]
+    #[clap(help = "The kind of account contract the class hash is for")]
+    pub variant: AccountVariant,
+
+    #[clap(long)]
+    #[clap(value_name = "CLASS_HASH")]
+    #[clap(required_if_eq("variant", "braavos"))]
+    #[clap(help = "The class a Braavos account upgrades itself to when it is deployed")]
+    pub implementation: Option<FieldElement>,
+
+    #[clap(long)]
+    #[clap(value_name = "PRIVATE_KEY")]
+    #[clap(help = "The private key that will control the account")]
+    pub private_key: FieldElement,
+
+    #[clap(long)]
+    #[clap(
+        help = "The salt used to compute the account address. Randomly generated if not provided."
+    )]
+    pub salt: Option<FieldElement>,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "STARKNET OPTIONS")]
+    pub starknet: StarkNetOptions,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "TRANSACTION OPTIONS")]
+    pub transaction: TransactionOptions,
+}
+
+/// How to make sure the account can pay for its deployment before sending it.
+#[derive(Debug, Clone, Copy)]
+pub enum Funding {
+    /// Ask the user to confirm that the account has been funded.
+    Confirm,
+    /// Wait for the balance of the account to cover the fee, checking it at the given interval.
+    Wait(Duration),
+}
+
+impl DeployAccountArgs {
+    /// Returns the deploy account transaction result along with its receipt if `--wait` is
+    /// specified. The progress messages are left out when `quiet` is set.
+    pub async fn run(
+        self,
+        quiet: bool,
+    ) -> Result<(
+        DeployAccountTransactionResult,
+        Option<MaybePendingTransactionReceipt>,
+    )> {
+        self.deploy(Funding::Confirm, quiet).await
+    }
+
+    pub async fn deploy(
+        self,
+        funding: Funding,
+        quiet: bool,
+    ) -> Result<(
+        DeployAccountTransactionResult,
+        Option<MaybePendingTransactionReceipt>,
+    )> {
+        let (account, class_hash, salt, constructor_calldata, transaction) = self.build();
+
+        let nonce = transaction.nonce.unwrap_or(FieldElement::ZERO);
+
+        if !quiet {
+            eprintln!("Account address : {:#x}", account.account);
+            eprintln!("Salt : {salt:#x}");
+        }
+
+        let res = if transaction.is_v3() {
+            let fees = account
+                .resolve_v3_fees(&transaction, |fees| {
+                    account.prepare_deploy_account_transaction_v3(
+                        class_hash,
+                        salt,
+                        &constructor_calldata,
+                        nonce,
+                        fees,
+                        true,
+                    )
+                })
+                .await?;
+
+            if !quiet {
+                eprintln!("L1 gas : {}", fees.l1_gas);
+                eprintln!("L1 gas price : {:#x}", fees.l1_gas_price);
+            }
+
+            let fee = u128::from(fees.l1_gas).saturating_mul(fees.l1_gas_price);
+            funding.ensure(&account, FeeToken::Strk, fee, quiet).await?;
+
+            let request = account
+                .prepare_deploy_account_transaction_v3(
+                    class_hash,
+                    salt,
+                    &constructor_calldata,
+                    nonce,
+                    fees,
+                    false,
+                )
+                .await?;
+
+            account.send_deploy_account_transaction_v3(&request).await?
+        } else {
+            let max_fee = match transaction.max_fee {
+                Some(fee) => fee,
+                None => {
+                    let request = account
+                        .prepare_deploy_account_transaction(
+                            class_hash,
+                            salt,
+                            &constructor_calldata,
+                            nonce,
+                            FieldElement::ZERO,
+                        )
+                        .await?;
+
+                    let fee = account
+                        .get_max_fee(&BroadcastedTransaction::DeployAccount(request))
+                        .await?;
+
+                    transaction.max_fee_from_estimate(fee)?
+                }
+            };
+
+            if !quiet {
+                eprintln!("Max fee : {max_fee:#x}");
+            }
+
+            let fee = max_fee.to_string().parse()?;
+            funding.ensure(&account, FeeToken::Eth, fee, quiet).await?;
+
+            let request = account
+                .prepare_deploy_account_transaction(
+                    class_hash,
+                    salt,
+                    &constructor_calldata,
+                    nonce,
+                    max_fee,
+                )
+                .await?;
+
+            account
+                .send_deploy_account_transaction(&request)
+                .await
+                .map_err(|e| eyre!(e))?
+        };
+
+        let receipt =
+            wait_if_requested(account.get_provider()?, res.transaction_hash, &transaction).await?;
+
+        Ok((res, receipt))
+    }
+
+    pub async fn estimate_fee(self) -> Result<FeeEstimate> {
+        let (account, class_hash, salt, constructor_calldata, transaction) = self.build();
+
+        let nonce = transaction.nonce.unwrap_or(FieldElement::ZERO);
+
+        if transaction.is_v3() {
+            let request = account
+                .prepare_deploy_account_transaction_v3(
+                    class_hash,
+                    salt,
+                    &constructor_calldata,
+                    nonce,
+                    V3Fees::from(&transaction),
+                    true,
+                )
+                .await?;
+
+            return account.estimate_fee_v3(&request).await?.try_into();
+        }
+
+        let request = account
+            .prepare_deploy_account_transaction(
+                class_hash,
+                salt,
+                &constructor_calldata,
+                nonce,
+                FieldElement::ZERO,
+            )
+            .await?;
+
+        account
+            .estimate_fee(&BroadcastedTransaction::DeployAccount(request))
+            .await
+            .map_err(|e| eyre!(e))
+    }
+
+    /// Returns the account at its counterfactual address, along with the class hash, salt and
+    /// constructor calldata used to deploy it.
+    fn build(
+        self,
+    ) -> (
+        SimpleAccount,
+        FieldElement,
+        FieldElement,
+        Vec<FieldElement>,
+        TransactionOptions,
+    ) {
+        let DeployAccountArgs {
+            class_hash,
+            variant,
+            implementation,
+            private_key,
+            salt,
+            starknet,
+            transaction,
+        } = self;
+
+        let salt = salt.unwrap_or_else(|| SigningKey::from_random().secret_scalar());
+
+        let public_key = SigningKey::from_secret_scalar(private_key)
+            .verifying_key()
+            .scalar();
+        let constructor_calldata = variant.constructor_calldata(public_key);
+
+        let address =
+            get_contract_address(salt, class_hash, &constructor_calldata, FieldElement::ZERO);
+
+        let mut account = SimpleAccount::new(
+            Some(JsonRpcClient::new(HttpTransport::new(starknet.rpc_url()))),
+            address,
+            private_key,
+            None,
+        );
+        account.rpc_url = Some(starknet.rpc_url());
+        account.variant = variant;
+        account.implementation = implementation;
+
+        (account, class_hash, salt, constructor_calldata, transaction)
+    }
+}
+
+impl Funding {
+    async fn ensure(
+        &self,
+        account: &SimpleAccount,
+        token: FeeToken,
+        fee: u128,
+        quiet: bool,
+    ) -> Result<()> {
+        let interval = match self {
+            Self::Confirm => return confirm_deployment(),
+            Self::Wait(interval) => *interval,
+        };
+
+        let provider = account.get_provider()?;
+        let latest = BlockId::Tag(BlockTag::Latest);
+
+        let mut balance = fee_token_balance(provider, token, account.account, &latest).await?;
+        if balance < fee && !quiet {
+            eprintln!(
+                "Waiting for {:#x} to be funded with at least {fee} {}...",
+                account.account,
+                token.symbol()
+            );
+        }
+
+        while balance < fee {
+            tokio::time::sleep(interval).await;
+            balance = fee_token_balance(provider, token, account.account, &latest).await?;
+        }
+
+        if !quiet {
+            eprintln!("Balance : {balance} {}", token.symbol());
+        }
+        Ok(())
+    }
+}
+
+/// The balance of the owner in a token used to pay fees, capped to `u128::MAX`.
+pub async fn fee_token_balance(
+    provider: &JsonRpcClient<HttpTransport>,
+    token: FeeToken,
+    owner: FieldElement,
+    block_id: &BlockId,
+) -> Result<u128> {
+    let balance = provider
+        .call(
+            &FunctionCall {
+                contract_address: token.address(),
+                entry_point_selector: get_selector_from_name("balanceOf")?,
+                calldata: vec![owner],
+            },
+            block_id,
+        )
+        .await?;
+
+    // the balance is a u256, given as its low and high parts
+    match balance.as_slice() {
+        [low, high] if *high == FieldElement::ZERO => Ok(low.to_string().parse()?),
+        [_, _] => Ok(u128::MAX),
+        _ => bail!("unexpected balance returned by the fee token: {balance:?}"),
+    }
+}
+
+fn confirm_deployment() -> Result<()> {
+    let funded = Confirm::new(
+        "Send the deploy transaction? The account must be funded with at least the max fee.",
+    )
+    .with_default(false)
+    .prompt()?;
+
+    if !funded {
+        bail!("deployment aborted")
+    }
+
+    Ok(())
+}
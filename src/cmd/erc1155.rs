@@ -0,0 +1,148 @@
+use super::parser::{AccountTokenIdParser, AddressParser, BlockIdParser, U256Parser};
+use crate::opts::display::DisplayOptions;
+use crate::opts::starknet::StarkNetOptions;
+use crate::probe::abi::u256_to_dec;
+use crate::probe::addressbook::AddressOrName;
+use crate::probe::utils::fmt::{Pretty, ValueFormat};
+use crate::probe::Probe;
+
+use clap::Subcommand;
+use comfy_table::modifiers::UTF8_SOLID_INNER_BORDERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::Table;
+use eyre::Result;
+use serde::Serialize;
+use starknet::core::types::FieldElement;
+use starknet::providers::jsonrpc::models::BlockId;
+
+#[derive(Debug, Subcommand)]
+pub enum Erc1155Commands {
+    #[clap(
+        about = "Get the balance of an account in an ERC1155 token, or of several with --batch."
+    )]
+    BalanceOf {
+        #[clap(value_name = "CONTRACT")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address of the ERC1155 contract.")]
+        contract: AddressOrName,
+
+        #[clap(value_name = "ACCOUNT")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(required_unless_present = "batch")]
+        #[clap(help = "The address of the account.")]
+        account: Option<AddressOrName>,
+
+        #[clap(value_name = "TOKEN_ID")]
+        #[clap(value_parser(U256Parser))]
+        #[clap(required_unless_present = "batch")]
+        #[clap(help = "The id of the token, in decimal or in hex.")]
+        token_id: Option<(u128, u128)>,
+
+        #[clap(long)]
+        #[clap(value_name = "ACCOUNT:TOKEN_ID")]
+        #[clap(num_args = 1..)]
+        #[clap(value_parser(AccountTokenIdParser))]
+        #[clap(conflicts_with_all = ["account", "token_id"])]
+        #[clap(
+            help = "Get the balances of several accounts in several tokens with a single balanceOfBatch call, e.g. --batch 0x123:1 0x456:0x2"
+        )]
+        batch: Vec<(AddressOrName, (u128, u128))>,
+
+        #[clap(next_line_help = true)]
+        #[clap(short, long = "block")]
+        #[clap(default_value = "latest")]
+        #[clap(value_parser(BlockIdParser))]
+        #[clap(
+            help = "The hash of the requested block, or number (height) of the requested block, or a block tag (e.g. latest, pending)."
+        )]
+        block_id: BlockId,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+}
+
+/// The balances of accounts in ERC1155 tokens, in the order they were queried.
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct Erc1155Balances(Vec<Erc1155Balance>);
+
+#[derive(Debug, Serialize)]
+pub struct Erc1155Balance {
+    account: FieldElement,
+    token_id: String,
+    balance: String,
+}
+
+impl Erc1155Commands {
+    pub async fn run(self, display: &DisplayOptions) -> Result<String> {
+        match self {
+            Self::BalanceOf {
+                contract,
+                account,
+                token_id,
+                batch,
+                block_id,
+                starknet,
+            } => {
+                let probe = Probe::new(starknet.rpc_url());
+                let contract = starknet.resolve_address(&contract).await?;
+
+                if let (Some(account), Some(token_id)) = (account, token_id) {
+                    let account = starknet.resolve_address(&account).await?;
+                    let (low, high) = probe
+                        .get_erc1155_balance(contract, account, token_id, &block_id)
+                        .await?;
+                    return Ok(u256_to_dec(low, high));
+                }
+
+                let (accounts, token_ids): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+                let batch = starknet
+                    .resolve_addresses(&accounts)
+                    .await?
+                    .into_iter()
+                    .zip(token_ids)
+                    .collect::<Vec<_>>();
+
+                let balances = probe
+                    .get_erc1155_balances(contract, &batch, &block_id)
+                    .await?;
+
+                display.display(&Erc1155Balances(
+                    batch
+                        .iter()
+                        .zip(balances)
+                        .map(
+                            |((account, (id_low, id_high)), (low, high))| Erc1155Balance {
+                                account: *account,
+                                token_id: u256_to_dec(*id_low, *id_high),
+                                balance: u256_to_dec(low, high),
+                            },
+                        )
+                        .collect(),
+                ))
+            }
+        }
+    }
+}
+
+impl Pretty for Erc1155Balances {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .set_header(vec!["ACCOUNT", "TOKEN ID", "BALANCE"]);
+
+        for balance in &self.0 {
+            table.add_row(vec![
+                fmt.felt(&balance.account),
+                balance.token_id.clone(),
+                balance.balance.clone(),
+            ]);
+        }
+
+        format!("{table}")
+    }
+}
@@ -0,0 +1,270 @@
+use super::account::simple_account::Account;
+use super::parser::{AddressParser, BlockIdParser};
+use super::send::{build_account, execute, get_nonce, wait_if_requested};
+use crate::opts::account::WalletOptions;
+use crate::opts::display::DisplayOptions;
+use crate::opts::starknet::StarkNetOptions;
+use crate::opts::transaction::TransactionOptions;
+use crate::probe::abi::u256_to_dec;
+use crate::probe::addressbook::AddressOrName;
+use crate::probe::tokens::{parse_units, resolve_token};
+use crate::probe::utils::decode_revert_reason;
+use crate::probe::utils::fmt::{format_u256_units, Pretty, ValueFormat};
+use crate::probe::Probe;
+
+use std::fmt::Write;
+
+use clap::{Parser, Subcommand};
+use comfy_table::modifiers::UTF8_SOLID_INNER_BORDERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::Table;
+use eyre::{eyre, Result};
+use serde::Serialize;
+use starknet::accounts::Call;
+use starknet::core::types::FieldElement;
+use starknet::core::utils::get_selector_from_name;
+use starknet::providers::jsonrpc::models::{BlockId, BlockTag};
+
+// the variants are only built once, when parsing the command line
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Subcommand)]
+pub enum Erc20Commands {
+    #[clap(about = "Transfer an amount of an ERC20 token to an address.")]
+    Transfer {
+        #[clap(value_name = "TOKEN")]
+        #[clap(
+            help = "The address of the token, or the symbol of a well-known one (see `probe tokens ls`)."
+        )]
+        token: String,
+
+        #[clap(value_name = "TO")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address receiving the tokens.")]
+        to: AddressOrName,
+
+        #[clap(flatten)]
+        args: Erc20SendArgs,
+    },
+
+    #[clap(about = "Allow an address to spend an amount of an ERC20 token of the account.")]
+    Approve {
+        #[clap(value_name = "TOKEN")]
+        #[clap(
+            help = "The address of the token, or the symbol of a well-known one (see `probe tokens ls`)."
+        )]
+        token: String,
+
+        #[clap(value_name = "SPENDER")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address allowed to spend the tokens.")]
+        spender: AddressOrName,
+
+        #[clap(flatten)]
+        args: Erc20SendArgs,
+    },
+
+    #[clap(
+        about = "Get the amount of an ERC20 token a spender is allowed to transfer from an owner."
+    )]
+    Allowance {
+        #[clap(value_name = "TOKEN")]
+        #[clap(
+            help = "The address of the token, or the symbol of a well-known one (see `probe tokens ls`)."
+        )]
+        token: String,
+
+        #[clap(value_name = "OWNER")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address owning the tokens.")]
+        owner: AddressOrName,
+
+        #[clap(value_name = "SPENDER")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address allowed to spend the tokens.")]
+        spender: AddressOrName,
+
+        #[clap(next_line_help = true)]
+        #[clap(short, long = "block")]
+        #[clap(default_value = "latest")]
+        #[clap(value_parser(BlockIdParser))]
+        #[clap(
+            help = "The hash of the requested block, or number (height) of the requested block, or a block tag (e.g. latest, pending)."
+        )]
+        block_id: BlockId,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+}
+
+/// The allowance of a spender over the tokens of an owner.
+#[derive(Debug, Serialize)]
+pub struct Allowance {
+    owner: FieldElement,
+    spender: FieldElement,
+    /// In the smallest unit of the token.
+    allowance: String,
+    /// In the units of the token, along with its symbol, unless the token doesn't tell them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amount: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct Erc20SendArgs {
+    #[clap(value_name = "AMOUNT")]
+    #[clap(
+        help = "The amount in the units of the token, e.g. 1.5, or in its smallest unit with --wei."
+    )]
+    amount: String,
+
+    #[clap(long)]
+    #[clap(help = "The amount is given in the smallest unit of the token.")]
+    wei: bool,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "STARKNET OPTIONS")]
+    starknet: StarkNetOptions,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "Wallet OPTIONS")]
+    wallet: WalletOptions,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "TRANSACTION OPTIONS")]
+    transaction: TransactionOptions,
+}
+
+impl Erc20Commands {
+    pub async fn run(self, display: &DisplayOptions) -> Result<String> {
+        match self {
+            Self::Transfer { token, to, args } => args.send(&token, "transfer", to, display).await,
+
+            Self::Approve {
+                token,
+                spender,
+                args,
+            } => args.send(&token, "approve", spender, display).await,
+
+            Self::Allowance {
+                token,
+                owner,
+                spender,
+                block_id,
+                starknet,
+            } => {
+                let chain = starknet.chain_id().await?;
+                let (token, known) = resolve_token(chain, &token)?;
+                let (owner, spender) = (owner.resolve(chain)?, spender.resolve(chain)?);
+
+                let probe = Probe::new(starknet.rpc_url());
+                let (low, high) = probe
+                    .get_erc20_allowance(token, owner, spender, &block_id)
+                    .await?;
+
+                // the units of a token outside of the registry are read from the token, and the
+                // allowance is only given in its smallest unit if it doesn't tell them
+                let units = match known {
+                    Some(known) => Some((known.decimals.into(), known.symbol)),
+                    None => probe.get_erc20_units(token, &block_id).await.ok(),
+                };
+
+                display.display(&Allowance {
+                    owner,
+                    spender,
+                    allowance: u256_to_dec(low, high),
+                    amount: units.map(|(decimals, symbol)| {
+                        format!("{} {symbol}", format_u256_units(low, high, decimals))
+                    }),
+                })
+            }
+        }
+    }
+}
+
+impl Erc20SendArgs {
+    /// Calls `transfer` or `approve` of the token with the amount in its smallest unit, and
+    /// returns the transaction hash along with the receipt if `--wait` is specified.
+    async fn send(
+        self,
+        token: &str,
+        function: &str,
+        recipient: AddressOrName,
+        display: &DisplayOptions,
+    ) -> Result<String> {
+        let Self {
+            amount,
+            wei,
+            starknet,
+            wallet,
+            transaction,
+        } = self;
+
+        let chain = starknet.chain_id().await?;
+        let (token, known) = resolve_token(chain, token)?;
+        let recipient = recipient.resolve(chain)?;
+
+        // the decimals of a token outside of the registry are read from the token itself
+        let decimals = match (wei, known) {
+            (true, _) => 0,
+            (false, Some(known)) => known.decimals.into(),
+            (false, None) => {
+                let (decimals, _) = Probe::new(starknet.rpc_url())
+                    .get_erc20_units(token, &BlockId::Tag(BlockTag::Pending))
+                    .await?;
+                decimals
+            }
+        };
+        let (low, high) = parse_units(&amount, decimals)?;
+
+        let call = Call {
+            to: token,
+            selector: get_selector_from_name(function)?,
+            calldata: vec![
+                recipient,
+                FieldElement::from_dec_str(&low.to_string())?,
+                FieldElement::from_dec_str(&high.to_string())?,
+            ],
+        };
+
+        let account = build_account(&wallet, starknet, false).await?;
+        let nonce = get_nonce(&account, &transaction).await?;
+        let res = execute(&account, &[call], nonce, &transaction)
+            .await
+            .map_err(|e| eyre!(decode_revert_reason(&e.to_string())))?;
+        let receipt =
+            wait_if_requested(account.get_provider()?, res.transaction_hash, &transaction).await?;
+
+        if display.quiet {
+            return Ok(format!("{:#x}", res.transaction_hash));
+        }
+
+        let mut out = format!("Transaction hash : {:#x}", res.transaction_hash);
+        if let Some(receipt) = receipt {
+            write!(
+                out,
+                "\n\n{}",
+                display.colorize(receipt.prettify(&display.value_format()))
+            )?;
+        }
+        Ok(out)
+    }
+}
+
+impl Pretty for Allowance {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .add_row(vec!["OWNER".to_string(), fmt.felt(&self.owner)])
+            .add_row(vec!["SPENDER".to_string(), fmt.felt(&self.spender)])
+            .add_row(vec!["ALLOWANCE".to_string(), self.allowance.clone()]);
+
+        if let Some(amount) = &self.amount {
+            table.add_row(vec!["AMOUNT".to_string(), amount.clone()]);
+        }
+
+        format!("{table}")
+    }
+}
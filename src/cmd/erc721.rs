@@ -0,0 +1,135 @@
+use super::parser::{AddressParser, BlockIdParser, U256Parser};
+use crate::opts::starknet::StarkNetOptions;
+use crate::probe::abi::u256_to_dec;
+use crate::probe::addressbook::AddressOrName;
+use crate::probe::Probe;
+
+use clap::Subcommand;
+use eyre::Result;
+use starknet::providers::jsonrpc::models::BlockId;
+
+#[derive(Debug, Subcommand)]
+pub enum Erc721Commands {
+    #[clap(about = "Get the owner of an ERC721 token.")]
+    OwnerOf {
+        #[clap(value_name = "COLLECTION")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address of the ERC721 contract.")]
+        collection: AddressOrName,
+
+        #[clap(value_name = "TOKEN_ID")]
+        #[clap(value_parser(U256Parser))]
+        #[clap(help = "The id of the token, in decimal or in hex.")]
+        token_id: (u128, u128),
+
+        #[clap(next_line_help = true)]
+        #[clap(short, long = "block")]
+        #[clap(default_value = "latest")]
+        #[clap(value_parser(BlockIdParser))]
+        #[clap(
+            help = "The hash of the requested block, or number (height) of the requested block, or a block tag (e.g. latest, pending)."
+        )]
+        block_id: BlockId,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(about = "Get the URI of the metadata of an ERC721 token.")]
+    TokenUri {
+        #[clap(value_name = "COLLECTION")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address of the ERC721 contract.")]
+        collection: AddressOrName,
+
+        #[clap(value_name = "TOKEN_ID")]
+        #[clap(value_parser(U256Parser))]
+        #[clap(help = "The id of the token, in decimal or in hex.")]
+        token_id: (u128, u128),
+
+        #[clap(next_line_help = true)]
+        #[clap(short, long = "block")]
+        #[clap(default_value = "latest")]
+        #[clap(value_parser(BlockIdParser))]
+        #[clap(
+            help = "The hash of the requested block, or number (height) of the requested block, or a block tag (e.g. latest, pending)."
+        )]
+        block_id: BlockId,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(about = "Get the number of tokens of an ERC721 collection owned by an address.")]
+    BalanceOf {
+        #[clap(value_name = "COLLECTION")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address of the ERC721 contract.")]
+        collection: AddressOrName,
+
+        #[clap(value_name = "OWNER")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address owning the tokens.")]
+        owner: AddressOrName,
+
+        #[clap(next_line_help = true)]
+        #[clap(short, long = "block")]
+        #[clap(default_value = "latest")]
+        #[clap(value_parser(BlockIdParser))]
+        #[clap(
+            help = "The hash of the requested block, or number (height) of the requested block, or a block tag (e.g. latest, pending)."
+        )]
+        block_id: BlockId,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+}
+
+impl Erc721Commands {
+    pub async fn run(self) -> Result<String> {
+        match self {
+            Self::OwnerOf {
+                collection,
+                token_id,
+                block_id,
+                starknet,
+            } => {
+                let collection = starknet.resolve_address(&collection).await?;
+                let owner = Probe::new(starknet.rpc_url())
+                    .get_erc721_owner(collection, token_id, &block_id)
+                    .await?;
+                Ok(format!("{owner:#x}"))
+            }
+
+            Self::TokenUri {
+                collection,
+                token_id,
+                block_id,
+                starknet,
+            } => {
+                let collection = starknet.resolve_address(&collection).await?;
+                Probe::new(starknet.rpc_url())
+                    .get_erc721_token_uri(collection, token_id, &block_id)
+                    .await
+            }
+
+            Self::BalanceOf {
+                collection,
+                owner,
+                block_id,
+                starknet,
+            } => {
+                let addresses = starknet.resolve_addresses(&[collection, owner]).await?;
+                let (collection, owner) = (addresses[0], addresses[1]);
+                let (low, high) = Probe::new(starknet.rpc_url())
+                    .get_erc721_balance(collection, owner, &block_id)
+                    .await?;
+                Ok(u256_to_dec(low, high))
+            }
+        }
+    }
+}
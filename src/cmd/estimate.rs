@@ -0,0 +1,122 @@
+use super::deploy_account::DeployAccountArgs;
+use super::send::{DeclareArgs, DeployArgs, InvokeArgs};
+
+use clap::{Arg, Subcommand};
+use eyre::{bail, Result};
+use starknet::providers::jsonrpc::models::FeeEstimate;
+
+#[derive(Subcommand, Debug)]
+pub enum EstimateCommands {
+    #[clap(about = "Estimate the fee of an invoke transaction.")]
+    #[clap(mut_arg("wait", hidden))]
+    #[clap(mut_arg("confirmations", hidden))]
+    #[clap(mut_arg("persist_nonce", hidden))]
+    #[clap(mut_arg("reset_nonce", hidden))]
+    #[clap(mut_arg("sign_only", hidden))]
+    #[clap(mut_arg("out", hidden))]
+    #[clap(mut_arg("dry_run", hidden))]
+    Invoke(InvokeArgs),
+
+    #[clap(about = "Estimate the fee of a declare transaction.")]
+    #[clap(mut_arg("wait", hidden))]
+    #[clap(mut_arg("confirmations", hidden))]
+    #[clap(mut_arg("persist_nonce", hidden))]
+    #[clap(mut_arg("reset_nonce", hidden))]
+    #[clap(mut_arg("sign_only", hidden))]
+    #[clap(mut_arg("out", hidden))]
+    Declare(DeclareArgs),
+
+    #[clap(
+        about = "Estimate the fee of deploying a contract through the Universal Deployer Contract."
+    )]
+    #[clap(mut_arg("wait", hidden))]
+    #[clap(mut_arg("confirmations", hidden))]
+    #[clap(mut_arg("persist_nonce", hidden))]
+    #[clap(mut_arg("reset_nonce", hidden))]
+    Deploy(DeployArgs),
+
+    #[clap(about = "Estimate the fee of deploying an account contract.")]
+    #[clap(mut_arg("wait", hidden))]
+    #[clap(mut_arg("confirmations", hidden))]
+    #[clap(mut_arg("persist_nonce", hidden))]
+    #[clap(mut_arg("reset_nonce", hidden))]
+    DeployAccount(DeployAccountArgs),
+}
+
+impl EstimateCommands {
+    pub async fn run(self) -> Result<FeeEstimate> {
+        if let Some(flag) = self.sending_flag() {
+            bail!("--{flag} can't be used when estimating the fee of a transaction");
+        }
+
+        match self {
+            Self::Invoke(args) => args.estimate_fee().await,
+            Self::Declare(args) => args.estimate_fee().await,
+            Self::Deploy(args) => args.estimate_fee().await,
+            Self::DeployAccount(args) => args.estimate_fee().await,
+        }
+    }
+
+    /// The first flag given about sending the transaction, which is only estimated here. The
+    /// options that require one of these flags can't be given without it.
+    fn sending_flag(&self) -> Option<&'static str> {
+        let (transaction, sign_only, dry_run) = match self {
+            Self::Invoke(args) => (&args.transaction, args.sign_only, args.dry_run),
+            Self::Declare(args) => (&args.transaction, args.sign_only, false),
+            Self::Deploy(args) => (&args.transaction, false, false),
+            Self::DeployAccount(args) => (&args.transaction, false, false),
+        };
+
+        [
+            ("wait", transaction.wait),
+            ("persist-nonce", transaction.persist_nonce),
+            ("sign-only", sign_only),
+            ("dry-run", dry_run),
+        ]
+        .into_iter()
+        .find(|(_, given)| *given)
+        .map(|(flag, _)| flag)
+    }
+}
+
+/// Hides the flags about sending the transaction, and the options that go with them, from the
+/// help of the estimate commands.
+fn hidden(arg: Arg) -> Arg {
+    arg.hide(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::probe::{App, Commands};
+    use clap::Parser;
+
+    #[test]
+    fn reject_sending_flags() {
+        let estimate = [
+            "probe",
+            "estimate",
+            "invoke",
+            "--to",
+            "0x1",
+            "--function",
+            "f",
+        ];
+
+        for (flags, expected) in [
+            (vec![], None),
+            (vec!["--wait", "--confirmations", "2"], Some("wait")),
+            (vec!["--persist-nonce"], Some("persist-nonce")),
+            (vec!["--dry-run"], Some("dry-run")),
+            (
+                vec!["--sign-only", "--nonce", "0x1", "--max-fee", "0x1"],
+                Some("sign-only"),
+            ),
+        ] {
+            let args = App::parse_from(estimate.iter().chain(&flags));
+            match args.command {
+                Commands::Estimate { commands } => assert_eq!(commands.sending_flag(), expected),
+                _ => unreachable!(),
+            }
+        }
+    }
+}
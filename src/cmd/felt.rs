@@ -0,0 +1,72 @@
+use crate::probe::utils::felt_pow;
+use crate::probe::utils::fmt::ValueFormat;
+
+use clap::Subcommand;
+use eyre::{eyre, Result};
+use starknet::core::types::FieldElement;
+
+#[derive(Debug, Subcommand)]
+pub enum FeltCommands {
+    #[clap(about = "Add two felts modulo the Stark prime.")]
+    Add {
+        #[clap(value_name = "X")]
+        x: FieldElement,
+        #[clap(value_name = "Y")]
+        y: FieldElement,
+    },
+
+    #[clap(about = "Subtract a felt from another modulo the Stark prime.")]
+    Sub {
+        #[clap(value_name = "X")]
+        x: FieldElement,
+        #[clap(value_name = "Y")]
+        y: FieldElement,
+    },
+
+    #[clap(about = "Multiply two felts modulo the Stark prime.")]
+    Mul {
+        #[clap(value_name = "X")]
+        x: FieldElement,
+        #[clap(value_name = "Y")]
+        y: FieldElement,
+    },
+
+    #[clap(
+        about = "Divide a felt by another in the field, i.e. multiply it by the inverse of the divisor modulo the Stark prime."
+    )]
+    Div {
+        #[clap(value_name = "X")]
+        x: FieldElement,
+        #[clap(value_name = "Y")]
+        y: FieldElement,
+    },
+
+    #[clap(about = "Compute the multiplicative inverse of a felt modulo the Stark prime.")]
+    Inv {
+        #[clap(value_name = "X")]
+        x: FieldElement,
+    },
+
+    #[clap(about = "Raise a felt to a power modulo the Stark prime.")]
+    Pow {
+        #[clap(value_name = "X")]
+        x: FieldElement,
+        #[clap(value_name = "EXPONENT")]
+        exponent: FieldElement,
+    },
+}
+
+impl FeltCommands {
+    pub fn run(self, fmt: &ValueFormat) -> Result<String> {
+        let felt = match self {
+            Self::Add { x, y } => x + y,
+            Self::Sub { x, y } => x - y,
+            Self::Mul { x, y } => x * y,
+            Self::Div { x, y } => x * y.invert().ok_or_else(|| eyre!("division by zero"))?,
+            Self::Inv { x } => x.invert().ok_or_else(|| eyre!("zero has no inverse"))?,
+            Self::Pow { x, exponent } => felt_pow(x, exponent),
+        };
+
+        Ok(fmt.felt(&felt))
+    }
+}
@@ -0,0 +1,235 @@
+use crate::opts::display::DisplayOptions;
+use crate::opts::ethereum::EthereumOptions;
+use crate::opts::starknet::StarkNetOptions;
+use crate::probe::abi::u256_to_dec;
+use crate::probe::ethereum::CoreContract;
+use crate::probe::utils::fmt::{Pretty, ValueFormat};
+use crate::probe::utils::send_rpc_request;
+
+use clap::{Subcommand, ValueEnum};
+use comfy_table::modifiers::UTF8_SOLID_INNER_BORDERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::Table;
+use eyre::Result;
+use serde::Serialize;
+use serde_json::{json, Value};
+use starknet::core::types::FieldElement;
+
+use super::parser::Bytes32Parser;
+
+// the variants are only built once, when parsing the command line
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Subcommand)]
+pub enum L1Commands {
+    #[clap(about = "Get the last StarkNet block whose state is settled on Ethereum.")]
+    State {
+        #[clap(long)]
+        #[clap(
+            help = "Compare the state root settled on Ethereum with the one the StarkNet node has for the same block."
+        )]
+        check: bool,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "ETHEREUM OPTIONS")]
+        ethereum: EthereumOptions,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(about = "Get whether a message between L1 and L2 is waiting to be consumed.")]
+    Message {
+        #[clap(value_name = "MESSAGE_HASH")]
+        #[clap(value_parser(Bytes32Parser))]
+        #[clap(help = "The hash of the message, as computed by message-hash.")]
+        hash: [u8; 32],
+
+        #[clap(long)]
+        #[clap(value_enum)]
+        #[clap(help = "The direction the message was sent in.")]
+        direction: MessageDirection,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "ETHEREUM OPTIONS")]
+        ethereum: EthereumOptions,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MessageDirection {
+    L1ToL2,
+    L2ToL1,
+}
+
+/// The StarkNet state settled on Ethereum.
+#[derive(Debug, Serialize)]
+pub struct SettledState {
+    block_number: i64,
+    state_root: FieldElement,
+    /// The state root of the same block according to the StarkNet node, with `--check`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    l2_state_root: Option<FieldElement>,
+}
+
+/// Where a message stands on the core contract.
+#[derive(Debug, Serialize)]
+pub struct MessageState {
+    direction: &'static str,
+    /// `PENDING` while a message sent from L1 waits to be consumed on L2, or `READY` while a
+    /// message sent from L2 can be consumed on L1. `NOT_PENDING` and `NOT_READY` otherwise.
+    status: &'static str,
+    /// The fee paid for a pending message sent from L1, in wei.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fee: Option<String>,
+    /// The number of copies of a message sent from L2 that can be consumed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    count: Option<String>,
+}
+
+impl L1Commands {
+    pub async fn run(self, display: &DisplayOptions) -> Result<String> {
+        match self {
+            Self::State {
+                check,
+                ethereum,
+                starknet,
+            } => {
+                let core = CoreContract::new(ethereum.eth_rpc_url, ethereum.core_contract).await?;
+
+                let block_number = core.state_block_number().await?;
+                let state_root = core.state_root().await?;
+
+                let l2_state_root = if check && block_number >= 0 {
+                    let update: Value = send_rpc_request(
+                        &starknet.rpc_url(),
+                        "starknet_getStateUpdate",
+                        json!({ "block_id": { "block_number": block_number } }),
+                    )
+                    .await?;
+                    Some(serde_json::from_value(update["new_root"].clone())?)
+                } else {
+                    None
+                };
+
+                display.display(&SettledState {
+                    block_number,
+                    state_root,
+                    l2_state_root,
+                })
+            }
+
+            Self::Message {
+                hash,
+                direction,
+                ethereum,
+            } => {
+                let core = CoreContract::new(ethereum.eth_rpc_url, ethereum.core_contract).await?;
+
+                let state = match direction {
+                    MessageDirection::L1ToL2 => {
+                        // the fee plus one while pending, so that a free message isn't zero
+                        let fee_plus_one = core.l1_to_l2_message(&hash).await?;
+                        let fee = match u256_halves(&fee_plus_one) {
+                            (0, 0) => None,
+                            (high, 0) => Some(u256_to_dec(u128::MAX, high - 1)),
+                            (high, low) => Some(u256_to_dec(low - 1, high)),
+                        };
+
+                        MessageState {
+                            direction: "L1_TO_L2",
+                            status: if fee.is_some() {
+                                "PENDING"
+                            } else {
+                                "NOT_PENDING"
+                            },
+                            fee,
+                            count: None,
+                        }
+                    }
+
+                    MessageDirection::L2ToL1 => {
+                        let count = core.l2_to_l1_message(&hash).await?;
+                        let (high, low) = u256_halves(&count);
+
+                        MessageState {
+                            direction: "L2_TO_L1",
+                            status: if (high, low) == (0, 0) {
+                                "NOT_READY"
+                            } else {
+                                "READY"
+                            },
+                            fee: None,
+                            count: Some(u256_to_dec(low, high)),
+                        }
+                    }
+                };
+
+                display.display(&state)
+            }
+        }
+    }
+}
+
+/// The high and low 128 bits of a big-endian 256 bits word.
+fn u256_halves(word: &[u8; 32]) -> (u128, u128) {
+    (
+        u128::from_be_bytes(word[..16].try_into().unwrap()),
+        u128::from_be_bytes(word[16..].try_into().unwrap()),
+    )
+}
+
+impl Pretty for SettledState {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .add_row(vec![
+                "BLOCK NUMBER".to_string(),
+                self.block_number.to_string(),
+            ])
+            .add_row(vec!["STATE ROOT".to_string(), fmt.felt(&self.state_root)]);
+
+        if let Some(root) = &self.l2_state_root {
+            let verdict = if *root == self.state_root {
+                "matches the root settled on L1"
+            } else {
+                "DIFFERS from the root settled on L1"
+            };
+            table.add_row(vec![
+                "L2 STATE ROOT".to_string(),
+                format!("{}\n{verdict}", fmt.felt(root)),
+            ]);
+        }
+
+        format!("{table}")
+    }
+}
+
+impl Pretty for MessageState {
+    fn prettify(&self, _: &ValueFormat) -> String {
+        let note = match self.status {
+            "PENDING" => "sent from L1, waiting to be consumed on L2",
+            "NOT_PENDING" => "consumed on L2, or never sent from L1",
+            "READY" => "settled on L1, waiting to be consumed there",
+            _ => "not settled on L1 yet, or already consumed",
+        };
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .add_row(vec!["DIRECTION", self.direction])
+            .add_row(vec!["STATUS", &format!("{}\n{note}", self.status)]);
+
+        if let Some(fee) = &self.fee {
+            table.add_row(vec!["FEE (WEI)", fee]);
+        }
+        if let Some(count) = &self.count {
+            table.add_row(vec!["COUNT", count]);
+        }
+
+        format!("{table}")
+    }
+}
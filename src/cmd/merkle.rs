@@ -0,0 +1,71 @@
+use crate::opts::display::DisplayOptions;
+use crate::probe::merkle::{MerkleHash, MerkleOutput, MerkleProof, MerkleTree};
+
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::{fs, io};
+
+use clap::Parser;
+use eyre::{eyre, Result};
+use starknet::core::types::FieldElement;
+
+#[derive(Debug, Parser)]
+pub struct MerkleArgs {
+    #[clap(value_name = "PATH")]
+    #[clap(
+        help = "Path to the leaves of the tree, felts separated by whitespace, commas or new lines. Read from stdin if not provided."
+    )]
+    path: Option<PathBuf>,
+
+    #[clap(long)]
+    #[clap(value_enum)]
+    #[clap(default_value_t = MerkleHash::Pedersen)]
+    #[clap(help = "The hash of the pairs of nodes, which are sorted before being hashed.")]
+    hash: MerkleHash,
+
+    #[clap(long)]
+    #[clap(value_name = "LEAF")]
+    #[clap(num_args = 1..)]
+    #[clap(help = "Print the inclusion proofs of these leaves along with the root.")]
+    proof: Vec<FieldElement>,
+}
+
+impl MerkleArgs {
+    pub fn run(self, display: &DisplayOptions) -> Result<String> {
+        let input = match self.path {
+            Some(path) => fs::read_to_string(path)?,
+            None => io::read_to_string(io::stdin())?,
+        };
+
+        let leaves = input
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|leaf| !leaf.is_empty())
+            .map(|leaf| FieldElement::from_str(leaf).map_err(|_| eyre!("invalid leaf `{leaf}`")))
+            .collect::<Result<Vec<_>>>()?;
+
+        let tree = MerkleTree::new(leaves, self.hash)?;
+
+        let proofs = self
+            .proof
+            .into_iter()
+            .map(|leaf| {
+                let index = tree
+                    .leaves()
+                    .iter()
+                    .position(|l| *l == leaf)
+                    .ok_or_else(|| eyre!("{leaf:#x} is not a leaf of the tree"))?;
+
+                Ok(MerkleProof {
+                    leaf,
+                    index,
+                    proof: tree.proof(index),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        display.display(&MerkleOutput {
+            root: tree.root(),
+            proofs,
+        })
+    }
+}
@@ -0,0 +1,262 @@
+use crate::opts::starknet::StarkNetOptions;
+use crate::probe::abi::{felt_to_u128, u256_to_dec};
+use crate::probe::utils::fmt::{Pretty, ValueFormat};
+use crate::probe::utils::{send_rpc_request, RpcErrorResponse};
+
+use clap::{ArgGroup, Parser};
+use comfy_table::modifiers::UTF8_SOLID_INNER_BORDERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::Table;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use starknet::core::types::FieldElement;
+use starknet::providers::jsonrpc::models::BlockId;
+
+use super::parser::BlockIdParser;
+
+/// The error code of a JSON-RPC method the node doesn't implement.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// The first element of the payload of a StarkGate withdrawal.
+const TRANSFER_FROM_STARKNET: FieldElement = FieldElement::ZERO;
+
+#[derive(Debug, Parser)]
+#[clap(group(ArgGroup::new("source").args(["block", "tx"]).required(true)))]
+pub struct MessagesArgs {
+    #[clap(next_line_help = true)]
+    #[clap(short, long)]
+    #[clap(value_name = "BLOCK_ID")]
+    #[clap(value_parser(BlockIdParser))]
+    #[clap(
+        help = "The hash of the block whose messages are listed, or its number (height), or a block tag (e.g. latest, pending)."
+    )]
+    pub block: Option<BlockId>,
+
+    #[clap(long)]
+    #[clap(value_name = "TX_HASH")]
+    #[clap(help = "The hash of the transaction whose messages are listed.")]
+    pub tx: Option<FieldElement>,
+
+    #[clap(long)]
+    #[clap(help = "Decode the payloads of the known bridge formats, e.g. StarkGate withdrawals.")]
+    pub decode: bool,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "STARKNET OPTIONS")]
+    pub starknet: StarkNetOptions,
+}
+
+/// The messages sent to L1 by a block or a transaction, in the order they were sent.
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct SentMessages(Vec<SentMessage>);
+
+#[derive(Debug, Serialize)]
+pub struct SentMessage {
+    transaction_hash: FieldElement,
+    #[serde(flatten)]
+    message: MsgToL1,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decoded: Option<Withdrawal>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct MsgToL1 {
+    from_address: FieldElement,
+    to_address: FieldElement,
+    payload: Vec<FieldElement>,
+}
+
+/// A token withdrawal through a StarkGate bridge, to be claimed on L1.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct Withdrawal {
+    l1_recipient: FieldElement,
+    /// The L1 token, only given by the bridges handling several tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    l1_token: Option<FieldElement>,
+    amount: String,
+}
+
+impl MessagesArgs {
+    pub async fn run(self) -> Result<SentMessages> {
+        let url = self.starknet.rpc_url();
+
+        let receipts: Vec<Value> = match (self.tx, self.block) {
+            (Some(hash), _) => vec![
+                send_rpc_request(
+                    &url,
+                    "starknet_getTransactionReceipt",
+                    json!({ "transaction_hash": hash }),
+                )
+                .await?,
+            ],
+            (None, Some(block_id)) => block_receipts(&url, &block_id).await?,
+            (None, None) => unreachable!("clap requires either --block or --tx"),
+        };
+
+        let mut messages = Vec::new();
+        for receipt in receipts {
+            let transaction_hash = serde_json::from_value(receipt["transaction_hash"].clone())?;
+            let sent: Vec<MsgToL1> =
+                serde_json::from_value(receipt.get("messages_sent").cloned().unwrap_or_default())
+                    .unwrap_or_default();
+
+            messages.extend(sent.into_iter().map(|message| {
+                SentMessage {
+                    transaction_hash,
+                    decoded: self
+                        .decode
+                        .then(|| decode_withdrawal(&message.payload))
+                        .flatten(),
+                    message,
+                }
+            }));
+        }
+
+        Ok(SentMessages(messages))
+    }
+}
+
+/// The receipts of the transactions of a block, fetched in one request when the node supports
+/// `starknet_getBlockWithReceipts`, and one by one otherwise.
+async fn block_receipts(url: &reqwest::Url, block_id: &BlockId) -> Result<Vec<Value>> {
+    let res: Result<Value> = send_rpc_request(
+        url,
+        "starknet_getBlockWithReceipts",
+        json!({ "block_id": block_id }),
+    )
+    .await;
+
+    match res {
+        Ok(mut block) => Ok(block["transactions"]
+            .as_array_mut()
+            .map(|txs| txs.iter_mut().map(|tx| tx["receipt"].take()).collect())
+            .unwrap_or_default()),
+
+        Err(e)
+            if e.downcast_ref::<RpcErrorResponse>()
+                .is_some_and(|e| e.code == METHOD_NOT_FOUND) =>
+        {
+            let block: Value = send_rpc_request(
+                url,
+                "starknet_getBlockWithTxHashes",
+                json!({ "block_id": block_id }),
+            )
+            .await?;
+
+            let mut receipts = Vec::new();
+            for hash in block["transactions"].as_array().into_iter().flatten() {
+                receipts.push(
+                    send_rpc_request(
+                        url,
+                        "starknet_getTransactionReceipt",
+                        json!({ "transaction_hash": hash }),
+                    )
+                    .await?,
+                );
+            }
+            Ok(receipts)
+        }
+
+        Err(e) => Err(e),
+    }
+}
+
+/// Reads the payload of a StarkGate withdrawal: `[TRANSFER_FROM_STARKNET, l1_recipient,
+/// amount_low, amount_high]` for the legacy bridges, with the L1 token before the amount for the
+/// multi-token bridge.
+fn decode_withdrawal(payload: &[FieldElement]) -> Option<Withdrawal> {
+    let (l1_recipient, l1_token, low, high) = match payload {
+        [kind, ..] if *kind != TRANSFER_FROM_STARKNET => return None,
+        [_, recipient, low, high] => (*recipient, None, low, high),
+        [_, recipient, token, low, high] => (*recipient, Some(*token), low, high),
+        _ => return None,
+    };
+
+    Some(Withdrawal {
+        l1_recipient,
+        l1_token,
+        amount: u256_to_dec(felt_to_u128(*low).ok()?, felt_to_u128(*high).ok()?),
+    })
+}
+
+impl Pretty for SentMessages {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .set_header(vec!["TRANSACTION", "FROM", "TO", "PAYLOAD"]);
+
+        for sent in &self.0 {
+            let payload = match &sent.decoded {
+                Some(withdrawal) => withdrawal.prettify(fmt),
+                None => sent.message.payload.prettify(fmt),
+            };
+
+            table.add_row(vec![
+                fmt.felt(&sent.transaction_hash),
+                fmt.felt(&sent.message.from_address),
+                fmt.felt(&sent.message.to_address),
+                payload,
+            ]);
+        }
+
+        format!("{table}")
+    }
+}
+
+impl Pretty for Withdrawal {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
+        let mut lines = vec![
+            "WITHDRAWAL".to_string(),
+            format!("recipient : {}", fmt.felt(&self.l1_recipient)),
+        ];
+        if let Some(token) = &self.l1_token {
+            lines.push(format!("token     : {}", fmt.felt(token)));
+        }
+        lines.push(format!("amount    : {}", self.amount));
+
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_starkgate_withdrawals() {
+        let felt = FieldElement::from;
+
+        assert_eq!(
+            decode_withdrawal(&[felt(0u64), felt(0xabu64), felt(1000u64), felt(0u64)]),
+            Some(Withdrawal {
+                l1_recipient: felt(0xabu64),
+                l1_token: None,
+                amount: "1000".to_string(),
+            })
+        );
+        assert_eq!(
+            decode_withdrawal(&[
+                felt(0u64),
+                felt(0xabu64),
+                felt(0xcdu64),
+                felt(5u64),
+                felt(0u64)
+            ]),
+            Some(Withdrawal {
+                l1_recipient: felt(0xabu64),
+                l1_token: Some(felt(0xcdu64)),
+                amount: "5".to_string(),
+            })
+        );
+
+        // not a transfer from starknet
+        assert_eq!(
+            decode_withdrawal(&[felt(1u64), felt(0xabu64), felt(5u64), felt(0u64)]),
+            None
+        );
+    }
+}
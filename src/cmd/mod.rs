@@ -1,6 +1,28 @@
 pub mod account;
+pub mod addressbook;
+pub mod bump;
+pub mod config;
+pub mod deploy_account;
+pub mod erc1155;
+pub mod erc20;
+pub mod erc721;
+pub mod estimate;
+pub mod felt;
+pub mod l1;
+pub mod merkle;
+pub mod messages;
+pub mod multisig;
+pub mod profile;
+pub mod replay;
 pub mod rpc;
 pub mod send;
+pub mod send_batch;
+pub mod simulate;
+pub mod tokens;
+pub mod tx_cost;
+pub mod tx_pending;
+pub mod u256;
+pub mod watch;
 
 pub mod parser;
 
@@ -0,0 +1,244 @@
+use super::send::write_transaction;
+use crate::opts::account::utils::read_json_file;
+use crate::opts::account::WalletOptions;
+use crate::opts::starknet::StarkNetOptions;
+use crate::probe::SimpleProbe;
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand};
+use eyre::{bail, eyre, Result};
+use serde::{Deserialize, Serialize};
+use starknet::core::crypto::{ecdsa_verify, Signature};
+use starknet::core::types::FieldElement;
+use starknet::providers::jsonrpc::models::{
+    BroadcastedInvokeTransaction, BroadcastedInvokeTransactionV1, BroadcastedTransaction,
+};
+use starknet::signers::Signer;
+
+#[derive(Debug, Subcommand)]
+pub enum TxCommands {
+    #[clap(about = "Sign a transaction as one of the signers of a multisig account.")]
+    SignHash(SignHashArgs),
+
+    #[clap(about = "Merge the signatures of the signers into the multisig transaction.")]
+    Aggregate(AggregateArgs),
+}
+
+#[derive(Debug, Parser)]
+pub struct SignHashArgs {
+    #[clap(value_name = "PATH")]
+    #[clap(help = "Path to the transaction JSON, e.g. one produced with --sign-only.")]
+    pub path: PathBuf,
+
+    #[clap(long = "out")]
+    #[clap(value_name = "PATH")]
+    #[clap(help = "Write the signature to the given file instead of printing it.")]
+    pub out: Option<PathBuf>,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "STARKNET OPTIONS")]
+    pub starknet: StarkNetOptions,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "WALLET OPTIONS")]
+    pub wallet: WalletOptions,
+}
+
+#[derive(Debug, Parser)]
+pub struct AggregateArgs {
+    #[clap(value_name = "PATH")]
+    #[clap(help = "Path to the transaction JSON the signatures were made for.")]
+    pub path: PathBuf,
+
+    #[clap(long = "signature")]
+    #[clap(required = true)]
+    #[clap(value_name = "PATH")]
+    #[clap(help = "A signature produced with `tx sign-hash`. Can be repeated, once per signer.")]
+    pub signatures: Vec<PathBuf>,
+
+    #[clap(long = "out")]
+    #[clap(value_name = "PATH")]
+    #[clap(help = "Write the signed transaction to the given file instead of printing it.")]
+    pub out: Option<PathBuf>,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "STARKNET OPTIONS")]
+    pub starknet: StarkNetOptions,
+}
+
+/// The signature of a transaction hash by one of the signers of a multisig account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureShare {
+    pub transaction_hash: FieldElement,
+    pub signer: FieldElement,
+    pub r: FieldElement,
+    pub s: FieldElement,
+}
+
+impl TxCommands {
+    pub async fn run(self, out: &mut dyn Write) -> Result<()> {
+        match self {
+            Self::SignHash(args) => args.run(out).await,
+            Self::Aggregate(args) => args.run(out).await,
+        }
+    }
+}
+
+impl SignHashArgs {
+    async fn run(self, out: &mut dyn Write) -> Result<()> {
+        let transaction = read_invoke_transaction(&self.path)?;
+        let hash = transaction_hash(&transaction, &self.starknet).await?;
+
+        // the keystore of the signer is looked up by the address of the multisig account
+        let mut wallet = self.wallet;
+        wallet.account = Some(transaction.sender_address.into());
+        let Some(account) = wallet.build_wallet()? else {
+            bail!("missing wallet")
+        };
+
+        let signature = account.sign_hash(&hash).await?;
+        let share = SignatureShare {
+            transaction_hash: hash,
+            signer: account.get_public_key().await?.scalar(),
+            r: signature.r,
+            s: signature.s,
+        };
+
+        let json = serde_json::to_string_pretty(&share)?;
+        match self.out {
+            Some(path) => std::fs::write(path, json)?,
+            None => writeln!(out, "{json}")?,
+        }
+
+        Ok(())
+    }
+}
+
+impl AggregateArgs {
+    async fn run(self, out: &mut dyn Write) -> Result<()> {
+        let mut transaction = read_invoke_transaction(&self.path)?;
+        let hash = transaction_hash(&transaction, &self.starknet).await?;
+
+        let shares = self
+            .signatures
+            .iter()
+            .map(read_json_file)
+            .collect::<Result<Vec<SignatureShare>>>()?;
+
+        transaction.signature = aggregate_signatures(hash, shares)?;
+        write_transaction(
+            &BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V1(transaction)),
+            self.out,
+            out,
+        )
+    }
+}
+
+/// Merges the signatures into the signature of a multisig transaction, as one `(signer, r, s)`
+/// triple per signer ordered by the public key of the signer. Every signature must be a valid
+/// signature of the transaction hash.
+pub fn aggregate_signatures(
+    hash: FieldElement,
+    mut shares: Vec<SignatureShare>,
+) -> Result<Vec<FieldElement>> {
+    shares.sort_by_key(|share| share.signer);
+    shares.dedup_by_key(|share| share.signer);
+
+    for share in &shares {
+        if share.transaction_hash != hash {
+            bail!(
+                "the signature of {:#x} is for the transaction {:#x}, not {hash:#x}",
+                share.signer,
+                share.transaction_hash
+            )
+        }
+
+        let signature = Signature {
+            r: share.r,
+            s: share.s,
+        };
+        if !ecdsa_verify(&share.signer, &hash, &signature)? {
+            bail!("invalid signature from {:#x}", share.signer)
+        }
+    }
+
+    Ok(shares
+        .into_iter()
+        .flat_map(|share| [share.signer, share.r, share.s])
+        .collect())
+}
+
+fn read_invoke_transaction(path: &Path) -> Result<BroadcastedInvokeTransactionV1> {
+    match read_json_file(path)? {
+        BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V1(transaction)) => {
+            Ok(transaction)
+        }
+        _ => Err(eyre!(
+            "only v1 invoke transactions can be signed by multisig accounts"
+        )),
+    }
+}
+
+/// The hash of the transaction, for the chain given with `--chain` or else the one of the node.
+async fn transaction_hash(
+    transaction: &BroadcastedInvokeTransactionV1,
+    starknet: &StarkNetOptions,
+) -> Result<FieldElement> {
+    let chain_id = starknet.chain_id().await?;
+
+    Ok(SimpleProbe::compute_invoke_tx_hash(
+        transaction.sender_address,
+        &transaction.calldata,
+        transaction.max_fee,
+        chain_id,
+        transaction.nonce,
+        FieldElement::ONE,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starknet::core::crypto::ecdsa_sign;
+    use starknet::signers::SigningKey;
+
+    #[test]
+    fn aggregate_multisig_signatures() {
+        let hash = FieldElement::from_hex_be("0x1234").unwrap();
+        let share = |private_key: u64| {
+            let private_key = FieldElement::from(private_key);
+            let signature = ecdsa_sign(&private_key, &hash).unwrap();
+            SignatureShare {
+                transaction_hash: hash,
+                signer: SigningKey::from_secret_scalar(private_key)
+                    .verifying_key()
+                    .scalar(),
+                r: signature.r,
+                s: signature.s,
+            }
+        };
+
+        let (first, second) = (share(2), share(3));
+        let signature = aggregate_signatures(hash, vec![first.clone(), second.clone()]).unwrap();
+
+        let (low, high) = if first.signer < second.signer {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        assert_eq!(
+            signature,
+            vec![low.signer, low.r, low.s, high.signer, high.r, high.s]
+        );
+
+        let forged = SignatureShare {
+            r: low.s,
+            s: low.r,
+            ..low.clone()
+        };
+        assert!(aggregate_signatures(hash, vec![forged]).is_err());
+        assert!(aggregate_signatures(FieldElement::ONE, vec![low]).is_err());
+    }
+}
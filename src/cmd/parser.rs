@@ -1,3 +1,8 @@
+use crate::opts::config::Config;
+use crate::opts::starknet::{parse_chain_id, Network};
+use crate::probe::abi::parse_u256;
+use crate::probe::addressbook::AddressOrName;
+use crate::probe::chains::KNOWN_CHAINS;
 use crate::probe::utils::canonicalize_path;
 
 use std::{path::PathBuf, str::FromStr};
@@ -5,10 +10,7 @@ use std::{path::PathBuf, str::FromStr};
 use clap::builder::{PossibleValue, TypedValueParser};
 use clap::error::{Error, ErrorKind};
 use starknet::{
-    core::{
-        chain_id::{MAINNET, TESTNET},
-        types::FieldElement,
-    },
+    core::types::FieldElement,
     providers::jsonrpc::models::{BlockId, BlockTag},
 };
 
@@ -66,22 +68,15 @@ impl TypedValueParser for ChainParser {
             .to_str()
             .ok_or_else(|| Error::raw(ErrorKind::InvalidUtf8, "invalid utf-8"))?;
 
-        if value.parse::<u128>().is_ok() {
-            FieldElement::from_str(value)
-                .map_err(|e| Error::raw(ErrorKind::InvalidValue, e.to_string()))
-        } else {
-            match value {
-                "mainnet" => Ok(MAINNET),
-                "goerli" => Ok(TESTNET),
-                _ => Err(Error::raw(ErrorKind::InvalidValue, "invalid chain id")),
-            }
-        }
+        parse_chain_id(value).map_err(|e| Error::raw(ErrorKind::InvalidValue, format!("{e}\n")))
     }
 
     fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
-        let possible_values: Vec<PossibleValue> =
-            vec![PossibleValue::new("mainnet"), PossibleValue::new("goerli")];
-        Some(Box::new(possible_values.into_iter()))
+        Some(Box::new(
+            KNOWN_CHAINS
+                .iter()
+                .map(|(alias, _)| PossibleValue::new(alias)),
+        ))
     }
 }
 
@@ -106,3 +101,165 @@ impl TypedValueParser for PathParser {
         canonicalize_path(value).map_err(|e| Error::raw(ErrorKind::ValueValidation, e))
     }
 }
+
+/// Parses the name of a network, looking up the ones defined in the config file.
+#[derive(Debug, Clone, Copy)]
+pub struct NetworkParser;
+
+impl TypedValueParser for NetworkParser {
+    type Value = Network;
+
+    #[allow(unused_variables)]
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> Result<Self::Value, Error> {
+        let value = value
+            .to_str()
+            .ok_or_else(|| Error::raw(ErrorKind::InvalidUtf8, "invalid utf-8"))?;
+
+        Config::load()
+            .and_then(|config| Network::resolve(value, &config))
+            .map_err(|e| Error::raw(ErrorKind::InvalidValue, format!("{e}\n")))
+    }
+}
+
+/// Parses a contract address, or a name saved in the address book. The name is resolved once the
+/// chain is known.
+#[derive(Debug, Clone, Copy)]
+pub struct AddressParser;
+
+impl TypedValueParser for AddressParser {
+    type Value = AddressOrName;
+
+    #[allow(unused_variables)]
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> Result<Self::Value, Error> {
+        let value = value
+            .to_str()
+            .ok_or_else(|| Error::raw(ErrorKind::InvalidUtf8, "invalid utf-8"))?;
+
+        AddressOrName::from_str(value)
+            .map_err(|e| Error::raw(ErrorKind::InvalidValue, format!("{e}\n")))
+    }
+}
+
+/// Parses the factor an estimated fee is multiplied by, which can't lower the fee.
+#[derive(Debug, Clone, Copy)]
+pub struct FeeMultiplierParser;
+
+impl TypedValueParser for FeeMultiplierParser {
+    type Value = f64;
+
+    #[allow(unused_variables)]
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> Result<Self::Value, Error> {
+        let value = value
+            .to_str()
+            .ok_or_else(|| Error::raw(ErrorKind::InvalidUtf8, "invalid utf-8"))?;
+
+        match value.parse::<f64>() {
+            Ok(multiplier) if multiplier.is_finite() && multiplier >= 1.0 => Ok(multiplier),
+            _ => Err(Error::raw(
+                ErrorKind::ValueValidation,
+                format!("`{value}` is not a fee multiplier, expected a number of at least 1\n"),
+            )),
+        }
+    }
+}
+
+/// Parses a 32 bytes word in hex, e.g. a keccak256 hash, which may not fit in a felt.
+#[derive(Debug, Clone, Copy)]
+pub struct Bytes32Parser;
+
+impl TypedValueParser for Bytes32Parser {
+    type Value = [u8; 32];
+
+    #[allow(unused_variables)]
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> Result<Self::Value, Error> {
+        let value = value
+            .to_str()
+            .ok_or_else(|| Error::raw(ErrorKind::InvalidUtf8, "invalid utf-8"))?;
+
+        let digits = value.trim_start_matches("0x");
+        if digits.len() > 64 {
+            return Err(Error::raw(
+                ErrorKind::InvalidValue,
+                format!("{value} is longer than 32 bytes\n"),
+            ));
+        }
+
+        let mut word = [0u8; 32];
+        hex::decode_to_slice(format!("{digits:0>64}"), &mut word)
+            .map_err(|e| Error::raw(ErrorKind::InvalidValue, format!("{value}: {e}\n")))?;
+        Ok(word)
+    }
+}
+
+/// Parses a u256, in decimal or in hex, into its low and high 128 bits.
+#[derive(Debug, Clone, Copy)]
+pub struct U256Parser;
+
+impl TypedValueParser for U256Parser {
+    type Value = (u128, u128);
+
+    #[allow(unused_variables)]
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> Result<Self::Value, Error> {
+        let value = value
+            .to_str()
+            .ok_or_else(|| Error::raw(ErrorKind::InvalidUtf8, "invalid utf-8"))?;
+
+        parse_u256(value).map_err(|e| Error::raw(ErrorKind::InvalidValue, format!("{e}\n")))
+    }
+}
+
+/// Parses an `ACCOUNT:TOKEN_ID` pair, where the account may be a name saved in the address book
+/// and the token id a u256 in decimal or in hex.
+#[derive(Debug, Clone, Copy)]
+pub struct AccountTokenIdParser;
+
+impl TypedValueParser for AccountTokenIdParser {
+    type Value = (AddressOrName, (u128, u128));
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> Result<Self::Value, Error> {
+        let value = value
+            .to_str()
+            .ok_or_else(|| Error::raw(ErrorKind::InvalidUtf8, "invalid utf-8"))?;
+
+        let Some((account, token_id)) = value.split_once(':') else {
+            return Err(Error::raw(
+                ErrorKind::InvalidValue,
+                format!("expected ACCOUNT:TOKEN_ID, got {value}\n"),
+            ));
+        };
+
+        let account = AddressParser.parse_ref(cmd, arg, account.as_ref())?;
+        let token_id = U256Parser.parse_ref(cmd, arg, token_id.as_ref())?;
+        Ok((account, token_id))
+    }
+}
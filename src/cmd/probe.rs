@@ -1,8 +1,36 @@
 use super::account::WalletCommands;
-use super::parser::BlockIdParser;
+use super::addressbook::AddressBookCommands;
+use super::bump::TxBumpArgs;
+use super::config::ConfigCommands;
+use super::deploy_account::DeployAccountArgs;
+use super::erc1155::Erc1155Commands;
+use super::erc20::Erc20Commands;
+use super::erc721::Erc721Commands;
+use super::estimate::EstimateCommands;
+use super::felt::FeltCommands;
+use super::l1::L1Commands;
+use super::merkle::MerkleArgs;
+use super::messages::MessagesArgs;
+use super::multisig::TxCommands;
+use super::parser::{AddressParser, BlockIdParser, Bytes32Parser, ChainParser};
+use super::profile::ProfileArgs;
+use super::replay::ReplayArgs;
 use super::rpc::RpcArgs;
-use super::send::InvokeArgs;
+use super::send::{DeclareArgs, DeployArgs, InvokeArgs, SendRawArgs};
+use super::send_batch::SendBatchArgs;
+use super::simulate::SimulateCommands;
+use super::tokens::TokensCommands;
+use super::tx_cost::TxCostArgs;
+use super::tx_pending::TxPendingArgs;
+use super::u256::U256Commands;
+use super::watch::WatchCommands;
+use crate::opts::display::DisplayOptions;
+use crate::opts::selector::SelectorOptions;
 use crate::opts::starknet::StarkNetOptions;
+use crate::probe::addressbook::AddressOrName;
+use crate::probe::fixed::FixedPoint;
+use crate::probe::secp::Curve;
+use crate::probe::trace::TraceFormat;
 
 use clap::{Parser, Subcommand};
 use starknet::{core::types::FieldElement, providers::jsonrpc::models::BlockId};
@@ -13,6 +41,9 @@ use std::path::PathBuf;
 pub struct App {
     #[clap(subcommand)]
     pub command: Commands,
+
+    #[clap(flatten)]
+    pub display: DisplayOptions,
 }
 
 #[derive(Subcommand, Debug)]
@@ -22,7 +53,7 @@ pub enum Commands {
     #[clap(about = "Convert decimal felt to hexadecimal.")]
     DecToHex {
         #[clap(value_name = "DECIMAL")]
-        dec: FieldElement,
+        decimal: FieldElement,
     },
 
     #[clap(visible_alias = "td")]
@@ -30,7 +61,7 @@ pub enum Commands {
     #[clap(about = "Convert hexadecimal felt to decimal.")]
     HexToDec {
         #[clap(value_name = "HEX")]
-        hex: FieldElement,
+        hexadecimal: FieldElement,
     },
 
     #[clap(visible_alias = "mxf")]
@@ -48,6 +79,54 @@ pub enum Commands {
     #[clap(about = "Get the minimum signed felt value.")]
     MinSignedFelt,
 
+    #[clap(visible_alias = "rnd")]
+    #[clap(name = "--random-felt")]
+    #[clap(about = "Generate random felts using a CSPRNG.")]
+    RandomFelt {
+        #[clap(long)]
+        #[clap(value_name = "N")]
+        #[clap(default_value_t = 1)]
+        #[clap(help = "The number of felts to generate.")]
+        count: usize,
+    },
+
+    #[clap(visible_alias = "etf")]
+    #[clap(name = "--eth-to-felt")]
+    #[clap(
+        about = "Convert an Ethereum address into a felt, checking its EIP-55 checksum if it is in mixed case."
+    )]
+    EthToFelt {
+        #[clap(value_name = "ADDRESS")]
+        address: String,
+    },
+
+    #[clap(visible_alias = "fte")]
+    #[clap(name = "--felt-to-eth")]
+    #[clap(about = "Convert a felt into an EIP-55 checksummed Ethereum address.")]
+    FeltToEth {
+        #[clap(value_name = "FELT")]
+        felt: FieldElement,
+    },
+
+    #[clap(visible_alias = "tsg")]
+    #[clap(name = "--to-signed")]
+    #[clap(
+        about = "Interpret a felt as a signed integer, the values above the maximum signed felt being negative."
+    )]
+    ToSigned {
+        #[clap(value_name = "FELT")]
+        felt: FieldElement,
+    },
+
+    #[clap(visible_alias = "fsg")]
+    #[clap(name = "--from-signed")]
+    #[clap(about = "Convert a signed decimal integer into its felt representation.")]
+    FromSigned {
+        #[clap(value_name = "INTEGER")]
+        #[clap(allow_hyphen_values = true)]
+        value: String,
+    },
+
     #[clap(visible_alias = "fa")]
     #[clap(name = "--from-ascii")]
     #[clap(about = "Convert from ASCII to Cairo short string.")]
@@ -64,11 +143,214 @@ pub enum Commands {
         short_str: String,
     },
 
+    #[clap(visible_alias = "tba")]
+    #[clap(name = "--to-bytearray")]
+    #[clap(about = "Serialize a string of any length into the felts of a Cairo ByteArray.")]
+    ToByteArray {
+        #[clap(value_name = "STRING")]
+        string: String,
+    },
+
+    #[clap(visible_alias = "fba")]
+    #[clap(name = "--from-bytearray")]
+    #[clap(about = "Deserialize the felts of a Cairo ByteArray into a string.")]
+    FromByteArray {
+        #[clap(value_name = "FELTS")]
+        #[clap(required = true)]
+        felts: Vec<FieldElement>,
+    },
+
     #[clap(visible_alias = "su")]
     #[clap(name = "--split-u256")]
     #[clap(about = "Split a uint256 into its low and high components.")]
     SplitU256 { value: String },
 
+    #[clap(visible_alias = "cu")]
+    #[clap(name = "--combine-u256")]
+    #[clap(about = "Combine the low and high components of a uint256 into its value.")]
+    CombineU256 {
+        #[clap(value_name = "LOW")]
+        low: String,
+        #[clap(value_name = "HIGH")]
+        high: String,
+    },
+
+    #[clap(visible_alias = "pos")]
+    #[clap(name = "--poseidon")]
+    #[clap(about = "Calculate the Poseidon hash on two field elements.")]
+    Poseidon {
+        #[clap(value_name = "X")]
+        x: FieldElement,
+        #[clap(value_name = "Y")]
+        y: FieldElement,
+    },
+
+    #[clap(visible_alias = "posm")]
+    #[clap(name = "--poseidon-many")]
+    #[clap(about = "Calculate the Poseidon hash on an array of field elements.")]
+    PoseidonMany {
+        #[clap(value_name = "FELTS")]
+        elements: Vec<FieldElement>,
+    },
+
+    #[clap(visible_alias = "hoe")]
+    #[clap(name = "--hash-on-elements")]
+    #[clap(
+        about = "Compute the Pedersen hash chain of an array of field elements, as in the legacy transaction hashes, or its Poseidon equivalent."
+    )]
+    HashOnElements {
+        #[clap(value_name = "FELTS")]
+        elements: Vec<FieldElement>,
+
+        #[clap(long)]
+        #[clap(help = "Hash the elements with Poseidon, as in the v3 transaction hashes.")]
+        poseidon: bool,
+    },
+
+    #[clap(visible_alias = "k256")]
+    #[clap(name = "--keccak256")]
+    #[clap(
+        about = "Hash abritrary data using Ethereum keccak256, which unlike StarkNet keccak is not truncated to fit in a felt."
+    )]
+    Keccak256 {
+        #[clap(value_name = "DATA")]
+        data: String,
+    },
+
+    #[clap(visible_alias = "tu")]
+    #[clap(name = "--to-unit")]
+    #[clap(about = "Convert a human amount of a token, e.g. 1.5, into its raw integer amount.")]
+    ToUnit {
+        #[clap(value_name = "AMOUNT")]
+        amount: String,
+
+        #[clap(long)]
+        #[clap(default_value_t = 18)]
+        #[clap(help = "The number of decimals of the token.")]
+        decimals: u32,
+
+        #[clap(long)]
+        #[clap(
+            help = "Print the low and high parts of the u256 amount, in the order of its calldata."
+        )]
+        u256: bool,
+    },
+
+    #[clap(visible_alias = "fu")]
+    #[clap(name = "--from-unit")]
+    #[clap(about = "Convert a raw integer amount of a token into a human amount.")]
+    FromUnit {
+        #[clap(value_name = "AMOUNT")]
+        #[clap(help = "The raw amount, in decimal or in hex.")]
+        amount: String,
+
+        #[clap(long)]
+        #[clap(default_value_t = 18)]
+        #[clap(help = "The number of decimals of the token.")]
+        decimals: u32,
+    },
+
+    #[clap(visible_alias = "tfx")]
+    #[clap(name = "--to-fixed")]
+    #[clap(
+        about = "Encode a decimal number, e.g. -1.5, in a Cairo fixed-point format: 64.61, wad or ray."
+    )]
+    ToFixed {
+        #[clap(value_name = "NUMBER")]
+        #[clap(allow_hyphen_values = true)]
+        value: String,
+
+        #[clap(long)]
+        #[clap(value_enum)]
+        format: FixedPoint,
+    },
+
+    #[clap(visible_alias = "ffx")]
+    #[clap(name = "--from-fixed")]
+    #[clap(
+        about = "Decode a raw value in a Cairo fixed-point format, 64.61, wad or ray, into a decimal number."
+    )]
+    FromFixed {
+        #[clap(value_name = "RAW")]
+        #[clap(help = "The raw value, in decimal or in hex.")]
+        value: String,
+
+        #[clap(long)]
+        #[clap(value_enum)]
+        format: FixedPoint,
+    },
+
+    #[clap(visible_alias = "sel")]
+    #[clap(name = "--selector")]
+    #[clap(about = "Compute the selector of a function or event name.")]
+    Selector {
+        #[clap(value_name = "NAME")]
+        name: String,
+    },
+
+    #[clap(visible_alias = "ptp")]
+    #[clap(name = "--private-to-public")]
+    #[clap(about = "Derive the Stark public key of a private key.")]
+    PrivateToPublic {
+        #[clap(value_name = "PRIVATE_KEY")]
+        private_key: FieldElement,
+
+        #[clap(long)]
+        #[clap(
+            help = "Print both coordinates of the public key point instead of only its x coordinate."
+        )]
+        full: bool,
+    },
+
+    #[clap(about = "Get the abi of the class of a contract.")]
+    Abi {
+        #[clap(value_name = "CONTRACT_ADDRESS")]
+        #[clap(value_parser(AddressParser))]
+        contract_address: AddressOrName,
+
+        #[clap(long)]
+        #[clap(value_name = "NAME")]
+        #[clap(help = "Only print the signature of the given function.")]
+        function: Option<String>,
+
+        #[clap(next_line_help = true)]
+        #[clap(short, long = "block")]
+        #[clap(default_value = "latest")]
+        #[clap(value_parser(BlockIdParser))]
+        #[clap(
+            help = "The hash of the requested block, or number (height) of the requested block, or a block tag (e.g. latest, pending)."
+        )]
+        block_id: BlockId,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(about = "Compare the abis of two classes.")]
+    AbiDiff {
+        #[clap(value_name = "CLASS_A")]
+        #[clap(help = "The hash of the class, or the path of its artifact or abi file.")]
+        class_a: String,
+
+        #[clap(value_name = "CLASS_B")]
+        #[clap(help = "The hash of the class, or the path of its artifact or abi file.")]
+        class_b: String,
+
+        #[clap(next_line_help = true)]
+        #[clap(short, long = "block")]
+        #[clap(default_value = "latest")]
+        #[clap(value_parser(BlockIdParser))]
+        #[clap(
+            help = "The hash of the requested block, or number (height) of the requested block, or a block tag (e.g. latest, pending)."
+        )]
+        block_id: BlockId,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
     #[clap(visible_alias = "acc")]
     #[clap(about = "Account management utilities")]
     Account {
@@ -76,6 +358,14 @@ pub enum Commands {
         commands: WalletCommands,
     },
 
+    #[clap(name = "addressbook")]
+    #[clap(visible_alias = "ab")]
+    #[clap(about = "Save addresses under names, to give them by name to other commands.")]
+    AddressBook {
+        #[clap(subcommand)]
+        commands: AddressBookCommands,
+    },
+
     #[clap(about = "Get the timestamp of a block.")]
     Age {
         #[clap(next_line_help = true)]
@@ -92,11 +382,15 @@ pub enum Commands {
     },
 
     #[clap(visible_alias = "bal")]
-    #[clap(about = "Get the ETH balance of an address.")]
+    #[clap(about = "Get the balance of one or more addresses in ETH or in another token.")]
     Balance {
         #[clap(value_name = "ADDRESS")]
-        #[clap(help = "The address whose balance you want to query.")]
-        address: FieldElement,
+        #[clap(required = true)]
+        #[clap(value_parser(AddressParser))]
+        #[clap(
+            help = "The addresses whose balances you want to query. The balances of several addresses are fetched concurrently and printed as a table."
+        )]
+        addresses: Vec<AddressOrName>,
 
         #[clap(next_line_help = true)]
         #[clap(short, long = "block")]
@@ -107,6 +401,21 @@ pub enum Commands {
         )]
         block_id: BlockId,
 
+        #[clap(long)]
+        #[clap(value_name = "TOKEN")]
+        #[clap(default_value = "ETH")]
+        #[clap(
+            help = "The token to get the balance in, by symbol (see `probe tokens ls`) or by address. The decimals and symbol of a token given by address are read from its contract."
+        )]
+        token: String,
+
+        #[clap(long)]
+        #[clap(visible_alias = "raw")]
+        #[clap(
+            help = "Print the exact balance in the smallest unit of the token, e.g. wei for ETH."
+        )]
+        wei: bool,
+
         #[clap(flatten)]
         #[clap(next_help_heading = "STARKNET OPTIONS")]
         starknet: StarkNetOptions,
@@ -129,12 +438,12 @@ pub enum Commands {
         #[clap(help = "Get the full information (incl. transactions) of the block.")]
         full: bool,
 
-        #[clap(long)]
-        field: Option<String>,
-
-        #[clap(short = 'j', long = "json")]
-        #[clap(help_heading = "Display options")]
-        to_json: bool,
+        #[clap(long = "field")]
+        #[clap(value_name = "PATH")]
+        #[clap(
+            help = "Only print the field at the given path, e.g. transactions.0.sender_address. Can be repeated."
+        )]
+        fields: Vec<String>,
 
         #[clap(flatten)]
         #[clap(next_help_heading = "STARKNET OPTIONS")]
@@ -152,7 +461,8 @@ pub enum Commands {
     #[clap(about = "Call a StarkNet function without creating a transaction.")]
     Call {
         #[clap(display_order = 1)]
-        contract_address: FieldElement,
+        #[clap(value_parser(AddressParser))]
+        contract_address: AddressOrName,
 
         #[clap(display_order = 2)]
         #[clap(help = "The name of the function to be called")]
@@ -161,13 +471,15 @@ pub enum Commands {
 
         #[clap(short, long)]
         #[clap(display_order = 3)]
-        #[clap(value_delimiter = ',')]
-        #[clap(help = "Comma seperated values e.g., 0x12345,0x69420,...")]
-        input: Vec<FieldElement>,
+        #[clap(num_args = 1..)]
+        #[clap(
+            help = "Comma seperated values e.g., 0x12345,0x69420,... With --abi, the values are typed e.g., recipient=0x123 amount=u256:1000"
+        )]
+        input: Vec<String>,
 
         #[clap(short, long)]
         #[clap(display_order = 4)]
-        #[clap(help = "Path to the contract's abi file to validate the call input.")]
+        #[clap(help = "The contract's abi file, to encode the input and decode the output.")]
         abi: Option<PathBuf>,
 
         #[clap(next_line_help = true)]
@@ -193,6 +505,9 @@ pub enum Commands {
         starknet: StarkNetOptions,
     },
 
+    #[clap(about = "List the known chain ids along with their aliases.")]
+    Chains,
+
     #[clap(visible_alias = "cl")]
     #[clap(
         about = "Get the contract class definition in the given block associated with the given hash"
@@ -215,11 +530,23 @@ pub enum Commands {
         starknet: StarkNetOptions,
     },
 
+    #[clap(about = "Print the effective configuration, along with where each value comes from.")]
+    #[clap(args_conflicts_with_subcommands = true)]
+    Config {
+        #[clap(subcommand)]
+        commands: Option<ConfigCommands>,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
     #[clap(visible_alias = "cd")]
     #[clap(about = "Get the contract class definition in the given block at the given address")]
     Code {
+        #[clap(value_parser(AddressParser))]
         #[clap(help = "The address of the contract whose class definition will be returned")]
-        contract_address: FieldElement,
+        contract_address: AddressOrName,
 
         #[clap(next_line_help = true)]
         #[clap(short, long = "block")]
@@ -256,8 +583,9 @@ pub enum Commands {
         about = "Get the contract class hash in the given block for the contract deployed at the given address"
     )]
     ContractClass {
+        #[clap(value_parser(AddressParser))]
         #[clap(help = "The address of the contract whose class hash will be returned")]
-        contract_address: FieldElement,
+        contract_address: AddressOrName,
 
         #[clap(next_line_help = true)]
         #[clap(short, long = "block")]
@@ -280,6 +608,58 @@ pub enum Commands {
         contract: PathBuf,
     },
 
+    #[clap(visible_alias = "dcl")]
+    #[clap(about = "Decode the calldata of a function using the contract's abi.")]
+    DecodeCalldata {
+        #[clap(help = "The name of the function the calldata is for")]
+        #[clap(value_name = "FUNCTION_NAME")]
+        function: String,
+
+        #[clap(value_delimiter = ',')]
+        #[clap(help = "Comma seperated values e.g., 0x12345,0x69420,...")]
+        calldata: Vec<FieldElement>,
+
+        #[clap(short, long)]
+        #[clap(required_unless_present = "contract")]
+        #[clap(help = "Path to the contract's abi file.")]
+        abi: Option<PathBuf>,
+
+        #[clap(long)]
+        #[clap(value_name = "CONTRACT_ADDRESS")]
+        #[clap(conflicts_with = "abi")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "Fetch the abi of the class of the given contract.")]
+        contract: Option<AddressOrName>,
+
+        #[clap(next_line_help = true)]
+        #[clap(short, long = "block")]
+        #[clap(default_value = "latest")]
+        #[clap(value_parser(BlockIdParser))]
+        #[clap(
+            help = "The hash of the requested block, or number (height) of the requested block, or a block tag (e.g. latest, pending)."
+        )]
+        block_id: BlockId,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "SELECTOR OPTIONS")]
+        selectors: SelectorOptions,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(about = "Declare a new contract class.")]
+    Declare(DeclareArgs),
+
+    #[clap(visible_alias = "dpl")]
+    #[clap(about = "Deploy a declared contract class through the Universal Deployer Contract.")]
+    Deploy(DeployArgs),
+
+    #[clap(visible_alias = "dpla")]
+    #[clap(about = "Deploy a new account contract.")]
+    DeployAccount(DeployAccountArgs),
+
     #[clap(visible_alias = "ec")]
     #[clap(about = "Perform ECDSA operations over the STARK-friendly elliptic curve.")]
     Ecdsa {
@@ -287,6 +667,109 @@ pub enum Commands {
         commands: EcdsaCommand,
     },
 
+    #[clap(about = "Transfer, approve or get the allowance of an ERC20 token, in its units.")]
+    Erc20 {
+        #[clap(subcommand)]
+        commands: Erc20Commands,
+    },
+
+    #[clap(about = "Get the owner, the metadata URI or the balance of ERC721 tokens.")]
+    Erc721 {
+        #[clap(subcommand)]
+        commands: Erc721Commands,
+    },
+
+    #[clap(about = "Get the balances of ERC1155 tokens.")]
+    Erc1155 {
+        #[clap(subcommand)]
+        commands: Erc1155Commands,
+    },
+
+    #[clap(visible_alias = "est")]
+    #[clap(about = "Estimate the fee of a transaction.")]
+    Estimate {
+        #[clap(subcommand)]
+        commands: EstimateCommands,
+    },
+
+    #[clap(about = "Estimate the fee to pay on L1 for sending a message to an L2 contract.")]
+    EstimateMessage {
+        #[clap(long)]
+        #[clap(value_name = "L1_ADDRESS")]
+        #[clap(help = "The address of the L1 contract sending the message")]
+        from: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "CONTRACT_ADDRESS")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address of the L2 contract receiving the message")]
+        to: AddressOrName,
+
+        #[clap(long)]
+        #[clap(value_name = "FUNCTION_NAME")]
+        #[clap(help = "The name of the l1_handler function handling the message")]
+        function: String,
+
+        #[clap(long)]
+        #[clap(value_delimiter = ',')]
+        #[clap(help = "Comma seperated values e.g., 0x12345,0x69420,...")]
+        payload: Vec<FieldElement>,
+
+        #[clap(next_line_help = true)]
+        #[clap(short, long = "block")]
+        #[clap(default_value = "pending")]
+        #[clap(value_parser(BlockIdParser))]
+        #[clap(
+            help = "The hash of the requested block, or number (height) of the requested block, or a block tag (e.g. latest, pending)."
+        )]
+        block_id: BlockId,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(about = "Encode the inputs of a function, given in a JSON file, into calldata.")]
+    Encode {
+        #[clap(short, long)]
+        #[clap(help = "Path to the contract's abi file.")]
+        abi: PathBuf,
+
+        #[clap(short, long)]
+        #[clap(value_name = "FUNCTION_NAME")]
+        #[clap(help = "The name of the function to encode the inputs of.")]
+        function: String,
+
+        #[clap(long)]
+        #[clap(value_name = "PATH")]
+        #[clap(
+            help = "Path to a JSON file with an object of the inputs keyed by their name, \
+        e.g. {\"recipient\": \"0x123\", \"amount\": \"1000\"}"
+        )]
+        args_file: PathBuf,
+    },
+
+    #[clap(visible_alias = "evs")]
+    #[clap(about = "Compute the keys an event starts with, to filter events by.")]
+    EventSelector {
+        #[clap(value_name = "EVENT")]
+        #[clap(
+            help = "The name of the event, or its path of variants from the contract's Event enum, \
+        e.g. ERC20Event::Transfer"
+        )]
+        event: String,
+
+        #[clap(long)]
+        #[clap(value_name = "VARIANT")]
+        #[clap(conflicts_with = "abi")]
+        #[clap(help = "The variants of the path that are flattened, which don't add a key.")]
+        flat: Vec<String>,
+
+        #[clap(short, long)]
+        #[clap(help = "Path to the contract's abi file, to find the path of the event in.")]
+        abi: Option<PathBuf>,
+    },
+
     #[clap(visible_alias = "ev")]
     #[clap(about = "Returns all events matching the given filter")]
     #[clap(
@@ -295,8 +778,9 @@ pub enum Commands {
     Events {
         #[clap(short = 'C', long)]
         #[clap(value_name = "CONTRACT_ADDRESS")]
+        #[clap(value_parser(AddressParser))]
         #[clap(help = "Address of the contract emitting the events")]
-        from: Option<FieldElement>,
+        from: Option<AddressOrName>,
 
         #[clap(short, long)]
         #[clap(value_delimiter = ',')]
@@ -304,6 +788,15 @@ pub enum Commands {
         #[clap(help = "Comma seperated values e.g., 0x12345,0x69420,...")]
         keys: Option<Vec<FieldElement>>,
 
+        #[clap(long)]
+        #[clap(value_name = "NAME")]
+        #[clap(requires = "from")]
+        #[clap(conflicts_with = "keys")]
+        #[clap(
+            help = "Only get the events of this name, e.g. Transfer, as declared in the abi of the contract, and decode them."
+        )]
+        event: Option<String>,
+
         #[clap(short, long)]
         #[clap(value_parser(BlockIdParser))]
         from_block: Option<BlockId>,
@@ -327,6 +820,14 @@ pub enum Commands {
         starknet: StarkNetOptions,
     },
 
+    #[clap(
+        about = "Add, subtract, multiply, divide, invert or raise felts to a power modulo the Stark prime."
+    )]
+    Felt {
+        #[clap(subcommand)]
+        commands: FeltCommands,
+    },
+
     #[clap(visible_alias = "idx")]
     #[clap(about = "Compute the address of a storage variable.")]
     Index {
@@ -336,6 +837,14 @@ pub enum Commands {
         keys: Vec<FieldElement>,
     },
 
+    #[clap(
+        about = "Show a felt as hex, decimal and signed integer, along with its bit length, bytes, short string and whether it is an x coordinate of the Stark curve."
+    )]
+    InspectFelt {
+        #[clap(value_name = "FELT")]
+        felt: FieldElement,
+    },
+
     #[clap(visible_alias = "inv")]
     #[clap(about = "Submit a new transaction to be added to the chain.")]
     Invoke(InvokeArgs),
@@ -347,14 +856,56 @@ pub enum Commands {
         data: String,
     },
 
-    #[clap(visible_alias = "n1")]
-    #[clap(about = "Get the latest nonce associated with the address.")]
-    Nonce {
-        contract_address: FieldElement,
+    #[clap(
+        about = "Query the StarkNet core contract on Ethereum, to check the finality of blocks and messages from L1."
+    )]
+    L1 {
+        #[clap(subcommand)]
+        commands: L1Commands,
+    },
 
-        #[clap(next_line_help = true)]
-        #[clap(default_value = "latest")]
-        #[clap(value_parser(BlockIdParser))]
+    #[clap(
+        about = "Build a Merkle tree of felts, e.g. an airdrop allowlist, and print its root along with the inclusion proofs of some leaves."
+    )]
+    Merkle(MerkleArgs),
+
+    #[clap(
+        about = "List the messages sent to L1 by the transactions of a block, or by a transaction."
+    )]
+    Messages(MessagesArgs),
+
+    #[clap(name = "message-status")]
+    #[clap(
+        about = "Get the status of the L2 transactions consuming the messages sent by an L1 transaction."
+    )]
+    MessageStatus {
+        #[clap(value_name = "L1_TX_HASH")]
+        #[clap(help = "The hash of the L1 transaction that sent the messages")]
+        hash: String,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(name = "message-hash")]
+    #[clap(
+        about = "Compute the hash of a message between L1 and L2, as logged by the StarkNet core contract."
+    )]
+    MessageHash {
+        #[clap(subcommand)]
+        commands: MessageHashCommand,
+    },
+
+    #[clap(visible_alias = "n1")]
+    #[clap(about = "Get the latest nonce associated with the address.")]
+    Nonce {
+        #[clap(value_parser(AddressParser))]
+        contract_address: AddressOrName,
+
+        #[clap(next_line_help = true)]
+        #[clap(default_value = "latest")]
+        #[clap(value_parser(BlockIdParser))]
         #[clap(
             help = "The hash of the requested block, or number (height) of the requested block, or a block tag (e.g. latest, pending)."
         )]
@@ -374,9 +925,51 @@ pub enum Commands {
         y: String,
     },
 
+    #[clap(about = "Aggregate the resources used by each entrypoint called in a transaction.")]
+    Profile(ProfileArgs),
+
+    #[clap(
+        about = "Re-simulate a transaction on top of its parent block and compare the result with its receipt."
+    )]
+    Replay(ReplayArgs),
+
     #[clap(about = "Perform a raw JSON-RPC request.")]
     Rpc(RpcArgs),
 
+    #[clap(about = "Find the name a selector was computed from, among the known names.")]
+    SelectorLookup {
+        #[clap(value_name = "SELECTOR")]
+        selector: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "PATH")]
+        #[clap(
+            help = "Add the names of the functions and events of the abi file to the known names."
+        )]
+        abi: Vec<PathBuf>,
+    },
+
+    #[clap(
+        about = "Perform ECDSA operations over the secp256k1 or secp256r1 elliptic curves, as used by Ethereum and by the accounts signing with Ethereum or passkey keys."
+    )]
+    Secp {
+        #[clap(subcommand)]
+        commands: SecpCommand,
+    },
+
+    #[clap(about = "Send the invoke transactions described in a JSON or TOML file.")]
+    SendBatch(SendBatchArgs),
+
+    #[clap(about = "Broadcast a pre-signed transaction.")]
+    SendRaw(SendRawArgs),
+
+    #[clap(visible_alias = "sim")]
+    #[clap(about = "Simulate a transaction without sending it.")]
+    Simulate {
+        #[clap(subcommand)]
+        commands: SimulateCommands,
+    },
+
     #[clap(about = "Get the information about the result of executing the requested block")]
     StateUpdate {
         #[clap(next_line_help = true)]
@@ -396,7 +989,8 @@ pub enum Commands {
     #[clap(visible_alias = "str")]
     #[clap(about = "Get the value of a contract's storage at the given index")]
     Storage {
-        contract_address: FieldElement,
+        #[clap(value_parser(AddressParser))]
+        contract_address: AddressOrName,
 
         index: FieldElement,
 
@@ -414,24 +1008,151 @@ pub enum Commands {
         starknet: StarkNetOptions,
     },
 
-    #[clap(name = "tx")]
-    #[clap(about = "Get information about a transaction.")]
-    Transaction {
+    #[clap(about = "Get the name, symbol, decimals and total supply of an ERC20 token.")]
+    Token {
+        #[clap(value_name = "TOKEN")]
+        #[clap(
+            help = "The address of the token, or the symbol of a well-known one (see `probe tokens ls`)."
+        )]
+        token: String,
+
+        #[clap(next_line_help = true)]
+        #[clap(short, long = "block")]
+        #[clap(default_value = "latest")]
+        #[clap(value_parser(BlockIdParser))]
+        #[clap(
+            help = "The hash of the requested block, or number (height) of the requested block, or a block tag (e.g. latest, pending)."
+        )]
+        block_id: BlockId,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(
+        about = "Detect whether a contract implements ERC20, ERC721, ERC1155 or an account, from the SRC-5 interfaces it supports or else from its ABI."
+    )]
+    TokenStandard {
+        #[clap(value_name = "ADDRESS")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address of the contract.")]
+        address: AddressOrName,
+
+        #[clap(next_line_help = true)]
+        #[clap(short, long = "block")]
+        #[clap(default_value = "latest")]
+        #[clap(value_parser(BlockIdParser))]
+        #[clap(
+            help = "The hash of the requested block, or number (height) of the requested block, or a block tag (e.g. latest, pending)."
+        )]
+        block_id: BlockId,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(about = "List the well-known tokens of a chain.")]
+    Tokens {
+        #[clap(subcommand)]
+        commands: TokensCommands,
+    },
+
+    #[clap(about = "Get the execution trace of a transaction.")]
+    Trace {
         #[clap(value_name = "TX_HASH")]
         hash: FieldElement,
 
         #[clap(long)]
-        field: Option<String>,
+        #[clap(value_enum)]
+        #[clap(default_value_t = TraceFormat::Tree)]
+        #[clap(conflicts_with = "json")]
+        #[clap(help = "The format in which the trace is printed.")]
+        format: TraceFormat,
 
-        #[clap(short = 'j', long = "json")]
-        #[clap(help_heading = "Display options")]
-        to_json: bool,
+        #[clap(flatten)]
+        #[clap(next_help_heading = "SELECTOR OPTIONS")]
+        selectors: SelectorOptions,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(about = "Get the execution traces of all the transactions in a block.")]
+    TraceBlock {
+        #[clap(next_line_help = true)]
+        #[clap(value_name = "BLOCK_ID")]
+        #[clap(default_value = "latest")]
+        #[clap(value_parser(BlockIdParser))]
+        #[clap(
+            help = "The hash of the requested block, or number (height) of the requested block, or a block tag (e.g. latest, pending)."
+        )]
+        block_id: BlockId,
+
+        #[clap(long)]
+        #[clap(value_name = "CONTRACT_ADDRESS")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "Only show the frames touching the given contract.")]
+        contract: Option<AddressOrName>,
+
+        #[clap(long)]
+        #[clap(value_enum)]
+        #[clap(default_value_t = TraceFormat::Tree)]
+        #[clap(conflicts_with = "json")]
+        #[clap(help = "The format in which the traces are printed.")]
+        format: TraceFormat,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "SELECTOR OPTIONS")]
+        selectors: SelectorOptions,
 
         #[clap(flatten)]
         #[clap(next_help_heading = "STARKNET OPTIONS")]
         starknet: StarkNetOptions,
     },
 
+    #[clap(name = "tx")]
+    #[clap(about = "Get information about a transaction.")]
+    #[clap(args_conflicts_with_subcommands = true)]
+    #[clap(subcommand_negates_reqs = true)]
+    Transaction {
+        #[clap(subcommand)]
+        commands: Option<TxCommands>,
+
+        #[clap(required = true)]
+        #[clap(value_name = "TX_HASH")]
+        hash: Option<FieldElement>,
+
+        #[clap(long = "field")]
+        #[clap(value_name = "PATH")]
+        #[clap(
+            help = "Only print the field at the given path, e.g. transactions.0.sender_address. Can be repeated."
+        )]
+        fields: Vec<String>,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(name = "tx-bump")]
+    #[clap(about = "Resubmit a pending invoke transaction with a higher max fee.")]
+    TransactionBump(TxBumpArgs),
+
+    #[clap(name = "tx-cost")]
+    #[clap(about = "Get the breakdown of the fee paid by a transaction for each of its calls.")]
+    TransactionCost(TxCostArgs),
+
+    #[clap(visible_alias = "txh")]
+    #[clap(name = "tx-hash")]
+    #[clap(about = "Compute the hash of a transaction from its fields, without any RPC call.")]
+    TransactionHash {
+        #[clap(subcommand)]
+        commands: TxHashCommand,
+    },
+
     #[clap(visible_alias = "txc")]
     #[clap(name = "tx-count")]
     #[clap(about = "Get the number of transactions in a block.")]
@@ -451,12 +1172,10 @@ pub enum Commands {
 
     #[clap(visible_alias = "txp")]
     #[clap(name = "tx-pending")]
-    #[clap(about = "Get the transactions in the transaction pool, recognized by the sequencer.")]
-    TransactionPending {
-        #[clap(flatten)]
-        #[clap(next_help_heading = "STARKNET OPTIONS")]
-        starknet: StarkNetOptions,
-    },
+    #[clap(
+        about = "Get the statistics of the transactions in the transaction pool, recognized by the sequencer."
+    )]
+    TransactionPending(TxPendingArgs),
 
     #[clap(visible_alias = "txs")]
     #[clap(name = "tx-status")]
@@ -477,18 +1196,41 @@ pub enum Commands {
         #[clap(value_name = "TX_HASH")]
         hash: FieldElement,
 
-        #[clap(long)]
-        field: Option<String>,
+        #[clap(long = "field")]
+        #[clap(value_name = "PATH")]
+        #[clap(
+            help = "Only print the field at the given path, e.g. transactions.0.sender_address. Can be repeated."
+        )]
+        fields: Vec<String>,
 
-        #[clap(short = 'j', long = "json")]
+        #[clap(long)]
         #[clap(help_heading = "Display options")]
-        to_json: bool,
+        #[clap(
+            help = "Decode the events using the abi of the contracts that emitted them. The JSON receipt gets the name and the decoded fields of each event."
+        )]
+        decode: bool,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "SELECTOR OPTIONS")]
+        selectors: SelectorOptions,
 
         #[clap(flatten)]
         #[clap(next_help_heading = "STARKNET OPTIONS")]
         starknet: StarkNetOptions,
     },
 
+    #[clap(about = "Add, subtract, multiply or divide u256 values, in decimal or in hex.")]
+    U256 {
+        #[clap(subcommand)]
+        commands: U256Commands,
+    },
+
+    #[clap(about = "Follow the chain as it grows, printing every new record as it appears.")]
+    Watch {
+        #[clap(subcommand)]
+        commands: WatchCommands,
+    },
+
     #[clap(visible_alias = "gca")]
     #[clap(about = "Generate call array calldata")]
     CallArray {
@@ -535,13 +1277,351 @@ pub enum EcdsaCommand {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum SecpCommand {
+    #[clap(
+        about = "Sign a message hash, printing the r and s of the signature and its recovery id."
+    )]
+    Sign {
+        #[clap(short, long)]
+        #[clap(value_name = "MESSAGE_HASH")]
+        #[clap(value_parser(Bytes32Parser))]
+        #[clap(help = "Message hash to be signed, e.g. a keccak256 hash.")]
+        message: [u8; 32],
+
+        #[clap(short, long)]
+        #[clap(value_name = "PRIVATE_KEY")]
+        #[clap(value_parser(Bytes32Parser))]
+        #[clap(help = "The private key for signing.")]
+        private_key: [u8; 32],
+
+        #[clap(long)]
+        #[clap(value_enum)]
+        #[clap(default_value_t = Curve::Secp256k1)]
+        curve: Curve,
+    },
+
+    #[clap(about = "Verify the signature of a message hash.")]
+    Verify {
+        #[clap(short, long)]
+        #[clap(value_name = "MESSAGE_HASH")]
+        #[clap(value_parser(Bytes32Parser))]
+        #[clap(help = "Message hash used in the signature.")]
+        message: [u8; 32],
+
+        #[clap(short, long)]
+        #[clap(required = true)]
+        #[clap(number_of_values = 2)]
+        #[clap(value_names = &["SIGNATURE_R", "SIGNATURE_S"])]
+        #[clap(value_parser(Bytes32Parser))]
+        signature: Vec<[u8; 32]>,
+
+        #[clap(short, long)]
+        #[clap(value_name = "PUBLIC_KEY")]
+        #[clap(help = "The public key for verification, SEC1 encoded in hex, compressed or not.")]
+        verifying_key: String,
+
+        #[clap(long)]
+        #[clap(value_enum)]
+        #[clap(default_value_t = Curve::Secp256k1)]
+        curve: Curve,
+    },
+
+    #[clap(about = "Derive the public key of a private key, SEC1 encoded in hex.")]
+    PublicKey {
+        #[clap(value_name = "PRIVATE_KEY")]
+        #[clap(value_parser(Bytes32Parser))]
+        private_key: [u8; 32],
+
+        #[clap(long)]
+        #[clap(help = "Print the compressed encoding of the key, its x coordinate only.")]
+        compressed: bool,
+
+        #[clap(long)]
+        #[clap(value_enum)]
+        #[clap(default_value_t = Curve::Secp256k1)]
+        curve: Curve,
+    },
+}
+
 #[cfg(test)]
 mod tests {
-    use super::App;
-    use clap::CommandFactory;
+    use super::{App, Commands};
+    use clap::{CommandFactory, Parser};
 
     #[test]
     fn verify_cli() {
         App::command().debug_assert()
     }
+
+    #[test]
+    fn parse_args_named_like_display_options() {
+        // arguments sharing the id of a global display option fail to be read back
+        for args in [
+            vec!["probe", "--to-hex", "10", "--dec"],
+            vec!["probe", "--to-dec", "0xa", "--hex"],
+            vec![
+                "probe",
+                "invoke",
+                "--to",
+                "0x1",
+                "--function",
+                "f",
+                "--output",
+                "json",
+            ],
+        ] {
+            App::try_parse_from(args).unwrap();
+        }
+    }
+
+    #[test]
+    fn declare_only_sends_v1_transactions() {
+        // declaring a class with a v3 transaction is not supported
+        for args in [
+            vec!["probe", "declare", "class.json", "--fee-token", "strk"],
+            vec!["probe", "declare", "class.json", "--version", "3"],
+            vec![
+                "probe",
+                "estimate",
+                "declare",
+                "class.json",
+                "--fee-token",
+                "strk",
+            ],
+        ] {
+            assert!(App::try_parse_from(args).is_err());
+        }
+
+        let args = App::parse_from(["probe", "declare", "class.json", "--fee-token", "eth"]);
+        match args.command {
+            Commands::Declare(args) => assert!(!args.transaction.is_v3()),
+            _ => unreachable!(),
+        }
+        App::parse_from([
+            "probe",
+            "invoke",
+            "--to",
+            "0x1",
+            "--function",
+            "f",
+            "--fee-token",
+            "strk",
+        ]);
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TxHashCommand {
+    #[clap(about = "Compute the hash of an invoke transaction.")]
+    Invoke {
+        #[clap(long)]
+        #[clap(value_name = "SENDER_ADDRESS")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address of the account sending the transaction.")]
+        sender: AddressOrName,
+
+        #[clap(long)]
+        #[clap(value_delimiter = ',')]
+        #[clap(help = "The calldata of the account's __execute__ function, comma seperated.")]
+        calldata: Vec<FieldElement>,
+
+        #[clap(long)]
+        #[clap(value_name = "MAX_FEE")]
+        max_fee: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "NONCE")]
+        nonce: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "CHAIN_ID")]
+        #[clap(value_parser(ChainParser))]
+        chain: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "VERSION")]
+        #[clap(default_value = "1")]
+        version: FieldElement,
+    },
+
+    #[clap(about = "Compute the hash of a declare transaction.")]
+    Declare {
+        #[clap(long)]
+        #[clap(value_name = "SENDER_ADDRESS")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address of the account sending the transaction.")]
+        sender: AddressOrName,
+
+        #[clap(long)]
+        #[clap(value_name = "CLASS_HASH")]
+        #[clap(help = "The hash of the class being declared.")]
+        class_hash: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "MAX_FEE")]
+        max_fee: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "NONCE")]
+        nonce: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "CHAIN_ID")]
+        #[clap(value_parser(ChainParser))]
+        chain: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "VERSION")]
+        #[clap(default_value = "1")]
+        version: FieldElement,
+    },
+
+    #[clap(about = "Compute the hash of a deploy account transaction.")]
+    DeployAccount {
+        #[clap(long)]
+        #[clap(value_name = "CLASS_HASH")]
+        #[clap(help = "The class hash of the account contract.")]
+        class_hash: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "SALT")]
+        #[clap(help = "The salt used to compute the account address.")]
+        salt: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_delimiter = ',')]
+        #[clap(help = "The inputs passed to the constructor, comma seperated.")]
+        constructor_calldata: Vec<FieldElement>,
+
+        #[clap(long)]
+        #[clap(value_name = "MAX_FEE")]
+        max_fee: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "NONCE")]
+        nonce: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "CHAIN_ID")]
+        #[clap(value_parser(ChainParser))]
+        chain: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "VERSION")]
+        #[clap(default_value = "1")]
+        version: FieldElement,
+    },
+
+    #[clap(
+        about = "Compute the hash of the l1_handler transaction consuming a message sent from L1."
+    )]
+    L1Handler {
+        #[clap(long)]
+        #[clap(value_name = "L1_ADDRESS")]
+        #[clap(required_unless_present = "log")]
+        #[clap(help = "The address of the L1 contract sending the message.")]
+        from: Option<FieldElement>,
+
+        #[clap(long)]
+        #[clap(value_name = "CONTRACT_ADDRESS")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(required_unless_present = "log")]
+        #[clap(help = "The address of the L2 contract receiving the message.")]
+        to: Option<AddressOrName>,
+
+        #[clap(long)]
+        #[clap(value_name = "FUNCTION_NAME")]
+        #[clap(required_unless_present_any = ["selector", "log"])]
+        #[clap(help = "The name of the l1_handler function handling the message.")]
+        function: Option<String>,
+
+        #[clap(long)]
+        #[clap(value_name = "SELECTOR")]
+        #[clap(conflicts_with = "function")]
+        #[clap(help = "The selector of the l1_handler function, instead of its name.")]
+        selector: Option<FieldElement>,
+
+        #[clap(long)]
+        #[clap(value_delimiter = ',')]
+        #[clap(help = "The payload of the message, comma seperated.")]
+        payload: Vec<FieldElement>,
+
+        #[clap(long)]
+        #[clap(value_name = "NONCE")]
+        #[clap(required_unless_present = "log")]
+        #[clap(help = "The nonce the core contract assigned to the message.")]
+        nonce: Option<FieldElement>,
+
+        #[clap(long)]
+        #[clap(value_name = "JSON|PATH")]
+        #[clap(conflicts_with_all = ["from", "to", "function", "selector", "payload", "nonce"])]
+        #[clap(
+            help = "The LogMessageToL2 event logged by the core contract when the message was sent, as given in the receipt of the L1 transaction. Either its JSON or a file holding it."
+        )]
+        log: Option<String>,
+
+        #[clap(long)]
+        #[clap(value_name = "CHAIN_ID")]
+        #[clap(value_parser(ChainParser))]
+        chain: FieldElement,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum MessageHashCommand {
+    #[clap(name = "l1-to-l2")]
+    #[clap(about = "Compute the hash of a message sent from L1 to the l1_handler of a contract.")]
+    L1ToL2 {
+        #[clap(long)]
+        #[clap(value_name = "L1_ADDRESS")]
+        #[clap(help = "The address of the L1 contract sending the message")]
+        from: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "CONTRACT_ADDRESS")]
+        #[clap(help = "The address of the L2 contract receiving the message")]
+        to: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "FUNCTION_NAME")]
+        #[clap(required_unless_present = "selector")]
+        #[clap(help = "The name of the l1_handler function handling the message")]
+        function: Option<String>,
+
+        #[clap(long)]
+        #[clap(value_name = "SELECTOR")]
+        #[clap(conflicts_with = "function")]
+        #[clap(help = "The selector of the l1_handler function, instead of its name")]
+        selector: Option<FieldElement>,
+
+        #[clap(long)]
+        #[clap(value_delimiter = ',')]
+        #[clap(help = "Comma seperated values e.g., 0x12345,0x69420,...")]
+        payload: Vec<FieldElement>,
+
+        #[clap(long)]
+        #[clap(value_name = "NONCE")]
+        #[clap(help = "The nonce the core contract assigned to the message")]
+        nonce: FieldElement,
+    },
+
+    #[clap(name = "l2-to-l1")]
+    #[clap(about = "Compute the hash of a message sent from a contract to L1.")]
+    L2ToL1 {
+        #[clap(long)]
+        #[clap(value_name = "CONTRACT_ADDRESS")]
+        #[clap(help = "The address of the L2 contract sending the message")]
+        from: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "L1_ADDRESS")]
+        #[clap(help = "The address of the L1 contract receiving the message")]
+        to: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_delimiter = ',')]
+        #[clap(help = "Comma seperated values e.g., 0x12345,0x69420,...")]
+        payload: Vec<FieldElement>,
+    },
 }
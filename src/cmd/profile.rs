@@ -0,0 +1,168 @@
+use crate::opts::starknet::StarkNetOptions;
+use crate::probe::trace::{flatten_calls, TraceCall};
+use crate::probe::utils::fmt::{Pretty, ValueFormat};
+use crate::probe::utils::send_rpc_request;
+
+use std::collections::BTreeMap;
+
+use clap::Parser;
+use comfy_table::modifiers::UTF8_SOLID_INNER_BORDERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::Table;
+use eyre::Result;
+use serde_json::{json, Value};
+use starknet::core::types::FieldElement;
+
+#[derive(Debug, Parser)]
+pub struct ProfileArgs {
+    #[clap(value_name = "TX_HASH")]
+    #[clap(help = "The hash of the transaction to profile")]
+    pub hash: FieldElement,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "STARKNET OPTIONS")]
+    pub starknet: StarkNetOptions,
+}
+
+/// The resources used by all the calls to an entrypoint of a contract.
+#[derive(Debug, Default)]
+struct ProfileEntry {
+    contract_address: String,
+    selector: String,
+    calls: usize,
+    total_steps: u64,
+    self_resources: BTreeMap<String, u64>,
+}
+
+#[derive(Debug)]
+pub struct Profile {
+    entries: Vec<ProfileEntry>,
+}
+
+impl ProfileArgs {
+    pub async fn run(self) -> Result<Profile> {
+        let trace: Value = send_rpc_request(
+            &self.starknet.rpc_url(),
+            "starknet_traceTransaction",
+            json!({ "transaction_hash": self.hash }),
+        )
+        .await?;
+
+        Ok(Profile::new(&flatten_calls(&trace)))
+    }
+}
+
+impl Profile {
+    /// Aggregates the resources of the calls per contract and entrypoint, sorted by the number of
+    /// steps executed by the entrypoint itself.
+    fn new(calls: &[TraceCall]) -> Self {
+        let mut entries: BTreeMap<(&str, &str), ProfileEntry> = BTreeMap::new();
+
+        for call in calls {
+            let entry = entries
+                .entry((&call.contract_address, &call.selector))
+                .or_insert_with(|| ProfileEntry {
+                    contract_address: call.contract_address.clone(),
+                    selector: call.selector.clone(),
+                    ..Default::default()
+                });
+
+            entry.calls += 1;
+            entry.total_steps += call.steps().unwrap_or_default();
+            for (name, amount) in &call.self_resources {
+                *entry.self_resources.entry(name.clone()).or_default() += amount;
+            }
+        }
+
+        let mut entries = entries.into_values().collect::<Vec<_>>();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.self_steps()));
+
+        Self { entries }
+    }
+}
+
+impl ProfileEntry {
+    fn self_steps(&self) -> u64 {
+        self.self_resources
+            .get("steps")
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl Pretty for Profile {
+    fn prettify(&self, _: &ValueFormat) -> String {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .set_header(vec![
+                "CONTRACT",
+                "SELECTOR",
+                "CALLS",
+                "SELF STEPS",
+                "TOTAL STEPS",
+                "SELF RESOURCES",
+            ]);
+
+        for entry in &self.entries {
+            table.add_row(vec![
+                entry.contract_address.clone(),
+                entry.selector.clone(),
+                entry.calls.to_string(),
+                entry.self_steps().to_string(),
+                entry.total_steps.to_string(),
+                entry
+                    .self_resources
+                    .iter()
+                    .filter(|(name, _)| *name != "steps")
+                    .map(|(name, value)| format!("{name}: {value}"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ]);
+        }
+
+        format!("{table}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregate_calls_per_entrypoint() {
+        let trace = json!({
+            "execute_invocation": {
+                "contract_address": "0x1",
+                "entry_point_selector": "0xa",
+                "execution_resources": { "steps": 500 },
+                "calls": [
+                    {
+                        "contract_address": "0x2",
+                        "entry_point_selector": "0xb",
+                        "execution_resources": { "steps": 200, "pedersen_builtin_applications": 2 },
+                        "calls": []
+                    },
+                    {
+                        "contract_address": "0x2",
+                        "entry_point_selector": "0xb",
+                        "execution_resources": { "steps": 250, "pedersen_builtin_applications": 3 },
+                        "calls": []
+                    }
+                ]
+            }
+        });
+
+        let profile = Profile::new(&flatten_calls(&trace));
+
+        assert_eq!(profile.entries.len(), 2);
+        assert_eq!(profile.entries[0].selector, "0xb");
+        assert_eq!(profile.entries[0].calls, 2);
+        assert_eq!(profile.entries[0].self_steps(), 450);
+        assert_eq!(profile.entries[0].self_resources["pedersen"], 5);
+        assert_eq!(profile.entries[1].selector, "0xa");
+        assert_eq!(profile.entries[1].self_steps(), 50);
+        assert_eq!(profile.entries[1].total_steps, 500);
+    }
+}
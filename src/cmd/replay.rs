@@ -0,0 +1,260 @@
+use crate::opts::starknet::StarkNetOptions;
+use crate::probe::utils::fmt::{Pretty, ValueFormat};
+use crate::probe::utils::send_rpc_request;
+use crate::probe::Probe;
+
+use clap::Parser;
+use comfy_table::modifiers::UTF8_SOLID_INNER_BORDERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::Table;
+use eyre::{bail, eyre, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use starknet::core::types::FieldElement;
+use starknet::providers::jsonrpc::models::BlockId;
+
+#[derive(Debug, Parser)]
+pub struct ReplayArgs {
+    #[clap(value_name = "TX_HASH")]
+    #[clap(help = "The hash of the transaction to replay")]
+    pub hash: FieldElement,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "STARKNET OPTIONS")]
+    pub starknet: StarkNetOptions,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+struct Event {
+    from_address: FieldElement,
+    keys: Vec<FieldElement>,
+    data: Vec<FieldElement>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderedEvent {
+    #[serde(default)]
+    order: u64,
+    keys: Vec<FieldElement>,
+    data: Vec<FieldElement>,
+}
+
+/// The parts of the execution of a transaction that are compared when replaying it.
+#[derive(Debug)]
+struct ExecutionOutcome {
+    reverted: bool,
+    fee: FieldElement,
+    events: Vec<Event>,
+}
+
+#[derive(Debug)]
+pub struct ReplayReport {
+    actual: ExecutionOutcome,
+    simulated: ExecutionOutcome,
+}
+
+impl ReplayArgs {
+    /// Simulates the transaction on top of the block preceding the one it was included in, and
+    /// compares the result with its receipt.
+    ///
+    /// The transactions that precede it in its own block are not applied, so a difference may
+    /// also come from one of them touching the same state.
+    pub async fn run(self) -> Result<ReplayReport> {
+        let ReplayArgs { hash, starknet } = self;
+        let url = &starknet.rpc_url();
+
+        let mut tx: Value = send_rpc_request(
+            url,
+            "starknet_getTransactionByHash",
+            json!({ "transaction_hash": hash }),
+        )
+        .await?;
+
+        let receipt: Value = send_rpc_request(
+            url,
+            "starknet_getTransactionReceipt",
+            json!({ "transaction_hash": hash }),
+        )
+        .await?;
+
+        match tx["type"].as_str() {
+            Some("INVOKE") | Some("DEPLOY_ACCOUNT") => {}
+            Some(ty) => bail!("{ty} transactions cannot be replayed"),
+            None => bail!("unknown type of transaction {hash:#x}"),
+        }
+
+        let block_number = receipt["block_number"]
+            .as_u64()
+            .ok_or_else(|| eyre!("transaction {hash:#x} is not yet included in a block"))?;
+        let parent = block_number
+            .checked_sub(1)
+            .ok_or_else(|| eyre!("transactions of the genesis block cannot be replayed"))?;
+
+        // the hash is not part of a broadcasted transaction
+        if let Some(tx) = tx.as_object_mut() {
+            tx.remove("transaction_hash");
+        }
+
+        let simulation = Probe::new(starknet.rpc_url())
+            .simulate_transaction(tx, &BlockId::Number(parent), false, false)
+            .await?;
+
+        Ok(ReplayReport {
+            actual: ExecutionOutcome::from_receipt(&receipt)?,
+            simulated: ExecutionOutcome::from_simulation(&simulation)?,
+        })
+    }
+}
+
+impl ExecutionOutcome {
+    fn from_receipt(receipt: &Value) -> Result<Self> {
+        Ok(Self {
+            reverted: receipt["execution_status"].as_str() == Some("REVERTED"),
+            fee: parse_fee(&receipt["actual_fee"])?,
+            events: serde_json::from_value(receipt["events"].clone())?,
+        })
+    }
+
+    fn from_simulation(simulation: &Value) -> Result<Self> {
+        let trace = &simulation["transaction_trace"];
+
+        let mut events = Vec::new();
+        for key in [
+            "constructor_invocation",
+            "validate_invocation",
+            "execute_invocation",
+            "fee_transfer_invocation",
+        ] {
+            let mut invocation_events = Vec::new();
+            collect_events(&trace[key], &mut invocation_events)?;
+
+            invocation_events.sort_by_key(|(order, _)| *order);
+            events.extend(invocation_events.into_iter().map(|(_, event)| event));
+        }
+
+        Ok(Self {
+            reverted: trace["execute_invocation"].get("revert_reason").is_some(),
+            fee: parse_fee(&simulation["fee_estimation"]["overall_fee"])?,
+            events,
+        })
+    }
+}
+
+/// Collects the events emitted by the invocation and its inner calls, along with their order.
+fn collect_events(invocation: &Value, events: &mut Vec<(u64, Event)>) -> Result<()> {
+    let from_address = match invocation["contract_address"].as_str() {
+        Some(address) => FieldElement::from_hex_be(address)?,
+        None => return Ok(()),
+    };
+
+    for event in invocation["events"].as_array().into_iter().flatten() {
+        let OrderedEvent { order, keys, data } = serde_json::from_value(event.clone())?;
+        events.push((
+            order,
+            Event {
+                from_address,
+                keys,
+                data,
+            },
+        ));
+    }
+
+    for call in invocation["calls"].as_array().into_iter().flatten() {
+        collect_events(call, events)?;
+    }
+
+    Ok(())
+}
+
+// the fee is a plain amount in older versions of the spec, and an amount with its unit in newer ones
+fn parse_fee(fee: &Value) -> Result<FieldElement> {
+    let amount = fee
+        .as_str()
+        .or_else(|| fee["amount"].as_str())
+        .ok_or_else(|| eyre!("missing fee amount"))?;
+
+    Ok(FieldElement::from_hex_be(amount)?)
+}
+
+impl Pretty for ReplayReport {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
+        let (actual, simulated) = (&self.actual, &self.simulated);
+        let matches = |eq: bool| if eq { "yes" } else { "no" }.to_string();
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .set_header(vec!["", "RECEIPT", "SIMULATION", "MATCH"])
+            .add_row(vec![
+                "REVERTED".to_string(),
+                actual.reverted.to_string(),
+                simulated.reverted.to_string(),
+                matches(actual.reverted == simulated.reverted),
+            ])
+            .add_row(vec![
+                "FEE".to_string(),
+                actual.fee.prettify(fmt),
+                simulated.fee.prettify(fmt),
+                matches(actual.fee == simulated.fee),
+            ])
+            .add_row(vec![
+                "EVENTS".to_string(),
+                actual.events.len().to_string(),
+                simulated.events.len().to_string(),
+                matches(actual.events == simulated.events),
+            ]);
+
+        format!("{table}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulated_events_in_execution_order() {
+        let simulation = json!({
+            "fee_estimation": { "overall_fee": "0x10" },
+            "transaction_trace": {
+                "validate_invocation": {
+                    "contract_address": "0x1",
+                    "events": [],
+                    "calls": []
+                },
+                "execute_invocation": {
+                    "contract_address": "0x1",
+                    "events": [{ "order": 1, "keys": ["0xb"], "data": [] }],
+                    "calls": [{
+                        "contract_address": "0x2",
+                        "events": [{ "order": 0, "keys": ["0xa"], "data": ["0x1"] }],
+                        "calls": []
+                    }]
+                },
+                "fee_transfer_invocation": {
+                    "contract_address": "0x3",
+                    "events": [{ "order": 0, "keys": ["0xc"], "data": [] }],
+                    "calls": []
+                }
+            }
+        });
+
+        let outcome = ExecutionOutcome::from_simulation(&simulation).unwrap();
+
+        assert!(!outcome.reverted);
+        assert_eq!(outcome.fee, FieldElement::from(0x10_u64));
+        assert_eq!(
+            outcome
+                .events
+                .iter()
+                .map(|e| (e.from_address, e.keys[0]))
+                .collect::<Vec<_>>(),
+            vec![
+                (FieldElement::TWO, FieldElement::from(0xa_u64)),
+                (FieldElement::ONE, FieldElement::from(0xb_u64)),
+                (FieldElement::THREE, FieldElement::from(0xc_u64)),
+            ]
+        );
+    }
+}
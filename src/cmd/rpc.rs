@@ -42,7 +42,7 @@ impl RpcArgs {
 
         let mut vec = Vec::new();
         if raw {
-            if let Some(p) = params.get(0) {
+            if let Some(p) = params.first() {
                 vec.push(serde_json::from_str(p)?)
             }
         } else {
@@ -100,4 +100,16 @@ mod tests {
             }
         };
     }
+
+    #[test]
+    fn raw_params_flag() {
+        // `--raw` belongs to rpc, not to the display options
+        let args = App::parse_from(["probe", "rpc", "starknet_chainId", "[]", "--raw"]);
+        assert!(!args.display.no_decode);
+
+        match args.command {
+            Commands::Rpc(args) => assert!(args.raw),
+            _ => unreachable!(),
+        };
+    }
 }
@@ -1,31 +1,115 @@
-use crate::cmd::account::simple_account::Account;
+use crate::cmd::account::simple_account::{Account, SimpleAccount};
+use crate::cmd::account::v3::V3Fees;
+use crate::cmd::account::variant::AccountVariant;
+use crate::cmd::parser::AddressParser;
+use crate::opts::account::descriptor::AccountDescriptor;
 use crate::opts::account::WalletOptions;
-use crate::opts::starknet::StarkNetOptions;
-use crate::opts::transaction::TransactionOptions;
+use crate::opts::config::Config;
+use crate::opts::starknet::{StarkNetOptions, StarknetChain};
+use crate::opts::transaction::{eth_fee_token_only, pre_v3_version_only, TransactionOptions};
+use crate::probe::abi::Abi;
+use crate::probe::addressbook::AddressOrName;
+use crate::probe::nonce::NonceManager;
+use crate::probe::utils::parse_contract_artifact;
+use crate::probe::{Probe, SimpleProbe};
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
 
 use clap::Parser;
 use eyre::{bail, eyre, Result};
+use serde_json::{json, Value};
 use starknet::accounts::Call;
+use starknet::core::crypto::pedersen_hash;
 use starknet::core::types::FieldElement;
-use starknet::core::utils::get_selector_from_name;
-use starknet::providers::jsonrpc::models::{BroadcastedTransaction, InvokeTransactionResult};
-use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+use starknet::core::utils::{get_contract_address, get_selector_from_name};
+use starknet::providers::jsonrpc::models::{
+    BlockId, BlockTag, BroadcastedTransaction, ContractClass, DeclareTransactionResult,
+    DeployAccountTransactionResult, ErrorCode, FeeEstimate, InvokeTransactionResult,
+    MaybePendingTransactionReceipt, TransactionReceipt, TransactionStatus,
+};
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient, JsonRpcClientError, RpcError};
+use starknet::signers::SigningKey;
+
+// Universal Deployer Contract address on mainnet, testnet, testnet2
+const UDC_ADDRESS: FieldElement = FieldElement::from_mont([
+    15144800532519055890,
+    15685625669053253235,
+    9333317513348225193,
+    121672436446604875,
+]);
 
 #[derive(Debug, Parser)]
 pub struct InvokeArgs {
     #[clap(long)]
     #[clap(value_name = "CONTRACT_ADDRESS")]
-    pub to: FieldElement,
+    #[clap(required_unless_present = "calls")]
+    #[clap(value_parser(AddressParser))]
+    pub to: Option<AddressOrName>,
 
     #[clap(long)]
     #[clap(value_name = "FUNCTION_NAME")]
-    pub function: String,
+    #[clap(required_unless_present = "calls")]
+    pub function: Option<String>,
 
     #[clap(long)]
     #[clap(value_delimiter = ',')]
     #[clap(help = "Comma seperated values e.g., 0x12345,0x69420,...")]
     pub calldata: Vec<FieldElement>,
 
+    #[clap(long)]
+    #[clap(value_name = "PATH")]
+    #[clap(requires_all = ["to", "function", "input"])]
+    #[clap(help = "Path to the contract's abi file, used to encode --input.")]
+    pub abi: Option<PathBuf>,
+
+    #[clap(long)]
+    #[clap(num_args = 1..)]
+    #[clap(requires = "abi")]
+    #[clap(conflicts_with = "calldata")]
+    #[clap(
+        help = "Typed values encoded according to the abi e.g., recipient=0x123 amount=u256:1000"
+    )]
+    pub input: Vec<String>,
+
+    #[clap(long = "call")]
+    #[clap(value_name = "CALL")]
+    #[clap(conflicts_with_all = ["to", "function", "calldata"])]
+    #[clap(
+        help = r#"Call to include in the transaction. Can be repeated to batch multiple calls.
+        example : --call "<contract address> <function name> [<calldata> ...]""#
+    )]
+    #[clap(
+        long_help = r#"Call to include in the transaction. Can be repeated to batch multiple calls, or a list of calls seperated with a hyphen, -
+        example : --call "<contract address> <function name> [<calldata> ...] - ...""#
+    )]
+    pub calls: Vec<String>,
+
+    #[clap(long)]
+    #[clap(requires_all = ["nonce", "max_fee"])]
+    #[clap(conflicts_with = "wait")]
+    #[clap(
+        help = "Sign the transaction without sending it and output the broadcastable JSON payload. Requires --nonce and --max-fee."
+    )]
+    pub sign_only: bool,
+
+    #[clap(long = "out")]
+    #[clap(value_name = "PATH")]
+    #[clap(requires = "sign_only")]
+    #[clap(
+        help = "Write the signed transaction to a file instead of stdout. Used with --sign-only."
+    )]
+    pub out: Option<PathBuf>,
+
+    #[clap(long)]
+    #[clap(conflicts_with_all = ["sign_only", "wait"])]
+    #[clap(
+        help = "Simulate the signed transaction and print its execution trace and estimated fee, without sending it."
+    )]
+    pub dry_run: bool,
+
     #[clap(flatten)]
     #[clap(next_help_heading = "STARKNET OPTIONS")]
     pub starknet: StarkNetOptions,
@@ -40,56 +124,777 @@ pub struct InvokeArgs {
 }
 
 impl InvokeArgs {
-    pub async fn run(self) -> Result<InvokeTransactionResult> {
+    /// Returns the invoke transaction result along with its receipt if `--wait` is specified.
+    pub async fn run(
+        self,
+    ) -> Result<(
+        InvokeTransactionResult,
+        Option<MaybePendingTransactionReceipt>,
+    )> {
+        let (account, calls, transaction) = self.build().await?;
+
+        let nonce = get_nonce(&account, &transaction).await?;
+        let res = execute(&account, &calls, nonce, &transaction).await?;
+        let receipt =
+            wait_if_requested(account.get_provider()?, res.transaction_hash, &transaction).await?;
+
+        Ok((res, receipt))
+    }
+
+    pub async fn estimate_fee(self) -> Result<FeeEstimate> {
+        let (account, calls, transaction) = self.build().await?;
+        let nonce = get_nonce(&account, &transaction).await?;
+        estimate_invoke(&account, &calls, nonce, &transaction).await
+    }
+
+    /// Simulates the fully built and signed transaction against the pending block instead of
+    /// sending it. The nonce is not committed.
+    pub async fn simulate(self, skip_validate: bool, skip_fee_charge: bool) -> Result<Value> {
+        let (account, calls, transaction) = self.build().await?;
+
+        let nonce = get_nonce(&account, &transaction).await?;
+        let request = prepare_invoke(&account, &calls, nonce, &transaction).await?;
+
+        Probe::new(account.get_rpc_url()?.clone())
+            .simulate_transaction(
+                request,
+                &BlockId::Tag(BlockTag::Pending),
+                skip_validate,
+                skip_fee_charge,
+            )
+            .await
+    }
+
+    /// Signs the transaction without sending it. No request is made to the network.
+    pub async fn sign(self) -> Result<BroadcastedTransaction> {
+        let (account, calls, transaction) = self.build().await?;
+        let (nonce, max_fee) = get_offline_fields(&transaction)?;
+
+        if transaction.is_v3() {
+            bail!("only v1 transactions can be signed offline")
+        }
+
+        let request = account
+            .prepare_invoke_transaction(&calls, nonce, max_fee)
+            .await?;
+
+        Ok(BroadcastedTransaction::Invoke(request))
+    }
+
+    /// Simulates the transaction as if it was sent by `sender`. Its signature is not validated so
+    /// that no key is needed, and the fee is only charged if `--max-fee` is specified.
+    pub async fn simulate_impersonated(
+        self,
+        sender: FieldElement,
+        skip_fee_charge: bool,
+    ) -> Result<Value> {
+        let calls = self.calls().await?;
         let InvokeArgs {
-            to,
-            function,
-            calldata,
             starknet,
-            wallet,
             transaction,
+            ..
         } = self;
 
-        let Some(mut account) = wallet.build_wallet()? else {
-            bail!("missing wallet")
+        if transaction.is_v3() {
+            bail!("only v1 transactions can be simulated with an impersonated sender")
+        }
+
+        let nonce = match transaction.nonce {
+            Some(nonce) => nonce,
+            None => {
+                JsonRpcClient::new(HttpTransport::new(starknet.rpc_url()))
+                    .get_nonce(&BlockId::Tag(BlockTag::Pending), sender)
+                    .await?
+            }
         };
 
+        let request = json!({
+            "type": "INVOKE",
+            "version": "0x1",
+            "sender_address": sender,
+            "calldata": SimpleProbe::generate_calldata_for_multicall_account(&calls),
+            "max_fee": transaction.max_fee.unwrap_or(FieldElement::ZERO),
+            "signature": [],
+            "nonce": nonce,
+        });
+
+        Probe::new(starknet.rpc_url())
+            .simulate_transaction(
+                request,
+                &BlockId::Tag(BlockTag::Pending),
+                true,
+                skip_fee_charge || transaction.max_fee.is_none(),
+            )
+            .await
+    }
+
+    async fn build(self) -> Result<(SimpleAccount, Vec<Call>, TransactionOptions)> {
+        let calls = self.calls().await?;
+        let account = build_account(&self.wallet, self.starknet, self.sign_only).await?;
+        Ok((account, calls, self.transaction))
+    }
+
+    async fn calls(&self) -> Result<Vec<Call>> {
+        match (&self.to, &self.function) {
+            (Some(to), Some(function)) => Ok(vec![Call {
+                to: self.starknet.resolve_address(to).await?,
+                selector: get_selector_from_name(function)?,
+                calldata: match self.abi {
+                    Some(ref abi) => {
+                        Abi::from_file(abi)?.encode_function_inputs(function, &self.input)?
+                    }
+                    None => self.calldata.clone(),
+                },
+            }]),
+            _ => SimpleProbe::parse_calls(&self.calls.join(" - ")),
+        }
+    }
+}
+
+/// Declares a legacy (Cairo 0) contract class, which can only be done with a v1 transaction, so
+/// paying the fee in STRK is rejected when parsing the arguments.
+#[derive(Debug, Parser)]
+#[clap(mut_arg("fee_token", eth_fee_token_only))]
+#[clap(mut_arg("version", pre_v3_version_only))]
+pub struct DeclareArgs {
+    #[clap(value_name = "CONTRACT")]
+    #[clap(help = "Path to the compiled contract artifact")]
+    pub contract: PathBuf,
+
+    #[clap(long)]
+    #[clap(requires_all = ["nonce", "max_fee"])]
+    #[clap(conflicts_with = "wait")]
+    #[clap(
+        help = "Sign the transaction without sending it and output the broadcastable JSON payload. Requires --nonce and --max-fee."
+    )]
+    pub sign_only: bool,
+
+    #[clap(long = "out")]
+    #[clap(value_name = "PATH")]
+    #[clap(requires = "sign_only")]
+    #[clap(
+        help = "Write the signed transaction to a file instead of stdout. Used with --sign-only."
+    )]
+    pub out: Option<PathBuf>,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "STARKNET OPTIONS")]
+    pub starknet: StarkNetOptions,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "Wallet OPTIONS")]
+    pub wallet: WalletOptions,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "TRANSACTION OPTIONS")]
+    pub transaction: TransactionOptions,
+}
+
+impl DeclareArgs {
+    /// Returns the declare transaction result along with its receipt if `--wait` is specified.
+    pub async fn run(
+        self,
+    ) -> Result<(
+        DeclareTransactionResult,
+        Option<MaybePendingTransactionReceipt>,
+    )> {
+        let (account, class_hash, contract_class, transaction) = self.build().await?;
+
+        let nonce = get_nonce(&account, &transaction).await?;
+        let max_fee =
+            get_declare_max_fee(&account, &contract_class, class_hash, nonce, &transaction).await?;
+
+        let request = account
+            .prepare_declare_transaction(&contract_class, class_hash, nonce, max_fee)
+            .await?;
+
+        let res = account
+            .send_declare_transaction(&request)
+            .await
+            .map_err(|e| eyre!(e))?;
+
+        commit_nonce(&account, nonce, res.transaction_hash, &transaction).await?;
+
+        let receipt =
+            wait_if_requested(account.get_provider()?, res.transaction_hash, &transaction).await?;
+
+        Ok((res, receipt))
+    }
+
+    /// Simulates the fully built and signed transaction against the pending block instead of
+    /// sending it. The nonce is not committed.
+    pub async fn simulate(self, skip_validate: bool, skip_fee_charge: bool) -> Result<Value> {
+        let (account, class_hash, contract_class, transaction) = self.build().await?;
+
+        let nonce = get_nonce(&account, &transaction).await?;
+        let max_fee =
+            get_declare_max_fee(&account, &contract_class, class_hash, nonce, &transaction).await?;
+
+        let request = account
+            .prepare_declare_transaction(&contract_class, class_hash, nonce, max_fee)
+            .await?;
+
+        Probe::new(account.get_rpc_url()?.clone())
+            .simulate_transaction(
+                serde_json::to_value(BroadcastedTransaction::Declare(request))?,
+                &BlockId::Tag(BlockTag::Pending),
+                skip_validate,
+                skip_fee_charge,
+            )
+            .await
+    }
+
+    pub async fn estimate_fee(self) -> Result<FeeEstimate> {
+        let (account, class_hash, contract_class, transaction) = self.build().await?;
+
+        let nonce = get_nonce(&account, &transaction).await?;
+        let request = account
+            .prepare_declare_transaction(&contract_class, class_hash, nonce, FieldElement::ZERO)
+            .await?;
+
         account
-            .provider
-            .get_or_insert(JsonRpcClient::new(HttpTransport::new(starknet.rpc_url)));
+            .estimate_fee(&BroadcastedTransaction::Declare(request))
+            .await
+            .map_err(|e| eyre!(e))
+    }
+
+    /// Signs the transaction without sending it. No request is made to the network.
+    pub async fn sign(self) -> Result<BroadcastedTransaction> {
+        let (account, class_hash, contract_class, transaction) = self.build().await?;
+        let (nonce, max_fee) = get_offline_fields(&transaction)?;
+
+        let request = account
+            .prepare_declare_transaction(&contract_class, class_hash, nonce, max_fee)
+            .await?;
+
+        Ok(BroadcastedTransaction::Declare(request))
+    }
+
+    async fn build(
+        self,
+    ) -> Result<(
+        SimpleAccount,
+        FieldElement,
+        ContractClass,
+        TransactionOptions,
+    )> {
+        let DeclareArgs {
+            contract,
+            sign_only,
+            starknet,
+            wallet,
+            transaction,
+            ..
+        } = self;
+
+        let account = build_account(&wallet, starknet, sign_only).await?;
+        let (class_hash, contract_class) = parse_contract_artifact(contract)?;
+
+        Ok((account, class_hash, contract_class, transaction))
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct DeployArgs {
+    #[clap(value_name = "CLASS_HASH")]
+    #[clap(help = "The hash of the declared class to deploy")]
+    pub class_hash: FieldElement,
+
+    #[clap(value_name = "CONSTRUCTOR_CALLDATA")]
+    #[clap(help = "The inputs passed to the constructor")]
+    pub constructor_calldata: Vec<FieldElement>,
+
+    #[clap(long)]
+    #[clap(
+        help = "The salt used to compute the contract address. Randomly generated if not provided."
+    )]
+    pub salt: Option<FieldElement>,
+
+    #[clap(long)]
+    #[clap(help = "Make the contract address unique to the deploying account")]
+    pub unique: bool,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "STARKNET OPTIONS")]
+    pub starknet: StarkNetOptions,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "Wallet OPTIONS")]
+    pub wallet: WalletOptions,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "TRANSACTION OPTIONS")]
+    pub transaction: TransactionOptions,
+}
+
+impl DeployArgs {
+    /// Returns the address of the deployed contract along with the invoke transaction result, and
+    /// its receipt if `--wait` is specified.
+    pub async fn run(
+        self,
+    ) -> Result<(
+        FieldElement,
+        InvokeTransactionResult,
+        Option<MaybePendingTransactionReceipt>,
+    )> {
+        let (account, contract_address, call, transaction) = self.build().await?;
+
+        let nonce = get_nonce(&account, &transaction).await?;
+        let res = execute(&account, &[call], nonce, &transaction).await?;
+        let receipt =
+            wait_if_requested(account.get_provider()?, res.transaction_hash, &transaction).await?;
+
+        Ok((contract_address, res, receipt))
+    }
+
+    pub async fn estimate_fee(self) -> Result<FeeEstimate> {
+        let (account, _, call, transaction) = self.build().await?;
+        let nonce = get_nonce(&account, &transaction).await?;
+        estimate_invoke(&account, &[call], nonce, &transaction).await
+    }
+
+    /// Returns the account, the address of the contract to be deployed and the call to the UDC.
+    async fn build(self) -> Result<(SimpleAccount, FieldElement, Call, TransactionOptions)> {
+        let DeployArgs {
+            class_hash,
+            constructor_calldata,
+            salt,
+            unique,
+            starknet,
+            wallet,
+            transaction,
+        } = self;
+
+        let account = build_account(&wallet, starknet, false).await?;
+
+        let salt = salt.unwrap_or_else(|| SigningKey::from_random().secret_scalar());
+
+        let contract_address = if unique {
+            get_contract_address(
+                pedersen_hash(&account.account, &salt),
+                class_hash,
+                &constructor_calldata,
+                UDC_ADDRESS,
+            )
+        } else {
+            get_contract_address(salt, class_hash, &constructor_calldata, FieldElement::ZERO)
+        };
+
+        let mut calldata = vec![
+            class_hash,
+            salt,
+            if unique {
+                FieldElement::ONE
+            } else {
+                FieldElement::ZERO
+            },
+            constructor_calldata.len().into(),
+        ];
+        calldata.extend(constructor_calldata);
 
         let call = Call {
-            to,
-            selector: get_selector_from_name(&function)?,
+            to: UDC_ADDRESS,
+            selector: get_selector_from_name("deployContract")?,
             calldata,
         };
 
-        let nonce = match transaction.nonce {
-            Some(nonce) => nonce,
-            None => account.get_nonce().await?,
+        Ok((account, contract_address, call, transaction))
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct SendRawArgs {
+    #[clap(value_name = "PATH")]
+    #[clap(help = "Path to the signed transaction JSON. Read from stdin if not provided.")]
+    pub path: Option<PathBuf>,
+
+    #[clap(long)]
+    #[clap(help = "Wait for the transaction to be accepted and print its receipt")]
+    pub wait: bool,
+
+    #[clap(long)]
+    #[clap(requires = "wait")]
+    #[clap(value_name = "N")]
+    #[clap(
+        help = "Number of blocks to wait for on top of the transaction's block. Used with --wait."
+    )]
+    pub confirmations: Option<u64>,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "STARKNET OPTIONS")]
+    pub starknet: StarkNetOptions,
+}
+
+#[derive(Debug)]
+pub enum RawTransactionResult {
+    Invoke(InvokeTransactionResult),
+    Declare(DeclareTransactionResult),
+    DeployAccount(DeployAccountTransactionResult),
+}
+
+impl RawTransactionResult {
+    pub fn transaction_hash(&self) -> FieldElement {
+        match self {
+            Self::Invoke(res) => res.transaction_hash,
+            Self::Declare(res) => res.transaction_hash,
+            Self::DeployAccount(res) => res.transaction_hash,
+        }
+    }
+}
+
+impl SendRawArgs {
+    /// Broadcasts a pre-signed transaction, e.g. one produced with `--sign-only`.
+    pub async fn run(
+        self,
+    ) -> Result<(RawTransactionResult, Option<MaybePendingTransactionReceipt>)> {
+        let SendRawArgs {
+            path,
+            wait,
+            confirmations,
+            starknet,
+        } = self;
+
+        let json = match path {
+            Some(path) => fs::read_to_string(path)?,
+            None => io::read_to_string(io::stdin())?,
         };
 
-        let max_fee = match transaction.max_fee {
-            Some(ref fee) => fee.to_owned(),
-            None => {
-                let request = account
-                    .prepare_invoke_transaction(&[call.clone()], nonce, FieldElement::ZERO)
-                    .await?;
-
-                account
-                    .get_max_fee(&BroadcastedTransaction::Invoke(request))
-                    .await
-                    .map(FieldElement::from)?
-            }
+        let transaction: BroadcastedTransaction = serde_json::from_str(&json)?;
+        let provider = JsonRpcClient::new(HttpTransport::new(starknet.rpc_url()));
+
+        let res = match transaction {
+            BroadcastedTransaction::Invoke(tx) => provider
+                .add_invoke_transaction(&tx)
+                .await
+                .map(RawTransactionResult::Invoke)?,
+            BroadcastedTransaction::Declare(tx) => provider
+                .add_declare_transaction(&tx)
+                .await
+                .map(RawTransactionResult::Declare)?,
+            BroadcastedTransaction::DeployAccount(tx) => provider
+                .add_deploy_account_transaction(&tx)
+                .await
+                .map(RawTransactionResult::DeployAccount)?,
+        };
+
+        let receipt = if wait {
+            let confirmations = confirmations.unwrap_or_default();
+            Some(wait_for_transaction(&provider, res.transaction_hash(), confirmations).await?)
+        } else {
+            None
         };
 
+        Ok((res, receipt))
+    }
+}
+
+/// Builds the account from the wallet options. An offline account has no provider, so the chain
+/// it signs for must be known beforehand, either from `--chain` or from the keystore.
+///
+/// The address of the account is taken from the account descriptor when it is not given, in which
+/// case the key of the wallet must be the one the descriptor was made for.
+pub async fn build_account(
+    wallet: &WalletOptions,
+    starknet: StarkNetOptions,
+    offline: bool,
+) -> Result<SimpleAccount> {
+    // the account of the config file is used unless one is given with the flags or the env
+    let defaults = Config::load()?.account;
+    let descriptor_path = starknet
+        .account_descriptor
+        .clone()
+        .or_else(|| defaults.descriptor());
+
+    let descriptor = match (descriptor_path, &wallet.account) {
+        (Some(path), None) => Some(AccountDescriptor::load(path)?),
+        _ => None,
+    };
+
+    let mut wallet = wallet.clone();
+    if !wallet.has_wallet() {
+        wallet.keystore_path = defaults.keystore();
+    }
+    if let Some(account) = &wallet.account {
+        wallet.account = Some(starknet.resolve_address(account).await?.into());
+    }
+    if let Some(descriptor) = &descriptor {
+        wallet.account = Some(descriptor.address.into());
+    }
+
+    let Some(mut account) = wallet.build_wallet()? else {
+        bail!("missing wallet")
+    };
+
+    account.variant = wallet
+        .variant
+        .or(descriptor.as_ref().map(|descriptor| descriptor.variant))
+        .unwrap_or(AccountVariant::OpenZeppelin);
+
+    if let Some(descriptor) = descriptor {
+        let public_key = SigningKey::from_secret_scalar(account.get_signing_key())
+            .verifying_key()
+            .scalar();
+
+        if public_key != descriptor.public_key {
+            bail!(
+                "the key of the wallet does not control the account {:#x}",
+                descriptor.address
+            )
+        }
+    }
+
+    if offline {
+        if let Some(chain) = starknet.chain() {
+            account.chain = Some(StarknetChain::try_from(chain)?);
+        }
+
+        if account.chain.is_none() {
+            bail!("the chain must be specified with --chain to sign offline")
+        }
+    } else {
+        account
+            .provider
+            .get_or_insert(JsonRpcClient::new(HttpTransport::new(starknet.rpc_url())));
+        account.rpc_url.get_or_insert(starknet.rpc_url());
+    }
+
+    Ok(account)
+}
+
+fn get_offline_fields(transaction: &TransactionOptions) -> Result<(FieldElement, FieldElement)> {
+    match (transaction.nonce, transaction.max_fee) {
+        (Some(nonce), Some(max_fee)) => Ok((nonce, max_fee)),
+        _ => bail!("--nonce and --max-fee are required to sign offline"),
+    }
+}
+
+/// Writes the signed transaction as JSON to the given file, or to `out` if none is given.
+pub fn write_transaction(
+    transaction: &BroadcastedTransaction,
+    output: Option<PathBuf>,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let json = serde_json::to_string_pretty(transaction)?;
+
+    match output {
+        Some(path) => fs::write(path, json)?,
+        None => writeln!(out, "{json}")?,
+    }
+
+    Ok(())
+}
+
+pub async fn get_nonce(
+    account: &SimpleAccount,
+    transaction: &TransactionOptions,
+) -> Result<FieldElement> {
+    match transaction.nonce {
+        Some(nonce) => Ok(nonce),
+        None => {
+            let manager = NonceManager::new(account.get_provider()?, transaction.persist_nonce)?;
+            if transaction.reset_nonce {
+                manager.reset(account.account).await?;
+            }
+            manager
+                .next_nonce(
+                    account.account,
+                    transaction.nonce_offset.unwrap_or_default(),
+                )
+                .await
+        }
+    }
+}
+
+/// Records the nonce as used by the transaction if `--persist-nonce` is specified.
+async fn commit_nonce(
+    account: &SimpleAccount,
+    nonce: FieldElement,
+    transaction_hash: FieldElement,
+    transaction: &TransactionOptions,
+) -> Result<()> {
+    NonceManager::new(account.get_provider()?, transaction.persist_nonce)?
+        .commit(account.account, nonce, transaction_hash)
+        .await
+}
+
+async fn estimate_invoke(
+    account: &SimpleAccount,
+    calls: &[Call],
+    nonce: FieldElement,
+    transaction: &TransactionOptions,
+) -> Result<FeeEstimate> {
+    if transaction.is_v3() {
+        let calldata = account.variant.encode_calls(calls);
         let request = account
-            .prepare_invoke_transaction(&[call], nonce, max_fee)
+            .prepare_invoke_transaction_v3(&calldata, nonce, V3Fees::from(transaction), true)
             .await?;
 
-        account
-            .send_invoke_transaction(&request)
+        return account.estimate_fee_v3(&request).await?.try_into();
+    }
+
+    let request = account
+        .prepare_invoke_transaction(calls, nonce, FieldElement::ZERO)
+        .await?;
+
+    account
+        .estimate_fee(&BroadcastedTransaction::Invoke(request))
+        .await
+        .map_err(|e| eyre!(e))
+}
+
+/// Signs and sends an invoke transaction from the account, estimating the max fee if it is not
+/// specified in the transaction options.
+pub async fn execute(
+    account: &SimpleAccount,
+    calls: &[Call],
+    nonce: FieldElement,
+    transaction: &TransactionOptions,
+) -> Result<InvokeTransactionResult> {
+    if transaction.is_v3() {
+        let res = account.execute_v3(calls, nonce, transaction).await?;
+        commit_nonce(account, nonce, res.transaction_hash, transaction).await?;
+        return Ok(res);
+    }
+
+    let max_fee = get_invoke_max_fee(account, calls, nonce, transaction).await?;
+
+    let request = account
+        .prepare_invoke_transaction(calls, nonce, max_fee)
+        .await?;
+
+    let res = account
+        .send_invoke_transaction(&request)
+        .await
+        .map_err(|e| eyre!(e))?;
+
+    commit_nonce(account, nonce, res.transaction_hash, transaction).await?;
+
+    Ok(res)
+}
+
+/// Builds and signs the invoke transaction, returning its JSON-RPC payload.
+async fn prepare_invoke(
+    account: &SimpleAccount,
+    calls: &[Call],
+    nonce: FieldElement,
+    transaction: &TransactionOptions,
+) -> Result<Value> {
+    if transaction.is_v3() {
+        return account.build_invoke_v3(calls, nonce, transaction).await;
+    }
+
+    let max_fee = get_invoke_max_fee(account, calls, nonce, transaction).await?;
+    let request = account
+        .prepare_invoke_transaction(calls, nonce, max_fee)
+        .await?;
+
+    Ok(serde_json::to_value(BroadcastedTransaction::Invoke(
+        request,
+    ))?)
+}
+
+async fn get_invoke_max_fee(
+    account: &SimpleAccount,
+    calls: &[Call],
+    nonce: FieldElement,
+    transaction: &TransactionOptions,
+) -> Result<FieldElement> {
+    match transaction.max_fee {
+        Some(fee) => Ok(fee),
+        None => estimate_invoke(account, calls, nonce, transaction)
             .await
-            .map_err(|e| eyre!(e))
+            .and_then(|fee| transaction.max_fee_from_estimate(fee.overall_fee)),
+    }
+}
+
+async fn get_declare_max_fee(
+    account: &SimpleAccount,
+    contract_class: &ContractClass,
+    class_hash: FieldElement,
+    nonce: FieldElement,
+    transaction: &TransactionOptions,
+) -> Result<FieldElement> {
+    if let Some(fee) = transaction.max_fee {
+        return Ok(fee);
     }
+
+    let request = account
+        .prepare_declare_transaction(contract_class, class_hash, nonce, FieldElement::ZERO)
+        .await?;
+
+    let fee = account
+        .get_max_fee(&BroadcastedTransaction::Declare(request))
+        .await?;
+
+    transaction.max_fee_from_estimate(fee)
+}
+
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(16);
+
+/// Polls the receipt of the transaction, with an exponential backoff, until it is either accepted
+/// on L2 or rejected. Once accepted, waits for `confirmations` more blocks to be built on top of
+/// the transaction's block.
+pub async fn wait_for_transaction(
+    provider: &JsonRpcClient<HttpTransport>,
+    transaction_hash: FieldElement,
+    confirmations: u64,
+) -> Result<MaybePendingTransactionReceipt> {
+    let mut interval = Duration::from_secs(1);
+
+    let (receipt, block_number) = loop {
+        match provider.get_transaction_receipt(transaction_hash).await {
+            Ok(MaybePendingTransactionReceipt::Receipt(receipt)) => {
+                let (status, block_number) = match &receipt {
+                    TransactionReceipt::Invoke(r) => (r.status, r.block_number),
+                    TransactionReceipt::Declare(r) => (r.status, r.block_number),
+                    TransactionReceipt::Deploy(r) => (r.status, r.block_number),
+                    TransactionReceipt::DeployAccount(r) => (r.status, r.block_number),
+                    TransactionReceipt::L1Handler(r) => (r.status, r.block_number),
+                };
+
+                match status {
+                    TransactionStatus::AcceptedOnL2 | TransactionStatus::AcceptedOnL1 => {
+                        break (receipt, block_number)
+                    }
+                    TransactionStatus::Rejected => {
+                        bail!("transaction {transaction_hash:#x} was rejected")
+                    }
+                    TransactionStatus::Pending => {}
+                }
+            }
+
+            // the transaction may not be known to the node yet
+            Ok(MaybePendingTransactionReceipt::PendingReceipt(_))
+            | Err(JsonRpcClientError::RpcError(RpcError::Code(
+                ErrorCode::TransactionHashNotFound,
+            ))) => {}
+
+            Err(e) => return Err(e.into()),
+        }
+
+        tokio::time::sleep(interval).await;
+        interval = (interval * 2).min(MAX_POLL_INTERVAL);
+    };
+
+    while provider.block_number().await? < block_number + confirmations {
+        tokio::time::sleep(interval).await;
+    }
+
+    Ok(MaybePendingTransactionReceipt::Receipt(receipt))
+}
+
+/// Waits for the transaction if `--wait` is specified in the transaction options.
+pub async fn wait_if_requested(
+    provider: &JsonRpcClient<HttpTransport>,
+    transaction_hash: FieldElement,
+    transaction: &TransactionOptions,
+) -> Result<Option<MaybePendingTransactionReceipt>> {
+    if !transaction.wait {
+        return Ok(None);
+    }
+
+    let confirmations = transaction.confirmations.unwrap_or_default();
+    wait_for_transaction(provider, transaction_hash, confirmations)
+        .await
+        .map(Some)
 }
@@ -0,0 +1,254 @@
+use super::account::simple_account::{Account, SimpleAccount};
+use super::send::{build_account, execute, get_nonce, wait_if_requested};
+use crate::opts::account::WalletOptions;
+use crate::opts::starknet::StarkNetOptions;
+use crate::opts::transaction::TransactionOptions;
+use crate::probe::utils::fmt::{Pretty, ValueFormat};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use comfy_table::modifiers::UTF8_SOLID_INNER_BORDERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::Table;
+use eyre::Result;
+use serde::Deserialize;
+use starknet::accounts::Call;
+use starknet::core::types::FieldElement;
+use starknet::core::utils::get_selector_from_name;
+
+#[derive(Debug, Parser)]
+pub struct SendBatchArgs {
+    #[clap(value_name = "PATH")]
+    #[clap(help = "Path to a JSON or TOML file describing the invoke transactions")]
+    #[clap(
+        long_help = r#"Path to a JSON or TOML file describing the invoke transactions. For example:
+
+[[invoke]]
+to = "0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"
+function = "transfer"
+calldata = ["0x1234", "0x64", "0x0"]"#
+    )]
+    pub path: PathBuf,
+
+    #[clap(long)]
+    #[clap(help = "Keep sending the remaining transactions if one of them fails")]
+    pub continue_on_error: bool,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "STARKNET OPTIONS")]
+    pub starknet: StarkNetOptions,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "Wallet OPTIONS")]
+    pub wallet: WalletOptions,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "TRANSACTION OPTIONS")]
+    pub transaction: TransactionOptions,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchFile {
+    invoke: Vec<BatchInvoke>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchInvoke {
+    to: FieldElement,
+    function: String,
+    #[serde(default)]
+    calldata: Vec<FieldElement>,
+}
+
+#[derive(Debug)]
+struct BatchEntry {
+    to: FieldElement,
+    function: String,
+    transaction_hash: Option<FieldElement>,
+    status: String,
+}
+
+#[derive(Debug, Default)]
+pub struct BatchSummary {
+    entries: Vec<BatchEntry>,
+    failed: usize,
+}
+
+impl BatchSummary {
+    pub fn failed(&self) -> usize {
+        self.failed
+    }
+}
+
+impl SendBatchArgs {
+    /// Sends the transactions one after the other, each with the nonce following the previous
+    /// one. Stops at the first failure unless `--continue-on-error` is specified.
+    pub async fn run(self) -> Result<BatchSummary> {
+        let SendBatchArgs {
+            path,
+            continue_on_error,
+            starknet,
+            wallet,
+            transaction,
+        } = self;
+
+        let batch = read_batch_file(path)?;
+        let account = build_account(&wallet, starknet, false).await?;
+
+        let mut nonce = get_nonce(&account, &transaction).await?;
+        let mut summary = BatchSummary::default();
+
+        for invoke in batch.invoke {
+            let res = send(&account, &invoke, nonce, &transaction).await;
+
+            let (transaction_hash, status) = match res {
+                Ok(hash) => {
+                    // the nonce is used up once the transaction is sent, even if it then fails
+                    nonce += FieldElement::ONE;
+
+                    let status = match transaction_status(&account, hash, &transaction).await {
+                        Ok(status) => status,
+                        Err(e) => {
+                            summary.failed += 1;
+                            format!("FAILED: {e}")
+                        }
+                    };
+                    (Some(hash), status)
+                }
+                Err(e) => {
+                    summary.failed += 1;
+                    (None, format!("FAILED: {e}"))
+                }
+            };
+
+            summary.entries.push(BatchEntry {
+                to: invoke.to,
+                function: invoke.function,
+                transaction_hash,
+                status,
+            });
+
+            if summary.failed > 0 && !continue_on_error {
+                break;
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+/// Sends the transaction, returning its hash.
+async fn send(
+    account: &SimpleAccount,
+    invoke: &BatchInvoke,
+    nonce: FieldElement,
+    transaction: &TransactionOptions,
+) -> Result<FieldElement> {
+    let call = Call {
+        to: invoke.to,
+        selector: get_selector_from_name(&invoke.function)?,
+        calldata: invoke.calldata.clone(),
+    };
+
+    let res = execute(account, &[call], nonce, transaction).await?;
+    Ok(res.transaction_hash)
+}
+
+/// The status of a sent transaction, which is only known if `--wait` is specified.
+async fn transaction_status(
+    account: &SimpleAccount,
+    transaction_hash: FieldElement,
+    transaction: &TransactionOptions,
+) -> Result<String> {
+    let receipt = wait_if_requested(account.get_provider()?, transaction_hash, transaction).await?;
+
+    let status = match receipt {
+        Some(receipt) => serde_json::to_value(receipt)?["status"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        None => "RECEIVED".to_string(),
+    };
+
+    Ok(status)
+}
+
+fn read_batch_file<P: AsRef<Path>>(path: P) -> Result<BatchFile> {
+    let content = fs::read_to_string(path.as_ref())?;
+
+    match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => Ok(toml::from_str(&content)?),
+        _ => Ok(serde_json::from_str(&content)?),
+    }
+}
+
+impl Pretty for BatchSummary {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .set_header(vec!["#", "TO", "FUNCTION", "TRANSACTION HASH", "STATUS"]);
+
+        for (i, entry) in self.entries.iter().enumerate() {
+            table.add_row(vec![
+                i.to_string(),
+                entry.to.prettify(fmt),
+                entry.function.clone(),
+                entry
+                    .transaction_hash
+                    .map(|hash| hash.prettify(fmt))
+                    .unwrap_or_default(),
+                entry.status.clone(),
+            ]);
+        }
+
+        format!("{table}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn parse_batch_file() {
+        let toml = r#"
+            [[invoke]]
+            to = "0x1234"
+            function = "transfer"
+            calldata = ["0x1", "0x2"]
+
+            [[invoke]]
+            to = "0x5678"
+            function = "approve"
+        "#;
+
+        let json = r#"{
+            "invoke": [
+                { "to": "0x1234", "function": "transfer", "calldata": ["0x1", "0x2"] },
+                { "to": "0x5678", "function": "approve" }
+            ]
+        }"#;
+
+        for batch in [
+            toml::from_str::<BatchFile>(toml).unwrap(),
+            serde_json::from_str::<BatchFile>(json).unwrap(),
+        ] {
+            assert_eq!(batch.invoke.len(), 2);
+            assert_eq!(
+                batch.invoke[0].to,
+                FieldElement::from_str("0x1234").unwrap()
+            );
+            assert_eq!(batch.invoke[0].function, "transfer");
+            assert_eq!(
+                batch.invoke[0].calldata,
+                vec![FieldElement::ONE, FieldElement::from_str("0x2").unwrap()]
+            );
+            assert!(batch.invoke[1].calldata.is_empty());
+        }
+    }
+}
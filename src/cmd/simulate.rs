@@ -0,0 +1,75 @@
+use super::send::{DeclareArgs, InvokeArgs};
+
+use clap::{Parser, Subcommand};
+use eyre::Result;
+use serde_json::Value;
+use starknet::core::types::FieldElement;
+
+#[derive(Subcommand, Debug)]
+pub enum SimulateCommands {
+    #[clap(about = "Simulate an invoke transaction.")]
+    Invoke {
+        #[clap(flatten)]
+        args: InvokeArgs,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "SIMULATION OPTIONS")]
+        simulation: SimulationOptions,
+
+        #[clap(long)]
+        #[clap(value_name = "ADDRESS")]
+        #[clap(help_heading = "SIMULATION OPTIONS")]
+        #[clap(conflicts_with_all = ["interactive", "private_key", "account", "keystore_path"])]
+        #[clap(
+            help = "Simulate the transaction on behalf of the given account, without its key. Implies --skip-validate."
+        )]
+        impersonate: Option<FieldElement>,
+    },
+
+    #[clap(about = "Simulate a declare transaction.")]
+    Declare {
+        #[clap(flatten)]
+        args: DeclareArgs,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "SIMULATION OPTIONS")]
+        simulation: SimulationOptions,
+    },
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct SimulationOptions {
+    #[clap(long)]
+    #[clap(help = "Skip the execution of the account's __validate__ entrypoint.")]
+    pub skip_validate: bool,
+
+    #[clap(long)]
+    #[clap(help = "Skip charging the transaction fee to the account.")]
+    pub skip_fee_charge: bool,
+}
+
+impl SimulateCommands {
+    /// Returns the simulation result, containing the transaction trace and its fee estimation.
+    pub async fn run(self) -> Result<Value> {
+        match self {
+            Self::Invoke {
+                args,
+                simulation,
+                impersonate: Some(sender),
+            } => {
+                args.simulate_impersonated(sender, simulation.skip_fee_charge)
+                    .await
+            }
+            Self::Invoke {
+                args, simulation, ..
+            } => {
+                args.simulate(simulation.skip_validate, simulation.skip_fee_charge)
+                    .await
+            }
+            Self::Declare { args, simulation } => {
+                args.simulate(simulation.skip_validate, simulation.skip_fee_charge)
+                    .await
+            }
+        }
+    }
+}
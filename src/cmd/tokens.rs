@@ -0,0 +1,39 @@
+use crate::opts::display::DisplayOptions;
+use crate::opts::starknet::StarkNetOptions;
+use crate::probe::tokens::known_tokens;
+use crate::probe::utils::fmt::pretty_tokens;
+
+use std::io::Write;
+
+use clap::Subcommand;
+use eyre::Result;
+
+#[derive(Debug, Subcommand)]
+pub enum TokensCommands {
+    #[clap(visible_alias = "list")]
+    #[clap(about = "List the well-known tokens of the chain of the network.")]
+    Ls {
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+}
+
+impl TokensCommands {
+    pub async fn run(self, display: &DisplayOptions, out: &mut dyn Write) -> Result<()> {
+        match self {
+            Self::Ls { starknet } => {
+                let tokens = known_tokens(starknet.chain_id().await?);
+
+                if display.is_json() {
+                    let json = serde_json::to_string_pretty(&tokens)?;
+                    writeln!(out, "{}", display.render(json)?)?;
+                } else {
+                    writeln!(out, "{}", display.colorize(pretty_tokens(&tokens)))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
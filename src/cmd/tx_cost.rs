@@ -0,0 +1,217 @@
+use crate::opts::starknet::StarkNetOptions;
+use crate::probe::trace::{flatten_calls, numeric_fields, TraceCall};
+use crate::probe::utils::fmt::{format_units, Pretty, ValueFormat};
+use crate::probe::utils::send_rpc_request;
+
+use std::collections::BTreeMap;
+
+use clap::Parser;
+use comfy_table::modifiers::UTF8_SOLID_INNER_BORDERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::Table;
+use eyre::{eyre, Result};
+use serde_json::{json, Value};
+use starknet::core::types::FieldElement;
+
+#[derive(Debug, Parser)]
+pub struct TxCostArgs {
+    #[clap(value_name = "TX_HASH")]
+    #[clap(help = "The hash of the transaction")]
+    pub hash: FieldElement,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "STARKNET OPTIONS")]
+    pub starknet: StarkNetOptions,
+}
+
+#[derive(Debug)]
+pub struct TxCost {
+    fee: u128,
+    // either WEI or FRI, depending on the token the fee was paid in
+    unit: String,
+    resources: BTreeMap<String, u64>,
+    calls: Vec<TraceCall>,
+}
+
+impl TxCostArgs {
+    pub async fn run(self) -> Result<TxCost> {
+        let TxCostArgs { hash, starknet } = self;
+        let url = &starknet.rpc_url();
+
+        let receipt: Value = send_rpc_request(
+            url,
+            "starknet_getTransactionReceipt",
+            json!({ "transaction_hash": hash }),
+        )
+        .await?;
+
+        let trace: Value = send_rpc_request(
+            url,
+            "starknet_traceTransaction",
+            json!({ "transaction_hash": hash }),
+        )
+        .await?;
+
+        TxCost::new(&receipt, &trace)
+    }
+}
+
+impl TxCost {
+    fn new(receipt: &Value, trace: &Value) -> Result<Self> {
+        let actual_fee = &receipt["actual_fee"];
+
+        // the fee is a plain amount in wei in older versions of the spec
+        let (fee, unit) = match actual_fee.as_str() {
+            Some(amount) => (amount, "WEI"),
+            None => (
+                actual_fee["amount"]
+                    .as_str()
+                    .ok_or_else(|| eyre!("missing fee amount"))?,
+                actual_fee["unit"].as_str().unwrap_or("WEI"),
+            ),
+        };
+
+        let mut resources = numeric_fields(&receipt["execution_resources"]);
+        resources.extend(numeric_fields(
+            &receipt["execution_resources"]["data_availability"],
+        ));
+
+        Ok(Self {
+            fee: u128::from_str_radix(fee.trim_start_matches("0x"), 16)?,
+            unit: unit.to_string(),
+            resources,
+            calls: flatten_calls(trace),
+        })
+    }
+
+    /// The share of the fee attributed to the call, in proportion to the number of steps it
+    /// executed. The sequencer charges for the transaction as a whole, so this is an estimate.
+    fn fee_share(&self, call: &TraceCall) -> Option<u128> {
+        let total: u64 = self
+            .calls
+            .iter()
+            .filter(|call| call.depth == 0)
+            .filter_map(|call| call.steps())
+            .sum();
+
+        match (call.steps(), total) {
+            (Some(steps), total) if total > 0 => {
+                Some(self.fee * u128::from(steps) / u128::from(total))
+            }
+            _ => None,
+        }
+    }
+
+    fn token(&self) -> &str {
+        if self.unit == "FRI" {
+            "STRK"
+        } else {
+            "ETH"
+        }
+    }
+}
+
+impl Pretty for TxCost {
+    fn prettify(&self, _: &ValueFormat) -> String {
+        let mut summary = Table::new();
+        summary
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .add_row(vec![format!("FEE ({})", self.unit), self.fee.to_string()])
+            .add_row(vec![
+                format!("FEE ({})", self.token()),
+                format_units(self.fee, 18),
+            ]);
+
+        for (name, value) in &self.resources {
+            summary.add_row(vec![
+                name.replace('_', " ").to_uppercase(),
+                value.to_string(),
+            ]);
+        }
+
+        let mut calls = Table::new();
+        calls
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .set_header(vec![
+                "CALL".to_string(),
+                "STEPS".to_string(),
+                "RESOURCES".to_string(),
+                format!("FEE SHARE ({})", self.unit),
+            ]);
+
+        for call in &self.calls {
+            calls.add_row(vec![
+                format!(
+                    "{}{} :: {}",
+                    "  ".repeat(call.depth),
+                    call.contract_address,
+                    call.selector
+                ),
+                call.steps().map(|s| s.to_string()).unwrap_or_default(),
+                call.resources
+                    .iter()
+                    .filter(|(name, _)| *name != "steps")
+                    .map(|(name, value)| format!("{name}: {value}"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                self.fee_share(call)
+                    .map(|fee| fee.to_string())
+                    .unwrap_or_default(),
+            ]);
+        }
+
+        format!("{summary}\n\n{calls}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_share_by_steps() {
+        let receipt = json!({
+            "actual_fee": { "amount": "0x3e8", "unit": "FRI" },
+            "execution_resources": {
+                "steps": 400,
+                "range_check_builtin_applications": 10,
+                "data_availability": { "l1_gas": 0, "l1_data_gas": 128 }
+            }
+        });
+        let trace = json!({
+            "validate_invocation": {
+                "contract_address": "0x1",
+                "entry_point_selector": "0xa",
+                "execution_resources": { "steps": 100 },
+                "calls": []
+            },
+            "execute_invocation": {
+                "contract_address": "0x1",
+                "entry_point_selector": "0xb",
+                "execution_resources": { "steps": 300, "range_check_builtin_applications": 10 },
+                "calls": [{
+                    "contract_address": "0x2",
+                    "entry_point_selector": "0xc",
+                    "execution_resources": { "steps": 150 },
+                    "calls": []
+                }]
+            }
+        });
+
+        let cost = TxCost::new(&receipt, &trace).unwrap();
+
+        assert_eq!(cost.fee, 1000);
+        assert_eq!(cost.token(), "STRK");
+        assert_eq!(cost.resources["l1_data_gas"], 128);
+        assert_eq!(cost.resources["range_check"], 10);
+        assert_eq!(
+            cost.calls
+                .iter()
+                .map(|call| cost.fee_share(call))
+                .collect::<Vec<_>>(),
+            vec![Some(250), Some(750), Some(375)]
+        );
+    }
+}
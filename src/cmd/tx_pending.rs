@@ -0,0 +1,215 @@
+use crate::opts::display::DisplayOptions;
+use crate::opts::starknet::StarkNetOptions;
+use crate::probe::addressbook::AddressOrName;
+use crate::probe::utils::fmt::{Pretty, ValueFormat};
+use crate::probe::Probe;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::time::{Duration, SystemTime};
+
+use clap::Parser;
+use comfy_table::modifiers::UTF8_SOLID_INNER_BORDERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::Table;
+use eyre::Result;
+use serde::Serialize;
+use starknet::core::types::FieldElement;
+use starknet::providers::jsonrpc::models::{InvokeTransaction, Transaction};
+
+use super::parser::AddressParser;
+
+#[derive(Debug, Parser)]
+pub struct TxPendingArgs {
+    #[clap(long)]
+    #[clap(value_name = "ADDR")]
+    #[clap(value_parser(AddressParser))]
+    #[clap(help = "Only count the transactions sent by this account.")]
+    pub sender: Option<AddressOrName>,
+
+    #[clap(long)]
+    #[clap(conflicts_with = "watch")]
+    #[clap(help = "Print the transactions themselves instead of their statistics.")]
+    pub list: bool,
+
+    #[clap(long)]
+    #[clap(help = "Keep refreshing the statistics until interrupted.")]
+    pub watch: bool,
+
+    #[clap(long)]
+    #[clap(value_name = "SECONDS")]
+    #[clap(default_value_t = 5)]
+    #[clap(requires = "watch")]
+    #[clap(help = "How often the statistics are refreshed with --watch.")]
+    pub interval: u64,
+
+    #[clap(flatten)]
+    #[clap(next_help_heading = "STARKNET OPTIONS")]
+    pub starknet: StarkNetOptions,
+}
+
+/// The statistics of the transactions in the transaction pool.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct MempoolStats {
+    transactions: usize,
+    /// The number of transactions of each type, e.g. `INVOKE`.
+    types: BTreeMap<&'static str, usize>,
+    senders: usize,
+    min_fee: Option<FieldElement>,
+    max_fee: Option<FieldElement>,
+}
+
+impl TxPendingArgs {
+    pub async fn run(self, display: &DisplayOptions, out: &mut dyn Write) -> Result<()> {
+        let probe = Probe::new(self.starknet.rpc_url());
+        let account = match &self.sender {
+            Some(sender) => Some(self.starknet.resolve_address(sender).await?),
+            None => None,
+        };
+
+        loop {
+            let transactions = probe
+                .pending_transactions()
+                .await?
+                .into_iter()
+                .filter(|tx| account.is_none() || sender(tx) == account)
+                .collect::<Vec<_>>();
+
+            if self.list {
+                let transactions = serde_json::to_string_pretty(&transactions)?;
+                writeln!(out, "{}", display.render(transactions)?)?;
+                return Ok(());
+            }
+
+            let stats = MempoolStats::new(&transactions);
+            if self.watch && !display.is_json() {
+                let now = humantime::format_rfc3339_seconds(SystemTime::now());
+                writeln!(out, "{now}")?;
+            }
+            writeln!(out, "{}", display.display(&stats)?)?;
+            out.flush()?;
+
+            if !self.watch {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_secs(self.interval)).await;
+        }
+    }
+}
+
+impl MempoolStats {
+    pub fn new(transactions: &[Transaction]) -> Self {
+        Self::from_summaries(
+            transactions
+                .iter()
+                .map(|tx| (kind(tx), sender(tx), max_fee(tx))),
+        )
+    }
+
+    fn from_summaries(
+        summaries: impl Iterator<Item = (&'static str, Option<FieldElement>, Option<FieldElement>)>,
+    ) -> Self {
+        let mut stats = Self::default();
+        let mut senders = BTreeSet::new();
+
+        for (kind, sender, fee) in summaries {
+            stats.transactions += 1;
+            *stats.types.entry(kind).or_default() += 1;
+            senders.extend(sender);
+
+            if let Some(fee) = fee {
+                stats.min_fee = Some(stats.min_fee.map_or(fee, |min| min.min(fee)));
+                stats.max_fee = Some(stats.max_fee.map_or(fee, |max| max.max(fee)));
+            }
+        }
+
+        stats.senders = senders.len();
+        stats
+    }
+}
+
+impl Pretty for MempoolStats {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
+        let fee = |fee: &Option<FieldElement>| {
+            fee.as_ref().map(|felt| fmt.felt(felt)).unwrap_or_default()
+        };
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .add_row(vec![
+                "TRANSACTIONS".to_string(),
+                self.transactions.to_string(),
+            ]);
+
+        for (kind, count) in &self.types {
+            table.add_row(vec![format!("  {kind}"), count.to_string()]);
+        }
+
+        table
+            .add_row(vec!["SENDERS".to_string(), self.senders.to_string()])
+            .add_row(vec!["MIN FEE".to_string(), fee(&self.min_fee)])
+            .add_row(vec!["MAX FEE".to_string(), fee(&self.max_fee)]);
+
+        format!("{table}")
+    }
+}
+
+fn kind(tx: &Transaction) -> &'static str {
+    match tx {
+        Transaction::Invoke(_) => "INVOKE",
+        Transaction::Declare(_) => "DECLARE",
+        Transaction::Deploy(_) => "DEPLOY",
+        Transaction::L1Handler(_) => "L1_HANDLER",
+        Transaction::DeployAccount(_) => "DEPLOY_ACCOUNT",
+    }
+}
+
+/// The account that sent the transaction. Deployments and L1 handlers have none.
+fn sender(tx: &Transaction) -> Option<FieldElement> {
+    match tx {
+        Transaction::Invoke(InvokeTransaction::V1(tx)) => Some(tx.sender_address),
+        Transaction::Invoke(InvokeTransaction::V0(tx)) => Some(tx.contract_address),
+        Transaction::Declare(tx) => Some(tx.sender_address),
+        _ => None,
+    }
+}
+
+fn max_fee(tx: &Transaction) -> Option<FieldElement> {
+    match tx {
+        Transaction::Invoke(InvokeTransaction::V1(tx)) => Some(tx.max_fee),
+        Transaction::Invoke(InvokeTransaction::V0(tx)) => Some(tx.max_fee),
+        Transaction::Declare(tx) => Some(tx.max_fee),
+        Transaction::DeployAccount(tx) => Some(tx.max_fee),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_of_pending_transactions() {
+        let felt = FieldElement::from;
+        let stats = MempoolStats::from_summaries(
+            [
+                ("INVOKE", Some(felt(1u64)), Some(felt(300u64))),
+                ("INVOKE", Some(felt(1u64)), Some(felt(100u64))),
+                ("DECLARE", Some(felt(2u64)), Some(felt(500u64))),
+                ("L1_HANDLER", None, None),
+            ]
+            .into_iter(),
+        );
+
+        assert_eq!(stats.transactions, 4);
+        assert_eq!(
+            stats.types,
+            BTreeMap::from([("DECLARE", 1), ("INVOKE", 2), ("L1_HANDLER", 1)])
+        );
+        assert_eq!(stats.senders, 2);
+        assert_eq!(stats.min_fee, Some(felt(100u64)));
+        assert_eq!(stats.max_fee, Some(felt(500u64)));
+    }
+}
@@ -0,0 +1,128 @@
+use super::parser::U256Parser;
+use crate::probe::utils::fmt::ValueFormat;
+
+use clap::Subcommand;
+use crypto_bigint::{CheckedAdd, CheckedMul, CheckedSub, U128, U256};
+use eyre::{eyre, Result};
+
+#[derive(Debug, Subcommand)]
+pub enum U256Commands {
+    #[clap(about = "Add two u256, failing on overflow.")]
+    Add {
+        #[clap(value_name = "X")]
+        #[clap(value_parser(U256Parser))]
+        x: (u128, u128),
+        #[clap(value_name = "Y")]
+        #[clap(value_parser(U256Parser))]
+        y: (u128, u128),
+    },
+
+    #[clap(about = "Subtract a u256 from another, failing on underflow.")]
+    Sub {
+        #[clap(value_name = "X")]
+        #[clap(value_parser(U256Parser))]
+        x: (u128, u128),
+        #[clap(value_name = "Y")]
+        #[clap(value_parser(U256Parser))]
+        y: (u128, u128),
+    },
+
+    #[clap(about = "Multiply two u256, failing on overflow.")]
+    Mul {
+        #[clap(value_name = "X")]
+        #[clap(value_parser(U256Parser))]
+        x: (u128, u128),
+        #[clap(value_name = "Y")]
+        #[clap(value_parser(U256Parser))]
+        y: (u128, u128),
+    },
+
+    #[clap(about = "Divide a u256 by another, rounding down.")]
+    Div {
+        #[clap(value_name = "X")]
+        #[clap(value_parser(U256Parser))]
+        x: (u128, u128),
+        #[clap(value_name = "Y")]
+        #[clap(value_parser(U256Parser))]
+        y: (u128, u128),
+    },
+}
+
+impl U256Commands {
+    pub fn run(self, fmt: &ValueFormat) -> Result<String> {
+        let result: U256 = match self {
+            Self::Add { x, y } => Option::from(to_uint(x).checked_add(&to_uint(y)))
+                .ok_or_else(|| eyre!("u256 addition overflow"))?,
+            Self::Sub { x, y } => Option::from(to_uint(x).checked_sub(&to_uint(y)))
+                .ok_or_else(|| eyre!("u256 subtraction underflow"))?,
+            Self::Mul { x, y } => Option::from(to_uint(x).checked_mul(&to_uint(y)))
+                .ok_or_else(|| eyre!("u256 multiplication overflow"))?,
+            Self::Div { x, y } => Option::from(to_uint(x).checked_div(&to_uint(y)))
+                .ok_or_else(|| eyre!("division by zero"))?,
+        };
+
+        let (high, low) = result.split();
+        Ok(fmt.u256(low.into(), high.into()))
+    }
+}
+
+fn to_uint((low, high): (u128, u128)) -> U256 {
+    U128::from_u128(high).concat(&U128::from_u128(low))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn u256_arithmetic() {
+        let max_low = (u128::MAX, 0);
+
+        // carries from the low into the high part
+        let sum = U256Commands::Add {
+            x: max_low,
+            y: (1, 0),
+        }
+        .run(&ValueFormat::default())
+        .unwrap();
+        assert_eq!(sum, "0x100000000000000000000000000000000");
+
+        let product = U256Commands::Mul {
+            x: max_low,
+            y: max_low,
+        }
+        .run(&ValueFormat::default())
+        .unwrap();
+        assert_eq!(
+            product,
+            "0xfffffffffffffffffffffffffffffffe00000000000000000000000000000001"
+        );
+
+        let quotient = U256Commands::Div {
+            x: (0, 1),
+            y: (2, 0),
+        }
+        .run(&ValueFormat::default())
+        .unwrap();
+        assert_eq!(quotient, "0x80000000000000000000000000000000");
+
+        assert!(U256Commands::Sub {
+            x: (1, 0),
+            y: (2, 0)
+        }
+        .run(&ValueFormat::default())
+        .is_err());
+        assert!(U256Commands::Mul {
+            x: (0, 1),
+            y: (0, 1)
+        }
+        .run(&ValueFormat::default())
+        .is_err());
+        assert!(U256Commands::Div {
+            x: (1, 0),
+            y: (0, 0)
+        }
+        .run(&ValueFormat::default())
+        .is_err());
+    }
+}
@@ -0,0 +1,1088 @@
+use crate::cmd::parser::AddressParser;
+use crate::opts::display::{DisplayOptions, OutputFormat};
+use crate::opts::notify::NotifyOptions;
+use crate::opts::starknet::StarkNetOptions;
+use crate::probe::abi::{felt_to_u128, u256_to_dec};
+use crate::probe::addressbook::AddressOrName;
+use crate::probe::tokens::resolve_token;
+use crate::probe::utils::fmt::{
+    event_csv_row, event_ndjson_line, format_u256_units, pretty_block_without_txs, pretty_event,
+    Pretty, ValueFormat, EVENT_CSV_HEADER,
+};
+use crate::probe::utils::send_rpc_request;
+use crate::probe::ws::{is_websocket, WsClient};
+use crate::probe::{Probe, TransactionState};
+
+use std::collections::VecDeque;
+use std::io::Write;
+use std::time::{Duration, SystemTime};
+
+use clap::Subcommand;
+use eyre::{bail, eyre, Result};
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use starknet::core::types::FieldElement;
+use starknet::core::utils::{get_selector_from_name, get_storage_var_address};
+use starknet::providers::jsonrpc::models::{
+    BlockId, EventFilter, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs,
+};
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+
+#[derive(Debug, Subcommand)]
+pub enum WatchCommands {
+    #[clap(about = "Print the latest block, then every new block as it appears.")]
+    Block {
+        #[clap(long)]
+        #[clap(help = "Include the transactions of the blocks.")]
+        full: bool,
+
+        #[clap(long)]
+        #[clap(value_name = "SECONDS")]
+        #[clap(default_value_t = 2)]
+        #[clap(help = "How often to check for a new block, unless subscribed over a websocket.")]
+        poll_interval: u64,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "NOTIFICATION OPTIONS")]
+        notify: NotifyOptions,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(
+        about = "Print the events emitted from a block onwards, then every new one as it appears."
+    )]
+    Events {
+        #[clap(short = 'C', long)]
+        #[clap(value_name = "CONTRACT_ADDRESS")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "Address of the contract emitting the events")]
+        from: Option<AddressOrName>,
+
+        #[clap(short, long)]
+        #[clap(value_delimiter = ',')]
+        #[clap(help = "The values used to filter the events")]
+        #[clap(help = "Comma seperated values e.g., 0x12345,0x69420,...")]
+        keys: Option<Vec<FieldElement>>,
+
+        #[clap(short, long)]
+        #[clap(value_name = "BLOCK_NUMBER")]
+        #[clap(help = "The block to start from, the latest block if not given.")]
+        from_block: Option<u64>,
+
+        #[clap(short = 's', long)]
+        #[clap(default_value_t = 100)]
+        #[clap(help = "The number of events to fetch per request.")]
+        chunk_size: u64,
+
+        #[clap(long)]
+        #[clap(value_name = "SECONDS")]
+        #[clap(default_value_t = 2)]
+        #[clap(help = "How often to check for a new block, unless subscribed over a websocket.")]
+        poll_interval: u64,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "NOTIFICATION OPTIONS")]
+        notify: NotifyOptions,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(visible_alias = "transaction")]
+    #[clap(
+        about = "Follow a transaction until it is accepted on L1, printing each change of its status."
+    )]
+    #[clap(
+        long_about = "Follow a transaction until it is accepted on L1, printing each change of its status. Exits with an error as soon as the transaction is known to be reverted or rejected."
+    )]
+    Tx {
+        #[clap(value_name = "TX_HASH")]
+        hash: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "SECONDS")]
+        #[clap(default_value_t = 2)]
+        #[clap(
+            help = "How often to check the status of the transaction, unless subscribed over a websocket."
+        )]
+        poll_interval: u64,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "NOTIFICATION OPTIONS")]
+        notify: NotifyOptions,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(
+        about = "Print the balance of an address whenever it changes, along with the transaction that changed it."
+    )]
+    Balance {
+        #[clap(value_name = "ADDRESS")]
+        #[clap(value_parser(AddressParser))]
+        #[clap(help = "The address whose balance you want to watch.")]
+        address: AddressOrName,
+
+        #[clap(long)]
+        #[clap(value_name = "TOKEN")]
+        #[clap(default_value = "ETH")]
+        #[clap(
+            help = "The token to watch the balance in, by symbol (see `probe tokens ls`) or by address."
+        )]
+        token: String,
+
+        #[clap(long)]
+        #[clap(visible_alias = "raw")]
+        #[clap(
+            help = "Print the exact balance in the smallest unit of the token, e.g. wei for ETH."
+        )]
+        wei: bool,
+
+        #[clap(long)]
+        #[clap(value_name = "SECONDS")]
+        #[clap(default_value_t = 2)]
+        #[clap(help = "How often to check for a new block, unless subscribed over a websocket.")]
+        poll_interval: u64,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "NOTIFICATION OPTIONS")]
+        notify: NotifyOptions,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+
+    #[clap(
+        about = "Print the value of a storage slot whenever it changes, along with the transaction that changed it."
+    )]
+    Storage {
+        #[clap(value_name = "ADDRESS")]
+        #[clap(value_parser(AddressParser))]
+        contract_address: AddressOrName,
+
+        #[clap(value_name = "KEY")]
+        key: FieldElement,
+
+        #[clap(long)]
+        #[clap(value_name = "SECONDS")]
+        #[clap(default_value_t = 2)]
+        #[clap(help = "How often to check for a new block, unless subscribed over a websocket.")]
+        poll_interval: u64,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "NOTIFICATION OPTIONS")]
+        notify: NotifyOptions,
+
+        #[clap(flatten)]
+        #[clap(next_help_heading = "STARKNET OPTIONS")]
+        starknet: StarkNetOptions,
+    },
+}
+
+impl WatchCommands {
+    pub async fn run(self, display: &DisplayOptions, out: &mut dyn Write) -> Result<()> {
+        // every record is written on its own as soon as it arrives, so it can't be reformatted
+        // as a whole
+        if display.query.is_some() {
+            bail!("--query cannot be used with watch")
+        }
+        let format = display.format();
+
+        match self {
+            Self::Block {
+                full,
+                poll_interval,
+                notify,
+                starknet,
+            } => {
+                let ndjson = match format {
+                    OutputFormat::Pretty => false,
+                    OutputFormat::Ndjson => true,
+                    _ => bail!("watch block only supports the pretty and ndjson output"),
+                };
+
+                let mut sink = Sink::new(out, display, &notify);
+                let mut node = Node::connect(&starknet, poll_interval).await?;
+                let mut chain = ChainTracker::default();
+                let mut next = None;
+
+                loop {
+                    let (latest, latest_hash) = node.head().await?;
+                    let mut number = next.unwrap_or(latest);
+
+                    // the head may have moved back to a block that was already printed
+                    if chain
+                        .hash_at(latest)
+                        .is_some_and(|hash| hash != latest_hash)
+                    {
+                        number =
+                            reorganize(&mut node, &mut chain, latest, ndjson, &mut sink).await?;
+                    }
+
+                    while number <= latest {
+                        let block = node.block(number).await?;
+
+                        if let MaybePendingBlockWithTxs::Block(block) = &block {
+                            if !chain.extends(number, block.parent_hash) {
+                                number = reorganize(
+                                    &mut node,
+                                    &mut chain,
+                                    number - 1,
+                                    ndjson,
+                                    &mut sink,
+                                )
+                                .await?;
+                                continue;
+                            }
+                            chain.push(number, block.block_hash);
+                        }
+
+                        let record = block_record(&block, full)?;
+                        let line = render_block(&block, &record, full, ndjson, display);
+                        sink.emit(line, &record).await?;
+                        number += 1;
+                    }
+
+                    next = Some(number);
+                }
+            }
+
+            Self::Events {
+                from,
+                keys,
+                from_block,
+                chunk_size,
+                poll_interval,
+                notify,
+                starknet,
+            } => {
+                if !matches!(
+                    format,
+                    OutputFormat::Pretty | OutputFormat::Ndjson | OutputFormat::Csv
+                ) {
+                    bail!("watch events only supports the pretty, ndjson and csv output")
+                }
+                if format == OutputFormat::Csv {
+                    writeln!(out, "{EVENT_CSV_HEADER}")?;
+                }
+                let mut sink = Sink::new(out, display, &notify);
+                let from = match &from {
+                    Some(from) => Some(starknet.resolve_address(from).await?),
+                    None => None,
+                };
+
+                let url = starknet.rpc_url();
+                if is_websocket(&url) {
+                    // the keys of the subscription are filtered by position, so the given keys
+                    // are the values accepted for the first one
+                    let mut params = json!({});
+                    if let Some(from) = from {
+                        params["from_address"] = json!(from);
+                    }
+                    if let Some(keys) = &keys {
+                        params["keys"] = json!([keys]);
+                    }
+                    if let Some(from_block) = from_block {
+                        params["block_id"] = json!({ "block_number": from_block });
+                    }
+
+                    let mut ws = WsClient::connect(&url).await?;
+                    ws.subscribe("starknet_subscribeEvents", params).await?;
+
+                    loop {
+                        let event = ws.next_notification().await?;
+                        if event.method != "starknet_subscriptionEvents" {
+                            continue;
+                        }
+                        let line = render_event(&event.result, format, display);
+                        sink.emit(line, &event.result).await?;
+                    }
+                }
+
+                let provider = JsonRpcClient::new(HttpTransport::new(url));
+                let mut next = match from_block {
+                    Some(block) => block,
+                    None => provider.block_number().await?,
+                };
+
+                loop {
+                    let latest = provider.block_number().await?;
+
+                    // the blocks since the last poll are queried as a single range, whose pages
+                    // are all written before moving the start past it
+                    if next <= latest {
+                        let filter = EventFilter {
+                            address: from,
+                            keys: keys.clone(),
+                            from_block: Some(BlockId::Number(next)),
+                            to_block: Some(BlockId::Number(latest)),
+                        };
+                        let mut continuation_token = None;
+
+                        loop {
+                            let page = provider
+                                .get_events(filter.clone(), continuation_token, chunk_size)
+                                .await?;
+
+                            for event in &page.events {
+                                let event = serde_json::to_value(event)?;
+                                let line = render_event(&event, format, display);
+                                sink.emit(line, &event).await?;
+                            }
+
+                            match page.continuation_token {
+                                Some(token) => continuation_token = Some(token),
+                                None => break,
+                            }
+                        }
+
+                        next = latest + 1;
+                    }
+
+                    tokio::time::sleep(Duration::from_secs(poll_interval)).await;
+                }
+            }
+
+            Self::Tx {
+                hash,
+                poll_interval,
+                notify,
+                starknet,
+            } => {
+                let ndjson = match format {
+                    OutputFormat::Pretty => false,
+                    OutputFormat::Ndjson => true,
+                    _ => bail!("watch tx only supports the pretty and ndjson output"),
+                };
+
+                let url = starknet.rpc_url();
+                let mut sink = Sink::new(out, display, &notify);
+                let mut last = None;
+
+                if is_websocket(&url) {
+                    let mut ws = WsClient::connect(&url).await?;
+                    ws.subscribe(
+                        "starknet_subscribeTransactionStatus",
+                        json!({ "transaction_hash": hash }),
+                    )
+                    .await?;
+
+                    loop {
+                        let status = ws.next_notification().await?;
+                        if status.method != "starknet_subscriptionTransactionStatus" {
+                            continue;
+                        }
+                        let state = TransactionState::from_json(&status.result["status"]);
+                        if report_state(hash, state, &mut last, ndjson, &mut sink).await? {
+                            return Ok(());
+                        }
+                    }
+                }
+
+                let probe = Probe::new(url);
+
+                loop {
+                    // nothing is printed until the node knows about the transaction
+                    if let Some(state) = probe.get_transaction_state(hash).await? {
+                        if report_state(hash, state, &mut last, ndjson, &mut sink).await? {
+                            return Ok(());
+                        }
+                    }
+
+                    tokio::time::sleep(Duration::from_secs(poll_interval)).await;
+                }
+            }
+
+            Self::Balance {
+                address,
+                token,
+                wei,
+                poll_interval,
+                notify,
+                starknet,
+            } => {
+                let mut node = Node::connect(&starknet, poll_interval).await?;
+                let chain = match starknet.chain() {
+                    Some(chain) => chain,
+                    None => node.request("starknet_chainId", json!([])).await?,
+                };
+                let (token, known) = resolve_token(chain, &token)?;
+                let address = address.resolve(chain)?;
+
+                // the balances of the tokens are stored as a u256 whose high part follows the low
+                // one, which is only needed to find the transaction that changed it
+                let key = get_storage_var_address("ERC20_balances", &[address])?;
+                let watched = Watched {
+                    contract: token,
+                    keys: vec![key, key + FieldElement::ONE],
+                    value: WatchedValue::Balance {
+                        account: address,
+                        units: known
+                            .filter(|_| !wei)
+                            .map(|known| (known.decimals.into(), known.symbol)),
+                    },
+                };
+
+                watched
+                    .run(node, format, Sink::new(out, display, &notify))
+                    .await
+            }
+
+            Self::Storage {
+                contract_address,
+                key,
+                poll_interval,
+                notify,
+                starknet,
+            } => {
+                let contract_address = starknet.resolve_address(&contract_address).await?;
+                let node = Node::connect(&starknet, poll_interval).await?;
+                let watched = Watched {
+                    contract: contract_address,
+                    keys: vec![key],
+                    value: WatchedValue::Storage,
+                };
+
+                watched
+                    .run(node, format, Sink::new(out, display, &notify))
+                    .await
+            }
+        }
+    }
+}
+
+/// A value read from the state at every block, as the low and high parts of a u256.
+struct Watched {
+    contract: FieldElement,
+    /// The storage keys a transaction writes to when it changes the value.
+    keys: Vec<FieldElement>,
+    value: WatchedValue,
+}
+
+enum WatchedValue {
+    Balance {
+        account: FieldElement,
+        /// The decimals and the symbol of the token, to print the balance in its units.
+        units: Option<(u32, String)>,
+    },
+    Storage,
+}
+
+/// A change of a watched value, and the transaction responsible for it if it could be found.
+#[derive(Debug, Serialize)]
+struct Change {
+    block_number: u64,
+    transaction_hash: Option<FieldElement>,
+    old: String,
+    new: String,
+    delta: String,
+}
+
+impl Watched {
+    /// Reads the value at every new block, and writes the changes only.
+    async fn run(&self, mut node: Node, format: OutputFormat, mut sink: Sink<'_>) -> Result<()> {
+        let ndjson = match format {
+            OutputFormat::Pretty => false,
+            OutputFormat::Ndjson => true,
+            _ => bail!("watch balance and watch storage only support the pretty and ndjson output"),
+        };
+
+        let fmt = sink.display.value_format();
+        let (latest, _) = node.head().await?;
+        let mut value = self.read(&mut node, latest).await?;
+        let mut next = latest + 1;
+
+        loop {
+            let (latest, _) = node.head().await?;
+
+            while next <= latest {
+                let new = self.read(&mut node, next).await?;
+
+                if new != value {
+                    // a node that doesn't trace blocks still reports the change, without the
+                    // transaction
+                    let block_id = json!({ "block_number": next });
+                    let traces: Result<Vec<Value>> = node
+                        .request(
+                            "starknet_traceBlockTransactions",
+                            json!({ "block_id": block_id }),
+                        )
+                        .await;
+                    let transaction_hash = traces
+                        .ok()
+                        .and_then(|traces| find_writer(&traces, self.contract, &self.keys));
+
+                    let (negative, delta) = u256_delta(value, new);
+                    let change = Change {
+                        block_number: next,
+                        transaction_hash,
+                        old: self.format(value, &fmt),
+                        new: self.format(new, &fmt),
+                        delta: format!(
+                            "{}{}",
+                            if negative { "-" } else { "+" },
+                            self.format(delta, &fmt)
+                        ),
+                    };
+                    let record = serde_json::to_value(&change)?;
+                    let line = render_change(&change, &record, ndjson, sink.display);
+                    sink.emit(line, &record).await?;
+
+                    value = new;
+                }
+
+                next += 1;
+            }
+        }
+    }
+
+    async fn read(&self, node: &mut Node, block: u64) -> Result<(u128, u128)> {
+        let block_id = json!({ "block_number": block });
+
+        match &self.value {
+            WatchedValue::Balance { account, .. } => {
+                let request = json!({
+                    "contract_address": self.contract,
+                    "entry_point_selector": get_selector_from_name("balanceOf")?,
+                    "calldata": [account],
+                });
+                let res: Vec<FieldElement> = node
+                    .request(
+                        "starknet_call",
+                        json!({ "request": request, "block_id": block_id }),
+                    )
+                    .await?;
+
+                match res.as_slice() {
+                    [low, high, ..] => Ok((felt_to_u128(*low)?, felt_to_u128(*high)?)),
+                    _ => bail!(
+                        "balanceOf of {:#x} returned {} values",
+                        self.contract,
+                        res.len()
+                    ),
+                }
+            }
+
+            WatchedValue::Storage => {
+                let value: FieldElement = node
+                    .request(
+                        "starknet_getStorageAt",
+                        json!({
+                            "contract_address": self.contract,
+                            "key": self.keys[0],
+                            "block_id": block_id,
+                        }),
+                    )
+                    .await?;
+
+                let bytes = value.to_bytes_be();
+                Ok((
+                    u128::from_be_bytes(bytes[16..].try_into()?),
+                    u128::from_be_bytes(bytes[..16].try_into()?),
+                ))
+            }
+        }
+    }
+
+    fn format(&self, (low, high): (u128, u128), fmt: &ValueFormat) -> String {
+        match &self.value {
+            WatchedValue::Balance {
+                units: Some((decimals, symbol)),
+                ..
+            } => format!("{} {symbol}", format_u256_units(low, high, *decimals)),
+            WatchedValue::Balance { units: None, .. } => u256_to_dec(low, high),
+            WatchedValue::Storage => fmt.felt(&u256_to_felt(low, high)),
+        }
+    }
+}
+
+fn render_change(
+    change: &Change,
+    record: &Value,
+    ndjson: bool,
+    display: &DisplayOptions,
+) -> String {
+    if display.quiet {
+        return change.new.clone();
+    }
+    if ndjson {
+        return record.to_string();
+    }
+
+    let transaction = match &change.transaction_hash {
+        Some(hash) => display.value_format().felt(hash),
+        None => "unknown".to_string(),
+    };
+    display.colorize(format!(
+        "BLOCK {} : {} -> {} ({}) by transaction {transaction}",
+        change.block_number, change.old, change.new, change.delta
+    ))
+}
+
+/// Finds the last transaction of the block that wrote to one of the keys of the contract, from
+/// the state diffs of the traces.
+fn find_writer(
+    traces: &[Value],
+    contract: FieldElement,
+    keys: &[FieldElement],
+) -> Option<FieldElement> {
+    let felt = |value: &Value| FieldElement::from_hex_be(value.as_str()?).ok();
+
+    traces
+        .iter()
+        .rev()
+        .find(|trace| {
+            let diffs = &trace["trace_root"]["state_diff"]["storage_diffs"];
+            diffs.as_array().into_iter().flatten().any(|diff| {
+                felt(&diff["address"]) == Some(contract)
+                    && diff["storage_entries"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .any(|entry| felt(&entry["key"]).is_some_and(|key| keys.contains(&key)))
+            })
+        })
+        .and_then(|trace| felt(&trace["transaction_hash"]))
+}
+
+/// The difference between two u256 given as their low and high parts, as whether it is negative
+/// and its magnitude.
+fn u256_delta(old: (u128, u128), new: (u128, u128)) -> (bool, (u128, u128)) {
+    let negative = (new.1, new.0) < (old.1, old.0);
+    let (a, b) = if negative { (old, new) } else { (new, old) };
+
+    let (low, borrow) = a.0.overflowing_sub(b.0);
+    (negative, (low, a.1 - b.1 - borrow as u128))
+}
+
+fn u256_to_felt(low: u128, high: u128) -> FieldElement {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(&high.to_be_bytes());
+    bytes[16..].copy_from_slice(&low.to_be_bytes());
+    // only storage values are printed as felts, which always fit
+    FieldElement::from_bytes_be(&bytes).unwrap_or_default()
+}
+
+/// Where the records of a watch go: written to the output as soon as they come, and sent to the
+/// notification targets.
+struct Sink<'a> {
+    out: &'a mut dyn Write,
+    display: &'a DisplayOptions,
+    notify: &'a NotifyOptions,
+}
+
+impl<'a> Sink<'a> {
+    fn new(out: &'a mut dyn Write, display: &'a DisplayOptions, notify: &'a NotifyOptions) -> Self {
+        Self {
+            out,
+            display,
+            notify,
+        }
+    }
+
+    /// Writes the line of a record, then sends the record to the notification targets.
+    async fn emit(&mut self, line: String, record: &Value) -> Result<()> {
+        writeln!(self.out, "{line}")?;
+        self.out.flush()?;
+
+        self.notify.notify(record).await;
+        Ok(())
+    }
+}
+
+/// Where the blocks come from: polled over HTTP, or pushed by a subscription over a websocket.
+/// Either way, the requests of the watchers go to the same endpoint.
+enum Node {
+    Http {
+        url: Url,
+        poll_interval: Duration,
+        polled: bool,
+    },
+    Ws(WsClient),
+}
+
+impl Node {
+    async fn connect(starknet: &StarkNetOptions, poll_interval: u64) -> Result<Self> {
+        let url = starknet.rpc_url();
+
+        if is_websocket(&url) {
+            let mut ws = WsClient::connect(&url).await?;
+            ws.subscribe("starknet_subscribeNewHeads", json!({}))
+                .await?;
+            Ok(Self::Ws(ws))
+        } else {
+            Ok(Self::Http {
+                url,
+                poll_interval: Duration::from_secs(poll_interval),
+                polled: false,
+            })
+        }
+    }
+
+    async fn request<T: DeserializeOwned>(&mut self, method: &str, params: Value) -> Result<T> {
+        match self {
+            Self::Http { url, .. } => send_rpc_request(url, method, params).await,
+            Self::Ws(ws) => ws.request(method, params).await,
+        }
+    }
+
+    /// Waits for the next head of the chain, returning its number and hash.
+    async fn head(&mut self) -> Result<(u64, FieldElement)> {
+        let head = match self {
+            Self::Http {
+                url,
+                poll_interval,
+                polled,
+            } => {
+                if *polled {
+                    tokio::time::sleep(*poll_interval).await;
+                }
+                *polled = true;
+
+                send_rpc_request(url, "starknet_blockHashAndNumber", json!([])).await?
+            }
+
+            Self::Ws(ws) => loop {
+                let head = ws.next_notification().await?;
+                if head.method == "starknet_subscriptionNewHeads" {
+                    break head.result;
+                }
+            },
+        };
+
+        let hash = serde_json::from_value(head["block_hash"].clone())?;
+        let number = head["block_number"]
+            .as_u64()
+            .ok_or_else(|| eyre!("the head of the chain has no block number"))?;
+        Ok((number, hash))
+    }
+
+    // the heads pushed by a subscription only hold the header, so the blocks are always fetched
+    async fn block(&mut self, number: u64) -> Result<MaybePendingBlockWithTxs> {
+        let block_id = json!({ "block_number": number });
+        self.request("starknet_getBlockWithTxs", json!({ "block_id": block_id }))
+            .await
+    }
+
+    async fn block_hash(&mut self, number: u64) -> Result<FieldElement> {
+        let block_id = json!({ "block_number": number });
+        let block: MaybePendingBlockWithTxHashes = self
+            .request(
+                "starknet_getBlockWithTxHashes",
+                json!({ "block_id": block_id }),
+            )
+            .await?;
+
+        match block {
+            MaybePendingBlockWithTxHashes::Block(block) => Ok(block.block_hash),
+            MaybePendingBlockWithTxHashes::PendingBlock(_) => bail!("block {number} is pending"),
+        }
+    }
+}
+
+/// How many of the printed blocks are remembered to find where the chain forked.
+const CHAIN_HISTORY: usize = 128;
+
+/// A reorganization of the chain, which replaced the printed blocks from `block_number` onwards.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct Reorg {
+    block_number: u64,
+    old_hash: FieldElement,
+    new_hash: FieldElement,
+    depth: u64,
+}
+
+/// The hashes of the last printed blocks, to tell whether a new block extends them.
+#[derive(Debug, Default)]
+struct ChainTracker {
+    blocks: VecDeque<(u64, FieldElement)>,
+}
+
+impl ChainTracker {
+    fn hash_at(&self, number: u64) -> Option<FieldElement> {
+        self.blocks
+            .iter()
+            .find(|(tracked, _)| *tracked == number)
+            .map(|(_, hash)| *hash)
+    }
+
+    /// Whether the block with the given parent comes right after the printed ones. Any block does
+    /// if its parent was not printed.
+    fn extends(&self, number: u64, parent_hash: FieldElement) -> bool {
+        match number
+            .checked_sub(1)
+            .and_then(|parent| self.hash_at(parent))
+        {
+            Some(hash) => hash == parent_hash,
+            None => true,
+        }
+    }
+
+    fn push(&mut self, number: u64, hash: FieldElement) {
+        if self.blocks.len() == CHAIN_HISTORY {
+            self.blocks.pop_front();
+        }
+        self.blocks.push_back((number, hash));
+    }
+
+    /// Forgets the blocks from `fork` onwards, which were replaced by the block `new_hash`.
+    fn rewind(&mut self, fork: u64, new_hash: FieldElement) -> Option<Reorg> {
+        let (tip, _) = *self.blocks.back()?;
+        let old_hash = self.hash_at(fork)?;
+
+        self.blocks.retain(|(number, _)| *number < fork);
+
+        Some(Reorg {
+            block_number: fork,
+            old_hash,
+            new_hash,
+            depth: tip - fork + 1,
+        })
+    }
+}
+
+/// Finds where the chain forked given a printed block that is no longer part of it, by walking
+/// back until the node agrees with the printed blocks. The reorg is written, and the number of
+/// the first block to print again is returned.
+async fn reorganize(
+    node: &mut Node,
+    chain: &mut ChainTracker,
+    replaced: u64,
+    ndjson: bool,
+    sink: &mut Sink<'_>,
+) -> Result<u64> {
+    let mut fork = replaced;
+    let mut new_hash = node.block_hash(fork).await?;
+
+    while let Some(parent) = fork.checked_sub(1) {
+        let printed = match chain.hash_at(parent) {
+            Some(hash) => hash,
+            None => break,
+        };
+        let hash = node.block_hash(parent).await?;
+        if hash == printed {
+            break;
+        }
+
+        fork = parent;
+        new_hash = hash;
+    }
+
+    if let Some(reorg) = chain.rewind(fork, new_hash) {
+        let mut record = serde_json::to_value(&reorg)?;
+        record["type"] = json!("REORG");
+
+        let line = render_reorg(&reorg, &record, ndjson, sink.display);
+        sink.emit(line, &record).await?;
+    }
+
+    Ok(fork)
+}
+
+fn render_reorg(reorg: &Reorg, record: &Value, ndjson: bool, display: &DisplayOptions) -> String {
+    if ndjson {
+        return record.to_string();
+    }
+
+    let fmt = display.value_format();
+    display.colorize(format!(
+        "REORG at block {} : {} replaced by {} ({} block(s) deep)",
+        reorg.block_number,
+        fmt.felt(&reorg.old_hash),
+        fmt.felt(&reorg.new_hash),
+        reorg.depth
+    ))
+}
+
+/// The block as JSON, without its transactions unless `full` is set.
+fn block_record(block: &MaybePendingBlockWithTxs, full: bool) -> Result<Value> {
+    let mut json = match block {
+        MaybePendingBlockWithTxs::Block(block) => serde_json::to_value(block)?,
+        MaybePendingBlockWithTxs::PendingBlock(block) => serde_json::to_value(block)?,
+    };
+    if !full {
+        json.as_object_mut().unwrap().remove("transactions");
+    }
+
+    Ok(json)
+}
+
+fn render_block(
+    block: &MaybePendingBlockWithTxs,
+    record: &Value,
+    full: bool,
+    ndjson: bool,
+    display: &DisplayOptions,
+) -> String {
+    let fmt = display.value_format();
+    if display.quiet {
+        return match block {
+            MaybePendingBlockWithTxs::Block(block) => fmt.felt(&block.block_hash),
+            MaybePendingBlockWithTxs::PendingBlock(block) => fmt.felt(&block.parent_hash),
+        };
+    }
+
+    if ndjson {
+        return record.to_string();
+    }
+
+    let pretty = if full {
+        block.prettify(&fmt)
+    } else {
+        pretty_block_without_txs(block, &fmt)
+    };
+    display.colorize(pretty)
+}
+
+fn render_event(event: &Value, format: OutputFormat, display: &DisplayOptions) -> String {
+    if display.quiet {
+        return event["transaction_hash"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+    }
+
+    match format {
+        OutputFormat::Csv => event_csv_row(event),
+        OutputFormat::Ndjson => event_ndjson_line(event),
+        _ => display.colorize(pretty_event(event, &display.value_format())),
+    }
+}
+
+/// Writes the state of the transaction if it changed since the last one, returning whether it
+/// reached its final status. Fails if the transaction was reverted or rejected.
+async fn report_state(
+    hash: FieldElement,
+    state: TransactionState,
+    last: &mut Option<TransactionState>,
+    ndjson: bool,
+    sink: &mut Sink<'_>,
+) -> Result<bool> {
+    if last.as_ref() != Some(&state) {
+        let record = json!({
+            "timestamp": humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+            "transaction_hash": format!("{hash:#x}"),
+            "status": state.status,
+            "reverted": state.reverted,
+            "block_number": state.block_number,
+        });
+
+        let line = render_state(&state, &record, ndjson, sink.display);
+        sink.emit(line, &record).await?;
+    }
+
+    if state.reverted {
+        bail!("transaction {hash:#x} was reverted")
+    }
+    let done = match state.status.as_str() {
+        "ACCEPTED_ON_L1" => true,
+        "REJECTED" => bail!("transaction {hash:#x} was rejected"),
+        _ => false,
+    };
+
+    *last = Some(state);
+    Ok(done)
+}
+
+fn render_state(
+    state: &TransactionState,
+    record: &Value,
+    ndjson: bool,
+    display: &DisplayOptions,
+) -> String {
+    if display.quiet {
+        return state.status.clone();
+    }
+    if ndjson {
+        return record.to_string();
+    }
+
+    let timestamp = record["timestamp"].as_str().unwrap_or_default();
+    let mut line = format!("{timestamp}  {}", state.status);
+    if state.reverted {
+        line.push_str(" (reverted)");
+    }
+    if let Some(block_number) = state.block_number {
+        line.push_str(&format!("  block {block_number}"));
+    }
+    display.colorize(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chain_tracker_rewinds_replaced_blocks() {
+        let mut chain = ChainTracker::default();
+        for number in 10..15 {
+            chain.push(number, FieldElement::from(number));
+        }
+
+        assert!(chain.extends(15, FieldElement::from(14_u64)));
+        assert!(!chain.extends(15, FieldElement::from(99_u64)));
+        // the parent of the first printed block is not known
+        assert!(chain.extends(10, FieldElement::from(99_u64)));
+
+        let reorg = chain.rewind(13, FieldElement::from(113_u64)).unwrap();
+        assert_eq!(
+            reorg,
+            Reorg {
+                block_number: 13,
+                old_hash: FieldElement::from(13_u64),
+                new_hash: FieldElement::from(113_u64),
+                depth: 2,
+            }
+        );
+        assert_eq!(chain.hash_at(12), Some(FieldElement::from(12_u64)));
+        assert_eq!(chain.hash_at(13), None);
+        assert!(chain.rewind(20, FieldElement::ZERO).is_none());
+    }
+
+    #[test]
+    fn find_the_writer_of_a_storage_key() {
+        let traces = vec![
+            json!({
+                "transaction_hash": "0xa",
+                "trace_root": { "state_diff": { "storage_diffs": [
+                    { "address": "0x1", "storage_entries": [{ "key": "0x5", "value": "0x1" }] }
+                ] } }
+            }),
+            json!({
+                "transaction_hash": "0xb",
+                "trace_root": { "state_diff": { "storage_diffs": [
+                    { "address": "0x01", "storage_entries": [{ "key": "0x6", "value": "0x2" }] }
+                ] } }
+            }),
+            json!({ "transaction_hash": "0xc", "trace_root": {} }),
+        ];
+
+        let writer = |keys: &[u64]| {
+            let keys = keys
+                .iter()
+                .map(|key| FieldElement::from(*key))
+                .collect::<Vec<_>>();
+            find_writer(&traces, FieldElement::ONE, &keys)
+        };
+        assert_eq!(writer(&[5, 6]), Some(FieldElement::from(0xb_u64)));
+        assert_eq!(writer(&[5]), Some(FieldElement::from(0xa_u64)));
+        assert_eq!(writer(&[7]), None);
+    }
+
+    #[test]
+    fn signed_u256_delta() {
+        assert_eq!(u256_delta((5, 0), (8, 0)), (false, (3, 0)));
+        assert_eq!(u256_delta((8, 0), (5, 0)), (true, (3, 0)));
+        assert_eq!(u256_delta((u128::MAX, 0), (1, 1)), (false, (2, 0)));
+        assert_eq!(u256_delta((1, 1), (u128::MAX, 0)), (true, (2, 0)));
+    }
+}
@@ -2,20 +2,57 @@ mod cmd;
 mod opts;
 mod probe;
 
-use crate::cmd::probe::{App, Commands, EcdsaCommand};
+use crate::cmd::config::show_config;
+use crate::cmd::probe::{
+    App, Commands, EcdsaCommand, MessageHashCommand, SecpCommand, TxHashCommand,
+};
+use crate::cmd::send::{write_transaction, RawTransactionResult};
+use crate::opts::config::Config;
+use crate::opts::display::OutputFormat;
+use crate::probe::abi::u256_to_dec;
+use crate::probe::chains::{chain_alias, known_chains};
+use crate::probe::ethereum::L1Message;
+use crate::probe::fixed;
+use crate::probe::secp;
+use crate::probe::tokens::resolve_token;
+use crate::probe::utils::decode_revert_reason;
+use crate::probe::utils::fmt::{
+    format_u256_units, pretty_balances, pretty_chains, pretty_messages_status,
+    pretty_simulated_transaction, Pretty,
+};
 use crate::probe::{Probe, SimpleProbe};
 
-use clap::Parser;
-use eyre::Result;
+use std::io::Write;
+
+use clap::{CommandFactory, FromArgMatches};
+use eyre::{bail, eyre, Result};
+use serde_json::json;
+use starknet::core::types::FieldElement;
+use starknet::core::utils::get_selector_from_name;
 use starknet::providers::jsonrpc::models::EventFilter;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cli = App::parse();
+    // a broken config only fails the commands that read it, the others run without its defaults
+    let command = match Config::load().and_then(|config| config.apply_defaults(App::command())) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("warning: ignoring the defaults of the config file: {e}");
+            App::command()
+        }
+    };
+
+    // the matches are kept to tell where the values of the options come from
+    let matches = command.get_matches();
+    let cli = App::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let display = cli.display;
+    let to_json = display.is_json();
+    let fmt = display.value_format();
+    let mut out = display.writer();
 
     match cli.command {
-        Commands::DecToHex { dec } => {
-            println!("{}", SimpleProbe::to_hex(&dec));
+        Commands::DecToHex { decimal } => {
+            writeln!(out, "{}", SimpleProbe::to_hex(&decimal))?;
         }
 
         Commands::Ecdsa { commands } => match commands {
@@ -24,7 +61,7 @@ async fn main() -> Result<()> {
                 private_key,
             } => {
                 let signature = SimpleProbe::ecdsa_sign(&private_key, &message)?;
-                println!("{:#x} {:#x}", signature.r, signature.s);
+                writeln!(out, "{:#x} {:#x}", signature.r, signature.s)?;
             }
 
             EcdsaCommand::Verify {
@@ -38,110 +75,434 @@ async fn main() -> Result<()> {
                     &signature[0],
                     &signature[1],
                 )?;
-                println!("{is_valid}");
+                writeln!(out, "{is_valid}")?;
             }
         },
 
+        Commands::Secp { commands } => match commands {
+            SecpCommand::Sign {
+                message,
+                private_key,
+                curve,
+            } => {
+                let signature = secp::sign(curve, &private_key, &message)?;
+                writeln!(
+                    out,
+                    "0x{} 0x{} {}",
+                    hex::encode(signature.r),
+                    hex::encode(signature.s),
+                    signature.v
+                )?;
+            }
+
+            SecpCommand::Verify {
+                message,
+                signature,
+                verifying_key,
+                curve,
+            } => {
+                let verifying_key = hex::decode(verifying_key.trim_start_matches("0x"))?;
+                let is_valid = secp::verify(
+                    curve,
+                    &verifying_key,
+                    &message,
+                    &signature[0],
+                    &signature[1],
+                )?;
+                writeln!(out, "{is_valid}")?;
+            }
+
+            SecpCommand::PublicKey {
+                private_key,
+                compressed,
+                curve,
+            } => {
+                let public_key = secp::public_key(curve, &private_key, compressed)?;
+                writeln!(out, "0x{}", hex::encode(public_key))?;
+            }
+        },
+
+        Commands::Felt { commands } => {
+            writeln!(out, "{}", commands.run(&fmt)?)?;
+        }
+
         Commands::FromAscii { ascii } => {
-            println!("{}", SimpleProbe::from_utf8(&ascii)?);
+            writeln!(out, "{}", SimpleProbe::from_utf8(&ascii)?)?;
         }
 
-        Commands::HexToDec { hex } => {
-            println!("{}", SimpleProbe::to_dec(&hex));
+        Commands::HexToDec { hexadecimal } => {
+            writeln!(out, "{}", SimpleProbe::to_dec(&hexadecimal))?;
+        }
+
+        Commands::InspectFelt { felt } => {
+            let inspection = SimpleProbe::inspect_felt(&felt)?;
+            writeln!(out, "{}", display.display(&inspection)?)?;
         }
 
         Commands::Keccak { data } => {
-            println!("{}", SimpleProbe::keccak(&data)?);
+            writeln!(out, "{}", SimpleProbe::keccak(&data)?)?;
+        }
+
+        Commands::Keccak256 { data } => {
+            writeln!(out, "{}", SimpleProbe::keccak256(&data)?)?;
+        }
+
+        Commands::L1 { commands } => {
+            writeln!(out, "{}", commands.run(&display).await?)?;
+        }
+
+        Commands::Merkle(args) => {
+            writeln!(out, "{}", args.run(&display)?)?;
+        }
+
+        Commands::Messages(args) => {
+            let messages = args.run().await?;
+            writeln!(out, "{}", display.display(&messages)?)?;
+        }
+
+        Commands::MessageStatus { hash, starknet } => {
+            let messages = Probe::new(starknet.rpc_url())
+                .get_messages_status(&hash)
+                .await?;
+
+            if to_json {
+                let json = serde_json::to_string_pretty(&messages)?;
+                writeln!(out, "{}", display.render(json)?)?;
+            } else {
+                writeln!(
+                    out,
+                    "{}",
+                    display.colorize(pretty_messages_status(&messages))
+                )?;
+            }
+        }
+
+        Commands::MessageHash { commands } => {
+            let hash = match commands {
+                MessageHashCommand::L1ToL2 {
+                    from,
+                    to,
+                    function,
+                    selector,
+                    payload,
+                    nonce,
+                } => {
+                    let selector = l1_handler_selector(selector, function)?;
+                    SimpleProbe::compute_l1_to_l2_message_hash(from, to, selector, &payload, nonce)
+                }
+
+                MessageHashCommand::L2ToL1 { from, to, payload } => {
+                    SimpleProbe::compute_l2_to_l1_message_hash(from, to, &payload)
+                }
+            };
+
+            writeln!(out, "{hash}")?;
         }
 
         Commands::MaxSignedFelt => {
-            println!("{}", SimpleProbe::max_signed_felt());
+            writeln!(out, "{}", SimpleProbe::max_signed_felt())?;
         }
 
-        Commands::MinSignedFelt => {
-            println!("{}", SimpleProbe::min_signed_felt())
+        Commands::MinSignedFelt => writeln!(out, "{}", SimpleProbe::min_signed_felt())?,
+
+        Commands::RandomFelt { count } => {
+            writeln!(out, "{}", SimpleProbe::random_felts(count, &fmt))?;
+        }
+
+        Commands::EthToFelt { address } => {
+            writeln!(out, "{}", SimpleProbe::eth_to_felt(&address)?)?;
+        }
+
+        Commands::FeltToEth { felt } => {
+            writeln!(out, "{}", SimpleProbe::felt_to_eth(&felt)?)?;
+        }
+
+        Commands::ToSigned { felt } => {
+            writeln!(out, "{}", SimpleProbe::to_signed(&felt)?)?;
+        }
+
+        Commands::FromSigned { value } => {
+            writeln!(out, "{}", SimpleProbe::from_signed(&value)?)?;
+        }
+
+        Commands::ToByteArray { string } => {
+            writeln!(out, "{}", SimpleProbe::str_to_byte_array(&string))?;
+        }
+
+        Commands::FromByteArray { felts } => {
+            writeln!(out, "{}", SimpleProbe::byte_array_to_str(&felts)?)?;
         }
 
         Commands::ToAscii { short_str } => {
-            println!("{}", SimpleProbe::str_to_felt(&short_str)?);
+            writeln!(out, "{}", SimpleProbe::str_to_felt(&short_str)?)?;
         }
 
         Commands::MaxUnsignedFelt => {
-            println!("{}", SimpleProbe::max_felt());
+            writeln!(out, "{}", SimpleProbe::max_felt())?;
         }
 
         Commands::Pedersen { x, y } => {
-            println!("{}", SimpleProbe::pedersen(&x, &y)?);
+            writeln!(out, "{}", SimpleProbe::pedersen(&x, &y)?)?;
+        }
+
+        Commands::Poseidon { x, y } => {
+            writeln!(out, "{}", SimpleProbe::poseidon(&x, &y))?;
+        }
+
+        Commands::PoseidonMany { elements } => {
+            writeln!(out, "{}", SimpleProbe::poseidon_many(&elements))?;
+        }
+
+        Commands::HashOnElements { elements, poseidon } => {
+            let hash = if poseidon {
+                SimpleProbe::poseidon_many(&elements)
+            } else {
+                SimpleProbe::hash_on_elements(&elements)
+            };
+            writeln!(out, "{hash}")?;
         }
 
         Commands::BlockNumber { starknet } => {
-            let res = Probe::new(starknet.rpc_url).block_number().await?;
-            println!("{res}");
+            let res = Probe::new(starknet.rpc_url()).block_number().await?;
+            writeln!(out, "{}", display.render(res)?)?;
         }
 
         Commands::ChainId { starknet } => {
-            let chain_id = Probe::new(starknet.rpc_url).chain_id().await?;
-            println!("{chain_id}");
+            let chain_id = Probe::new(starknet.rpc_url()).chain_id().await?;
+
+            match chain_alias(chain_id) {
+                Some(alias) if !(display.no_decode || display.quiet) => {
+                    writeln!(out, "{} ({alias})", fmt.felt(&chain_id))?
+                }
+                _ => writeln!(out, "{}", fmt.felt(&chain_id))?,
+            }
+        }
+
+        Commands::Chains => {
+            let chains = known_chains();
+
+            if to_json {
+                let json = serde_json::to_string_pretty(&chains)?;
+                writeln!(out, "{}", display.render(json)?)?;
+            } else {
+                writeln!(out, "{}", display.colorize(pretty_chains(&chains, &fmt)))?;
+            }
+        }
+
+        Commands::Trace {
+            hash,
+            format,
+            selectors,
+            starknet,
+        } => {
+            let res = Probe::new(starknet.rpc_url())
+                .with_value_format(fmt)
+                .with_selector_registry(selectors.registry())
+                .trace_transaction(hash, format, to_json)
+                .await?;
+            writeln!(out, "{}", display.render(res)?)?;
         }
 
+        Commands::TraceBlock {
+            block_id,
+            contract,
+            format,
+            selectors,
+            starknet,
+        } => {
+            let contract = match &contract {
+                Some(contract) => Some(starknet.resolve_address(contract).await?),
+                None => None,
+            };
+            let res = Probe::new(starknet.rpc_url())
+                .with_value_format(fmt)
+                .with_selector_registry(selectors.registry())
+                .trace_block_transactions(&block_id, contract, format, to_json)
+                .await?;
+            writeln!(out, "{}", display.render(res)?)?;
+        }
+
+        Commands::Transaction {
+            commands: Some(commands),
+            ..
+        } => commands.run(&mut out).await?,
+
         Commands::Transaction {
             hash,
-            field,
-            to_json,
+            fields,
             starknet,
+            ..
         } => {
-            let res = Probe::new(starknet.rpc_url)
-                .get_transaction_by_hash(hash, field, to_json)
+            let hash = hash.expect("the hash is required without a subcommand");
+            let res = Probe::new(starknet.rpc_url())
+                .with_value_format(fmt)
+                .get_transaction_by_hash(hash, &fields, to_json)
                 .await?;
-            println!("{res}");
+            writeln!(out, "{}", display.render(res)?)?;
         }
 
         Commands::TransactionStatus { hash, starknet } => {
-            let res = Probe::new(starknet.rpc_url)
-                .get_transaction_receipt(hash, Some("status".to_string()), false)
+            let res = Probe::new(starknet.rpc_url())
+                .with_value_format(fmt)
+                .get_transaction_receipt(hash, &["status".to_string()], false, false)
                 .await?;
-            println!("{res}");
+            writeln!(out, "{}", display.render(res)?)?;
         }
 
         Commands::TransactionReceipt {
             hash,
-            field,
-            to_json,
+            fields,
+            decode,
+            selectors,
             starknet,
         } => {
-            let res = Probe::new(starknet.rpc_url)
-                .get_transaction_receipt(hash, field, to_json)
+            let res = Probe::new(starknet.rpc_url())
+                .with_value_format(fmt)
+                .with_selector_registry(selectors.registry())
+                .get_transaction_receipt(hash, &fields, to_json, decode)
                 .await?;
-            println!("{res}");
+            writeln!(out, "{}", display.render(res)?)?;
         }
 
         Commands::Block {
             id,
             full,
-            to_json,
-            field,
+            mut fields,
             starknet,
         } => {
-            let block = Probe::new(starknet.rpc_url)
-                .block(id, full, field, to_json)
+            if display.quiet && fields.is_empty() {
+                fields.push("block_hash".to_string());
+            }
+
+            let block = Probe::new(starknet.rpc_url())
+                .with_value_format(fmt)
+                .block(id, full, &fields, to_json)
                 .await?;
-            println!("{block}")
+            writeln!(out, "{}", display.render(block)?)?
         }
 
         Commands::Age { block_id, starknet } => {
-            let timestamp = Probe::new(starknet.rpc_url)
-                .block(block_id, false, Some("timestamp".to_string()), false)
+            let timestamp = Probe::new(starknet.rpc_url())
+                .with_value_format(fmt)
+                .block(block_id, false, &["timestamp".to_string()], false)
                 .await?;
 
-            println!("{timestamp}");
+            writeln!(out, "{timestamp}")?;
+        }
+
+        Commands::TransactionCost(args) => {
+            let cost = args.run().await?;
+            writeln!(out, "{}", display.colorize(cost.prettify(&fmt)))?;
+        }
+
+        Commands::TransactionBump(args) => {
+            let (max_fee, res) = args.run().await?;
+
+            if display.quiet {
+                writeln!(out, "{:#x}", res.transaction_hash)?;
+            } else {
+                writeln!(out, "Max fee : {max_fee:#x}")?;
+                writeln!(out, "Transaction hash : {:#x}", res.transaction_hash)?;
+            }
+        }
+
+        Commands::TransactionHash { commands } => {
+            let hash = match commands {
+                TxHashCommand::Invoke {
+                    sender,
+                    calldata,
+                    max_fee,
+                    nonce,
+                    chain,
+                    version,
+                } => SimpleProbe::compute_invoke_tx_hash(
+                    sender.resolve(chain)?,
+                    &calldata,
+                    max_fee,
+                    chain,
+                    nonce,
+                    version,
+                ),
+
+                TxHashCommand::Declare {
+                    sender,
+                    class_hash,
+                    max_fee,
+                    nonce,
+                    chain,
+                    version,
+                } => SimpleProbe::compute_declare_tx_hash(
+                    sender.resolve(chain)?,
+                    class_hash,
+                    max_fee,
+                    chain,
+                    nonce,
+                    version,
+                ),
+
+                TxHashCommand::DeployAccount {
+                    class_hash,
+                    salt,
+                    constructor_calldata,
+                    max_fee,
+                    nonce,
+                    chain,
+                    version,
+                } => SimpleProbe::compute_deploy_account_tx_hash(
+                    class_hash,
+                    salt,
+                    &constructor_calldata,
+                    max_fee,
+                    chain,
+                    nonce,
+                    version,
+                ),
+
+                TxHashCommand::L1Handler {
+                    from,
+                    to,
+                    function,
+                    selector,
+                    payload,
+                    nonce,
+                    log,
+                    chain,
+                } => {
+                    let message = match log {
+                        Some(log) => L1Message::from_log(&read_json_arg(&log)?)?,
+                        // clap requires all of them without a log
+                        None => L1Message {
+                            from_address: from.unwrap_or_default(),
+                            to_address: match to {
+                                Some(to) => to.resolve(chain)?,
+                                None => FieldElement::ZERO,
+                            },
+                            selector: l1_handler_selector(selector, function)?,
+                            payload,
+                            nonce: nonce.unwrap_or_default(),
+                        },
+                    };
+
+                    SimpleProbe::compute_l1_handler_tx_hash(
+                        message.from_address,
+                        message.to_address,
+                        message.selector,
+                        &message.payload,
+                        message.nonce,
+                        chain,
+                    )
+                }
+            };
+
+            writeln!(out, "{hash:#x}")?;
         }
 
         Commands::TransactionCount { block_id, starknet } => {
-            let total = Probe::new(starknet.rpc_url)
+            let total = Probe::new(starknet.rpc_url())
                 .get_block_transaction_count(block_id)
                 .await?;
 
-            println!("{total}");
+            writeln!(out, "{total}")?;
         }
 
         Commands::Nonce {
@@ -149,15 +510,64 @@ async fn main() -> Result<()> {
             block_id,
             starknet,
         } => {
-            let nonce = Probe::new(starknet.rpc_url)
+            let contract_address = starknet.resolve_address(&contract_address).await?;
+            let nonce = Probe::new(starknet.rpc_url())
+                .with_value_format(fmt)
                 .get_nonce(contract_address, &block_id)
                 .await?;
-            println!("{nonce}");
+            writeln!(out, "{nonce}")?;
         }
 
-        Commands::TransactionPending { starknet } => {
-            let transactions = Probe::new(starknet.rpc_url).pending_transactions().await?;
-            println!("{transactions}");
+        Commands::TransactionPending(args) => {
+            if args.watch {
+                out.stream()?;
+            }
+            args.run(&display, &mut out).await?;
+        }
+
+        Commands::Config { commands, starknet } => match commands {
+            Some(commands) => commands.run(&mut out)?,
+            None => {
+                let matches = matches
+                    .subcommand_matches("config")
+                    .expect("config command");
+                writeln!(out, "{}", show_config(&starknet, matches, &display)?)?;
+            }
+        },
+
+        Commands::Watch { commands } => {
+            out.stream()?;
+            commands.run(&display, &mut out).await?;
+        }
+
+        Commands::Token {
+            token,
+            block_id,
+            starknet,
+        } => {
+            let chain = starknet.chain_id().await?;
+            let (token, _) = resolve_token(chain, &token)?;
+
+            let metadata = Probe::new(starknet.rpc_url())
+                .get_erc20_metadata(token, &block_id)
+                .await?;
+            writeln!(out, "{}", display.display(&metadata)?)?;
+        }
+
+        Commands::TokenStandard {
+            address,
+            block_id,
+            starknet,
+        } => {
+            let address = starknet.resolve_address(&address).await?;
+            let standards = Probe::new(starknet.rpc_url())
+                .get_token_standards(address, &block_id)
+                .await?;
+            writeln!(out, "{}", display.display(&standards)?)?;
+        }
+
+        Commands::Tokens { commands } => {
+            commands.run(&display, &mut out).await?;
         }
 
         Commands::Storage {
@@ -166,16 +576,55 @@ async fn main() -> Result<()> {
             block_id,
             starknet,
         } => {
-            let res = Probe::new(starknet.rpc_url)
+            let contract_address = starknet.resolve_address(&contract_address).await?;
+            let res = Probe::new(starknet.rpc_url())
+                .with_value_format(fmt)
                 .get_storage_at(contract_address, index, &block_id)
                 .await?;
 
-            println!("{res}");
+            writeln!(out, "{}", display.render(res)?)?;
         }
 
         Commands::Rpc(rpc_args) => {
             let res = rpc_args.run().await?;
-            println!("{res}");
+            writeln!(out, "{}", display.render(res)?)?;
+        }
+
+        Commands::SendBatch(args) => {
+            let summary = args.run().await?;
+            writeln!(out, "{}", display.colorize(summary.prettify(&fmt)))?;
+
+            if summary.failed() > 0 {
+                bail!("{} transaction(s) failed", summary.failed());
+            }
+        }
+
+        Commands::SendRaw(args) => {
+            let (res, receipt) = args.run().await?;
+
+            if display.quiet {
+                let value = match res {
+                    RawTransactionResult::Declare(ref res) => res.class_hash,
+                    RawTransactionResult::DeployAccount(ref res) => res.contract_address,
+                    RawTransactionResult::Invoke(_) => res.transaction_hash(),
+                };
+                writeln!(out, "{value:#x}")?;
+            } else {
+                match res {
+                    RawTransactionResult::Declare(ref res) => {
+                        writeln!(out, "Class hash : {:#x}", res.class_hash)?
+                    }
+                    RawTransactionResult::DeployAccount(ref res) => {
+                        writeln!(out, "Contract address : {:#x}", res.contract_address)?
+                    }
+                    RawTransactionResult::Invoke(_) => {}
+                }
+                writeln!(out, "Transaction hash : {:#x}", res.transaction_hash())?;
+
+                if let Some(receipt) = receipt {
+                    writeln!(out, "\n{}", display.colorize(receipt.prettify(&fmt)))?;
+                }
+            }
         }
 
         Commands::Call {
@@ -186,18 +635,20 @@ async fn main() -> Result<()> {
             block_id,
             starknet,
         } => {
-            let res = Probe::new(starknet.rpc_url)
+            let contract_address = starknet.resolve_address(&contract_address).await?;
+            let res = Probe::new(starknet.rpc_url())
+                .with_value_format(fmt)
                 .call(&contract_address, &function, &input, &block_id, &abi)
                 .await?;
 
-            println!("{res}");
+            writeln!(out, "{}", display.render(res)?)?;
         }
 
         Commands::StateUpdate { block_id, starknet } => {
-            let res = Probe::new(starknet.rpc_url)
-                .get_state_update(&block_id)
+            let res = Probe::new(starknet.rpc_url())
+                .get_state_update(&block_id, to_json)
                 .await?;
-            println!("{res}");
+            writeln!(out, "{}", display.render(res)?)?;
         }
 
         Commands::Index {
@@ -205,12 +656,12 @@ async fn main() -> Result<()> {
             keys,
         } => {
             let res = SimpleProbe::get_storage_index(&variable_name, &keys)?;
-            println!("{res:#x}");
+            writeln!(out, "{res:#x}")?;
         }
 
         Commands::ContractHash { contract } => {
             let res = SimpleProbe::compute_contract_hash(contract)?;
-            println!("{res:#x}");
+            writeln!(out, "{res:#x}")?;
         }
 
         Commands::Class {
@@ -218,10 +669,10 @@ async fn main() -> Result<()> {
             block_id,
             starknet,
         } => {
-            let res = Probe::new(starknet.rpc_url)
-                .get_class_code(hash, &block_id)
+            let res = Probe::new(starknet.rpc_url())
+                .get_class_code(hash, &block_id, to_json)
                 .await?;
-            println!("{res}");
+            writeln!(out, "{}", display.render(res)?)?;
         }
 
         Commands::Code {
@@ -229,10 +680,11 @@ async fn main() -> Result<()> {
             block_id,
             starknet,
         } => {
-            let res = Probe::new(starknet.rpc_url)
-                .get_contract_code(contract_address, &block_id)
+            let contract_address = starknet.resolve_address(&contract_address).await?;
+            let res = Probe::new(starknet.rpc_url())
+                .get_contract_code(contract_address, &block_id, to_json)
                 .await?;
-            println!("{res}");
+            writeln!(out, "{}", display.render(res)?)?;
         }
 
         Commands::ContractClass {
@@ -240,10 +692,11 @@ async fn main() -> Result<()> {
             block_id,
             starknet,
         } => {
-            let res = Probe::new(starknet.rpc_url)
+            let contract_address = starknet.resolve_address(&contract_address).await?;
+            let res = Probe::new(starknet.rpc_url())
                 .get_contract_class(contract_address, &block_id)
                 .await?;
-            println!("{res}");
+            writeln!(out, "{}", display.render(res)?)?;
         }
 
         Commands::ComputeAddress {
@@ -254,7 +707,99 @@ async fn main() -> Result<()> {
         } => {
             let res =
                 SimpleProbe::compute_contract_address(caller_address, salt, class_hash, &calldata);
-            println!("{res}");
+            writeln!(out, "{}", display.render(res)?)?;
+        }
+
+        Commands::Abi {
+            contract_address,
+            function,
+            block_id,
+            starknet,
+        } => {
+            let contract_address = starknet.resolve_address(&contract_address).await?;
+            let res = Probe::new(starknet.rpc_url())
+                .get_abi(contract_address, function.as_deref(), &block_id, to_json)
+                .await?;
+            writeln!(out, "{}", display.render(res)?)?;
+        }
+
+        Commands::AbiDiff {
+            class_a,
+            class_b,
+            block_id,
+            starknet,
+        } => {
+            let res = Probe::new(starknet.rpc_url())
+                .abi_diff(&class_a, &class_b, &block_id, to_json)
+                .await?;
+            writeln!(out, "{}", display.render(res)?)?;
+        }
+
+        Commands::DecodeCalldata {
+            function,
+            calldata,
+            abi,
+            contract,
+            block_id,
+            selectors,
+            starknet,
+        } => {
+            let contract = match &contract {
+                Some(contract) => Some(starknet.resolve_address(contract).await?),
+                None => None,
+            };
+            let res = Probe::new(starknet.rpc_url())
+                .with_selector_registry(selectors.registry())
+                .decode_calldata(&function, &calldata, &abi, contract, &block_id)
+                .await?;
+            writeln!(out, "{}", display.render(res)?)?;
+        }
+
+        Commands::Estimate { commands } => {
+            let fee = commands.run().await?;
+            writeln!(out, "{}", display.display(&fee)?)?;
+        }
+
+        Commands::Profile(args) => {
+            let profile = args.run().await?;
+            writeln!(out, "{}", display.colorize(profile.prettify(&fmt)))?;
+        }
+
+        Commands::Replay(args) => {
+            let report = args.run().await?;
+            writeln!(out, "{}", display.colorize(report.prettify(&fmt)))?;
+        }
+
+        Commands::EstimateMessage {
+            from,
+            to,
+            function,
+            payload,
+            block_id,
+            starknet,
+        } => {
+            let to = starknet.resolve_address(&to).await?;
+            let res = Probe::new(starknet.rpc_url())
+                .estimate_message_fee(from, to, &function, &payload, &block_id, to_json)
+                .await?;
+            writeln!(out, "{}", display.render(res)?)?;
+        }
+
+        Commands::Simulate { commands } => {
+            let res = commands.run().await?;
+            if to_json {
+                writeln!(
+                    out,
+                    "{}",
+                    display.render(serde_json::to_string_pretty(&res)?)?
+                )?;
+            } else {
+                writeln!(
+                    out,
+                    "{}",
+                    display.colorize(pretty_simulated_transaction(&res, &fmt))
+                )?;
+            }
         }
 
         Commands::Events {
@@ -262,43 +807,205 @@ async fn main() -> Result<()> {
             continuation_token,
             from,
             keys,
+            event,
             from_block,
             to_block,
             starknet,
         } => {
-            let res = Probe::new(starknet.rpc_url)
-                .get_events(
-                    EventFilter {
-                        address: from,
-                        from_block,
-                        to_block,
-                        keys,
-                    },
-                    chunk_size,
-                    continuation_token,
-                )
-                .await?;
-            println!("{res}");
+            let from = match &from {
+                Some(from) => Some(starknet.resolve_address(from).await?),
+                None => None,
+            };
+            let probe = Probe::new(starknet.rpc_url()).with_value_format(fmt);
+            let mut filter = EventFilter {
+                address: from,
+                from_block,
+                to_block,
+                keys,
+            };
+
+            // the keys of the filter are the values accepted for the first key of the events,
+            // the keys of nested events that come after it are matched on the events received
+            let event = match (event, from) {
+                (Some(name), Some(contract)) => {
+                    let (abi, keys) = probe.resolve_event(contract, &name).await?;
+                    filter.keys = keys.first().map(|key| vec![*key]);
+                    Some((abi, keys))
+                }
+                _ => None,
+            };
+            let event_keys = event.as_ref().map_or(&[][..], |(_, keys)| keys.as_slice());
+
+            match display.format() {
+                format @ (OutputFormat::Csv | OutputFormat::Ndjson) => {
+                    if display.query.is_some() {
+                        bail!("--query cannot be used with csv or ndjson output")
+                    }
+
+                    probe
+                        .stream_events(
+                            filter,
+                            event_keys,
+                            chunk_size,
+                            continuation_token,
+                            format == OutputFormat::Csv,
+                            &mut out,
+                        )
+                        .await?;
+                }
+
+                _ => {
+                    let res = match &event {
+                        Some((abi, keys)) => {
+                            probe
+                                .get_decoded_events(
+                                    filter,
+                                    abi,
+                                    keys,
+                                    chunk_size,
+                                    continuation_token,
+                                    to_json,
+                                )
+                                .await?
+                        }
+                        None => {
+                            probe
+                                .get_events(filter, chunk_size, continuation_token, to_json)
+                                .await?
+                        }
+                    };
+                    writeln!(out, "{}", display.render(res)?)?;
+                }
+            }
+        }
+
+        Commands::Encode {
+            abi,
+            function,
+            args_file,
+        } => {
+            writeln!(out, "{}", SimpleProbe::encode(&abi, &function, &args_file)?)?;
+        }
+
+        Commands::EventSelector { event, flat, abi } => {
+            writeln!(out, "{}", SimpleProbe::event_selector(&event, &flat, &abi)?)?;
+        }
+
+        Commands::Selector { name } => {
+            writeln!(out, "{}", SimpleProbe::selector(&name)?)?;
+        }
+
+        Commands::PrivateToPublic { private_key, full } => {
+            writeln!(
+                out,
+                "{}",
+                SimpleProbe::private_to_public(&private_key, full)?
+            )?;
+        }
+
+        Commands::SelectorLookup { selector, abi } => {
+            writeln!(out, "{}", SimpleProbe::selector_lookup(selector, &abi)?)?;
+        }
+
+        Commands::ToUnit {
+            amount,
+            decimals,
+            u256,
+        } => {
+            let (low, high) = SimpleProbe::to_unit(&amount, decimals)?;
+            if u256 {
+                writeln!(out, "{low:#x} {high:#x}")?;
+            } else {
+                writeln!(out, "{}", u256_to_dec(low, high))?;
+            }
+        }
+
+        Commands::FromUnit { amount, decimals } => {
+            writeln!(out, "{}", SimpleProbe::from_unit(&amount, decimals)?)?;
+        }
+
+        Commands::ToFixed { value, format } => {
+            writeln!(out, "{}", fixed::to_fixed(&value, format)?)?;
+        }
+
+        Commands::FromFixed { value, format } => {
+            writeln!(out, "{}", fixed::from_fixed(&value, format)?)?;
         }
 
         Commands::SplitU256 { value } => {
             let res = SimpleProbe::split_u256(&value)?;
-            println!("{} {}", res.0, res.1);
+            writeln!(out, "{} {}", res.0, res.1)?;
+        }
+
+        Commands::CombineU256 { low, high } => {
+            writeln!(out, "{}", SimpleProbe::combine_u256(&low, &high, &fmt)?)?;
+        }
+
+        Commands::U256 { commands } => {
+            writeln!(out, "{}", commands.run(&fmt)?)?;
         }
 
         Commands::Account { commands } => {
-            commands.run().await?;
+            commands.run(&display, &mut out).await?;
+        }
+
+        Commands::AddressBook { commands } => {
+            commands.run(&display, &mut out).await?;
         }
 
         Commands::Balance {
-            address,
+            addresses,
             block_id,
+            token,
+            wei,
             starknet,
         } => {
-            let res = Probe::new(starknet.rpc_url)
-                .get_eth_balance(address, block_id)
+            let addresses = starknet.resolve_addresses(&addresses).await?;
+            let chain = starknet.chain_id().await?;
+            let (token_address, known) = resolve_token(chain, &token)?;
+
+            let probe = Probe::new(starknet.rpc_url());
+            let balances = probe
+                .get_erc20_balances(token_address, &addresses, &block_id)
                 .await?;
-            println!("{res}");
+
+            // the units of a token outside of the registry are read from the token, which is
+            // printed in its smallest unit if it doesn't tell them
+            let exact = display.hex || display.dec || wei || display.quiet;
+            let units = match known {
+                _ if exact => None,
+                Some(known) => Some((known.decimals.into(), known.symbol)),
+                None => probe.get_erc20_units(token_address, &block_id).await.ok(),
+            };
+
+            let balances = balances
+                .into_iter()
+                .map(|(low, high)| match &units {
+                    _ if display.hex || display.dec => fmt.u256(low, high),
+                    Some((decimals, symbol)) => {
+                        format!("{} {symbol}", format_u256_units(low, high, *decimals))
+                    }
+                    None => u256_to_dec(low, high),
+                })
+                .collect::<Vec<_>>();
+
+            if let [balance] = &balances[..] {
+                writeln!(out, "{balance}")?;
+            } else if to_json {
+                let balances = addresses
+                    .iter()
+                    .zip(&balances)
+                    .map(|(address, balance)| json!({ "address": address, "balance": balance }))
+                    .collect::<Vec<_>>();
+                let json = serde_json::to_string_pretty(&balances)?;
+                writeln!(out, "{}", display.render(json)?)?;
+            } else {
+                writeln!(
+                    out,
+                    "{}",
+                    display.colorize(pretty_balances(&addresses, &balances, &fmt))
+                )?;
+            }
         }
 
         Commands::CallArray { calls } => {
@@ -308,14 +1015,123 @@ async fn main() -> Result<()> {
                 .map(|e| format!("{e:#x}"))
                 .collect::<Vec<String>>();
 
-            println!("{}", vec.join(" "))
+            writeln!(out, "{}", vec.join(" "))?
         }
 
         Commands::Invoke(args) => {
-            let res = args.run().await?;
-            println!("Transaction hash : {:#x}", res.transaction_hash);
+            if args.sign_only {
+                let output = args.out.clone();
+                write_transaction(&args.sign().await?, output, &mut out)?;
+            } else if args.dry_run {
+                writeln!(
+                    out,
+                    "{}",
+                    display.colorize(pretty_simulated_transaction(
+                        &args.simulate(false, false).await?,
+                        &fmt
+                    ))
+                )?;
+            } else {
+                let (res, receipt) = args
+                    .run()
+                    .await
+                    .map_err(|e| eyre!(decode_revert_reason(&e.to_string())))?;
+
+                if display.quiet {
+                    writeln!(out, "{:#x}", res.transaction_hash)?;
+                } else {
+                    writeln!(out, "Transaction hash : {:#x}", res.transaction_hash)?;
+
+                    if let Some(receipt) = receipt {
+                        writeln!(out, "\n{}", display.colorize(receipt.prettify(&fmt)))?;
+                    }
+                }
+            }
+        }
+
+        Commands::Erc20 { commands } => {
+            writeln!(out, "{}", commands.run(&display).await?)?;
+        }
+
+        Commands::Erc721 { commands } => {
+            writeln!(out, "{}", commands.run().await?)?;
+        }
+
+        Commands::Erc1155 { commands } => {
+            writeln!(out, "{}", commands.run(&display).await?)?;
+        }
+
+        Commands::Declare(args) => {
+            if args.sign_only {
+                let output = args.out.clone();
+                write_transaction(&args.sign().await?, output, &mut out)?;
+            } else {
+                let (res, receipt) = args.run().await?;
+
+                if display.quiet {
+                    writeln!(out, "{:#x}", res.class_hash)?;
+                } else {
+                    writeln!(out, "Class hash : {:#x}", res.class_hash)?;
+                    writeln!(out, "Transaction hash : {:#x}", res.transaction_hash)?;
+
+                    if let Some(receipt) = receipt {
+                        writeln!(out, "\n{}", display.colorize(receipt.prettify(&fmt)))?;
+                    }
+                }
+            }
+        }
+
+        Commands::Deploy(args) => {
+            let (address, res, receipt) = args.run().await?;
+
+            if display.quiet {
+                writeln!(out, "{address:#x}")?;
+            } else {
+                writeln!(out, "Contract address : {address:#x}")?;
+                writeln!(out, "Transaction hash : {:#x}", res.transaction_hash)?;
+
+                if let Some(receipt) = receipt {
+                    writeln!(out, "\n{}", display.colorize(receipt.prettify(&fmt)))?;
+                }
+            }
+        }
+
+        Commands::DeployAccount(args) => {
+            let (res, receipt) = args.run(display.quiet).await?;
+
+            if display.quiet {
+                writeln!(out, "{:#x}", res.contract_address)?;
+            } else {
+                writeln!(out, "Transaction hash : {:#x}", res.transaction_hash)?;
+
+                if let Some(receipt) = receipt {
+                    writeln!(out, "\n{}", display.colorize(receipt.prettify(&fmt)))?;
+                }
+            }
         }
     }
 
-    Ok(())
+    out.finish()
+}
+
+/// The selector of the `l1_handler` of a message, given either as is or by its function name.
+/// clap requires one of them.
+fn l1_handler_selector(
+    selector: Option<FieldElement>,
+    function: Option<String>,
+) -> Result<FieldElement> {
+    match selector {
+        Some(selector) => Ok(selector),
+        None => Ok(get_selector_from_name(&function.unwrap_or_default())?),
+    }
+}
+
+/// Reads a JSON argument, given either as is or as the path of a file holding it.
+fn read_json_arg(arg: &str) -> Result<serde_json::Value> {
+    let json = if arg.trim_start().starts_with(['{', '[']) {
+        arg.to_string()
+    } else {
+        std::fs::read_to_string(arg).map_err(|e| eyre!("failed to read {arg}: {e}"))?
+    };
+    Ok(serde_json::from_str(&json)?)
 }
@@ -0,0 +1,56 @@
+use super::utils::read_json_file;
+use crate::cmd::account::variant::AccountVariant;
+
+use std::fs;
+use std::path::Path;
+
+use eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::FieldElement;
+
+/// Describes a deployed account, so that it can be referenced by its file with `--account`
+/// instead of giving its address to every command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountDescriptor {
+    pub address: FieldElement,
+    pub class_hash: FieldElement,
+    pub variant: AccountVariant,
+    pub public_key: FieldElement,
+}
+
+impl AccountDescriptor {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        read_json_file(path)
+            .wrap_err_with(|| format!("failed to read the account descriptor {}", path.display()))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_and_load_descriptor() {
+        let path = std::env::temp_dir().join("probe-account-descriptor-test.json");
+        let descriptor = AccountDescriptor {
+            address: FieldElement::from_hex_be("0x123").unwrap(),
+            class_hash: FieldElement::from_hex_be("0x456").unwrap(),
+            variant: AccountVariant::Argent,
+            public_key: FieldElement::from_hex_be("0x789").unwrap(),
+        };
+
+        descriptor.save(&path).unwrap();
+        let content: serde_json::Value = read_json_file(&path).unwrap();
+
+        assert_eq!(content["variant"], "argent");
+        assert_eq!(AccountDescriptor::load(&path).unwrap(), descriptor);
+
+        fs::remove_file(path).unwrap();
+    }
+}
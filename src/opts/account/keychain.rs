@@ -0,0 +1,30 @@
+//! Private keys stored in the keychain of the operating system (the macOS Keychain, the Windows
+//! Credential Manager or the Secret Service), so that they never live on disk in plaintext.
+
+use eyre::{eyre, Result};
+use keyring::Entry;
+use starknet::core::types::FieldElement;
+
+/// The service the keys are stored under, with the name of the key as the user.
+const KEYCHAIN_SERVICE: &str = "starknet-probe";
+
+pub fn store_key(name: &str, private_key: FieldElement) -> Result<()> {
+    entry(name)?.set_password(&format!("{private_key:#x}"))?;
+    Ok(())
+}
+
+pub fn load_key(name: &str) -> Result<FieldElement> {
+    let key = entry(name)?
+        .get_password()
+        .map_err(|e| eyre!("unable to read the key `{name}` from the keychain: {e}"))?;
+    Ok(FieldElement::from_hex_be(&key)?)
+}
+
+pub fn remove_key(name: &str) -> Result<()> {
+    entry(name)?.delete_password()?;
+    Ok(())
+}
+
+fn entry(name: &str) -> Result<Entry> {
+    Ok(Entry::new(KEYCHAIN_SERVICE, name)?)
+}
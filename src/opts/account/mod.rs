@@ -1,19 +1,25 @@
+pub mod descriptor;
+pub mod keychain;
 pub mod utils;
 
+use self::keychain::load_key;
 use self::utils::get_from_keystore;
 use crate::cmd::account::simple_account::SimpleAccount;
+use crate::cmd::account::variant::AccountVariant;
+use crate::cmd::parser::AddressParser;
+use crate::probe::addressbook::AddressOrName;
 
 use std::{path::PathBuf, str::FromStr};
 
 use clap::{ArgGroup, Parser};
-use eyre::Result;
+use eyre::{bail, Result};
 use inquire::CustomType;
 use starknet::core::types::FieldElement;
 
 #[derive(Debug, Clone, Parser, Default)]
-#[clap(group(ArgGroup::new("wallet-method").args(["private_key", "keystore_path"])))]
+#[clap(group(ArgGroup::new("wallet-method").args(["private_key", "keystore_path", "keychain"])))]
 #[clap(group(ArgGroup::new("password-method").args(["keystore_password", "keystore_password_file"]).requires("keystore_path")))]
-#[clap(group(ArgGroup::new("wallet-interactive").args(["interactive"]).conflicts_with_all(["private_key", "account", "keystore_path", "keystore_password", "keystore_password_file"])))]
+#[clap(group(ArgGroup::new("wallet-interactive").args(["interactive"]).conflicts_with_all(["private_key", "account", "keystore_path", "keystore_password", "keystore_password_file", "keychain"])))]
 pub struct WalletOptions {
     #[clap(short, long)]
     #[clap(help_heading = "WALLET OPTIONS - RAW")]
@@ -21,18 +27,25 @@ pub struct WalletOptions {
     pub interactive: bool,
 
     #[clap(long)]
-    #[clap(requires = "account")]
     #[clap(value_name = "PRIVATE_KEY")]
     #[clap(help_heading = "WALLET OPTIONS - RAW")]
     #[clap(help = "The raw private key associated with the account contract.")]
     pub private_key: Option<FieldElement>,
 
-    #[clap(long)]
+    #[clap(long = "account-address")]
     #[clap(value_name = "FROM")]
     #[clap(requires = "wallet-method")]
+    #[clap(value_parser(AddressParser))]
     #[clap(help_heading = "WALLET OPTIONS - RAW")]
     #[clap(help = "Account contract to initiate the transaction from.")]
-    pub account: Option<FieldElement>,
+    pub account: Option<AddressOrName>,
+
+    #[clap(long = "account-variant")]
+    #[clap(value_enum)]
+    #[clap(value_name = "VARIANT")]
+    #[clap(help_heading = "WALLET OPTIONS - RAW")]
+    #[clap(help = "The kind of account contract. Taken from the account descriptor if not given.")]
+    pub variant: Option<AccountVariant>,
 
     #[clap(long = "keystore")]
     #[clap(value_name = "PATH")]
@@ -54,16 +67,40 @@ pub struct WalletOptions {
     #[clap(help_heading = "WALLET OPTIONS - KEYSTORE")]
     #[clap(help = "The keystore password file path. Used with --keystore.")]
     pub keystore_password_file: Option<PathBuf>,
+
+    #[clap(long = "keychain")]
+    #[clap(value_name = "NAME")]
+    #[clap(help_heading = "WALLET OPTIONS - KEYCHAIN")]
+    #[clap(help = "Use the private key stored under the given name in the OS keychain.")]
+    pub keychain: Option<String>,
 }
 
 impl WalletOptions {
     pub fn build_wallet(&self) -> Result<Option<SimpleAccount>> {
-        match self.keystore()?.or_else(|| self.raw()) {
+        let account = match self.keystore()? {
+            Some(account) => Some(account),
+            None => self.keychain()?,
+        };
+
+        let account = match account {
+            Some(account) => Some(account),
+            None => self.raw()?,
+        };
+
+        match account {
             Some(account) => Ok(Some(account)),
             None => self.interactive(),
         }
     }
 
+    /// Whether a way to get the key of the wallet is given.
+    pub fn has_wallet(&self) -> bool {
+        self.interactive
+            || self.private_key.is_some()
+            || self.keystore_path.is_some()
+            || self.keychain.is_some()
+    }
+
     pub fn interactive(&self) -> Result<Option<SimpleAccount>> {
         Ok(if self.interactive {
             let felt_prompter = |message: &'static str| {
@@ -84,21 +121,50 @@ impl WalletOptions {
         })
     }
 
-    pub fn raw(&self) -> Option<SimpleAccount> {
-        match (self.account, self.private_key) {
+    pub fn raw(&self) -> Result<Option<SimpleAccount>> {
+        Ok(match (self.account_address()?, self.private_key) {
             (Some(from), Some(pk)) => Some(SimpleAccount::new(None, from, pk, None)),
             _ => None,
-        }
+        })
+    }
+
+    pub fn keychain(&self) -> Result<Option<SimpleAccount>> {
+        let Some(name) = &self.keychain else {
+            return Ok(None);
+        };
+
+        let Some(account) = self.account_address()? else {
+            bail!("the account address must be given to use a key from the keychain")
+        };
+
+        Ok(Some(SimpleAccount::new(
+            None,
+            account,
+            load_key(name)?,
+            None,
+        )))
     }
 
     pub fn keystore(&self) -> Result<Option<SimpleAccount>> {
         get_from_keystore(
-            self.account,
+            self.account_address()?,
             self.keystore_path.as_ref(),
             self.keystore_password.as_ref(),
             self.keystore_password_file.as_ref(),
         )
     }
+
+    /// The address given with `--account-address`. A saved name must have been resolved with the
+    /// chain of the network beforehand.
+    fn account_address(&self) -> Result<Option<FieldElement>> {
+        match &self.account {
+            Some(AddressOrName::Address(address)) => Ok(Some(*address)),
+            Some(AddressOrName::Name(name)) => {
+                bail!("`{name}` must be resolved with the chain of the network first")
+            }
+            None => Ok(None),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -116,7 +182,7 @@ mod tests {
 
         let file = Path::new("./tests/test-keys/test-key1.json");
         let opts = WalletOptions {
-            account: Some(account_addr),
+            account: Some(account_addr.into()),
             keystore_path: Some(file.to_path_buf()),
             keystore_password: Some("12345".to_string()),
             ..Default::default()
@@ -152,7 +218,7 @@ mod tests {
         let password_file = Path::new("./tests/test-keys/password1");
 
         let opts = WalletOptions {
-            account: Some(account_addr),
+            account: Some(account_addr.into()),
             keystore_path: Some(file.to_path_buf()),
             keystore_password_file: Some(password_file.to_path_buf()),
             ..Default::default()
@@ -183,12 +249,12 @@ mod tests {
         let private_key = FieldElement::from_hex_be("").unwrap();
 
         let opts = WalletOptions {
-            account: Some(from),
+            account: Some(from.into()),
             private_key: Some(private_key),
             ..Default::default()
         };
 
-        let wallet = opts.raw().unwrap();
+        let wallet = opts.raw().unwrap().unwrap();
 
         assert!(wallet.chain.is_none());
         assert_eq!(wallet.account, from);
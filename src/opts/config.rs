@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::Command;
+use eyre::{bail, eyre, Result};
+use serde::Deserialize;
+use toml::Value;
+
+pub const CONFIG_FILE: &str = ".starknet/probe/config.toml";
+
+/// The starter config file written by `probe config init`.
+pub const CONFIG_TEMPLATE: &str = r#"# The configuration of probe. Every section of it is optional.
+
+# Networks to connect to with `--network <NAME>`, alongside mainnet, sepolia and goerli. A network
+# named after one of those replaces it, e.g. to use a private endpoint.
+[networks.devnet]
+rpc_url = "http://localhost:5050/rpc"
+# The chain id as a number or as a short string. It is asked to the node when left out.
+# chain_id = "SN_GOERLI"
+
+# The account that sends the transactions when no wallet is given on the command line. The
+# STARKNET_ACCOUNT and STARKNET_KEYSTORE environment variables take precedence over it.
+# [account]
+# descriptor = "~/.starknet/accounts/main.json"
+# keystore = "~/.starknet/keystore/main.json"
+
+# Default options of the commands, by the name of the command and of the option. They are
+# overridden by the command line and the environment.
+# [defaults.events]
+# chunk-size = 500
+#
+# [defaults.block]
+# full = true
+"#;
+
+/// The configuration of probe, read from `~/.starknet/probe/config.toml`. The file is optional
+/// and every section of it may be left out.
+///
+/// ```toml
+/// [networks.devnet]
+/// rpc_url = "http://localhost:5050/rpc"
+/// chain_id = "SN_GOERLI"
+///
+/// [account]
+/// descriptor = "~/.starknet/accounts/main.json"
+/// keystore = "~/.starknet/keystore/main.json"
+///
+/// [defaults.events]
+/// chunk-size = 500
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub networks: BTreeMap<String, NetworkConfig>,
+
+    #[serde(default)]
+    pub account: AccountConfig,
+
+    /// The default options of the commands, by the name of the command (e.g. `events` or
+    /// `tx-hash invoke` for a nested one) and then the name of the option.
+    #[serde(default)]
+    pub defaults: BTreeMap<String, BTreeMap<String, Value>>,
+}
+
+/// A network defined by the user, to be used with `--network`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkConfig {
+    pub rpc_url: String,
+    /// The chain id, either as a number or as a short string e.g. `SN_MAIN`.
+    pub chain_id: Option<String>,
+}
+
+/// The default account of the commands that send transactions.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountConfig {
+    /// The account descriptor, as given with `--account`.
+    pub descriptor: Option<String>,
+    /// The keystore of the key of the account, as given with `--keystore`.
+    pub keystore: Option<String>,
+}
+
+impl AccountConfig {
+    pub fn descriptor(&self) -> Option<PathBuf> {
+        self.descriptor.as_deref().map(expand_path)
+    }
+
+    pub fn keystore(&self) -> Option<PathBuf> {
+        self.keystore.as_deref().map(expand_path)
+    }
+}
+
+impl Config {
+    pub fn load() -> Result<Self> {
+        Self::from_file(config_path()?)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| eyre!("invalid config file {}: {e}", path.display()))
+    }
+
+    /// Sets the defaults of the config file as the default values of the options of the
+    /// commands, so that the command line and the environment still take precedence.
+    pub fn apply_defaults(&self, mut cmd: Command) -> Result<Command> {
+        for (path, options) in &self.defaults {
+            let mut sub = &mut cmd;
+            for name in path.split_whitespace() {
+                sub = sub
+                    .get_subcommands_mut()
+                    .find(|sub| sub.get_name() == name || sub.get_all_aliases().any(|a| a == name))
+                    .ok_or_else(|| {
+                        eyre!("unknown command `{path}` in the defaults of the config")
+                    })?;
+            }
+
+            for (option, value) in options {
+                let id = sub
+                    .get_arguments()
+                    .find(|arg| {
+                        arg.get_long() == Some(option.as_str()) || arg.get_id().as_str() == option
+                    })
+                    .map(|arg| arg.get_id().as_str().to_string())
+                    .ok_or_else(|| {
+                        eyre!("unknown option `{option}` of `{path}` in the defaults of the config")
+                    })?;
+
+                let values = default_values(value)?;
+                *sub = std::mem::take(sub)
+                    .mut_arg(id, |arg| arg.default_values(values).required(false));
+            }
+        }
+
+        Ok(cmd)
+    }
+}
+
+/// The default values of an option, as given to clap. clap only takes static strings, which is
+/// fine as the config is read once per run.
+fn default_values(value: &Value) -> Result<Vec<&'static str>> {
+    let values = match value {
+        Value::Array(values) => values.iter().map(scalar).collect::<Result<Vec<_>>>()?,
+        value => vec![scalar(value)?],
+    };
+
+    Ok(values
+        .into_iter()
+        .map(|value| &*Box::leak(value.into_boxed_str()))
+        .collect())
+}
+
+fn scalar(value: &Value) -> Result<String> {
+    match value {
+        Value::String(value) => Ok(value.clone()),
+        Value::Integer(_) | Value::Float(_) | Value::Boolean(_) | Value::Datetime(_) => {
+            Ok(value.to_string())
+        }
+        Value::Array(_) | Value::Table(_) => bail!("invalid default value `{value}`"),
+    }
+}
+
+pub fn config_path() -> Result<PathBuf> {
+    let home = home::home_dir().ok_or_else(|| eyre!("could not find the home directory"))?;
+    Ok(home.join(CONFIG_FILE))
+}
+
+fn expand_path(path: &str) -> PathBuf {
+    PathBuf::from(shellexpand::tilde(path).as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::probe::{App, Commands};
+
+    use clap::{CommandFactory, FromArgMatches};
+
+    #[test]
+    fn parse_config_template() {
+        let config: Config = toml::from_str(CONFIG_TEMPLATE).unwrap();
+        assert_eq!(
+            config.networks["devnet"].rpc_url,
+            "http://localhost:5050/rpc"
+        );
+        assert!(config.account.descriptor().is_none());
+
+        let config: Config = toml::from_str(
+            r#"
+            [account]
+            descriptor = "~/.starknet/accounts/main.json"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.account.descriptor().unwrap(),
+            home::home_dir()
+                .unwrap()
+                .join(".starknet/accounts/main.json")
+        );
+        assert!(config.account.keystore().is_none());
+    }
+
+    #[test]
+    fn apply_command_defaults() {
+        let config: Config = toml::from_str(
+            r#"
+            [defaults.events]
+            chunk-size = 500
+
+            [defaults.b]
+            full = true
+            field = ["block_hash", "timestamp"]
+            "#,
+        )
+        .unwrap();
+        let cmd = config.apply_defaults(App::command()).unwrap();
+
+        let parse = |args: &[&str]| App::from_arg_matches(&cmd.clone().get_matches_from(args));
+        match parse(&["probe", "events"]).unwrap().command {
+            Commands::Events { chunk_size, .. } => assert_eq!(chunk_size, 500),
+            _ => unreachable!(),
+        }
+        match parse(&["probe", "events", "-s", "10"]).unwrap().command {
+            Commands::Events { chunk_size, .. } => assert_eq!(chunk_size, 10),
+            _ => unreachable!(),
+        }
+        match parse(&["probe", "block", "latest"]).unwrap().command {
+            Commands::Block { full, fields, .. } => {
+                assert!(full);
+                assert_eq!(fields, ["block_hash", "timestamp"]);
+            }
+            _ => unreachable!(),
+        }
+
+        let config: Config = toml::from_str("[defaults.events]\nunknown = 1").unwrap();
+        assert!(config.apply_defaults(App::command()).is_err());
+    }
+}
@@ -0,0 +1,412 @@
+use crate::probe::utils::fmt::{colorize, Pretty, ValueFormat};
+
+use std::fs;
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, ValueEnum};
+use eyre::{bail, eyre, Result};
+use jaq_core::load::{Arena, File, Loader};
+use jaq_core::{Compiler, Ctx, RcIter};
+use jaq_json::Val;
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Pretty,
+    Json,
+    JsonCompact,
+    Yaml,
+    /// One row per event, only supported by `events` and `watch events`.
+    Csv,
+    /// One line of JSON per event or block, only supported by `events` and `watch`.
+    Ndjson,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ColorChoice {
+    /// Colors when printing to a terminal, unless `NO_COLOR` is set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// How the results of the commands are printed. The options are global so that they are given
+/// the same way to every command.
+#[derive(Debug, Clone, Default, Parser)]
+pub struct DisplayOptions {
+    #[clap(long)]
+    #[clap(global = true)]
+    #[clap(value_enum)]
+    #[clap(value_name = "FORMAT")]
+    #[clap(default_value_t)]
+    #[clap(help_heading = "Display options")]
+    #[clap(help = "The format the result is printed in.")]
+    pub output: OutputFormat,
+
+    #[clap(short = 'j', long = "json")]
+    #[clap(global = true)]
+    #[clap(conflicts_with = "output")]
+    #[clap(help_heading = "Display options")]
+    #[clap(help = "Print the result as JSON. Shorthand for --output json.")]
+    pub json: bool,
+
+    #[clap(long)]
+    #[clap(global = true)]
+    #[clap(value_name = "EXPR")]
+    #[clap(help_heading = "Display options")]
+    #[clap(
+        help = "A jq filter applied to the JSON result, e.g. '[.transactions[] | select(.type == \"DECLARE\") | .transaction_hash]'."
+    )]
+    pub query: Option<String>,
+
+    #[clap(long)]
+    #[clap(global = true)]
+    #[clap(help_heading = "Display options")]
+    #[clap(
+        help = "Print felts and timestamps as is, without the decoded short strings and dates."
+    )]
+    #[clap(
+        long_help = "Print felts and timestamps as is, without the decoded short strings and dates.
+
+This is not named --raw since a global --raw would clash with `rpc --raw`, which passes the params as is, and with the --raw alias of --wei of `balance` and `watch balance`."
+    )]
+    pub no_decode: bool,
+
+    #[clap(long)]
+    #[clap(global = true)]
+    #[clap(help_heading = "Display options")]
+    #[clap(help = "Print felts in decimal.")]
+    pub dec: bool,
+
+    #[clap(long)]
+    #[clap(global = true)]
+    #[clap(conflicts_with = "dec")]
+    #[clap(help_heading = "Display options")]
+    #[clap(help = "Print felts in hex. This is the default.")]
+    pub hex: bool,
+
+    #[clap(long)]
+    #[clap(global = true)]
+    #[clap(value_enum)]
+    #[clap(value_name = "WHEN")]
+    #[clap(default_value_t)]
+    #[clap(help_heading = "Display options")]
+    #[clap(help = "When to color the pretty output.")]
+    pub color: ColorChoice,
+
+    #[clap(short = 'o', long)]
+    #[clap(global = true)]
+    #[clap(value_name = "PATH")]
+    #[clap(help_heading = "Display options")]
+    #[clap(help = "Write the result to the given file instead of printing it.")]
+    pub output_file: Option<PathBuf>,
+
+    #[clap(short, long)]
+    #[clap(global = true)]
+    #[clap(help_heading = "Display options")]
+    #[clap(
+        help = "Only print the primary value of the result, e.g. the transaction hash, without labels or progress messages."
+    )]
+    pub quiet: bool,
+}
+
+impl DisplayOptions {
+    pub fn format(&self) -> OutputFormat {
+        if self.json {
+            OutputFormat::Json
+        } else {
+            self.output
+        }
+    }
+
+    /// Whether the result should be machine-readable rather than rendered for humans. A query
+    /// always needs the JSON result to run on.
+    pub fn is_json(&self) -> bool {
+        self.format() != OutputFormat::Pretty || self.query.is_some()
+    }
+
+    /// How the felts and timestamps are rendered.
+    pub fn value_format(&self) -> ValueFormat {
+        ValueFormat {
+            raw: self.no_decode,
+            decimal: self.dec,
+        }
+    }
+
+    fn colors(&self) -> bool {
+        match self.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                self.output_file.is_none()
+                    && !matches!(std::env::var_os("NO_COLOR"), Some(v) if !v.is_empty())
+                    && std::io::stdout().is_terminal()
+            }
+        }
+    }
+
+    /// Where the result is written: the file given with `--output-file`, or else stdout.
+    pub fn writer(&self) -> Output {
+        match &self.output_file {
+            Some(path) => Output::Buffered {
+                path: path.clone(),
+                buffer: Vec::new(),
+            },
+            None => Output::Stdout(io::stdout()),
+        }
+    }
+
+    /// Colors pretty output when colors are enabled.
+    pub fn colorize(&self, output: String) -> String {
+        if self.colors() {
+            colorize(&output)
+        } else {
+            output
+        }
+    }
+
+    /// Converts the output of a command into the requested format. Commands give their result
+    /// as pretty JSON when `is_json` is set, which is only reformatted for the compact JSON and
+    /// YAML formats or when a query is given. Any other output is left as is.
+    pub fn render(&self, output: impl ToString) -> Result<String> {
+        let output = output.to_string();
+
+        // a single string is printed without quotes so that it can be used as is
+        if self.quiet {
+            if let Ok(Value::String(value)) = serde_json::from_str(&output) {
+                return Ok(value);
+            }
+        }
+
+        if self.query.is_none() {
+            match self.format() {
+                OutputFormat::Pretty => return Ok(self.colorize(output)),
+                OutputFormat::Json => return Ok(output),
+                _ => {}
+            }
+        }
+
+        match serde_json::from_str::<Value>(&output) {
+            Ok(value) => self.render_json(value),
+            Err(_) if self.query.is_some() => Err(eyre!(
+                "--query can only be used with commands that output JSON"
+            )),
+            Err(_) => Ok(output),
+        }
+    }
+
+    /// Renders a result that has both a human readable and a serialized form.
+    pub fn display<T>(&self, value: &T) -> Result<String>
+    where
+        T: Pretty + Serialize,
+    {
+        if self.is_json() {
+            self.render_json(serde_json::to_value(value)?)
+        } else {
+            Ok(self.colorize(value.prettify(&self.value_format())))
+        }
+    }
+
+    /// Renders every value the query outputs on its own, or the value itself without a query.
+    fn render_json(&self, value: Value) -> Result<String> {
+        let values = match &self.query {
+            Some(query) => run_query(query, value)?,
+            None => vec![value],
+        };
+
+        values
+            .iter()
+            .map(|value| {
+                Ok(match self.format() {
+                    OutputFormat::Pretty | OutputFormat::Json => {
+                        serde_json::to_string_pretty(value)?
+                    }
+                    OutputFormat::JsonCompact => serde_json::to_string(value)?,
+                    OutputFormat::Yaml => serde_yaml::to_string(value)?.trim_end().to_string(),
+                    OutputFormat::Csv | OutputFormat::Ndjson => {
+                        bail!("csv and ndjson output are only supported by the events and watch commands")
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(|values| values.join("\n"))
+    }
+}
+
+/// Where the result of a command is written. The result written to a file is kept until the
+/// command succeeds, so that a failing command leaves an existing file as it was.
+pub enum Output {
+    Stdout(io::Stdout),
+    Buffered { path: PathBuf, buffer: Vec<u8> },
+    File(BufWriter<fs::File>),
+}
+
+impl Output {
+    /// Writes the result to the file as it comes rather than once the command is done, for the
+    /// commands that run until they are interrupted.
+    pub fn stream(&mut self) -> Result<()> {
+        if let Output::Buffered { path, buffer } = self {
+            let mut file = create_file(path)?;
+            file.write_all(buffer)?;
+            *self = Output::File(file);
+        }
+        Ok(())
+    }
+
+    /// Writes out the result of the command, which succeeded.
+    pub fn finish(self) -> Result<()> {
+        match self {
+            Output::Stdout(mut stdout) => stdout.flush()?,
+            Output::Buffered { path, buffer } => {
+                let mut file = create_file(&path)?;
+                file.write_all(&buffer)?;
+                file.flush()?;
+            }
+            Output::File(mut file) => file.flush()?,
+        }
+        Ok(())
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Stdout(stdout) => stdout.write(buf),
+            Output::Buffered { buffer, .. } => buffer.write(buf),
+            Output::File(file) => file.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Stdout(stdout) => stdout.flush(),
+            Output::Buffered { .. } => Ok(()),
+            Output::File(file) => file.flush(),
+        }
+    }
+}
+
+/// Creates the file, along with its parent directories if needed.
+fn create_file(path: &Path) -> Result<BufWriter<fs::File>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(BufWriter::new(fs::File::create(path)?))
+}
+
+/// Runs the jq filter on the value, returning all the values it outputs.
+fn run_query(query: &str, value: Value) -> Result<Vec<Value>> {
+    let invalid = || eyre!("`{query}` is not a valid query");
+
+    let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+    let arena = Arena::default();
+    let modules = loader
+        .load(
+            &arena,
+            File {
+                code: query,
+                path: (),
+            },
+        )
+        .map_err(|_| invalid())?;
+
+    let filter = Compiler::default()
+        .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+        .compile(modules)
+        .map_err(|_| invalid())?;
+
+    let inputs = RcIter::new(core::iter::empty());
+    filter
+        .run((Ctx::new([], &inputs), Val::from(value)))
+        .map(|value| {
+            value
+                .map(Value::from)
+                .map_err(|e| eyre!("failed to run query: {e}"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_output_formats() {
+        let display = |output| DisplayOptions {
+            output,
+            ..Default::default()
+        };
+        let output = "{\n  \"block_number\": 1,\n  \"keys\": [\n    \"0x1\"\n  ]\n}";
+
+        assert_eq!(display(OutputFormat::Json).render(output).unwrap(), output);
+        assert_eq!(
+            display(OutputFormat::JsonCompact).render(output).unwrap(),
+            r#"{"block_number":1,"keys":["0x1"]}"#
+        );
+        assert_eq!(
+            display(OutputFormat::Yaml).render(output).unwrap(),
+            "block_number: 1\nkeys:\n- '0x1'"
+        );
+        assert_eq!(
+            display(OutputFormat::Yaml).render("0x1 0x2").unwrap(),
+            "0x1 0x2"
+        );
+
+        let quiet = DisplayOptions {
+            quiet: true,
+            ..Default::default()
+        };
+        assert_eq!(quiet.render("\"0x1\"").unwrap(), "0x1");
+    }
+
+    #[test]
+    fn query_json_output() {
+        let display = |query: &str| DisplayOptions {
+            query: Some(query.to_string()),
+            ..Default::default()
+        };
+        let output = r#"{"transactions":[{"type":"DECLARE","transaction_hash":"0x1"},{"type":"INVOKE","transaction_hash":"0x2"},{"type":"DECLARE","transaction_hash":"0x3"}]}"#;
+
+        assert!(display(".").is_json());
+        assert_eq!(
+            display(r#"[.transactions[] | select(.type == "DECLARE") | .transaction_hash]"#)
+                .render(output)
+                .unwrap(),
+            "[\n  \"0x1\",\n  \"0x3\"\n]"
+        );
+        assert_eq!(
+            display(".transactions[].type").render(output).unwrap(),
+            "\"DECLARE\"\n\"INVOKE\"\n\"DECLARE\""
+        );
+        assert!(display(".transactions[").render(output).is_err());
+        assert!(display(".").render("0x1 0x2").is_err());
+    }
+
+    #[test]
+    fn output_file_written_on_success() {
+        let path = std::env::temp_dir().join(format!("probe-output-{}.txt", std::process::id()));
+        fs::write(&path, "previous result\n").unwrap();
+
+        let display = DisplayOptions {
+            output_file: Some(path.clone()),
+            ..Default::default()
+        };
+
+        // a command failing after writing part of its result leaves the file as it was
+        let mut out = display.writer();
+        writeln!(out, "partial result").unwrap();
+        drop(out);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "previous result\n");
+
+        let mut out = display.writer();
+        writeln!(out, "result").unwrap();
+        out.finish().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "result\n");
+
+        fs::remove_file(path).unwrap();
+    }
+}
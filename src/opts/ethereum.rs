@@ -0,0 +1,19 @@
+use clap::Parser;
+use reqwest::Url;
+use starknet::core::types::FieldElement;
+
+#[derive(Debug, Clone, Parser)]
+pub struct EthereumOptions {
+    #[clap(long)]
+    #[clap(value_name = "URL")]
+    #[clap(env = "ETH_RPC_URL")]
+    #[clap(help = "The RPC endpoint of the Ethereum node")]
+    pub eth_rpc_url: Url,
+
+    #[clap(long)]
+    #[clap(value_name = "L1_ADDRESS")]
+    #[clap(
+        help = "The address of the StarkNet core contract. Defaults to the one of the Ethereum chain, for mainnet and sepolia."
+    )]
+    pub core_contract: Option<FieldElement>,
+}
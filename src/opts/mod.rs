@@ -1,3 +1,8 @@
 pub mod account;
+pub mod config;
+pub mod display;
+pub mod ethereum;
+pub mod notify;
+pub mod selector;
 pub mod starknet;
 pub mod transaction;
@@ -0,0 +1,70 @@
+use std::process::Stdio;
+
+use clap::Parser;
+use eyre::{bail, Result};
+use reqwest::Url;
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+#[derive(Debug, Clone, Parser)]
+pub struct NotifyOptions {
+    #[clap(long)]
+    #[clap(value_name = "URL")]
+    #[clap(help = "Send every record as JSON to the URL, in the body of a POST request.")]
+    pub notify_url: Option<Url>,
+
+    #[clap(long)]
+    #[clap(value_name = "CMD")]
+    #[clap(help = "Run the shell command for every record, with the record as JSON on its stdin.")]
+    pub exec: Option<String>,
+}
+
+impl NotifyOptions {
+    /// Sends the record to the URL and the command. A failed notification is reported without
+    /// stopping the command, so that a watch keeps running while the receiver is down.
+    pub async fn notify(&self, record: &Value) {
+        if let Some(url) = &self.notify_url {
+            if let Err(e) = post(url, record).await {
+                eprintln!("failed to notify {url}: {e}");
+            }
+        }
+
+        if let Some(command) = &self.exec {
+            if let Err(e) = exec(command, record).await {
+                eprintln!("failed to run `{command}`: {e}");
+            }
+        }
+    }
+}
+
+async fn post(url: &Url, record: &Value) -> Result<()> {
+    reqwest::Client::new()
+        .post(url.clone())
+        .json(record)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+/// Runs the command to completion, so that the records are handled in the order they come.
+async fn exec(command: &str, record: &Value) -> Result<()> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(record.to_string().as_bytes()).await?;
+    }
+
+    let status = child.wait().await?;
+    if !status.success() {
+        bail!("exited with {status}")
+    }
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use clap::Parser;
+use reqwest::Url;
+
+#[derive(Debug, Clone, Parser)]
+pub struct SelectorOptions {
+    #[clap(long)]
+    #[clap(value_name = "URL")]
+    #[clap(env = "STARKNET_SELECTOR_REGISTRY")]
+    #[clap(
+        help = "A registry to resolve the selectors that are not known locally. \
+    It is queried at <URL>/<SELECTOR> and must respond with {\"name\": <NAME>}."
+    )]
+    pub selector_registry: Option<Url>,
+
+    #[clap(long)]
+    #[clap(help = "Only resolve selectors from the local database, without any network lookup.")]
+    pub offline: bool,
+}
+
+impl SelectorOptions {
+    /// The registry to query, if network lookups are enabled.
+    pub fn registry(&self) -> Option<Url> {
+        if self.offline {
+            None
+        } else {
+            self.selector_registry.clone()
+        }
+    }
+}
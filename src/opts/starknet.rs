@@ -1,13 +1,22 @@
-use crate::cmd::parser::ChainParser;
+use super::config::Config;
+use crate::cmd::parser::{ChainParser, NetworkParser};
+use crate::probe::addressbook::AddressOrName;
+use crate::probe::chains::known_chains;
+use crate::probe::ws::{is_websocket, WsClient};
 
+use std::path::PathBuf;
 use std::{fmt, str::FromStr};
 
 use clap::Parser;
+use eyre::{eyre, Result};
 use reqwest::Url;
+use serde_json::json;
 use starknet::core::{
     chain_id::{MAINNET, TESTNET, TESTNET2},
     types::FieldElement,
+    utils::cairo_short_string_to_felt,
 };
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 
 #[derive(Debug, Clone, Parser)]
 pub struct StarkNetOptions {
@@ -18,11 +27,154 @@ pub struct StarkNetOptions {
     #[clap(default_value = "http://localhost:5050/rpc")]
     pub rpc_url: Url,
 
+    #[clap(long)]
+    #[clap(value_name = "NETWORK")]
+    #[clap(value_parser(NetworkParser))]
+    #[clap(
+        help = "The network to connect to: mainnet, sepolia, goerli or one defined in the config file. Takes precedence over --rpc-url."
+    )]
+    pub network: Option<Network>,
+
     #[clap(long)]
     #[clap(env = "STARKNET_CHAIN")]
     #[clap(value_name = "CHAIN_ID")]
     #[clap(value_parser(ChainParser))]
     pub chain: Option<FieldElement>,
+
+    #[clap(long = "account")]
+    #[clap(value_name = "PATH")]
+    #[clap(env = "STARKNET_ACCOUNT")]
+    #[clap(help = "An account descriptor file, to use in place of --account-address")]
+    pub account_descriptor: Option<PathBuf>,
+}
+
+impl StarkNetOptions {
+    /// The RPC endpoint of the network if one is given, or else the one of `--rpc-url`.
+    pub fn rpc_url(&self) -> Url {
+        match &self.network {
+            Some(network) => network.rpc_url.clone(),
+            None => self.rpc_url.clone(),
+        }
+    }
+
+    /// The chain id given with `--chain`, or else the one of the network.
+    pub fn chain(&self) -> Option<FieldElement> {
+        self.chain
+            .or_else(|| self.network.as_ref().and_then(|n| n.chain_id))
+    }
+
+    /// The chain id given with `--chain` or by the network, or else the one of the node.
+    pub async fn chain_id(&self) -> Result<FieldElement> {
+        let url = self.rpc_url();
+        match self.chain() {
+            Some(chain_id) => Ok(chain_id),
+            None if is_websocket(&url) => {
+                WsClient::connect(&url)
+                    .await?
+                    .request("starknet_chainId", json!([]))
+                    .await
+            }
+            None => Ok(JsonRpcClient::new(HttpTransport::new(url))
+                .chain_id()
+                .await?),
+        }
+    }
+
+    /// The address itself, or the address saved under the name for the chain. The chain id is
+    /// only looked up for a name.
+    pub async fn resolve_address(&self, address: &AddressOrName) -> Result<FieldElement> {
+        match address {
+            AddressOrName::Address(address) => Ok(*address),
+            AddressOrName::Name(_) => address.resolve(self.chain_id().await?),
+        }
+    }
+
+    /// Resolves each of the addresses, looking up the chain id at most once.
+    pub async fn resolve_addresses(
+        &self,
+        addresses: &[AddressOrName],
+    ) -> Result<Vec<FieldElement>> {
+        let chain = match addresses
+            .iter()
+            .any(|address| matches!(address, AddressOrName::Name(_)))
+        {
+            true => self.chain_id().await?,
+            false => FieldElement::ZERO,
+        };
+
+        addresses
+            .iter()
+            .map(|address| address.resolve(chain))
+            .collect()
+    }
+}
+
+/// The public networks, with an RPC endpoint anyone can use.
+const PUBLIC_NETWORKS: [(&str, &str, &str); 3] = [
+    (
+        "mainnet",
+        "https://starknet-mainnet.public.blastapi.io",
+        "SN_MAIN",
+    ),
+    (
+        "sepolia",
+        "https://starknet-sepolia.public.blastapi.io",
+        "SN_SEPOLIA",
+    ),
+    (
+        "goerli",
+        "https://starknet-testnet.public.blastapi.io",
+        "SN_GOERLI",
+    ),
+];
+
+/// A network to connect to, given by name with `--network`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Network {
+    pub rpc_url: Url,
+    /// The chain id of the network, if it is known without asking the node.
+    pub chain_id: Option<FieldElement>,
+}
+
+impl Network {
+    /// Finds the network with the given name. The networks of the config file are looked up
+    /// first, so that they can replace the public ones e.g. with a private endpoint.
+    pub fn resolve(name: &str, config: &Config) -> Result<Self> {
+        if let Some(network) = config.networks.get(name) {
+            return Ok(Self {
+                rpc_url: Url::parse(&network.rpc_url)?,
+                chain_id: network
+                    .chain_id
+                    .as_deref()
+                    .map(parse_chain_id)
+                    .transpose()?,
+            });
+        }
+
+        let (_, rpc_url, chain_id) = PUBLIC_NETWORKS
+            .iter()
+            .find(|(public, ..)| *public == name)
+            .ok_or_else(|| eyre!("unknown network `{name}`"))?;
+
+        Ok(Self {
+            rpc_url: Url::parse(rpc_url)?,
+            chain_id: Some(cairo_short_string_to_felt(chain_id)?),
+        })
+    }
+}
+
+/// Parses a chain id given by the alias of a known chain, as a number, or as its short string
+/// e.g. `SN_MAIN`. The aliases and short strings of the known chains are matched in any case.
+pub fn parse_chain_id(chain_id: &str) -> Result<FieldElement> {
+    if let Some(chain) = known_chains().into_iter().find(|chain| {
+        chain.alias.eq_ignore_ascii_case(chain_id) || chain.name.eq_ignore_ascii_case(chain_id)
+    }) {
+        Ok(chain.id)
+    } else if chain_id.starts_with("0x") || chain_id.chars().all(|c| c.is_ascii_digit()) {
+        FieldElement::from_str(chain_id).map_err(|_| eyre!("invalid chain id `{chain_id}`"))
+    } else {
+        cairo_short_string_to_felt(chain_id).map_err(|_| eyre!("invalid chain id `{chain_id}`"))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -32,7 +184,6 @@ pub enum StarknetChain {
     Testnet2,
 }
 
-#[allow(unused)]
 impl StarknetChain {
     pub fn get_id(&self) -> FieldElement {
         match self {
@@ -57,6 +208,22 @@ impl fmt::Display for StarknetChain {
 #[error("invalid chain id")]
 pub struct InvalidStarknetChain;
 
+impl TryFrom<FieldElement> for StarknetChain {
+    type Error = InvalidStarknetChain;
+
+    fn try_from(id: FieldElement) -> Result<Self, Self::Error> {
+        if id == MAINNET {
+            Ok(Self::Mainnet)
+        } else if id == TESTNET {
+            Ok(Self::Testnet)
+        } else if id == TESTNET2 {
+            Ok(Self::Testnet2)
+        } else {
+            Err(InvalidStarknetChain)
+        }
+    }
+}
+
 impl FromStr for StarknetChain {
     type Err = InvalidStarknetChain;
 
@@ -70,3 +237,55 @@ impl FromStr for StarknetChain {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_networks() {
+        let config: Config = toml::from_str(
+            r#"
+            [networks.devnet]
+            rpc_url = "http://localhost:5050/rpc"
+
+            [networks.mainnet]
+            rpc_url = "https://example.com/rpc"
+            chain_id = "SN_MAIN"
+            "#,
+        )
+        .unwrap();
+
+        let devnet = Network::resolve("devnet", &config).unwrap();
+        assert_eq!(devnet.rpc_url.as_str(), "http://localhost:5050/rpc");
+        assert_eq!(devnet.chain_id, None);
+
+        let mainnet = Network::resolve("mainnet", &config).unwrap();
+        assert_eq!(mainnet.rpc_url.as_str(), "https://example.com/rpc");
+        assert_eq!(mainnet.chain_id, Some(MAINNET));
+
+        let goerli = Network::resolve("goerli", &Config::default()).unwrap();
+        assert_eq!(goerli.chain_id, Some(TESTNET));
+
+        assert!(Network::resolve("unknown", &config).is_err());
+    }
+
+    #[test]
+    fn parse_chain_aliases() {
+        for chain_id in [
+            "mainnet",
+            "MAINNET",
+            "SN_MAIN",
+            "sn_main",
+            "0x534e5f4d41494e",
+            "23448594291968334",
+        ] {
+            assert_eq!(parse_chain_id(chain_id).unwrap(), MAINNET);
+        }
+        assert_eq!(parse_chain_id("goerli2").unwrap(), TESTNET2);
+        assert_eq!(
+            parse_chain_id("KATANA").unwrap(),
+            cairo_short_string_to_felt("KATANA").unwrap()
+        );
+    }
+}
@@ -1,4 +1,10 @@
-use clap::Parser;
+use crate::cmd::parser::FeeMultiplierParser;
+use crate::probe::tokens::{ETH_ADDRESS, STRK_ADDRESS};
+
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{Arg, Parser, ValueEnum};
+use eyre::{bail, Result};
+use serde::Serialize;
 use starknet::core::types::FieldElement;
 
 #[derive(Debug, Clone, Parser)]
@@ -10,6 +16,39 @@ pub struct TransactionOptions {
     #[clap(help = "The maximal fee that can be charged for including the transaction")]
     pub max_fee: Option<FieldElement>,
 
+    #[clap(long)]
+    #[clap(value_name = "MULTIPLIER")]
+    #[clap(value_parser(FeeMultiplierParser))]
+    #[clap(conflicts_with = "max_fee")]
+    #[clap(help = "The factor by which the estimated fee is multiplied to get the max fee")]
+    pub fee_multiplier: Option<f64>,
+
+    #[clap(long)]
+    #[clap(value_name = "MAX_FEE_CAP")]
+    #[clap(conflicts_with = "max_fee")]
+    #[clap(help = "Refuse to send the transaction if its estimated fee is above this value")]
+    pub max_fee_cap: Option<FieldElement>,
+
+    #[clap(long)]
+    #[clap(value_name = "N")]
+    #[clap(conflicts_with = "nonce")]
+    #[clap(help = "Offset added to the pending nonce of the account")]
+    pub nonce_offset: Option<u64>,
+
+    #[clap(long)]
+    #[clap(conflicts_with = "nonce")]
+    #[clap(
+        help = "Keep track of the nonces used by the account locally, to send transactions back to back."
+    )]
+    pub persist_nonce: bool,
+
+    #[clap(long)]
+    #[clap(requires = "persist_nonce")]
+    #[clap(
+        help = "Forget the nonce kept locally for the account and start again from the pending nonce of the node."
+    )]
+    pub reset_nonce: bool,
+
     #[clap(long)]
     #[clap(value_delimiter = ',')]
     #[clap(help = "The transaction signature")]
@@ -18,6 +57,141 @@ pub struct TransactionOptions {
     #[clap(long)]
     #[clap(help = "Version of the transaction scheme")]
     pub version: Option<u64>,
+
+    #[clap(long)]
+    #[clap(value_enum)]
+    #[clap(value_name = "TOKEN")]
+    #[clap(default_value_t = FeeToken::Eth)]
+    #[clap(help = "The token used to pay the fee. Paying in STRK sends a v3 transaction.")]
+    pub fee_token: FeeToken,
+
+    #[clap(long)]
+    #[clap(value_name = "AMOUNT")]
+    #[clap(
+        help = "The maximum amount of L1 gas the transaction can consume. Used with v3 transactions."
+    )]
+    pub l1_gas: Option<u64>,
+
+    #[clap(long)]
+    #[clap(value_name = "PRICE")]
+    #[clap(help = "The maximum price per unit of L1 gas, in FRI. Used with v3 transactions.")]
+    pub l1_gas_price: Option<u128>,
+
+    #[clap(long)]
+    #[clap(value_name = "TIP")]
+    #[clap(default_value_t = 0)]
+    #[clap(help = "The tip paid to the sequencer. Used with v3 transactions.")]
+    pub tip: u64,
+
+    #[clap(long)]
+    #[clap(value_enum)]
+    #[clap(value_name = "MODE")]
+    #[clap(default_value_t = DataAvailabilityMode::L1)]
+    #[clap(help = "The data availability mode of the nonce. Used with v3 transactions.")]
+    pub nonce_da_mode: DataAvailabilityMode,
+
+    #[clap(long)]
+    #[clap(value_enum)]
+    #[clap(value_name = "MODE")]
+    #[clap(default_value_t = DataAvailabilityMode::L1)]
+    #[clap(help = "The data availability mode of the fee. Used with v3 transactions.")]
+    pub fee_da_mode: DataAvailabilityMode,
+
+    #[clap(long)]
+    #[clap(help = "Wait for the transaction to be accepted and print its receipt")]
+    pub wait: bool,
+
+    #[clap(long)]
+    #[clap(requires = "wait")]
+    #[clap(value_name = "N")]
+    #[clap(
+        help = "Number of blocks to wait for on top of the transaction's block. Used with --wait."
+    )]
+    pub confirmations: Option<u64>,
+}
+
+impl TransactionOptions {
+    /// Scales the estimated fee by `--fee-multiplier`, without going above `--max-fee-cap`.
+    /// Fails if the estimated fee alone is already above the cap.
+    pub fn scale_estimated_fee(&self, estimated_fee: u128) -> Result<u128> {
+        let cap = match self.max_fee_cap {
+            Some(cap) => Some(cap.to_string().parse::<u128>()?),
+            None => None,
+        };
+
+        if let Some(cap) = cap {
+            if estimated_fee > cap {
+                bail!("estimated fee {estimated_fee} is above the max fee cap {cap}")
+            }
+        }
+
+        let fee = (estimated_fee as f64 * self.fee_multiplier.unwrap_or(1.0)) as u128;
+        Ok(cap.map_or(fee, |cap| fee.min(cap)))
+    }
+
+    pub fn max_fee_from_estimate(&self, estimated_fee: u64) -> Result<FieldElement> {
+        let fee = self.scale_estimated_fee(estimated_fee.into())?;
+        Ok(FieldElement::from_dec_str(&fee.to_string())?)
+    }
+
+    /// Whether the transaction should be sent as a v3 transaction, either because it is requested
+    /// with `--version 3` or because the fee is paid in STRK.
+    pub fn is_v3(&self) -> bool {
+        self.version == Some(3) || self.fee_token == FeeToken::Strk
+    }
+}
+
+/// Restricts `--fee-token` to ETH, for the transactions that can't be sent as v3 yet.
+pub fn eth_fee_token_only(arg: Arg) -> Arg {
+    arg.value_parser(PossibleValuesParser::new(["eth"]).map(|_| FeeToken::Eth))
+        .help("The token used to pay the fee. Only ETH is supported by this command.")
+}
+
+/// Restricts `--version` to the versions before v3, for the transactions that can't be sent as
+/// v3 yet.
+pub fn pre_v3_version_only(arg: Arg) -> Arg {
+    arg.value_parser(clap::value_parser!(u64).range(..3))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FeeToken {
+    Eth,
+    Strk,
+}
+
+impl FeeToken {
+    /// The address of the token contract, which is the same on mainnet and on the testnets.
+    pub fn address(&self) -> FieldElement {
+        let address = match self {
+            Self::Eth => ETH_ADDRESS,
+            Self::Strk => STRK_ADDRESS,
+        };
+        FieldElement::from_hex_be(address).unwrap()
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Eth => "ETH",
+            Self::Strk => "STRK",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ValueEnum)]
+pub enum DataAvailabilityMode {
+    #[clap(name = "l1")]
+    L1,
+    #[clap(name = "l2")]
+    L2,
+}
+
+impl DataAvailabilityMode {
+    pub fn as_u64(&self) -> u64 {
+        match self {
+            Self::L1 => 0,
+            Self::L2 => 1,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -28,10 +202,11 @@ mod tests {
     use starknet::core::types::FieldElement;
 
     use super::TransactionOptions;
+    use clap::Parser;
 
     #[test]
     fn parse_tx_options() {
-        let cli = TransactionOptions::command().get_matches_from(&[
+        let cli = TransactionOptions::command().get_matches_from([
             "transaction_options",
             "--signature",
             "0x124142,0x3323234,0x12324131",
@@ -63,4 +238,29 @@ mod tests {
             FieldElement::from_str("0x256").unwrap(),
         );
     }
+
+    #[test]
+    fn scale_estimated_fee() {
+        let opts =
+            TransactionOptions::parse_from(["transaction_options", "--fee-multiplier", "1.5"]);
+        assert_eq!(opts.scale_estimated_fee(1000).unwrap(), 1500);
+
+        let opts = TransactionOptions::parse_from([
+            "transaction_options",
+            "--fee-multiplier",
+            "1.5",
+            "--max-fee-cap",
+            "1200",
+        ]);
+        assert_eq!(opts.scale_estimated_fee(1000).unwrap(), 1200);
+        assert!(opts.scale_estimated_fee(1300).is_err());
+
+        // a multiplier that would lower the fee, down to a max fee of 0, is rejected
+        for multiplier in ["0", "0.5", "-1", "NaN", "inf", "abc"] {
+            let arg = format!("--fee-multiplier={multiplier}");
+            assert!(TransactionOptions::try_parse_from(["transaction_options", &arg]).is_err());
+        }
+        let opts = TransactionOptions::parse_from(["transaction_options", "--fee-multiplier", "1"]);
+        assert_eq!(opts.scale_estimated_fee(1000).unwrap(), 1000);
+    }
 }
@@ -0,0 +1,1978 @@
+//! Encoding and decoding of the typed values described by a contract ABI, to and from felts. Both
+//! the ABIs of Cairo 0 contracts and of Cairo 1 (Sierra) contracts are supported.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::str::FromStr;
+use std::{fmt, fs};
+
+use eyre::{eyre, Result};
+use serde_json::{json, Value};
+use starknet::core::types::FieldElement;
+use starknet::core::utils::{cairo_short_string_to_felt, get_selector_from_name};
+
+/// A value decoded from felts according to its ABI type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decoded {
+    Felt(FieldElement),
+    Bool(bool),
+    /// A u256 in decimal.
+    U256(String),
+    /// A signed integer, e.g. `core::integer::i128`.
+    Signed(i128),
+    /// A selector along with the name it was computed from.
+    Selector(FieldElement, String),
+    /// A felt that holds a Cairo short string.
+    ShortString(String),
+    /// A Cairo 1 `ByteArray`, as text.
+    ByteArray(String),
+    Array(Vec<Decoded>),
+    Tuple(Vec<Decoded>),
+    Struct(Vec<(String, Decoded)>),
+    Enum(String, Box<Decoded>),
+}
+
+/// A function parameter along with its decoded value.
+#[derive(Debug)]
+pub struct DecodedParam {
+    pub name: String,
+    pub r#type: String,
+    pub value: Decoded,
+}
+
+/// An event along with its decoded fields.
+#[derive(Debug)]
+pub struct DecodedEvent {
+    pub name: String,
+    pub params: Vec<DecodedParam>,
+}
+
+/// A difference between two ABIs, with the entries rendered as signatures.
+#[derive(Debug, PartialEq, Eq)]
+pub enum AbiChange {
+    Added(String),
+    Removed(String),
+    Changed {
+        name: String,
+        before: String,
+        after: String,
+    },
+}
+
+/// A value given by the user, to be encoded according to its ABI type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Input {
+    Scalar(String),
+    /// `[a, b]`, `(a, b)` or `{a, b}`, for arrays, tuples and structs.
+    List(Vec<Input>),
+    /// `name: value`, either a struct member or a type hint such as `u256:1000`.
+    Field(String, Box<Input>),
+    /// `Name(value)`, an enum variant with its value.
+    Variant(String, Vec<Input>),
+}
+
+#[derive(Debug)]
+pub struct Abi {
+    entries: Vec<Value>,
+    functions: HashMap<String, Value>,
+    types: HashMap<String, Value>,
+    events: HashMap<String, Value>,
+}
+
+impl Abi {
+    /// Parses an ABI, given either as a JSON array or as a string containing the JSON array, the
+    /// latter being how the node returns the ABI of Sierra classes. A contract class, such as the
+    /// artifact produced by the compiler, can also be given in place of its ABI.
+    pub fn parse(abi: &Value) -> Result<Self> {
+        let entries = match abi {
+            Value::String(abi) => serde_json::from_str(abi)?,
+            Value::Object(class) if class.contains_key("abi") => return Self::parse(&class["abi"]),
+            abi => abi.clone(),
+        };
+
+        let entries = entries
+            .as_array()
+            .ok_or_else(|| eyre!("the abi must be an array of entries"))?;
+
+        let mut abi = Self {
+            entries: entries.clone(),
+            functions: HashMap::new(),
+            types: HashMap::new(),
+            events: HashMap::new(),
+        };
+        abi.add_entries(entries);
+
+        Ok(abi)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::parse(&serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    fn add_entries(&mut self, entries: &[Value]) {
+        for entry in entries {
+            let name = entry["name"].as_str().unwrap_or_default().to_string();
+
+            match entry["type"].as_str() {
+                Some("function") | Some("l1_handler") | Some("constructor") => {
+                    self.functions.insert(name, entry.clone());
+                }
+                Some("struct") | Some("enum") => {
+                    self.types.insert(name, entry.clone());
+                }
+                Some("event") => {
+                    self.events.insert(name, entry.clone());
+                }
+                // the functions of Cairo 1 contracts are grouped by interface
+                Some("interface") => {
+                    if let Some(items) = entry["items"].as_array() {
+                        self.add_entries(items);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The entries of the ABI, in the order they are declared.
+    pub fn entries(&self) -> &[Value] {
+        &self.entries
+    }
+
+    /// Returns the declaration of a struct or an enum. `Option` and `Result` are also known when
+    /// they are not declared, which is the case in hand written ABIs.
+    fn type_entry(&self, ty: &str) -> Option<Cow<'_, Value>> {
+        if let Some(entry) = self.types.get(ty) {
+            return Some(Cow::Borrowed(entry));
+        }
+
+        let variants = if let Some(inner) = generic_argument(ty, "core::option::Option") {
+            json!([{ "name": "Some", "type": inner }, { "name": "None", "type": "()" }])
+        } else if let Some(inner) = generic_argument(ty, "core::result::Result") {
+            match split_top_level(inner)[..] {
+                [ok, err] => json!([{ "name": "Ok", "type": ok }, { "name": "Err", "type": err }]),
+                _ => return None,
+            }
+        } else {
+            return None;
+        };
+
+        Some(Cow::Owned(
+            json!({ "type": "enum", "name": ty, "variants": variants }),
+        ))
+    }
+
+    /// The names declared in the ABI that selectors are computed from, i.e. the names of the
+    /// functions and of the events. Cairo 1 events are named after their variant.
+    pub fn selector_names(&self) -> Vec<&str> {
+        let mut names = self
+            .functions
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+
+        for (name, event) in &self.events {
+            match event["kind"].as_str() {
+                None => names.push(name),
+                Some("enum") => names.extend(
+                    members(&event["variants"])
+                        .iter()
+                        .filter_map(|variant| variant["name"].as_str()),
+                ),
+                Some(_) => {}
+            }
+        }
+
+        names
+    }
+
+    /// The functions then the events declared in the ABI, each sorted by name.
+    pub fn declarations(&self) -> Vec<&Value> {
+        let mut functions = self.functions.iter().collect::<Vec<_>>();
+        let mut events = self.events.iter().collect::<Vec<_>>();
+        functions.sort_by_key(|(name, _)| *name);
+        events.sort_by_key(|(name, _)| *name);
+
+        functions
+            .into_iter()
+            .chain(events)
+            .map(|(_, entry)| entry)
+            .collect()
+    }
+
+    /// Returns the entry of a function, be it part of an interface or not.
+    pub fn function(&self, name: &str) -> Result<&Value> {
+        self.functions
+            .get(name)
+            .ok_or_else(|| eyre!("no function `{name}` found in the abi"))
+    }
+
+    /// Compares the functions, events and types of two ABIs, entries being matched by name. The
+    /// changes are sorted by name within each group.
+    pub fn diff(&self, other: &Abi) -> Vec<AbiChange> {
+        let groups = [
+            (&self.functions, &other.functions),
+            (&self.events, &other.events),
+            (&self.types, &other.types),
+        ];
+
+        let mut changes = Vec::new();
+        for (before, after) in groups {
+            let mut names = before.keys().chain(after.keys()).collect::<Vec<_>>();
+            names.sort();
+            names.dedup();
+
+            for name in names {
+                match (
+                    before.get(name).map(signature),
+                    after.get(name).map(signature),
+                ) {
+                    (Some(before), None) => changes.push(AbiChange::Removed(before)),
+                    (None, Some(after)) => changes.push(AbiChange::Added(after)),
+                    (Some(before), Some(after)) if before != after => {
+                        changes.push(AbiChange::Changed {
+                            name: name.clone(),
+                            before,
+                            after,
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        changes
+    }
+
+    pub fn decode_function_inputs(
+        &self,
+        function: &str,
+        calldata: &[FieldElement],
+    ) -> Result<Vec<DecodedParam>> {
+        let mut decoder = Decoder::new(self, calldata);
+        let params = decoder.decode_params(members(&self.function(function)?["inputs"]))?;
+        decoder.finish()?;
+
+        Ok(params)
+    }
+
+    /// Decodes the values returned by a function. The outputs of Cairo 1 functions are not named.
+    pub fn decode_function_outputs(
+        &self,
+        function: &str,
+        result: &[FieldElement],
+    ) -> Result<Vec<DecodedParam>> {
+        let mut decoder = Decoder::new(self, result);
+        let params = decoder.decode_params(members(&self.function(function)?["outputs"]))?;
+        decoder.finish()?;
+
+        Ok(params)
+    }
+
+    /// Decodes an event from its keys and data. Returns `None` if the event is not part of the
+    /// abi.
+    ///
+    /// The first key of Cairo 0 events is the selector of their name. Cairo 1 events are variants
+    /// of the `Event` enum of the contract, each nested variant adding the selector of its name
+    /// to the keys, and each flattened one adding nothing.
+    pub fn decode_event(
+        &self,
+        keys: &[FieldElement],
+        data: &[FieldElement],
+    ) -> Result<Option<DecodedEvent>> {
+        let selector = match keys.first() {
+            Some(selector) => *selector,
+            None => return Ok(None),
+        };
+
+        for (name, event) in &self.events {
+            match event["kind"].as_str() {
+                // Cairo 0 events and the events of the first versions of Cairo 1
+                None if get_selector_from_name(name)? == selector => {
+                    let mut keys = Decoder::new(self, &keys[1..]);
+                    let mut data = Decoder::new(self, data);
+
+                    let mut params = keys.decode_params(members(&event["keys"]))?;
+                    params.extend(data.decode_params(members(&event["data"]))?);
+                    params.extend(data.decode_params(members(&event["inputs"]))?);
+                    keys.finish()?;
+                    data.finish()?;
+
+                    return Ok(Some(DecodedEvent {
+                        name: name.clone(),
+                        params,
+                    }));
+                }
+
+                Some("enum") if !self.is_event_variant(name) => {
+                    if let Some(event) = self.decode_enum_event(event, keys, data)? {
+                        return Ok(Some(event));
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the keys that an event starts with. `name` is either the name of the event, i.e.
+    /// of its variant for Cairo 1 events, or the name of its type.
+    ///
+    /// Cairo 1 events get a key for each nested variant on their path from the `Event` enum of
+    /// the contract, and none for flattened ones.
+    pub fn event_keys(&self, name: &str) -> Result<Vec<FieldElement>> {
+        if let Some(event) = self.events.get(name) {
+            if event["kind"].is_null() {
+                return Ok(vec![get_selector_from_name(name)?]);
+            }
+        }
+
+        for (root, event) in &self.events {
+            if event["kind"] == "enum" && !self.is_event_variant(root) {
+                let mut keys = Vec::new();
+                if self.find_event_keys(event, name, &mut keys)? {
+                    return Ok(keys);
+                }
+            }
+        }
+
+        Err(eyre!("no event `{name}` found in the abi"))
+    }
+
+    fn find_event_keys(
+        &self,
+        event: &Value,
+        name: &str,
+        keys: &mut Vec<FieldElement>,
+    ) -> Result<bool> {
+        for variant in members(&event["variants"]) {
+            let variant_name = variant["name"].as_str().unwrap_or_default();
+            let variant_type = variant["type"].as_str().unwrap_or_default();
+
+            let nested = variant["kind"] == "nested";
+            if nested {
+                keys.push(get_selector_from_name(variant_name)?);
+            }
+
+            if variant_name == name || variant_type == name {
+                return Ok(true);
+            }
+
+            if let Some(inner) = self.events.get(variant_type) {
+                if inner["kind"] == "enum" && self.find_event_keys(inner, name, keys)? {
+                    return Ok(true);
+                }
+            }
+
+            if nested {
+                keys.pop();
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn decode_enum_event(
+        &self,
+        event: &Value,
+        keys: &[FieldElement],
+        data: &[FieldElement],
+    ) -> Result<Option<DecodedEvent>> {
+        for variant in event["variants"].as_array().into_iter().flatten() {
+            let name = variant["name"].as_str().unwrap_or_default();
+            let inner = match self
+                .events
+                .get(variant["type"].as_str().unwrap_or_default())
+            {
+                Some(inner) => inner,
+                None => continue,
+            };
+
+            let keys = match variant["kind"].as_str() {
+                Some("nested") if keys.first() == Some(&get_selector_from_name(name)?) => {
+                    &keys[1..]
+                }
+                Some("flat") => keys,
+                _ => continue,
+            };
+
+            match inner["kind"].as_str() {
+                Some("enum") => {
+                    if let Some(event) = self.decode_enum_event(inner, keys, data)? {
+                        return Ok(Some(event));
+                    }
+                }
+
+                Some("struct") => {
+                    let mut keys = Decoder::new(self, keys);
+                    let mut data = Decoder::new(self, data);
+
+                    let mut params = Vec::new();
+                    for member in members(&inner["members"]) {
+                        let decoder = match member["kind"].as_str() {
+                            Some("key") => &mut keys,
+                            _ => &mut data,
+                        };
+                        params.extend(decoder.decode_params(std::slice::from_ref(member))?);
+                    }
+                    keys.finish()?;
+                    data.finish()?;
+
+                    return Ok(Some(DecodedEvent {
+                        name: name.to_string(),
+                        params,
+                    }));
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Whether the event is the variant of another event, as opposed to the root `Event` enum of
+    /// the contract.
+    fn is_event_variant(&self, name: &str) -> bool {
+        self.events.values().any(|event| {
+            event["variants"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .any(|variant| variant["type"] == name)
+        })
+    }
+
+    /// Encodes the inputs of a function into calldata. The inputs are either all positional, or
+    /// all named as `name=value`. The length of Cairo 0 arrays, which is a separate parameter, is
+    /// derived from the array itself and must not be given.
+    pub fn encode_function_inputs(
+        &self,
+        function: &str,
+        inputs: &[String],
+    ) -> Result<Vec<FieldElement>> {
+        let params = self.encoded_params(function)?;
+
+        let inputs = inputs
+            .iter()
+            .flat_map(|input| split_top_level(input))
+            .collect::<Vec<_>>();
+
+        let named = inputs
+            .iter()
+            .map(|input| {
+                input
+                    .split_once('=')
+                    .filter(|(name, _)| is_identifier(name.trim()))
+                    .map(|(name, value)| (name.trim(), Input::parse(value)))
+            })
+            .collect::<Option<Vec<_>>>()
+            .filter(|named| !named.is_empty());
+
+        let inputs = match named {
+            Some(named) => order_named_inputs(function, &params, &named)?,
+
+            None => {
+                if inputs.len() != params.len() {
+                    return Err(eyre!(
+                        "expected {} input(s) but got {}",
+                        params.len(),
+                        inputs.len()
+                    ));
+                }
+                inputs.into_iter().map(Input::parse).collect()
+            }
+        };
+
+        self.encode_params(&params, &inputs)
+    }
+
+    /// Encodes the inputs of a function into calldata, given as a JSON object of the inputs keyed
+    /// by their name. Structs are objects of their members, and enums are objects with the
+    /// variant as single key, e.g. `{ "Some": 1 }`, or just the name of the variant.
+    pub fn encode_function_args(&self, function: &str, args: &Value) -> Result<Vec<FieldElement>> {
+        let params = self.encoded_params(function)?;
+
+        let named = args
+            .as_object()
+            .ok_or_else(|| eyre!("the arguments must be an object of the inputs by name"))?
+            .iter()
+            .map(|(name, value)| (name.as_str(), Input::from_json(value)))
+            .collect::<Vec<_>>();
+
+        let inputs = order_named_inputs(function, &params, &named)?;
+        self.encode_params(&params, &inputs)
+    }
+
+    /// The names and types of the inputs of a function that are given by the user, which excludes
+    /// the length of Cairo 0 arrays.
+    fn encoded_params(&self, function: &str) -> Result<Vec<(&str, &str)>> {
+        let params = members(&self.function(function)?["inputs"]);
+
+        Ok(params
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !is_array_len(params, *i))
+            .map(|(_, param)| {
+                (
+                    param["name"].as_str().unwrap_or_default(),
+                    param["type"].as_str().unwrap_or_default(),
+                )
+            })
+            .collect())
+    }
+
+    fn encode_params(
+        &self,
+        params: &[(&str, &str)],
+        inputs: &[Input],
+    ) -> Result<Vec<FieldElement>> {
+        let mut encoder = Encoder {
+            abi: self,
+            data: Vec::new(),
+        };
+        for ((_, ty), input) in params.iter().zip(inputs) {
+            encoder.encode(ty, input)?;
+        }
+
+        Ok(encoder.data)
+    }
+}
+
+/// Orders named inputs as the parameters of the function.
+fn order_named_inputs(
+    function: &str,
+    params: &[(&str, &str)],
+    named: &[(&str, Input)],
+) -> Result<Vec<Input>> {
+    if let Some((name, _)) = named
+        .iter()
+        .find(|(name, _)| !params.iter().any(|(param, _)| param == name))
+    {
+        return Err(eyre!("function `{function}` has no input `{name}`"));
+    }
+
+    params
+        .iter()
+        .map(|(param, _)| {
+            named
+                .iter()
+                .find(|(name, _)| name == param)
+                .map(|(_, value)| value.clone())
+                .ok_or_else(|| eyre!("missing input `{param}`"))
+        })
+        .collect()
+}
+
+struct Encoder<'a> {
+    abi: &'a Abi,
+    data: Vec<FieldElement>,
+}
+
+impl<'a> Encoder<'a> {
+    fn encode(&mut self, ty: &str, input: &Input) -> Result<()> {
+        let invalid = || eyre!("invalid value {input:?} for type `{ty}`");
+
+        if let Input::Field(hint, value) = input {
+            return match (hint.as_str(), value.as_ref()) {
+                ("str", Input::Scalar(value)) => {
+                    self.data.push(cairo_short_string_to_felt(value)?);
+                    Ok(())
+                }
+                ("str", _) => Err(invalid()),
+                // other hints are only there for readability, the type is given by the abi
+                _ => self.encode(ty, value),
+            };
+        }
+
+        if let Some(inner) = generic_argument(ty, "core::zeroable::NonZero") {
+            return self.encode(inner, input);
+        }
+
+        if let Some(inner) = ty.strip_prefix('(').and_then(|ty| ty.strip_suffix(')')) {
+            let types = split_top_level(inner);
+            return match input {
+                Input::List(values) if values.len() == types.len() => {
+                    for (ty, value) in types.into_iter().zip(values) {
+                        self.encode(ty, value)?;
+                    }
+                    Ok(())
+                }
+                _ => Err(invalid()),
+            };
+        }
+
+        if let Some(inner) = ty.strip_suffix('*').or_else(|| {
+            generic_argument(ty, "core::array::Array")
+                .or_else(|| generic_argument(ty, "core::array::Span"))
+        }) {
+            let Input::List(values) = input else {
+                return Err(invalid());
+            };
+
+            self.data.push(FieldElement::from(values.len() as u64));
+            for value in values {
+                self.encode(inner, value)?;
+            }
+            return Ok(());
+        }
+
+        match (ty, input) {
+            ("Uint256" | "core::integer::u256", Input::Scalar(value)) => {
+                let (low, high) = parse_u256(value)?;
+                self.data
+                    .push(FieldElement::from_dec_str(&low.to_string())?);
+                self.data
+                    .push(FieldElement::from_dec_str(&high.to_string())?);
+            }
+
+            ("core::byte_array::ByteArray", Input::Scalar(value)) => {
+                let string = ['\'', '"']
+                    .iter()
+                    .find_map(|q| value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)))
+                    .unwrap_or(value);
+                self.data.extend(byte_array_to_felts(string));
+            }
+
+            ("core::bool", Input::Scalar(value)) => {
+                let value = match value.as_str() {
+                    "true" => FieldElement::ONE,
+                    "false" => FieldElement::ZERO,
+                    value => parse_felt(value)?,
+                };
+                self.data.push(value);
+            }
+
+            (ty, input) => match (self.abi.type_entry(ty), input) {
+                (Some(entry), Input::List(values)) if entry["type"] == "struct" => {
+                    let members = entry["members"].as_array().cloned().unwrap_or_default();
+                    if members.len() != values.len() {
+                        return Err(invalid());
+                    }
+
+                    for (i, member) in members.iter().enumerate() {
+                        let name = member["name"].as_str().unwrap_or_default();
+                        // members are either all named or all positional
+                        let value = values
+                            .iter()
+                            .find_map(|value| match value {
+                                Input::Field(field, value) if field == name => Some(value.as_ref()),
+                                _ => None,
+                            })
+                            .unwrap_or(&values[i]);
+
+                        self.encode(member["type"].as_str().unwrap_or_default(), value)?;
+                    }
+                }
+
+                // a variant given as an object with the variant as single key
+                (Some(entry), Input::List(values)) if entry["type"] == "enum" => {
+                    match &values[..] {
+                        [Input::Field(name, value)] => self.encode(
+                            ty,
+                            &Input::Variant(name.clone(), vec![value.as_ref().clone()]),
+                        )?,
+                        _ => return Err(invalid()),
+                    }
+                }
+
+                (Some(entry), Input::Scalar(name) | Input::Variant(name, _))
+                    if entry["type"] == "enum" =>
+                {
+                    let variants = entry["variants"].as_array().cloned().unwrap_or_default();
+                    let (index, variant) = variants
+                        .iter()
+                        .enumerate()
+                        .find(|(_, variant)| variant["name"] == name.as_str())
+                        .ok_or_else(|| eyre!("no variant `{name}` in enum `{ty}`"))?;
+
+                    self.data.push(FieldElement::from(index as u64));
+
+                    let variant_type = variant["type"].as_str().unwrap_or("()");
+                    match input {
+                        Input::Variant(_, values) if values.len() == 1 => {
+                            self.encode(variant_type, &values[0])?
+                        }
+                        Input::Variant(_, values) => {
+                            self.encode(variant_type, &Input::List(values.clone()))?
+                        }
+                        _ => self.encode(variant_type, &Input::List(Vec::new()))?,
+                    }
+                }
+
+                (None, Input::Scalar(value)) => self.data.push(parse_felt(value)?),
+
+                _ => return Err(invalid()),
+            },
+        }
+
+        Ok(())
+    }
+}
+
+impl Input {
+    fn from_json(value: &Value) -> Self {
+        match value {
+            Value::String(value) => Input::Scalar(value.clone()),
+            Value::Array(values) => Input::List(values.iter().map(Input::from_json).collect()),
+            Value::Object(members) => Input::List(
+                members
+                    .iter()
+                    .map(|(name, value)| {
+                        Input::Field(name.clone(), Box::new(Input::from_json(value)))
+                    })
+                    .collect(),
+            ),
+            // the value of unit variants
+            Value::Null => Input::List(Vec::new()),
+            value => Input::Scalar(value.to_string()),
+        }
+    }
+
+    fn parse(input: &str) -> Self {
+        let input = input.trim();
+
+        for (open, close) in [('[', ']'), ('(', ')'), ('{', '}')] {
+            if let Some(inner) = input
+                .strip_prefix(open)
+                .and_then(|input| input.strip_suffix(close))
+            {
+                return Input::List(
+                    split_top_level(inner)
+                        .into_iter()
+                        .map(Input::parse)
+                        .collect(),
+                );
+            }
+        }
+
+        if let Some((name, value)) = input.split_once(':') {
+            if is_identifier(name.trim()) && !value.trim().is_empty() {
+                return Input::Field(name.trim().to_string(), Box::new(Input::parse(value)));
+            }
+        }
+
+        if let Some((name, values)) = input
+            .strip_suffix(')')
+            .and_then(|input| input.split_once('('))
+        {
+            if is_identifier(name) {
+                return Input::Variant(
+                    name.to_string(),
+                    split_top_level(values)
+                        .into_iter()
+                        .map(Input::parse)
+                        .collect(),
+                );
+            }
+        }
+
+        Input::Scalar(input.to_string())
+    }
+}
+
+/// Whether the parameter at `index` is the length of the Cairo 0 array that follows it.
+fn is_array_len(params: &[Value], index: usize) -> bool {
+    let name = params[index]["name"].as_str().unwrap_or_default();
+
+    match (name.strip_suffix("_len"), params.get(index + 1)) {
+        (Some(array), Some(next)) => {
+            next["name"] == array && next["type"].as_str().unwrap_or_default().ends_with('*')
+        }
+        _ => false,
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parses a felt given in hex, in decimal, possibly negative, or as a quoted short string.
+fn parse_felt(value: &str) -> Result<FieldElement> {
+    let quoted = ['\'', '"']
+        .iter()
+        .find_map(|q| value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)));
+
+    if let Some(string) = quoted {
+        return Ok(cairo_short_string_to_felt(string)?);
+    }
+
+    match value.strip_prefix('-') {
+        Some(value) => Ok(FieldElement::ZERO - FieldElement::from_str(value)?),
+        None => Ok(FieldElement::from_str(value)?),
+    }
+}
+
+/// Parses a u256, in hex or in decimal, into its low and high 128 bits.
+pub fn parse_u256(value: &str) -> Result<(u128, u128)> {
+    let invalid = || eyre!("invalid u256 `{value}`");
+
+    if let Some(hex) = value.strip_prefix("0x") {
+        if hex.is_empty() || hex.len() > 64 {
+            return Err(invalid());
+        }
+
+        let split = hex.len().saturating_sub(32);
+        let high = match split {
+            0 => 0,
+            split => u128::from_str_radix(&hex[..split], 16).map_err(|_| invalid())?,
+        };
+        let low = u128::from_str_radix(&hex[split..], 16).map_err(|_| invalid())?;
+
+        return Ok((low, high));
+    }
+
+    if value.is_empty() {
+        return Err(invalid());
+    }
+
+    let (mut low, mut high) = (0u128, 0u128);
+    for c in value.chars() {
+        let digit = u128::from(c.to_digit(10).ok_or_else(invalid)?);
+
+        // (high, low) = (high, low) * 10 + digit, with low split in two 64 bits halves
+        let lo = (low & u128::from(u64::MAX)) * 10 + digit;
+        let hi = (low >> 64) * 10 + (lo >> 64);
+        low = (hi << 64) | (lo & u128::from(u64::MAX));
+        high = high
+            .checked_mul(10)
+            .and_then(|high| high.checked_add(hi >> 64))
+            .ok_or_else(invalid)?;
+    }
+
+    Ok((low, high))
+}
+
+struct Decoder<'a> {
+    abi: &'a Abi,
+    data: &'a [FieldElement],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(abi: &'a Abi, data: &'a [FieldElement]) -> Self {
+        Self {
+            abi,
+            data,
+            position: 0,
+        }
+    }
+
+    fn decode_params(&mut self, params: &[Value]) -> Result<Vec<DecodedParam>> {
+        params
+            .iter()
+            .map(|param| {
+                let r#type = param["type"].as_str().unwrap_or_default().to_string();
+                Ok(DecodedParam {
+                    name: param["name"].as_str().unwrap_or_default().to_string(),
+                    value: self.decode(&r#type)?,
+                    r#type,
+                })
+            })
+            .collect()
+    }
+
+    /// Fails if some of the felts were not decoded.
+    fn finish(&self) -> Result<()> {
+        if self.position < self.data.len() {
+            return Err(eyre!(
+                "expected {} felt(s) but got {}",
+                self.position,
+                self.data.len()
+            ));
+        }
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<FieldElement> {
+        let felt = self
+            .data
+            .get(self.position)
+            .copied()
+            .ok_or_else(|| eyre!("not enough felts to decode the calldata"))?;
+
+        self.position += 1;
+        Ok(felt)
+    }
+
+    fn decode(&mut self, ty: &str) -> Result<Decoded> {
+        if let Some(inner) = generic_argument(ty, "core::zeroable::NonZero") {
+            return self.decode(inner);
+        }
+
+        if let Some(inner) = ty.strip_prefix('(').and_then(|ty| ty.strip_suffix(')')) {
+            return Ok(Decoded::Tuple(
+                split_top_level(inner)
+                    .into_iter()
+                    .map(|ty| self.decode(ty))
+                    .collect::<Result<_>>()?,
+            ));
+        }
+
+        // Cairo 0 arrays are preceded by their length, as a separate parameter
+        if let Some(inner) = ty.strip_suffix('*') {
+            let len = match self.position {
+                0 => return Err(eyre!("missing length of array of type `{ty}`")),
+                position => self.data[position - 1],
+            };
+            return self.decode_array(inner, len);
+        }
+
+        if let Some(inner) = generic_argument(ty, "core::array::Array")
+            .or_else(|| generic_argument(ty, "core::array::Span"))
+        {
+            let len = self.next()?;
+            return self.decode_array(inner, len);
+        }
+
+        match ty {
+            "Uint256" | "core::integer::u256" => {
+                let low = self.next()?;
+                let high = self.next()?;
+                Ok(Decoded::U256(u256_to_dec(
+                    felt_to_u128(low)?,
+                    felt_to_u128(high)?,
+                )))
+            }
+
+            "core::bool" => Ok(Decoded::Bool(self.next()? != FieldElement::ZERO)),
+
+            "core::integer::i8"
+            | "core::integer::i16"
+            | "core::integer::i32"
+            | "core::integer::i64"
+            | "core::integer::i128" => {
+                let felt = self.next()?;
+                // negative values are represented as `P - |value|`
+                let value = match felt_to_u128(felt) {
+                    Ok(value) => i128::try_from(value).ok(),
+                    Err(_) => felt_to_u128(FieldElement::ZERO - felt)
+                        .ok()
+                        .and_then(|value| i128::try_from(value).ok())
+                        .map(|value| -value),
+                };
+                Ok(Decoded::Signed(value.ok_or_else(|| {
+                    eyre!("{felt:#x} is out of the range of type `{ty}`")
+                })?))
+            }
+
+            "felt" | "core::felt252" => {
+                let felt = self.next()?;
+                Ok(match short_string(felt) {
+                    Some(string) => Decoded::ShortString(string),
+                    None => Decoded::Felt(felt),
+                })
+            }
+
+            // full words of 31 bytes, followed by the remaining bytes and their number
+            "core::byte_array::ByteArray" => {
+                let (string, len) = byte_array_from_felts(&self.data[self.position..])?;
+                self.position += len;
+                Ok(Decoded::ByteArray(string))
+            }
+
+            ty => match self.abi.type_entry(ty) {
+                Some(entry) if entry["type"] == "struct" => {
+                    let mut members = Vec::new();
+                    for member in entry["members"].as_array().into_iter().flatten() {
+                        let name = member["name"].as_str().unwrap_or_default().to_string();
+                        let value = self.decode(member["type"].as_str().unwrap_or_default())?;
+                        members.push((name, value));
+                    }
+                    Ok(Decoded::Struct(members))
+                }
+
+                Some(entry) => {
+                    let index = felt_to_u128(self.next()?)? as usize;
+                    let variant = entry["variants"]
+                        .get(index)
+                        .ok_or_else(|| eyre!("no variant {index} in enum `{ty}`"))?;
+
+                    let name = variant["name"].as_str().unwrap_or_default().to_string();
+                    let value = self.decode(variant["type"].as_str().unwrap_or("()"))?;
+                    Ok(Decoded::Enum(name, Box::new(value)))
+                }
+
+                // felts and all the types that fit in a single felt, e.g. integers and addresses
+                None => Ok(Decoded::Felt(self.next()?)),
+            },
+        }
+    }
+
+    fn decode_array(&mut self, ty: &str, len: FieldElement) -> Result<Decoded> {
+        let len = felt_to_u128(len)?;
+        if len > (self.data.len() - self.position) as u128 {
+            return Err(eyre!("not enough felts to decode an array of length {len}"));
+        }
+
+        Ok(Decoded::Array(
+            (0..len).map(|_| self.decode(ty)).collect::<Result<_>>()?,
+        ))
+    }
+}
+
+/// Renders an ABI entry as a Cairo-like signature, e.g. `function transfer(recipient: felt,
+/// amount: Uint256) -> (success: felt)`. The items of interfaces are rendered on their own
+/// lines, indented.
+pub fn signature(entry: &Value) -> String {
+    let name = entry["name"].as_str().unwrap_or_default();
+    let params = |value: &Value| {
+        members(value)
+            .iter()
+            .map(|param| {
+                let ty = param["type"].as_str().unwrap_or_default();
+                match (param["name"].as_str(), param["kind"].as_str()) {
+                    (Some(name), Some(kind)) => format!("{name}: {ty} ({kind})"),
+                    (Some(name), None) => format!("{name}: {ty}"),
+                    (None, _) => ty.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match entry["type"].as_str().unwrap_or_default() {
+        kind @ ("function" | "l1_handler" | "constructor") => {
+            let mut signature = format!("{kind} {name}({})", params(&entry["inputs"]));
+
+            let outputs = members(&entry["outputs"]);
+            match outputs {
+                [] => {}
+                [output] if output.get("name").is_none() => {
+                    write!(signature, " -> {}", params(&entry["outputs"])).unwrap()
+                }
+                _ => write!(signature, " -> ({})", params(&entry["outputs"])).unwrap(),
+            }
+
+            // the mutability is in camel case in Cairo 0 ABIs
+            if let Some(mutability) = entry
+                .get("state_mutability")
+                .or_else(|| entry.get("stateMutability"))
+                .and_then(Value::as_str)
+            {
+                write!(signature, " [{mutability}]").unwrap();
+            }
+
+            signature
+        }
+
+        "struct" => format!("struct {name} {{ {} }}", params(&entry["members"])),
+        "enum" => format!("enum {name} {{ {} }}", params(&entry["variants"])),
+
+        "event" => match entry["kind"].as_str() {
+            Some("struct") => format!("event {name} {{ {} }}", params(&entry["members"])),
+            Some(_) => format!("event {name} {{ {} }}", params(&entry["variants"])),
+            None => {
+                let fields = ["keys", "data", "inputs"]
+                    .iter()
+                    .map(|key| params(&entry[*key]))
+                    .filter(|params| !params.is_empty())
+                    .collect::<Vec<_>>();
+                format!("event {name}({})", fields.join(", "))
+            }
+        },
+
+        "interface" => {
+            let mut interface = format!("interface {name}");
+            for item in members(&entry["items"]) {
+                write!(interface, "\n    {}", signature(item)).unwrap();
+            }
+            interface
+        }
+
+        "impl" => format!(
+            "impl {name} of {}",
+            entry["interface_name"].as_str().unwrap_or_default()
+        ),
+
+        ty => format!("{ty} {name}"),
+    }
+}
+
+/// Serializes a string into the felts of a Cairo 1 `ByteArray`: the number of full words of 31
+/// bytes, the full words, then the word holding the remaining bytes and their number.
+pub fn byte_array_to_felts(string: &str) -> Vec<FieldElement> {
+    let chunks = string.as_bytes().chunks(31).collect::<Vec<_>>();
+    let (words, pending) = match chunks.last() {
+        Some(last) if last.len() < 31 => (&chunks[..chunks.len() - 1], *last),
+        _ => (&chunks[..], &[][..]),
+    };
+
+    // a slice of at most 31 bytes always fits in a felt
+    let felt = |bytes: &[u8]| FieldElement::from_byte_slice_be(bytes).unwrap();
+
+    let mut felts = vec![FieldElement::from(words.len() as u64)];
+    felts.extend(words.iter().map(|word| felt(word)));
+    felts.push(felt(pending));
+    felts.push(FieldElement::from(pending.len() as u64));
+    felts
+}
+
+/// Deserializes a Cairo 1 `ByteArray` from the start of `data`. Returns the string along with the
+/// number of felts it spans.
+pub fn byte_array_from_felts(data: &[FieldElement]) -> Result<(String, usize)> {
+    let missing = || eyre!("not enough felts to decode a byte array");
+
+    let words = felt_to_u128(*data.first().ok_or_else(missing)?)?;
+    if words > data.len().saturating_sub(3) as u128 {
+        return Err(missing());
+    }
+    let words = words as usize;
+
+    let mut bytes = Vec::new();
+    for word in &data[1..=words] {
+        bytes.extend_from_slice(&word.to_bytes_be()[1..]);
+    }
+
+    let pending_word = data[words + 1].to_bytes_be();
+    let pending_len = felt_to_u128(data[words + 2])? as usize;
+    if pending_len > 30 {
+        return Err(eyre!(
+            "invalid byte array pending word length {pending_len}"
+        ));
+    }
+    bytes.extend_from_slice(&pending_word[32 - pending_len..]);
+
+    Ok((String::from_utf8_lossy(&bytes).into_owned(), words + 3))
+}
+
+/// Returns the text held by the felt if it is a short string of at least two printable characters.
+/// Single characters are left out as they can't be told apart from small numbers.
+pub fn short_string(felt: FieldElement) -> Option<String> {
+    let bytes = felt.to_bytes_be();
+    let start = bytes.iter().position(|b| *b != 0)?;
+    let bytes = &bytes[start..];
+
+    if bytes.len() >= 2 && bytes.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    } else {
+        None
+    }
+}
+
+fn members(value: &Value) -> &[Value] {
+    value.as_array().map(Vec::as_slice).unwrap_or_default()
+}
+
+/// Returns `T` if `ty` is `name::<T>`.
+fn generic_argument<'a>(ty: &'a str, name: &str) -> Option<&'a str> {
+    ty.strip_prefix(name)?
+        .strip_prefix("::<")?
+        .strip_suffix('>')
+}
+
+/// Splits comma separated types or values, ignoring the commas of nested ones.
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut res = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+
+    for (i, c) in list.char_indices() {
+        match c {
+            '(' | '<' | '[' | '{' => depth += 1,
+            ')' | '>' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                res.push(list[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    let last = list[start..].trim();
+    if !last.is_empty() {
+        res.push(last);
+    }
+
+    res
+}
+
+pub fn felt_to_u128(felt: FieldElement) -> Result<u128> {
+    felt.to_string()
+        .parse()
+        .map_err(|_| eyre!("{felt:#x} does not fit in 128 bits"))
+}
+
+pub fn u256_to_dec(low: u128, high: u128) -> String {
+    if high == 0 {
+        return low.to_string();
+    }
+
+    // big-endian 64 bits limbs, divided by 10 until they are all zero
+    let mut limbs = [
+        (high >> 64) as u64,
+        high as u64,
+        (low >> 64) as u64,
+        low as u64,
+    ];
+    let mut digits = Vec::new();
+
+    while limbs.iter().any(|limb| *limb != 0) {
+        let mut remainder = 0u128;
+        for limb in limbs.iter_mut() {
+            let current = (remainder << 64) | u128::from(*limb);
+            *limb = (current / 10) as u64;
+            remainder = current % 10;
+        }
+        digits.push(char::from(b'0' + remainder as u8));
+    }
+
+    digits.iter().rev().collect()
+}
+
+impl Decoded {
+    /// Returns the felts of the value that are selectors, as told by their name, e.g. the
+    /// `selector` member of a `Call`.
+    pub fn selectors(&self, name: &str) -> Vec<FieldElement> {
+        match self {
+            Decoded::Felt(felt) if name.contains("selector") => vec![*felt],
+            Decoded::Array(values) | Decoded::Tuple(values) => {
+                values.iter().flat_map(|v| v.selectors(name)).collect()
+            }
+            Decoded::Struct(members) => members
+                .iter()
+                .flat_map(|(name, value)| value.selectors(name))
+                .collect(),
+            Decoded::Enum(_, value) => value.selectors(name),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Replaces the selectors of the value that are named by their name.
+    pub fn name_selectors(&mut self, name: &str, names: &HashMap<FieldElement, String>) {
+        match self {
+            Decoded::Felt(felt) if name.contains("selector") => {
+                if let Some(selector_name) = names.get(felt) {
+                    *self = Decoded::Selector(*felt, selector_name.clone());
+                }
+            }
+            Decoded::Array(values) | Decoded::Tuple(values) => {
+                for value in values {
+                    value.name_selectors(name, names);
+                }
+            }
+            Decoded::Struct(members) => {
+                for (name, value) in members {
+                    value.name_selectors(name, names);
+                }
+            }
+            Decoded::Enum(_, value) => value.name_selectors(name, names),
+            _ => {}
+        }
+    }
+}
+
+impl DecodedEvent {
+    /// An event that could not be decoded, named after its first key.
+    pub fn raw(name: &str, keys: &[FieldElement], data: &[FieldElement]) -> Self {
+        let felts = |felts: &[FieldElement]| {
+            Decoded::Array(felts.iter().copied().map(Decoded::Felt).collect())
+        };
+
+        Self {
+            name: name.to_string(),
+            params: vec![
+                DecodedParam {
+                    name: "keys".to_string(),
+                    r#type: String::new(),
+                    value: felts(keys),
+                },
+                DecodedParam {
+                    name: "data".to_string(),
+                    r#type: String::new(),
+                    value: felts(data),
+                },
+            ],
+        }
+    }
+}
+
+impl fmt::Display for Decoded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |values: &mut dyn Iterator<Item = String>| values.collect::<Vec<_>>().join(", ");
+
+        match self {
+            Decoded::Felt(felt) => write!(f, "{felt:#x}"),
+            Decoded::Bool(value) => write!(f, "{value}"),
+            Decoded::U256(value) => write!(f, "{value}"),
+            Decoded::Signed(value) => write!(f, "{value}"),
+            Decoded::Selector(selector, name) => write!(f, "{selector:#x} ({name})"),
+            Decoded::ShortString(value) => write!(f, "'{value}'"),
+            Decoded::ByteArray(value) => write!(f, "{value:?}"),
+            Decoded::Array(values) => {
+                write!(f, "[{}]", join(&mut values.iter().map(|v| v.to_string())))
+            }
+            Decoded::Tuple(values) => {
+                write!(f, "({})", join(&mut values.iter().map(|v| v.to_string())))
+            }
+            Decoded::Struct(members) => write!(
+                f,
+                "{{ {} }}",
+                join(&mut members.iter().map(|(name, v)| format!("{name}: {v}")))
+            ),
+            Decoded::Enum(name, value) => match value.as_ref() {
+                Decoded::Tuple(values) if values.is_empty() => write!(f, "{name}"),
+                value => write!(f, "{name}({value})"),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn felts(values: &[u64]) -> Vec<FieldElement> {
+        values.iter().map(|v| FieldElement::from(*v)).collect()
+    }
+
+    #[test]
+    fn decode_cairo_0_calldata() {
+        let abi = Abi::parse(&json!([
+            {
+                "type": "struct",
+                "name": "Uint256",
+                "size": 2,
+                "members": [
+                    { "name": "low", "type": "felt", "offset": 0 },
+                    { "name": "high", "type": "felt", "offset": 1 }
+                ]
+            },
+            {
+                "type": "function",
+                "name": "transfer",
+                "inputs": [
+                    { "name": "recipient", "type": "felt" },
+                    { "name": "amount", "type": "Uint256" },
+                    { "name": "data_len", "type": "felt" },
+                    { "name": "data", "type": "felt*" }
+                ],
+                "outputs": []
+            }
+        ]))
+        .unwrap();
+
+        let params = abi
+            .decode_function_inputs("transfer", &felts(&[0x123, 1000, 1, 2, 7, 8]))
+            .unwrap();
+
+        assert_eq!(
+            params
+                .iter()
+                .map(|p| format!("{}: {}", p.name, p.value))
+                .collect::<Vec<_>>(),
+            vec![
+                "recipient: 0x123",
+                "amount: 340282366920938463463374607431768212456",
+                "data_len: 0x2",
+                "data: [0x7, 0x8]",
+            ]
+        );
+
+        assert!(abi
+            .decode_function_inputs("transfer", &felts(&[0x123, 1000, 0, 2, 7]))
+            .is_err());
+    }
+
+    #[test]
+    fn decode_cairo_1_calldata() {
+        let abi = Abi::parse(&Value::String(
+            json!([
+                {
+                    "type": "struct",
+                    "name": "example::Order",
+                    "members": [
+                        { "name": "id", "type": "core::integer::u64" },
+                        { "name": "filled", "type": "core::bool" }
+                    ]
+                },
+                {
+                    "type": "enum",
+                    "name": "example::Side",
+                    "variants": [
+                        { "name": "Buy", "type": "()" },
+                        { "name": "Sell", "type": "core::integer::u256" }
+                    ]
+                },
+                {
+                    "type": "interface",
+                    "name": "example::IExchange",
+                    "items": [{
+                        "type": "function",
+                        "name": "place",
+                        "inputs": [
+                            { "name": "orders", "type": "core::array::Array::<example::Order>" },
+                            { "name": "side", "type": "example::Side" },
+                            { "name": "pair", "type": "(core::felt252, core::felt252)" }
+                        ],
+                        "outputs": [],
+                        "state_mutability": "external"
+                    }]
+                }
+            ])
+            .to_string(),
+        ))
+        .unwrap();
+
+        let params = abi
+            .decode_function_inputs("place", &felts(&[2, 1, 0, 2, 1, 1, 5, 0, 0xa, 0xb]))
+            .unwrap();
+
+        assert_eq!(
+            params
+                .iter()
+                .map(|p| p.value.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "[{ id: 0x1, filled: false }, { id: 0x2, filled: true }]",
+                "Sell(5)",
+                "(0xa, 0xb)",
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_cairo_0_inputs() {
+        let abi = Abi::parse(&json!([{
+            "type": "function",
+            "name": "transfer",
+            "inputs": [
+                { "name": "recipient", "type": "felt" },
+                { "name": "amount", "type": "Uint256" },
+                { "name": "data_len", "type": "felt" },
+                { "name": "data", "type": "felt*" }
+            ],
+            "outputs": []
+        }]))
+        .unwrap();
+
+        let inputs = [
+            "data=[7, 'a']",
+            "recipient=0x123",
+            "amount=u256:340282366920938463463374607431768212456",
+        ]
+        .map(String::from);
+        let expected = vec![
+            FieldElement::from(0x123_u64),
+            FieldElement::from(1000_u64),
+            FieldElement::ONE,
+            FieldElement::TWO,
+            FieldElement::from(7_u64),
+            FieldElement::from(b'a'),
+        ];
+
+        assert_eq!(
+            abi.encode_function_inputs("transfer", &inputs).unwrap(),
+            expected
+        );
+        assert_eq!(
+            abi.encode_function_inputs(
+                "transfer",
+                &["0x123,0x1000000000000000000000000000003e8", "[7,'a']"].map(String::from)
+            )
+            .unwrap(),
+            expected
+        );
+
+        assert!(abi
+            .encode_function_inputs("transfer", &["recipient=0x1"].map(String::from))
+            .is_err());
+    }
+
+    #[test]
+    fn encode_cairo_1_inputs() {
+        let abi = Abi::parse(&json!([
+            {
+                "type": "struct",
+                "name": "example::Order",
+                "members": [
+                    { "name": "id", "type": "core::integer::u64" },
+                    { "name": "filled", "type": "core::bool" }
+                ]
+            },
+            {
+                "type": "enum",
+                "name": "example::Side",
+                "variants": [
+                    { "name": "Buy", "type": "()" },
+                    { "name": "Sell", "type": "core::integer::u256" }
+                ]
+            },
+            {
+                "type": "function",
+                "name": "place",
+                "inputs": [
+                    { "name": "orders", "type": "core::array::Array::<example::Order>" },
+                    { "name": "side", "type": "example::Side" },
+                    { "name": "pair", "type": "(core::felt252, core::felt252)" }
+                ],
+                "outputs": []
+            }
+        ]))
+        .unwrap();
+
+        let calldata = abi
+            .encode_function_inputs(
+                "place",
+                &[
+                    "[{1, false}, {filled: true, id: 2}]",
+                    "Sell(5)",
+                    "(0xa, 0xb)",
+                ]
+                .map(String::from),
+            )
+            .unwrap();
+
+        assert_eq!(calldata, felts(&[2, 1, 0, 2, 1, 1, 5, 0, 0xa, 0xb]));
+        assert_eq!(
+            abi.decode_function_inputs("place", &calldata)
+                .unwrap()
+                .iter()
+                .map(|p| p.value.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "[{ id: 0x1, filled: false }, { id: 0x2, filled: true }]",
+                "Sell(5)",
+                "(0xa, 0xb)",
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_events() {
+        let selector = |name| get_selector_from_name(name).unwrap();
+
+        let cairo_0 = Abi::parse(&json!([{
+            "type": "event",
+            "name": "Transfer",
+            "keys": [],
+            "data": [
+                { "name": "from_", "type": "felt" },
+                { "name": "to", "type": "felt" },
+                { "name": "value", "type": "Uint256" }
+            ]
+        }]))
+        .unwrap();
+
+        let event = cairo_0
+            .decode_event(&[selector("Transfer")], &felts(&[1, 2, 3, 0]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.name, "Transfer");
+        assert_eq!(
+            event
+                .params
+                .iter()
+                .map(|p| (p.name.as_str(), p.value.to_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("from_", "0x1".into()),
+                ("to", "0x2".into()),
+                ("value", "3".into())
+            ]
+        );
+        assert!(cairo_0
+            .decode_event(&[selector("Approval")], &[])
+            .unwrap()
+            .is_none());
+
+        let cairo_1 = Abi::parse(&json!([
+            {
+                "type": "event",
+                "name": "token::Transfer",
+                "kind": "struct",
+                "members": [
+                    { "name": "from", "type": "core::felt252", "kind": "key" },
+                    { "name": "value", "type": "core::integer::u256", "kind": "data" }
+                ]
+            },
+            {
+                "type": "event",
+                "name": "token::Component::Event",
+                "kind": "enum",
+                "variants": [
+                    { "name": "Transfer", "type": "token::Transfer", "kind": "nested" }
+                ]
+            },
+            {
+                "type": "event",
+                "name": "token::Contract::Event",
+                "kind": "enum",
+                "variants": [
+                    { "name": "ComponentEvent", "type": "token::Component::Event", "kind": "flat" }
+                ]
+            }
+        ]))
+        .unwrap();
+
+        let event = cairo_1
+            .decode_event(
+                &[selector("Transfer"), FieldElement::from(5_u64)],
+                &felts(&[7, 0]),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.name, "Transfer");
+        assert_eq!(
+            event
+                .params
+                .iter()
+                .map(|p| (p.name.as_str(), p.value.to_string()))
+                .collect::<Vec<_>>(),
+            vec![("from", "0x5".into()), ("value", "7".into())]
+        );
+    }
+
+    #[test]
+    fn decode_function_outputs() {
+        let abi = Abi::parse(&json!([
+            {
+                "type": "function",
+                "name": "name",
+                "inputs": [],
+                "outputs": [{ "type": "core::byte_array::ByteArray" }],
+                "state_mutability": "view"
+            },
+            {
+                "type": "function",
+                "name": "info",
+                "inputs": [],
+                "outputs": [{ "type": "(core::felt252, core::integer::u256, core::array::Span::<core::felt252>)" }],
+                "state_mutability": "view"
+            }
+        ]))
+        .unwrap();
+
+        // "Starknet Token" fits in the pending word
+        let mut result = vec![FieldElement::ZERO];
+        result.push(cairo_short_string_to_felt("Starknet Token").unwrap());
+        result.push(FieldElement::from(14_u64));
+
+        let outputs = abi.decode_function_outputs("name", &result).unwrap();
+        assert_eq!(outputs[0].value.to_string(), "\"Starknet Token\"");
+
+        let mut result = vec![cairo_short_string_to_felt("STRK").unwrap()];
+        result.extend(felts(&[1000, 0, 2, 1, 0x4142]));
+
+        let outputs = abi.decode_function_outputs("info", &result).unwrap();
+        assert_eq!(outputs[0].name, "");
+        assert_eq!(outputs[0].value.to_string(), "('STRK', 1000, [0x1, 'AB'])");
+    }
+
+    #[test]
+    fn render_signatures() {
+        let abi = Abi::parse(&json!([
+            {
+                "type": "function",
+                "name": "transfer",
+                "inputs": [
+                    { "name": "recipient", "type": "felt" },
+                    { "name": "amount", "type": "Uint256" }
+                ],
+                "outputs": [{ "name": "success", "type": "felt" }]
+            },
+            {
+                "type": "interface",
+                "name": "token::IToken",
+                "items": [{
+                    "type": "function",
+                    "name": "balance_of",
+                    "inputs": [{ "name": "account", "type": "core::starknet::contract_address::ContractAddress" }],
+                    "outputs": [{ "type": "core::integer::u256" }],
+                    "state_mutability": "view"
+                }]
+            },
+            {
+                "type": "event",
+                "name": "token::Transfer",
+                "kind": "struct",
+                "members": [{ "name": "from", "type": "core::felt252", "kind": "key" }]
+            }
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            abi.entries().iter().map(signature).collect::<Vec<_>>(),
+            vec![
+                "function transfer(recipient: felt, amount: Uint256) -> (success: felt)",
+                "interface token::IToken
+    function balance_of(account: core::starknet::contract_address::ContractAddress) -> core::integer::u256 [view]",
+                "event token::Transfer { from: core::felt252 (key) }",
+            ]
+        );
+        assert_eq!(
+            signature(abi.function("balance_of").unwrap()),
+            "function balance_of(account: core::starknet::contract_address::ContractAddress) -> core::integer::u256 [view]"
+        );
+    }
+
+    #[test]
+    fn sierra_class_abi() {
+        // the node returns the abi of Sierra classes as a string
+        let class = json!({
+            "sierra_program": [],
+            "abi": json!([
+                {
+                    "type": "impl",
+                    "name": "VaultImpl",
+                    "interface_name": "vault::IVault"
+                },
+                {
+                    "type": "enum",
+                    "name": "core::option::Option::<core::integer::u256>",
+                    "variants": [
+                        { "name": "Some", "type": "core::integer::u256" },
+                        { "name": "None", "type": "()" }
+                    ]
+                },
+                {
+                    "type": "interface",
+                    "name": "vault::IVault",
+                    "items": [{
+                        "type": "function",
+                        "name": "rebalance",
+                        "inputs": [
+                            { "name": "delta", "type": "core::integer::i128" },
+                            { "name": "limit", "type": "core::option::Option::<core::integer::u256>" },
+                            { "name": "divisor", "type": "core::zeroable::NonZero::<core::integer::u256>" }
+                        ],
+                        "outputs": [],
+                        "state_mutability": "external"
+                    }]
+                }
+            ])
+            .to_string()
+        });
+
+        let abi = Abi::parse(&class).unwrap();
+
+        let calldata = abi
+            .encode_function_inputs(
+                "rebalance",
+                &["-5", "Some(0x100000000000000000000000000000001)", "3"].map(String::from),
+            )
+            .unwrap();
+
+        assert_eq!(calldata[0], FieldElement::ZERO - FieldElement::from(5_u64));
+        assert_eq!(&calldata[1..], felts(&[0, 1, 1, 3, 0]));
+        assert_eq!(
+            abi.decode_function_inputs("rebalance", &calldata)
+                .unwrap()
+                .iter()
+                .map(|p| p.value.to_string())
+                .collect::<Vec<_>>(),
+            vec!["-5", "Some(340282366920938463463374607431768211457)", "3"]
+        );
+    }
+
+    #[test]
+    fn undeclared_option_and_result() {
+        let abi = Abi::parse(&json!([
+            {
+                "type": "struct",
+                "name": "book::Level",
+                "members": [
+                    { "name": "price", "type": "core::integer::u128" },
+                    { "name": "size", "type": "core::integer::u256" }
+                ]
+            },
+            {
+                "type": "function",
+                "name": "settle",
+                "inputs": [
+                    { "name": "levels", "type": "core::option::Option::<core::array::Span::<book::Level>>" },
+                    { "name": "referrer", "type": "core::option::Option::<core::felt252>" }
+                ],
+                "outputs": [
+                    { "type": "core::result::Result::<core::integer::u64, core::felt252>" }
+                ]
+            }
+        ]))
+        .unwrap();
+
+        let calldata = abi
+            .encode_function_inputs(
+                "settle",
+                &["Some([{price: 10, size: 2}, {11, 3}])", "None"].map(String::from),
+            )
+            .unwrap();
+        assert_eq!(calldata, felts(&[0, 2, 10, 2, 0, 11, 3, 0, 1]));
+
+        assert_eq!(
+            abi.decode_function_inputs("settle", &calldata)
+                .unwrap()
+                .iter()
+                .map(|p| p.value.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "Some([{ price: 0xa, size: 2 }, { price: 0xb, size: 3 }])",
+                "None"
+            ]
+        );
+
+        let result = vec![
+            FieldElement::ONE,
+            cairo_short_string_to_felt("too late").unwrap(),
+        ];
+        assert_eq!(
+            abi.decode_function_outputs("settle", &result).unwrap()[0]
+                .value
+                .to_string(),
+            "Err('too late')"
+        );
+    }
+
+    #[test]
+    fn byte_array_roundtrip() {
+        let exact = "a".repeat(31);
+        for string in ["", "hello", exact.as_str(), &"long string ".repeat(5)] {
+            let felts = byte_array_to_felts(string);
+            assert_eq!(
+                byte_array_from_felts(&felts).unwrap(),
+                (string.to_string(), felts.len())
+            );
+        }
+
+        assert_eq!(
+            byte_array_to_felts(&exact)[..],
+            [
+                FieldElement::ONE,
+                cairo_short_string_to_felt(&exact).unwrap(),
+                FieldElement::ZERO,
+                FieldElement::ZERO
+            ]
+        );
+        assert!(byte_array_from_felts(&felts(&[2, 1, 0])).is_err());
+    }
+
+    #[test]
+    fn nested_event_keys() {
+        let abi = Abi::parse(&json!([
+            {
+                "type": "event",
+                "name": "token::Transfer",
+                "kind": "struct",
+                "members": []
+            },
+            {
+                "type": "event",
+                "name": "token::Component::Event",
+                "kind": "enum",
+                "variants": [{ "name": "Transfer", "type": "token::Transfer", "kind": "nested" }]
+            },
+            {
+                "type": "event",
+                "name": "token::Contract::Event",
+                "kind": "enum",
+                "variants": [
+                    { "name": "TokenEvent", "type": "token::Component::Event", "kind": "flat" },
+                    { "name": "NestedEvent", "type": "token::Component::Event", "kind": "nested" }
+                ]
+            }
+        ]))
+        .unwrap();
+
+        let selector = |name| get_selector_from_name(name).unwrap();
+
+        assert_eq!(
+            abi.event_keys("Transfer").unwrap(),
+            vec![selector("Transfer")]
+        );
+        assert_eq!(
+            abi.event_keys("token::Transfer").unwrap(),
+            vec![selector("Transfer")]
+        );
+        assert_eq!(
+            abi.event_keys("NestedEvent").unwrap(),
+            vec![selector("NestedEvent")]
+        );
+        assert!(abi.event_keys("Approval").is_err());
+    }
+
+    #[test]
+    fn encode_json_args() {
+        let abi = Abi::parse(&json!([
+            {
+                "type": "struct",
+                "name": "swap::Route",
+                "members": [
+                    { "name": "pool", "type": "core::starknet::contract_address::ContractAddress" },
+                    { "name": "zero_for_one", "type": "core::bool" }
+                ]
+            },
+            {
+                "type": "function",
+                "name": "swap",
+                "inputs": [
+                    { "name": "routes", "type": "core::array::Array::<swap::Route>" },
+                    { "name": "amount", "type": "core::integer::u256" },
+                    { "name": "referrer", "type": "core::option::Option::<core::felt252>" },
+                    { "name": "deadline", "type": "core::option::Option::<core::integer::u64>" }
+                ],
+                "outputs": []
+            }
+        ]))
+        .unwrap();
+
+        let args = json!({
+            "amount": "0x100000000000000000000000000000005",
+            "routes": [{ "zero_for_one": true, "pool": "0xabc" }],
+            "referrer": { "Some": "'ref'" },
+            "deadline": { "None": null }
+        });
+
+        assert_eq!(
+            abi.encode_function_args("swap", &args).unwrap(),
+            vec![
+                FieldElement::ONE,
+                FieldElement::from(0xabc_u64),
+                FieldElement::ONE,
+                FieldElement::from(5_u64),
+                FieldElement::ONE,
+                FieldElement::ZERO,
+                cairo_short_string_to_felt("ref").unwrap(),
+                FieldElement::ONE,
+            ]
+        );
+        assert!(abi
+            .encode_function_args("swap", &json!({ "amount": 1 }))
+            .is_err());
+    }
+
+    #[test]
+    fn diff_abis() {
+        let before = Abi::parse(&json!([
+            { "type": "struct", "name": "Pool", "members": [{ "name": "fee", "type": "felt" }] },
+            { "type": "function", "name": "swap", "inputs": [{ "name": "amount", "type": "felt" }], "outputs": [] },
+            { "type": "function", "name": "pause", "inputs": [], "outputs": [] },
+            { "type": "event", "name": "Swap", "keys": [], "data": [{ "name": "amount", "type": "felt" }] }
+        ]))
+        .unwrap();
+        let after = Abi::parse(&json!([
+            { "type": "struct", "name": "Pool", "members": [{ "name": "fee", "type": "felt" }] },
+            { "type": "function", "name": "swap", "inputs": [{ "name": "amount", "type": "Uint256" }], "outputs": [] },
+            { "type": "function", "name": "upgrade", "inputs": [{ "name": "class_hash", "type": "felt" }], "outputs": [] },
+            { "type": "event", "name": "Swap", "keys": [], "data": [{ "name": "amount", "type": "felt" }] }
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            before.diff(&after),
+            vec![
+                AbiChange::Removed("function pause()".to_string()),
+                AbiChange::Changed {
+                    name: "swap".to_string(),
+                    before: "function swap(amount: felt)".to_string(),
+                    after: "function swap(amount: Uint256)".to_string(),
+                },
+                AbiChange::Added("function upgrade(class_hash: felt)".to_string()),
+            ]
+        );
+        assert!(after.diff(&after).is_empty());
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use eyre::{bail, eyre, Result};
+use starknet::core::types::FieldElement;
+
+pub const ADDRESS_BOOK_FILE: &str = ".starknet/probe/addressbook.json";
+
+/// Saved addresses, keyed by chain id and then by name.
+pub type Contacts = BTreeMap<String, BTreeMap<String, FieldElement>>;
+
+/// An address, or a name saved in the address book. A name is only resolved once the chain is
+/// known, as the same name may point to different addresses on different chains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressOrName {
+    Address(FieldElement),
+    Name(String),
+}
+
+impl AddressOrName {
+    /// The address saved under the name for the chain, or the address itself.
+    pub fn resolve(&self, chain: FieldElement) -> Result<FieldElement> {
+        match self {
+            Self::Address(address) => Ok(*address),
+            Self::Name(name) => AddressBook::from_home_dir()?.resolve(chain, name),
+        }
+    }
+}
+
+impl FromStr for AddressOrName {
+    type Err = eyre::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        if value.is_empty() {
+            bail!("an address or a saved name is required")
+        }
+
+        match FieldElement::from_str(value) {
+            Ok(address) => Ok(Self::Address(address)),
+            Err(_) => Ok(Self::Name(value.to_string())),
+        }
+    }
+}
+
+impl From<FieldElement> for AddressOrName {
+    fn from(address: FieldElement) -> Self {
+        Self::Address(address)
+    }
+}
+
+impl fmt::Display for AddressOrName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Address(address) => write!(f, "{address:#x}"),
+            Self::Name(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// Addresses saved under a name for each chain, so that they can be given by name to the commands
+/// that take an address.
+pub struct AddressBook {
+    path: PathBuf,
+}
+
+impl AddressBook {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// The address book kept in the home directory.
+    pub fn from_home_dir() -> Result<Self> {
+        let home = home::home_dir().ok_or_else(|| eyre!("could not find the home directory"))?;
+        Ok(Self::new(home.join(ADDRESS_BOOK_FILE)))
+    }
+
+    pub fn add(&self, chain: FieldElement, name: &str, address: FieldElement) -> Result<()> {
+        // a name that reads as a felt would never be looked up
+        if name.is_empty() || FieldElement::from_str(name).is_ok() {
+            bail!("`{name}` can't be used as a name as it is a valid address")
+        }
+
+        let mut contacts = self.load()?;
+        contacts
+            .entry(format!("{chain:#x}"))
+            .or_default()
+            .insert(name.to_string(), address);
+
+        self.save(&contacts)
+    }
+
+    /// Removes the name saved for the chain, returning whether there was one.
+    pub fn remove(&self, chain: FieldElement, name: &str) -> Result<bool> {
+        let mut contacts = self.load()?;
+        let chain = format!("{chain:#x}");
+
+        let removed = match contacts.get_mut(&chain) {
+            Some(names) => names.remove(name).is_some(),
+            None => false,
+        };
+
+        if contacts.get(&chain).is_some_and(|names| names.is_empty()) {
+            contacts.remove(&chain);
+        }
+
+        self.save(&contacts)?;
+        Ok(removed)
+    }
+
+    /// Finds the address saved under the name for the chain. Names saved for other chains are
+    /// not looked at.
+    pub fn resolve(&self, chain: FieldElement, name: &str) -> Result<FieldElement> {
+        self.load()?
+            .get(&format!("{chain:#x}"))
+            .and_then(|names| names.get(name))
+            .copied()
+            .ok_or_else(|| {
+                eyre!("`{name}` is neither an address nor a name saved for the chain {chain:#x}")
+            })
+    }
+
+    pub fn load(&self) -> Result<Contacts> {
+        if !self.path.exists() {
+            return Ok(Contacts::new());
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn save(&self, contacts: &Contacts) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(contacts)?)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starknet::core::chain_id::{MAINNET, TESTNET};
+
+    #[test]
+    fn address_book_roundtrip() {
+        let path =
+            std::env::temp_dir().join(format!("probe-address-book-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let book = AddressBook::new(&path);
+        let (alice, bob) = (
+            FieldElement::from(0x1234_u64),
+            FieldElement::from(0x5678_u64),
+        );
+
+        assert!(book.add(MAINNET, "0x1", alice).is_err());
+        assert!(book.resolve(MAINNET, "alice").is_err());
+
+        book.add(MAINNET, "alice", alice).unwrap();
+        book.add(MAINNET, "bob", bob).unwrap();
+        assert_eq!(book.resolve(MAINNET, "alice").unwrap(), alice);
+        assert_eq!(book.resolve(MAINNET, "bob").unwrap(), bob);
+
+        // names are only looked up on the chain they are saved for
+        assert!(book.resolve(TESTNET, "bob").is_err());
+        book.add(TESTNET, "bob", alice).unwrap();
+        assert_eq!(book.resolve(TESTNET, "bob").unwrap(), alice);
+        assert_eq!(book.resolve(MAINNET, "bob").unwrap(), bob);
+
+        assert!(book.remove(TESTNET, "bob").unwrap());
+        assert!(!book.remove(TESTNET, "bob").unwrap());
+        assert!(book.resolve(TESTNET, "bob").is_err());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn parse_address_or_name() {
+        assert_eq!(
+            "0x1234".parse::<AddressOrName>().unwrap(),
+            AddressOrName::Address(FieldElement::from(0x1234_u64))
+        );
+        assert_eq!(
+            "alice".parse::<AddressOrName>().unwrap(),
+            AddressOrName::Name("alice".to_string())
+        );
+        assert!("".parse::<AddressOrName>().is_err());
+
+        // addresses don't need the address book
+        let address = AddressOrName::Address(FieldElement::from(0x1234_u64));
+        assert_eq!(
+            address.resolve(MAINNET).unwrap(),
+            FieldElement::from(0x1234_u64)
+        );
+    }
+}
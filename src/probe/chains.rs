@@ -0,0 +1,51 @@
+use serde::Serialize;
+use starknet::core::types::FieldElement;
+use starknet::core::utils::cairo_short_string_to_felt;
+
+/// The known chains, by their alias and the short string of their id.
+pub const KNOWN_CHAINS: [(&str, &str); 4] = [
+    ("mainnet", "SN_MAIN"),
+    ("sepolia", "SN_SEPOLIA"),
+    ("goerli", "SN_GOERLI"),
+    ("goerli2", "SN_GOERLI2"),
+];
+
+/// A known chain, as listed by `probe chains`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KnownChain {
+    pub alias: &'static str,
+    pub name: &'static str,
+    pub id: FieldElement,
+}
+
+pub fn known_chains() -> Vec<KnownChain> {
+    KNOWN_CHAINS
+        .iter()
+        .map(|(alias, name)| KnownChain {
+            alias,
+            name,
+            id: cairo_short_string_to_felt(name).unwrap(),
+        })
+        .collect()
+}
+
+/// The alias of the chain if it is a known one, e.g. `mainnet` for `SN_MAIN`.
+pub fn chain_alias(chain_id: FieldElement) -> Option<&'static str> {
+    known_chains()
+        .into_iter()
+        .find(|chain| chain.id == chain_id)
+        .map(|chain| chain.alias)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starknet::core::chain_id::{MAINNET, TESTNET};
+
+    #[test]
+    fn known_chain_aliases() {
+        assert_eq!(chain_alias(MAINNET), Some("mainnet"));
+        assert_eq!(chain_alias(TESTNET), Some("goerli"));
+        assert_eq!(chain_alias(FieldElement::ONE), None);
+    }
+}
@@ -0,0 +1,267 @@
+use eyre::{bail, eyre, Result};
+use reqwest::Url;
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+use starknet::core::types::FieldElement;
+
+use super::utils::send_rpc_request;
+
+/// The StarkNet core contracts, by the id of the Ethereum chain they are deployed on.
+const CORE_CONTRACTS: [(u64, &str); 2] = [
+    (1, "0xc662c410C0ECf747543f5bA90660f6ABeBD9C8c4"),
+    (11155111, "0xE2Bb56ee936fd6433DC0F6e7e3b8365C906AA057"),
+];
+
+/// The signature of the event the core contract logs for a message sent to L2.
+const LOG_MESSAGE_TO_L2: &str = "LogMessageToL2(address,uint256,uint256,uint256[],uint256,uint256)";
+
+/// A message sent from L1 to the `l1_handler` of a contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct L1Message {
+    pub from_address: FieldElement,
+    pub to_address: FieldElement,
+    pub selector: FieldElement,
+    pub payload: Vec<FieldElement>,
+    pub nonce: FieldElement,
+}
+
+impl L1Message {
+    /// Reads the message from the `LogMessageToL2` event of the core contract, as found in the
+    /// logs of the receipt of the L1 transaction. The sender, recipient and selector are its
+    /// topics, while the payload, nonce and fee are ABI encoded in its data.
+    pub fn from_log(log: &Value) -> Result<Self> {
+        let topics = log["topics"]
+            .as_array()
+            .ok_or_else(|| eyre!("the log has no topics"))?
+            .iter()
+            .map(|topic| parse_word(topic.as_str().unwrap_or_default()))
+            .collect::<Result<Vec<_>>>()?;
+
+        let [signature, from_address, to_address, selector] = topics.as_slice() else {
+            bail!(
+                "expected the 4 topics of a LogMessageToL2 event, got {}",
+                topics.len()
+            )
+        };
+        if signature[..] != Keccak256::digest(LOG_MESSAGE_TO_L2.as_bytes())[..] {
+            bail!("the log is not a LogMessageToL2 event")
+        }
+
+        let data = hex::decode(
+            log["data"]
+                .as_str()
+                .unwrap_or_default()
+                .trim_start_matches("0x"),
+        )?;
+        let words = data.chunks_exact(32).collect::<Vec<_>>();
+        let word = |i: usize| {
+            words
+                .get(i)
+                .ok_or_else(|| eyre!("the data of the log is too short"))
+                .and_then(|word| Ok(FieldElement::from_bytes_be(&(*word).try_into()?)?))
+        };
+        let index = |felt: FieldElement| -> Result<usize> { Ok(felt.to_string().parse()?) };
+
+        // the payload is a dynamic array, found at the offset in bytes of its first word
+        let offset = index(word(0)?)? / 32;
+        let len = index(word(offset)?)?;
+        let payload = (offset + 1..offset + 1 + len)
+            .map(word)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            from_address: FieldElement::from_bytes_be(from_address)?,
+            to_address: FieldElement::from_bytes_be(to_address)?,
+            selector: FieldElement::from_bytes_be(selector)?,
+            payload,
+            nonce: word(1)?,
+        })
+    }
+}
+
+/// Parses a 32 bytes word in hex.
+fn parse_word(hex: &str) -> Result<[u8; 32]> {
+    let mut word = [0u8; 32];
+    hex::decode_to_slice(format!("{:0>64}", hex.trim_start_matches("0x")), &mut word)?;
+    Ok(word)
+}
+
+/// Parses a 20 bytes Ethereum address. An address in mixed case must match its EIP-55 checksum,
+/// while an all lowercase or uppercase one carries none.
+pub fn parse_address(address: &str) -> Result<[u8; 20]> {
+    let digits = address
+        .strip_prefix("0x")
+        .filter(|digits| digits.len() == 40)
+        .ok_or_else(|| eyre!("`{address}` is not a 0x prefixed address of 20 bytes"))?;
+
+    let mut bytes = [0u8; 20];
+    hex::decode_to_slice(digits, &mut bytes)
+        .map_err(|_| eyre!("`{address}` is not a hex address"))?;
+
+    let mixed_case = digits.chars().any(|c| c.is_ascii_lowercase())
+        && digits.chars().any(|c| c.is_ascii_uppercase());
+    if mixed_case && checksum_address(&bytes) != address {
+        bail!("`{address}` doesn't match its EIP-55 checksum")
+    }
+
+    Ok(bytes)
+}
+
+/// The EIP-55 checksummed form of an address, where each letter is uppercased if the matching
+/// nibble of the keccak256 of the lowercase address is 8 or more.
+pub fn checksum_address(address: &[u8; 20]) -> String {
+    let lowercase = hex::encode(address);
+    let hash = Keccak256::digest(lowercase.as_bytes());
+
+    let digits: String = lowercase
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    format!("0x{digits}")
+}
+
+/// The StarkNet core contract on Ethereum, where the state of StarkNet is settled and the
+/// messages between the two chains go through.
+pub struct CoreContract {
+    url: Url,
+    address: FieldElement,
+}
+
+impl CoreContract {
+    /// The core contract at the given address, or else the known one of the Ethereum chain.
+    pub async fn new(url: Url, address: Option<FieldElement>) -> Result<Self> {
+        let address = match address {
+            Some(address) => address,
+            None => {
+                let chain_id: String = send_rpc_request(&url, "eth_chainId", json!([])).await?;
+                let chain_id = u64::from_str_radix(chain_id.trim_start_matches("0x"), 16)?;
+
+                let (_, address) = CORE_CONTRACTS
+                    .iter()
+                    .find(|(id, _)| *id == chain_id)
+                    .ok_or_else(|| {
+                        eyre!("no known core contract on Ethereum chain {chain_id}, use --core-contract")
+                    })?;
+                FieldElement::from_hex_be(address)?
+            }
+        };
+
+        Ok(Self { url, address })
+    }
+
+    /// The number of the last StarkNet block whose state was settled on L1.
+    pub async fn state_block_number(&self) -> Result<i64> {
+        let word = self.call("stateBlockNumber()", &[]).await?;
+        // an int256, which is -1 before the first state update
+        Ok(i64::from_be_bytes(word[24..].try_into().unwrap()))
+    }
+
+    /// The state root of the last StarkNet block settled on L1.
+    pub async fn state_root(&self) -> Result<FieldElement> {
+        let word = self.call("stateRoot()", &[]).await?;
+        Ok(FieldElement::from_bytes_be(&word)?)
+    }
+
+    /// The fee of a message sent from L1, plus one, while it waits to be consumed on L2, or zero
+    /// once consumed.
+    pub async fn l1_to_l2_message(&self, hash: &[u8; 32]) -> Result<[u8; 32]> {
+        self.call("l1ToL2Messages(bytes32)", hash).await
+    }
+
+    /// The number of copies of a message sent from L2 that are settled on L1 and not consumed yet.
+    pub async fn l2_to_l1_message(&self, hash: &[u8; 32]) -> Result<[u8; 32]> {
+        self.call("l2ToL1Messages(bytes32)", hash).await
+    }
+
+    /// Calls a view function of the contract, returning the first word of its result.
+    async fn call(&self, signature: &str, args: &[u8]) -> Result<[u8; 32]> {
+        let mut data = function_selector(signature).to_vec();
+        data.extend_from_slice(args);
+
+        let to = format!("0x{}", hex::encode(&self.address.to_bytes_be()[12..]));
+        let res: String = send_rpc_request(
+            &self.url,
+            "eth_call",
+            json!([{ "to": to, "data": format!("0x{}", hex::encode(data)) }, "latest"]),
+        )
+        .await?;
+
+        let res = hex::decode(res.trim_start_matches("0x"))?;
+        res.get(..32)
+            .and_then(|word| word.try_into().ok())
+            .ok_or_else(|| eyre!("unexpected result from the core contract for {signature}"))
+    }
+}
+
+/// The first 4 bytes of the keccak256 of a function signature, which select it in a call.
+fn function_selector(signature: &str) -> [u8; 4] {
+    Keccak256::digest(signature.as_bytes())[..4]
+        .try_into()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_from_log() {
+        let word = |n: u64| format!("{n:064x}");
+        let log = json!({
+            "topics": [
+                format!("0x{}", hex::encode(Keccak256::digest(LOG_MESSAGE_TO_L2.as_bytes()))),
+                format!("0x{}", word(0xa1)),
+                format!("0x{}", word(0xb2)),
+                format!("0x{}", word(0xc3)),
+            ],
+            // the offset of the payload, the nonce, the fee, then the payload
+            "data": format!("0x{}", [0x60, 7, 1000, 2, 10, 20].map(word).concat()),
+        });
+
+        let felt = FieldElement::from;
+        assert_eq!(
+            L1Message::from_log(&log).unwrap(),
+            L1Message {
+                from_address: felt(0xa1u64),
+                to_address: felt(0xb2u64),
+                selector: felt(0xc3u64),
+                payload: vec![felt(10u64), felt(20u64)],
+                nonce: felt(7u64),
+            }
+        );
+
+        let mut other = log.clone();
+        other["topics"][0] = json!(format!("0x{}", word(1)));
+        assert!(L1Message::from_log(&other).is_err());
+    }
+
+    #[test]
+    fn checksummed_addresses() {
+        for (_, address) in CORE_CONTRACTS {
+            let bytes = parse_address(&address.to_lowercase()).unwrap();
+            assert_eq!(checksum_address(&bytes), address);
+            assert_eq!(parse_address(address).unwrap(), bytes);
+        }
+
+        let wrong_checksum = CORE_CONTRACTS[0].1.replacen('c', "C", 1);
+        assert!(parse_address(&wrong_checksum).is_err());
+        assert!(parse_address("0x1234").is_err());
+    }
+
+    #[test]
+    fn erc20_function_selector() {
+        assert_eq!(
+            function_selector("transfer(address,uint256)"),
+            [0xa9, 0x05, 0x9c, 0xbb]
+        );
+    }
+}
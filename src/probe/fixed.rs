@@ -0,0 +1,184 @@
+//! The fixed-point encodings of decimal numbers used by Cairo DeFi protocols: the signed 64.61
+//! format of the Cairo 0 `Math64x61` library, and the unsigned wad and ray integers scaled by
+//! 10^18 and 10^27.
+
+use super::abi::{parse_u256, u256_to_dec};
+use super::tokens::parse_units;
+use super::utils::fmt::format_u256_units;
+
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use eyre::{bail, eyre, Result};
+use starknet::core::types::FieldElement;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FixedPoint {
+    /// A signed felt holding the number times 2^61, below 2^64 in absolute value.
+    #[value(name = "64x61")]
+    Fixed64x61,
+    /// An integer holding the number times 10^18.
+    Wad,
+    /// An integer holding the number times 10^27.
+    Ray,
+}
+
+impl FixedPoint {
+    /// The number of decimals the encoding holds.
+    fn decimals(&self) -> u32 {
+        match self {
+            Self::Fixed64x61 => FIXED_64X61_DECIMALS,
+            Self::Wad => 18,
+            Self::Ray => 27,
+        }
+    }
+}
+
+const FRACTION_BITS: u32 = 61;
+
+/// The number of decimals needed for a 64.61 number to read back as the same felt, as 10^-19 is
+/// below 2^-61.
+const FIXED_64X61_DECIMALS: u32 = 19;
+
+/// Encodes a decimal number, e.g. `-1.5`, in the fixed-point format. The 64.61 encoding is
+/// printed as a felt, and the wad and ray ones as decimal integers.
+pub fn to_fixed(value: &str, format: FixedPoint) -> Result<String> {
+    match format {
+        FixedPoint::Fixed64x61 => Ok(format!("{:#x}", to_64x61(value)?)),
+        FixedPoint::Wad | FixedPoint::Ray => {
+            let (low, high) = parse_units(value, format.decimals())?;
+            Ok(u256_to_dec(low, high))
+        }
+    }
+}
+
+/// Decodes a raw fixed-point value, in decimal or in hex, into a decimal number.
+pub fn from_fixed(value: &str, format: FixedPoint) -> Result<String> {
+    match format {
+        FixedPoint::Fixed64x61 => {
+            let felt =
+                FieldElement::from_str(value).map_err(|_| eyre!("invalid felt `{value}`"))?;
+            from_64x61(felt)
+        }
+        FixedPoint::Wad | FixedPoint::Ray => {
+            let (low, high) = parse_u256(value)?;
+            Ok(format_u256_units(low, high, format.decimals()))
+        }
+    }
+}
+
+fn to_64x61(value: &str) -> Result<FieldElement> {
+    let invalid = || eyre!("invalid number `{value}`");
+
+    let (negative, magnitude) = match value.strip_prefix('-') {
+        Some(magnitude) => (true, magnitude),
+        None => (false, value),
+    };
+    let (whole, fraction) = magnitude.split_once('.').unwrap_or((magnitude, ""));
+    if whole.is_empty() && fraction.is_empty()
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > FIXED_64X61_DECIMALS as usize {
+        bail!("`{value}` has more than the {FIXED_64X61_DECIMALS} decimals a 64.61 number holds")
+    }
+
+    let whole = match whole.trim_start_matches('0') {
+        "" => 0,
+        whole => whole
+            .parse::<u64>()
+            .map_err(|_| eyre!("`{value}` doesn't fit in a 64.61 number, which is below 2^64"))?,
+    };
+
+    // the fraction, rounded to the nearest multiple of 2^-61
+    let scale = 10u128.pow(fraction.len() as u32);
+    let fraction = match fraction {
+        "" => 0,
+        fraction => fraction.parse::<u128>().map_err(|_| invalid())?,
+    };
+    let fraction = ((fraction << FRACTION_BITS) + scale / 2) / scale;
+
+    let raw = (u128::from(whole) << FRACTION_BITS) + fraction;
+    let felt = FieldElement::from_dec_str(&raw.to_string())?;
+    Ok(if negative { -felt } else { felt })
+}
+
+fn from_64x61(felt: FieldElement) -> Result<String> {
+    let out_of_range = || eyre!("{felt:#x} is not a 64.61 number, which is below 2^64");
+
+    // the negative numbers are the felts above the maximum signed felt, as in Cairo
+    let (negative, magnitude) = match felt_to_u128(felt) {
+        Some(raw) => (false, raw),
+        None => (true, felt_to_u128(-felt).ok_or_else(out_of_range)?),
+    };
+    if magnitude >> (64 + FRACTION_BITS) != 0 {
+        return Err(out_of_range());
+    }
+
+    let whole = magnitude >> FRACTION_BITS;
+    let fraction = magnitude & ((1 << FRACTION_BITS) - 1);
+    let fraction = (fraction * 10u128.pow(FIXED_64X61_DECIMALS)) >> FRACTION_BITS;
+
+    let sign = if negative && magnitude != 0 { "-" } else { "" };
+    let fraction = format!("{fraction:0>width$}", width = FIXED_64X61_DECIMALS as usize);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        Ok(format!("{sign}{whole}"))
+    } else {
+        Ok(format!("{sign}{whole}.{fraction}"))
+    }
+}
+
+fn felt_to_u128(felt: FieldElement) -> Option<u128> {
+    let bytes = felt.to_bytes_be();
+    match bytes[..16].iter().all(|byte| *byte == 0) {
+        true => Some(u128::from_be_bytes(bytes[16..].try_into().unwrap())),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_64x61() {
+        let one = 1u128 << 61;
+        let felt = |raw: u128| FieldElement::from_dec_str(&raw.to_string()).unwrap();
+
+        assert_eq!(to_64x61("1").unwrap(), felt(one));
+        assert_eq!(to_64x61("1.5").unwrap(), felt(one + one / 2));
+        assert_eq!(to_64x61("-0.25").unwrap(), -felt(one / 4));
+        assert_eq!(from_64x61(felt(one + one / 2)).unwrap(), "1.5");
+        assert_eq!(from_64x61(-felt(one / 4)).unwrap(), "-0.25");
+        assert_eq!(from_64x61(FieldElement::ZERO).unwrap(), "0");
+
+        // the decimals printed read back as the same felt
+        for raw in [felt(1), felt(one / 3), -felt(7 * one + 12345)] {
+            assert_eq!(to_64x61(&from_64x61(raw).unwrap()).unwrap(), raw);
+        }
+
+        assert!(to_64x61("18446744073709551616").is_err());
+        assert!(to_64x61("1.-5").is_err());
+        assert!(from_64x61(felt(u128::MAX)).is_err());
+    }
+
+    #[test]
+    fn wad_and_ray() {
+        assert_eq!(
+            to_fixed("1.5", FixedPoint::Wad).unwrap(),
+            "1500000000000000000"
+        );
+        assert_eq!(
+            from_fixed("1000000000000000000000000000", FixedPoint::Ray).unwrap(),
+            "1"
+        );
+    }
+}
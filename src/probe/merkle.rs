@@ -0,0 +1,126 @@
+use super::utils::poseidon_hash;
+
+use clap::ValueEnum;
+use eyre::{eyre, Result};
+use serde::Serialize;
+use starknet::core::{crypto::pedersen_hash, types::FieldElement};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MerkleHash {
+    Pedersen,
+    Poseidon,
+}
+
+impl MerkleHash {
+    /// Hashes a pair of nodes, smallest first, so that a proof doesn't need to tell on which
+    /// side each sibling is, as in the OpenZeppelin `merkle_proof` verification.
+    fn hash_pair(&self, a: FieldElement, b: FieldElement) -> FieldElement {
+        let (a, b) = if a.to_bytes_be() <= b.to_bytes_be() {
+            (a, b)
+        } else {
+            (b, a)
+        };
+
+        match self {
+            Self::Pedersen => pedersen_hash(&a, &b),
+            Self::Poseidon => poseidon_hash(a, b),
+        }
+    }
+}
+
+/// A Merkle tree over leaves given in order, where the last node of a layer of odd length is
+/// carried up to the next layer as is.
+#[derive(Debug)]
+pub struct MerkleTree {
+    /// The leaves first, up to the layer of the root.
+    layers: Vec<Vec<FieldElement>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: Vec<FieldElement>, hash: MerkleHash) -> Result<Self> {
+        if leaves.is_empty() {
+            return Err(eyre!("a merkle tree needs at least one leaf"));
+        }
+
+        let mut layers = vec![leaves];
+        while let Some(layer) = layers.last().filter(|layer| layer.len() > 1) {
+            let next = layer
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash.hash_pair(*a, *b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+
+        Ok(Self { layers })
+    }
+
+    pub fn root(&self) -> FieldElement {
+        self.layers[self.layers.len() - 1][0]
+    }
+
+    pub fn leaves(&self) -> &[FieldElement] {
+        &self.layers[0]
+    }
+
+    /// The siblings of the leaf at `index` from the bottom of the tree up, skipping the layers
+    /// where its node is carried up without a sibling.
+    pub fn proof(&self, mut index: usize) -> Vec<FieldElement> {
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
+
+/// The root of a Merkle tree along with the inclusion proofs of the requested leaves.
+#[derive(Debug, Serialize)]
+pub struct MerkleOutput {
+    pub root: FieldElement,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub proofs: Vec<MerkleProof>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MerkleProof {
+    pub leaf: FieldElement,
+    pub index: usize,
+    pub proof: Vec<FieldElement>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merkle_proofs_lead_to_root() {
+        for hash in [MerkleHash::Pedersen, MerkleHash::Poseidon] {
+            let leaves = (1u64..=5).map(FieldElement::from).collect::<Vec<_>>();
+            let tree = MerkleTree::new(leaves.clone(), hash).unwrap();
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let node = tree
+                    .proof(index)
+                    .into_iter()
+                    .fold(*leaf, |node, sibling| hash.hash_pair(node, sibling));
+                assert_eq!(node, tree.root());
+            }
+
+            // the fifth leaf is carried up to the last layer, where it meets the rest of the tree
+            assert_eq!(tree.proof(4).len(), 1);
+        }
+
+        let single = MerkleTree::new(vec![FieldElement::ONE], MerkleHash::Pedersen).unwrap();
+        assert_eq!(single.root(), FieldElement::ONE);
+        assert!(single.proof(0).is_empty());
+
+        assert!(MerkleTree::new(vec![], MerkleHash::Pedersen).is_err());
+    }
+}
@@ -1,62 +1,218 @@
+pub mod abi;
+pub mod addressbook;
+pub mod chains;
+pub mod ethereum;
+pub mod fixed;
+pub mod merkle;
+pub mod nonce;
+pub mod secp;
+pub mod selectors;
+pub mod standards;
+pub mod tokens;
+pub mod trace;
 pub mod utils;
+pub mod ws;
 
-use self::utils::fmt::{pretty_block_without_txs, Pretty};
+use self::abi::{
+    byte_array_from_felts, byte_array_to_felts, felt_to_u128, parse_u256, short_string, signature,
+    Abi, AbiChange, DecodedEvent,
+};
+use self::selectors::{SelectorDatabase, SelectorResolver};
+use self::standards::{DetectedStandard, TokenStandards, INTERFACES};
+use self::tokens::{parse_units, TokenMetadata};
+use self::trace::{
+    entry_point_selectors, flatten_calls, folded_stacks, name_entry_points, TraceFormat,
+};
+use self::utils::fmt::{
+    event_csv_row, event_ndjson_line, format_u256_units, pretty_abi_changes,
+    pretty_block_without_txs, pretty_contract_class, pretty_decoded_events, pretty_decoded_params,
+    pretty_events, pretty_fee_estimation, pretty_state_update, pretty_transaction_receipt,
+    pretty_transaction_trace, Pretty, ValueFormat, EVENT_CSV_HEADER,
+};
+use self::utils::{decode_revert_reason, select_fields, send_rpc_request, RpcErrorResponse};
 
 use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use crypto_bigint::U256;
 use eyre::{eyre, Report, Result};
+use futures_util::future::try_join_all;
 use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha3::{Digest, Keccak256};
 use starknet::accounts::Call;
 use starknet::core::utils::get_selector_from_name;
-use starknet::providers::jsonrpc::models::{BlockId, EventFilter, FunctionCall};
+use starknet::providers::jsonrpc::models::{
+    BlockId, BlockTag, Event, EventFilter, FunctionCall, Transaction,
+};
 use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use starknet::{
     core::{
-        crypto::{ecdsa_sign, ecdsa_verify, pedersen_hash, Signature},
+        crypto::{compute_hash_on_elements, ecdsa_sign, ecdsa_verify, pedersen_hash, Signature},
         types::{ContractArtifact, FieldElement, FromStrError},
         utils::{
             cairo_short_string_to_felt, get_contract_address, get_storage_var_address,
             parse_cairo_short_string, starknet_keccak,
         },
     },
-    providers::jsonrpc::models::MaybePendingBlockWithTxs,
+    providers::jsonrpc::models::{MaybePendingBlockWithTxs, MaybePendingTransactionReceipt},
 };
+use starknet_curve::curve_params::{BETA, GENERATOR};
+
+const PREFIX_INVOKE: FieldElement = FieldElement::from_mont([
+    18443034532770911073,
+    18446744073709551615,
+    18446744073709551615,
+    513398556346534256,
+]);
+
+const PREFIX_DECLARE: FieldElement = FieldElement::from_mont([
+    17542456862011667323,
+    18446744073709551615,
+    18446744073709551615,
+    191557713328401194,
+]);
+
+const PREFIX_DEPLOY_ACCOUNT: FieldElement = FieldElement::from_mont([
+    3350261884043292318,
+    18443211694809419988,
+    18446744073709551615,
+    461298303000467581,
+]);
+
+const PREFIX_L1_HANDLER: FieldElement = FieldElement::from_mont([
+    1365666230910873368,
+    18446744073708665300,
+    18446744073709551615,
+    157895833347907735,
+]);
+
+/// The L2 transaction of a message sent from L1, as given by `starknet_getMessagesStatus`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MessageStatus {
+    /// The hash of the `l1_handler` transaction consuming the message.
+    pub transaction_hash: String,
+    pub finality_status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+}
+
+/// Where a transaction stands on its way to finality.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TransactionState {
+    /// `RECEIVED`, `ACCEPTED_ON_L2`, `ACCEPTED_ON_L1` or `REJECTED`.
+    pub status: String,
+    pub reverted: bool,
+    pub block_number: Option<u64>,
+}
+
+impl TransactionState {
+    /// Reads the state from a receipt, or from the status pushed by a subscription which has the
+    /// same fields.
+    pub fn from_json(receipt: &serde_json::Value) -> Self {
+        // older nodes give a single `status`, where `PENDING` means not included in a block yet
+        let status = match receipt["finality_status"]
+            .as_str()
+            .or(receipt["status"].as_str())
+        {
+            Some("PENDING") | None => "RECEIVED",
+            Some(status) => status,
+        };
+
+        Self {
+            status: status.to_string(),
+            reverted: receipt["execution_status"].as_str() == Some("REVERTED"),
+            block_number: receipt["block_number"].as_u64(),
+        }
+    }
+}
 
 pub struct Probe {
     client: JsonRpcClient<HttpTransport>,
+    url: Url,
+    selectors: SelectorResolver,
+    fmt: ValueFormat,
 }
 
 impl Probe {
     pub fn new(url: Url) -> Self {
         Self {
-            client: JsonRpcClient::new(HttpTransport::new(url)),
+            client: JsonRpcClient::new(HttpTransport::new(url.clone())),
+            url,
+            selectors: SelectorResolver::new(None),
+            fmt: ValueFormat::default(),
         }
     }
 
+    /// Renders the values as chosen with the display options.
+    pub fn with_value_format(mut self, fmt: ValueFormat) -> Self {
+        self.fmt = fmt;
+        self
+    }
+
+    /// Uses the registry to name the selectors that are not known locally.
+    pub fn with_selector_registry(mut self, registry: Option<Url>) -> Self {
+        self.selectors = SelectorResolver::new(registry);
+        self
+    }
+
+    /// Simulates the signed transaction on top of the given block and returns the result,
+    /// containing its trace and fee estimation.
+    pub async fn simulate_transaction(
+        &self,
+        transaction: serde_json::Value,
+        block_id: &BlockId,
+        skip_validate: bool,
+        skip_fee_charge: bool,
+    ) -> Result<serde_json::Value> {
+        let mut flags = Vec::new();
+        if skip_validate {
+            flags.push("SKIP_VALIDATE");
+        }
+        if skip_fee_charge {
+            flags.push("SKIP_FEE_CHARGE");
+        }
+
+        let mut res: Vec<serde_json::Value> = send_rpc_request(
+            &self.url,
+            "starknet_simulateTransactions",
+            json!({
+                "block_id": block_id,
+                "transactions": [transaction],
+                "simulation_flags": flags,
+            }),
+        )
+        .await?;
+
+        res.pop()
+            .ok_or_else(|| eyre!("no simulation result returned"))
+    }
+
     pub async fn block(
         &self,
         block_id: BlockId,
         full: bool,
-        field: Option<String>,
+        fields: &[String],
         to_json: bool,
     ) -> Result<String> {
         let block = self.client.get_block_with_txs(&block_id).await?;
 
-        if to_json || field.is_some() {
+        if to_json || !fields.is_empty() {
             let mut json = match block {
                 MaybePendingBlockWithTxs::Block(block) => serde_json::to_value(block)?,
                 MaybePendingBlockWithTxs::PendingBlock(block) => serde_json::to_value(block)?,
             };
 
-            if let Some(field) = field {
-                json = json
-                    .get(&field)
-                    .ok_or_else(|| eyre!("`{field}` is not a valid block field."))?
-                    .to_owned();
+            if !fields.is_empty() {
+                json = select_fields(json, fields, "block")?;
             } else if !full {
                 json.as_object_mut().unwrap().remove("transactions");
             }
@@ -65,9 +221,9 @@ impl Probe {
         } else {
             Ok(format!("\n{}", {
                 if full {
-                    block.prettify()
+                    block.prettify(&self.fmt)
                 } else {
-                    pretty_block_without_txs(&block)
+                    pretty_block_without_txs(&block, &self.fmt)
                 }
             }))
         }
@@ -82,14 +238,14 @@ impl Probe {
         Ok(self.client.block_number().await?)
     }
 
-    pub async fn chain_id(&self) -> Result<String> {
-        Ok(self.client.chain_id().await?.to_string())
+    pub async fn chain_id(&self) -> Result<FieldElement> {
+        Ok(self.client.chain_id().await?)
     }
 
     pub async fn get_transaction_by_hash(
         &self,
         transaction_hash: FieldElement,
-        field: Option<String>,
+        fields: &[String],
         to_json: bool,
     ) -> Result<String> {
         let tx = self
@@ -97,52 +253,246 @@ impl Probe {
             .get_transaction_by_hash(transaction_hash)
             .await?;
 
-        if to_json || field.is_some() {
+        if to_json || !fields.is_empty() {
             let mut value = serde_json::to_value(tx)?;
 
-            if let Some(field) = field {
-                value = value
-                    .get(&field)
-                    .ok_or_else(|| eyre!("`{}` is not a valid transaction field.", field))?
-                    .to_owned();
+            if !fields.is_empty() {
+                value = select_fields(value, fields, "transaction")?;
             }
 
             Ok(serde_json::to_string_pretty(&value)?)
         } else {
-            Ok(format!("\n{}", tx.prettify()))
+            Ok(format!("\n{}", tx.prettify(&self.fmt)))
         }
     }
 
     pub async fn get_transaction_receipt(
         &self,
         transaction_hash: FieldElement,
-        field: Option<String>,
+        fields: &[String],
         to_json: bool,
+        decode: bool,
     ) -> Result<String> {
-        let receipt = self
-            .client
-            .get_transaction_receipt(transaction_hash)
-            .await?;
+        // fetched as is since the revert reason is not part of the receipt model
+        let receipt_json: serde_json::Value = send_rpc_request(
+            &self.url,
+            "starknet_getTransactionReceipt",
+            json!({ "transaction_hash": transaction_hash }),
+        )
+        .await?;
+        let receipt: MaybePendingTransactionReceipt = serde_json::from_value(receipt_json.clone())?;
+        let revert_reason = receipt_json["revert_reason"].as_str();
 
-        if to_json || field.is_some() {
-            let mut json = serde_json::to_value(&receipt)?;
+        let decoded = if decode {
+            let events: Vec<Event> = serde_json::from_value(receipt_json["events"].clone())?;
+
+            // the abi of the contracts as of the block the transaction was included in
+            let block_id = match receipt_json["block_hash"].as_str() {
+                Some(hash) => BlockId::Hash(FieldElement::from_hex_be(hash)?),
+                None => BlockId::Tag(BlockTag::Pending),
+            };
+
+            Some(self.decode_events(&events, &block_id).await?)
+        } else {
+            None
+        };
+
+        if to_json || !fields.is_empty() {
+            let mut json = receipt_json.clone();
+
+            if let (Some(decoded), Some(events)) = (&decoded, json["events"].as_array_mut()) {
+                for (event, decoded) in events.iter_mut().zip(decoded) {
+                    if let Some(decoded) = decoded {
+                        add_decoded_event(event, decoded);
+                    }
+                }
+            }
 
-            if let Some(field) = field {
-                json = json
-                    .get(&field)
-                    .ok_or_else(|| eyre!("`{field}` is not a valid transaction receipt field."))?
-                    .to_owned();
+            if !fields.is_empty() {
+                json = select_fields(json, fields, "transaction receipt")?;
             }
 
             Ok(serde_json::to_string_pretty(&json)?)
         } else {
-            Ok(format!("\n{}", receipt.prettify()))
+            Ok(format!(
+                "\n{}",
+                pretty_transaction_receipt(&receipt, revert_reason, decoded.as_deref(), &self.fmt)
+            ))
+        }
+    }
+
+    /// The state of the transaction, or `None` if the node doesn't know about it yet.
+    pub async fn get_transaction_state(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<Option<TransactionState>> {
+        let receipt: serde_json::Value = match send_rpc_request(
+            &self.url,
+            "starknet_getTransactionReceipt",
+            json!({ "transaction_hash": transaction_hash }),
+        )
+        .await
+        {
+            Ok(receipt) => receipt,
+            // the code of a missing transaction was changed from 25 to 29 in v0.4 of the spec
+            Err(e) if matches!(e.downcast_ref::<RpcErrorResponse>(), Some(e) if e.code == 25 || e.code == 29) => {
+                return Ok(None)
+            }
+            Err(e) => return Err(e),
+        };
+
+        Ok(Some(TransactionState::from_json(&receipt)))
+    }
+
+    /// Decodes the events using the abi of the contracts that emitted them. Events that are not
+    /// described by the abi are left undecoded.
+    async fn decode_events(
+        &self,
+        events: &[Event],
+        block_id: &BlockId,
+    ) -> Result<Vec<Option<DecodedEvent>>> {
+        let mut abis: HashMap<FieldElement, Option<Abi>> = HashMap::new();
+        let mut decoded = Vec::with_capacity(events.len());
+
+        for event in events {
+            if let Entry::Vacant(entry) = abis.entry(event.from_address) {
+                entry.insert(self.get_class_abi(event.from_address, block_id).await.ok());
+            }
+
+            decoded.push(match &abis[&event.from_address] {
+                Some(abi) => abi.decode_event(&event.keys, &event.data).ok().flatten(),
+                None => None,
+            });
+        }
+
+        // the events the abi doesn't describe are at least named after their first key
+        let names = self
+            .selectors
+            .resolve_all(
+                events
+                    .iter()
+                    .zip(&decoded)
+                    .filter(|(_, decoded)| decoded.is_none())
+                    .filter_map(|(event, _)| event.keys.first().copied()),
+            )
+            .await;
+
+        for (event, decoded) in events.iter().zip(decoded.iter_mut()) {
+            if decoded.is_some() {
+                continue;
+            }
+
+            if let Some(name) = event.keys.first().and_then(|key| names.get(key)) {
+                *decoded = Some(DecodedEvent::raw(name, &event.keys, &event.data));
+            }
+        }
+
+        Ok(decoded)
+    }
+
+    pub async fn trace_transaction(
+        &self,
+        transaction_hash: FieldElement,
+        format: TraceFormat,
+        to_json: bool,
+    ) -> Result<String> {
+        let mut trace: serde_json::Value = send_rpc_request(
+            &self.url,
+            "starknet_traceTransaction",
+            json!({ "transaction_hash": transaction_hash }),
+        )
+        .await?;
+
+        if to_json {
+            return Ok(serde_json::to_string_pretty(&trace)?);
+        }
+
+        match format {
+            TraceFormat::Tree => {
+                let names = self
+                    .selectors
+                    .resolve_all(entry_point_selectors(&trace))
+                    .await;
+                name_entry_points(&mut trace, &names);
+                Ok(format!("\n{}", pretty_transaction_trace(&trace, &self.fmt)))
+            }
+            TraceFormat::Folded => Ok(folded_stacks(&flatten_calls(&trace), None)),
+        }
+    }
+
+    /// Returns the traces of all the transactions in the block. If `contract` is specified, only
+    /// the frames touching the contract are kept.
+    pub async fn trace_block_transactions(
+        &self,
+        block_id: &BlockId,
+        contract: Option<FieldElement>,
+        format: TraceFormat,
+        to_json: bool,
+    ) -> Result<String> {
+        let mut traces: Vec<serde_json::Value> = send_rpc_request(
+            &self.url,
+            "starknet_traceBlockTransactions",
+            json!({ "block_id": block_id }),
+        )
+        .await?;
+
+        if let Some(contract) = contract {
+            traces.retain_mut(|trace| retain_frames_touching(&mut trace["trace_root"], contract));
+        }
+
+        if to_json {
+            return Ok(serde_json::to_string_pretty(&traces)?);
+        }
+
+        if format == TraceFormat::Tree {
+            let selectors = traces
+                .iter()
+                .flat_map(|trace| entry_point_selectors(&trace["trace_root"]));
+            let names = self.selectors.resolve_all(selectors).await;
+
+            for trace in &mut traces {
+                name_entry_points(&mut trace["trace_root"], &names);
+            }
         }
+
+        let traces = traces.iter().map(|trace| {
+            let hash = trace["transaction_hash"].as_str().unwrap_or_default();
+            let trace = &trace["trace_root"];
+
+            match format {
+                TraceFormat::Tree => {
+                    format!(
+                        "\nTRANSACTION {hash}\n{}",
+                        pretty_transaction_trace(trace, &self.fmt)
+                    )
+                }
+                // the stacks of each transaction are rooted at its hash
+                TraceFormat::Folded => folded_stacks(&flatten_calls(trace), Some(hash)),
+            }
+        });
+
+        Ok(traces.collect::<Vec<_>>().join("\n"))
     }
 
-    pub async fn pending_transactions(&self) -> Result<String> {
-        let res = self.client.pending_transactions().await?;
-        Ok(serde_json::to_string_pretty(&res)?)
+    /// The status of the `l1_handler` transactions of the messages sent to L2 by an L1
+    /// transaction, whose hash may not fit in a felt.
+    pub async fn get_messages_status(&self, l1_tx_hash: &str) -> Result<Vec<MessageStatus>> {
+        let digits = l1_tx_hash.trim_start_matches("0x");
+        if digits.is_empty() || digits.len() > 64 || !digits.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return Err(eyre!("invalid L1 transaction hash {l1_tx_hash}"));
+        }
+
+        send_rpc_request(
+            &self.url,
+            "starknet_getMessagesStatus",
+            json!({ "transaction_hash": format!("0x{digits}") }),
+        )
+        .await
+    }
+
+    pub async fn pending_transactions(&self) -> Result<Vec<Transaction>> {
+        Ok(self.client.pending_transactions().await?)
     }
 
     pub async fn get_nonce(
@@ -151,8 +501,7 @@ impl Probe {
         block_id: &BlockId,
     ) -> Result<String> {
         let nonce = self.client.get_nonce(block_id, contract_address).await?;
-        let nonce = format!("{:#x}", nonce.to_string().parse::<u128>()?);
-        Ok(nonce)
+        Ok(self.fmt.felt(&nonce))
     }
 
     pub async fn get_storage_at(
@@ -166,133 +515,689 @@ impl Probe {
             .get_storage_at(contract_address, key, block_id)
             .await?;
 
-        Ok(format!("{res:#x}"))
+        Ok(self.fmt.felt(&res))
     }
 
     pub async fn call(
         &self,
         contract_address: &FieldElement,
         function_name: &str,
-        calldata: &Vec<FieldElement>,
+        input: &[String],
         block_id: &BlockId,
         abi: &Option<PathBuf>,
     ) -> Result<String> {
-        if let Some(abi) = abi {
-            let expected_input_count = utils::count_function_inputs(abi, function_name)?;
-            if expected_input_count != calldata.len() as u64 {
-                return Err(eyre!(
-                    "expected {} input(s) but got {}",
-                    expected_input_count,
-                    calldata.len()
-                ));
-            }
-        }
+        let abi = abi.as_ref().map(Abi::from_file).transpose()?;
+
+        let calldata = match &abi {
+            Some(abi) => abi.encode_function_inputs(function_name, input)?,
+            None => input
+                .iter()
+                .flat_map(|input| input.split(','))
+                .map(FieldElement::from_str)
+                .collect::<Result<Vec<_>, _>>()?,
+        };
 
         let res = self
             .client
             .call(
                 FunctionCall {
-                    calldata: calldata.to_owned(),
+                    calldata,
                     contract_address: contract_address.to_owned(),
                     entry_point_selector: get_selector_from_name(function_name)?,
                 },
                 block_id,
             )
-            .await?;
+            .await
+            .map_err(|e| eyre!(decode_revert_reason(&e.to_string())))?;
+
+        if let Some(abi) = abi {
+            let outputs = abi.decode_function_outputs(function_name, &res)?;
+            return Ok(pretty_decoded_params(&outputs));
+        }
 
         let res = res
-            .into_iter()
-            .map(|value| format!("{value:#x}"))
+            .iter()
+            .map(|felt| self.fmt.felt(felt))
             .collect::<Vec<String>>();
 
         Ok(res.join(" "))
     }
 
-    pub async fn get_state_update(&self, block_id: &BlockId) -> Result<String> {
+    /// Estimates the fee to attach on L1 to a message sent to the `l1_handler` of a contract.
+    pub async fn estimate_message_fee(
+        &self,
+        from_address: FieldElement,
+        to_address: FieldElement,
+        function_name: &str,
+        payload: &[FieldElement],
+        block_id: &BlockId,
+        to_json: bool,
+    ) -> Result<String> {
+        let from_address = from_address.to_bytes_be();
+        if from_address[..12].iter().any(|b| *b != 0) {
+            return Err(eyre!("L1 address must be at most 20 bytes long"));
+        }
+
+        let fee: serde_json::Value = send_rpc_request(
+            &self.url,
+            "starknet_estimateMessageFee",
+            json!({
+                "message": {
+                    "from_address": format!("0x{}", hex::encode(&from_address[12..])),
+                    "to_address": to_address,
+                    "entry_point_selector": get_selector_from_name(function_name)?,
+                    "payload": payload,
+                },
+                "block_id": block_id,
+            }),
+        )
+        .await?;
+
+        if to_json {
+            Ok(serde_json::to_string_pretty(&fee)?)
+        } else {
+            Ok(pretty_fee_estimation(&fee))
+        }
+    }
+
+    /// Decodes the calldata of a function into its typed parameters, using either the given abi
+    /// file or the abi of the class of the contract.
+    pub async fn decode_calldata(
+        &self,
+        function_name: &str,
+        calldata: &[FieldElement],
+        abi: &Option<PathBuf>,
+        contract_address: Option<FieldElement>,
+        block_id: &BlockId,
+    ) -> Result<String> {
+        let abi = match (abi, contract_address) {
+            (Some(path), _) => Abi::from_file(path)?,
+            (None, Some(address)) => self.get_class_abi(address, block_id).await?,
+            (None, None) => return Err(eyre!("either an abi or a contract address is required")),
+        };
+
+        let mut params = abi.decode_function_inputs(function_name, calldata)?;
+
+        let selectors = params
+            .iter()
+            .flat_map(|param| param.value.selectors(&param.name))
+            .collect::<Vec<_>>();
+        let names = self.selectors.resolve_all(selectors).await;
+        for param in &mut params {
+            param.value.name_selectors(&param.name, &names);
+        }
+
+        Ok(pretty_decoded_params(&params))
+    }
+
+    /// Returns the ABI of the class of a contract, or only the entry of `function` if specified.
+    pub async fn get_abi(
+        &self,
+        contract_address: FieldElement,
+        function: Option<&str>,
+        block_id: &BlockId,
+        to_json: bool,
+    ) -> Result<String> {
+        let abi = self.get_class_abi(contract_address, block_id).await?;
+
+        match (function, to_json) {
+            (Some(function), true) => Ok(serde_json::to_string_pretty(abi.function(function)?)?),
+            (Some(function), false) => Ok(signature(abi.function(function)?)),
+            (None, true) => Ok(serde_json::to_string_pretty(abi.entries())?),
+            (None, false) => Ok(abi
+                .entries()
+                .iter()
+                .map(signature)
+                .collect::<Vec<_>>()
+                .join("\n")),
+        }
+    }
+
+    /// Compares the ABIs of two classes, each given either as a class hash or as the path of a
+    /// class artifact or ABI file.
+    pub async fn abi_diff(
+        &self,
+        class_a: &str,
+        class_b: &str,
+        block_id: &BlockId,
+        to_json: bool,
+    ) -> Result<String> {
+        let before = self.load_abi(class_a, block_id).await?;
+        let after = self.load_abi(class_b, block_id).await?;
+        let changes = before.diff(&after);
+
+        if !to_json {
+            return Ok(pretty_abi_changes(&changes));
+        }
+
+        let changes = changes
+            .iter()
+            .map(|change| match change {
+                AbiChange::Added(after) => json!({ "change": "added", "after": after }),
+                AbiChange::Removed(before) => json!({ "change": "removed", "before": before }),
+                AbiChange::Changed {
+                    name,
+                    before,
+                    after,
+                } => json!({ "change": "changed", "name": name, "before": before, "after": after }),
+            })
+            .collect::<Vec<_>>();
+
+        Ok(serde_json::to_string_pretty(&changes)?)
+    }
+
+    async fn load_abi(&self, class: &str, block_id: &BlockId) -> Result<Abi> {
+        if Path::new(class).is_file() {
+            return Abi::from_file(class);
+        }
+
+        let class_hash = FieldElement::from_hex_be(class)
+            .map_err(|_| eyre!("`{class}` is neither a class hash nor a file"))?;
+        let class: serde_json::Value = send_rpc_request(
+            &self.url,
+            "starknet_getClass",
+            json!({ "block_id": block_id, "class_hash": class_hash }),
+        )
+        .await?;
+
+        Abi::parse(&class)
+    }
+
+    /// Fetches the ABI of the class of a contract. The names it declares are recorded in the
+    /// selector database along the way.
+    async fn get_class_abi(
+        &self,
+        contract_address: FieldElement,
+        block_id: &BlockId,
+    ) -> Result<Abi> {
+        let class: serde_json::Value = send_rpc_request(
+            &self.url,
+            "starknet_getClassAt",
+            json!({ "block_id": block_id, "contract_address": contract_address }),
+        )
+        .await?;
+
+        let abi = Abi::parse(&class["abi"])?;
+        // the database is only there to help reading selectors, it must not fail the command
+        let _ = SelectorDatabase::default().add(abi.selector_names());
+
+        Ok(abi)
+    }
+
+    pub async fn get_state_update(&self, block_id: &BlockId, to_json: bool) -> Result<String> {
         let res = self.client.get_state_update(block_id).await?;
         let res = serde_json::to_value(res)?;
-        Ok(serde_json::to_string_pretty(&res)?)
+
+        if to_json {
+            Ok(serde_json::to_string_pretty(&res)?)
+        } else {
+            Ok(pretty_state_update(&res))
+        }
     }
 
     pub async fn get_class_code(
         &self,
         class_hash: FieldElement,
         block_id: &BlockId,
+        to_json: bool,
     ) -> Result<String> {
         let res = self.client.get_class(block_id, class_hash).await?;
         let res = serde_json::to_value(res)?;
-        Ok(serde_json::to_string_pretty(&res)?)
+
+        if to_json {
+            Ok(serde_json::to_string_pretty(&res)?)
+        } else {
+            Ok(pretty_contract_class(&res))
+        }
     }
 
     pub async fn get_contract_code(
         &self,
         contract_address: FieldElement,
         block_id: &BlockId,
+        to_json: bool,
     ) -> Result<String> {
         let res = self.client.get_class_at(block_id, contract_address).await?;
         let res = serde_json::to_value(res)?;
-        Ok(serde_json::to_string_pretty(&res)?)
+
+        if to_json {
+            Ok(serde_json::to_string_pretty(&res)?)
+        } else {
+            Ok(pretty_contract_class(&res))
+        }
     }
 
     pub async fn get_contract_class(
         &self,
-        contract_address: FieldElement,
+        contract_address: FieldElement,
+        block_id: &BlockId,
+    ) -> Result<String> {
+        let res = self
+            .client
+            .get_class_hash_at(block_id, contract_address)
+            .await?;
+        Ok(format!("{res:#x}"))
+    }
+
+    pub async fn get_events(
+        &self,
+        filter: EventFilter,
+        chunk_size: u64,
+        continuation_token: Option<String>,
+        to_json: bool,
+    ) -> Result<String> {
+        let res = self
+            .client
+            .get_events(filter, continuation_token, chunk_size)
+            .await?;
+        let value = serde_json::to_value(res)?;
+
+        if to_json {
+            Ok(serde_json::to_string_pretty(&value)?)
+        } else {
+            Ok(pretty_events(&value, &self.fmt))
+        }
+    }
+
+    /// The current ABI of a contract along with the keys that one of its events, given by name,
+    /// starts with.
+    pub async fn resolve_event(
+        &self,
+        contract_address: FieldElement,
+        name: &str,
+    ) -> Result<(Abi, Vec<FieldElement>)> {
+        let abi = self
+            .get_class_abi(contract_address, &BlockId::Tag(BlockTag::Pending))
+            .await?;
+        let keys = abi.event_keys(name)?;
+        Ok((abi, keys))
+    }
+
+    /// Returns the events matching the filter whose keys start with `keys`, decoded with the ABI
+    /// of the contract emitting them. The node only filters the events on their first key, so the
+    /// remaining keys of nested events are matched here and a page may hold fewer events than
+    /// the chunk size.
+    pub async fn get_decoded_events(
+        &self,
+        filter: EventFilter,
+        abi: &Abi,
+        keys: &[FieldElement],
+        chunk_size: u64,
+        continuation_token: Option<String>,
+        to_json: bool,
+    ) -> Result<String> {
+        let page = self
+            .client
+            .get_events(filter, continuation_token, chunk_size)
+            .await?;
+
+        let events = page
+            .events
+            .iter()
+            .filter(|event| event.keys.starts_with(keys))
+            .map(|event| {
+                let decoded = abi.decode_event(&event.keys, &event.data).ok().flatten();
+                Ok((serde_json::to_value(event)?, decoded))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if !to_json {
+            return Ok(pretty_decoded_events(
+                &events,
+                page.continuation_token.as_deref(),
+                &self.fmt,
+            ));
+        }
+
+        let events = events
+            .into_iter()
+            .map(|(mut event, decoded)| {
+                if let Some(decoded) = decoded {
+                    add_decoded_event(&mut event, &decoded);
+                }
+                event
+            })
+            .collect::<Vec<_>>();
+
+        Ok(serde_json::to_string_pretty(&json!({
+            "events": events,
+            "continuation_token": page.continuation_token,
+        }))?)
+    }
+
+    /// Writes the events matching the filter one per line, as either CSV or NDJSON, following the
+    /// continuation tokens so that the rows of every page are written as soon as it arrives. Only
+    /// the events whose keys start with `keys` are written.
+    pub async fn stream_events(
+        &self,
+        filter: EventFilter,
+        keys: &[FieldElement],
+        chunk_size: u64,
+        mut continuation_token: Option<String>,
+        csv: bool,
+        out: &mut dyn Write,
+    ) -> Result<()> {
+        if csv {
+            writeln!(out, "{EVENT_CSV_HEADER}")?;
+        }
+
+        loop {
+            let page = self
+                .client
+                .get_events(filter.clone(), continuation_token, chunk_size)
+                .await?;
+
+            for event in page.events.iter().filter(|e| e.keys.starts_with(keys)) {
+                let event = serde_json::to_value(event)?;
+                let row = if csv {
+                    event_csv_row(&event)
+                } else {
+                    event_ndjson_line(&event)
+                };
+                writeln!(out, "{row}")?;
+            }
+            out.flush()?;
+
+            match page.continuation_token {
+                Some(token) => continuation_token = Some(token),
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// The balance of the account in an ERC20 token, as the low and high parts of a u256.
+    pub async fn get_erc20_balance(
+        &self,
+        token: FieldElement,
+        account: FieldElement,
+        block_id: &BlockId,
+    ) -> Result<(u128, u128)> {
+        self.call_u256_view(token, "balanceOf", vec![account], block_id)
+            .await
+    }
+
+    /// The balances of several accounts in an ERC20 token, fetched concurrently and returned in
+    /// the order of the accounts.
+    pub async fn get_erc20_balances(
+        &self,
+        token: FieldElement,
+        accounts: &[FieldElement],
+        block_id: &BlockId,
+    ) -> Result<Vec<(u128, u128)>> {
+        try_join_all(
+            accounts
+                .iter()
+                .map(|account| self.get_erc20_balance(token, *account, block_id)),
+        )
+        .await
+    }
+
+    /// The amount of an ERC20 token the spender is allowed to transfer from the owner, as the low
+    /// and high parts of a u256.
+    pub async fn get_erc20_allowance(
+        &self,
+        token: FieldElement,
+        owner: FieldElement,
+        spender: FieldElement,
+        block_id: &BlockId,
+    ) -> Result<(u128, u128)> {
+        self.call_u256_view(token, "allowance", vec![owner, spender], block_id)
+            .await
+    }
+
+    /// The decimals and the symbol of an ERC20 token, read from its contract.
+    pub async fn get_erc20_units(
+        &self,
+        token: FieldElement,
+        block_id: &BlockId,
+    ) -> Result<(u32, String)> {
+        let (decimals, symbol) = tokio::try_join!(
+            self.call_view(token, "decimals", vec![], block_id),
+            self.call_view(token, "symbol", vec![], block_id),
+        )?;
+
+        Ok((decode_decimals(token, &decimals)?, decode_string(&symbol)?))
+    }
+
+    /// The metadata of an ERC20 token, whose views are called in parallel.
+    pub async fn get_erc20_metadata(
+        &self,
+        token: FieldElement,
+        block_id: &BlockId,
+    ) -> Result<TokenMetadata> {
+        let (name, symbol, decimals, total_supply) = tokio::try_join!(
+            self.call_view(token, "name", vec![], block_id),
+            self.call_view(token, "symbol", vec![], block_id),
+            self.call_view(token, "decimals", vec![], block_id),
+            self.call_view(token, "totalSupply", vec![], block_id),
+        )?;
+
+        let total_supply = match total_supply[..] {
+            [low, high] => (felt_to_u128(low)?, felt_to_u128(high)?),
+            _ => {
+                return Err(eyre!(
+                    "{token:#x} returned {} felts for totalSupply, expected a u256",
+                    total_supply.len()
+                ))
+            }
+        };
+
+        Ok(TokenMetadata {
+            address: token,
+            name: decode_string(&name)?,
+            symbol: decode_string(&symbol)?,
+            decimals: decode_decimals(token, &decimals)?,
+            total_supply,
+        })
+    }
+
+    /// The standards the contract implements, recognized from the SRC-5 interfaces it claims to
+    /// support, or else from the functions declared in its ABI.
+    pub async fn get_token_standards(
+        &self,
+        contract: FieldElement,
+        block_id: &BlockId,
+    ) -> Result<TokenStandards> {
+        // the standards are still recognized from SRC-5 if the ABI can't be read
+        let abi = self.get_class_abi(contract, block_id).await.ok();
+
+        let mut standards = Vec::new();
+        for interface in &INTERFACES {
+            let mut source = None;
+
+            for id in interface.ids {
+                let id = FieldElement::from_hex_be(id)?;
+                if self
+                    .supports_interface(contract, id, block_id)
+                    .await
+                    .unwrap_or(false)
+                {
+                    source = Some("SRC-5");
+                    break;
+                }
+            }
+
+            if source.is_none()
+                && abi
+                    .as_ref()
+                    .is_some_and(|abi| interface.is_declared_in(abi))
+            {
+                source = Some("ABI");
+            }
+
+            if let Some(source) = source {
+                standards.push(DetectedStandard {
+                    name: interface.name,
+                    source,
+                });
+            }
+        }
+
+        Ok(TokenStandards {
+            address: contract,
+            standards,
+        })
+    }
+
+    /// Whether the contract claims to support the interface, through the snake case
+    /// `supports_interface` of SRC-5 or the camel case `supportsInterface` of the Cairo 0
+    /// contracts.
+    async fn supports_interface(
+        &self,
+        contract: FieldElement,
+        interface_id: FieldElement,
+        block_id: &BlockId,
+    ) -> Result<bool> {
+        let res = match self
+            .call_view(contract, "supports_interface", vec![interface_id], block_id)
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => {
+                self.call_view(contract, "supportsInterface", vec![interface_id], block_id)
+                    .await?
+            }
+        };
+        Ok(res.first() == Some(&FieldElement::ONE))
+    }
+
+    /// Calls a view of an ERC20 token returning a u256.
+    async fn call_u256_view(
+        &self,
+        token: FieldElement,
+        function: &str,
+        calldata: Vec<FieldElement>,
+        block_id: &BlockId,
+    ) -> Result<(u128, u128)> {
+        let res = self.call_view(token, function, calldata, block_id).await?;
+        match res[..] {
+            [low, high] => Ok((felt_to_u128(low)?, felt_to_u128(high)?)),
+            _ => Err(eyre!(
+                "{token:#x} returned {} felts for {function}, expected a u256",
+                res.len()
+            )),
+        }
+    }
+
+    /// The owner of an ERC721 token.
+    pub async fn get_erc721_owner(
+        &self,
+        collection: FieldElement,
+        (low, high): (u128, u128),
+        block_id: &BlockId,
+    ) -> Result<FieldElement> {
+        let res = self
+            .call_view(collection, "ownerOf", u256_felts(low, high)?, block_id)
+            .await?;
+        res.first()
+            .copied()
+            .ok_or_else(|| eyre!("{collection:#x} returned no owner"))
+    }
+
+    /// The URI of the metadata of an ERC721 token.
+    pub async fn get_erc721_token_uri(
+        &self,
+        collection: FieldElement,
+        (low, high): (u128, u128),
+        block_id: &BlockId,
+    ) -> Result<String> {
+        let res = self
+            .call_view(collection, "tokenURI", u256_felts(low, high)?, block_id)
+            .await?;
+        decode_string(&res)
+    }
+
+    /// The number of ERC721 tokens of the collection owned by the account.
+    pub async fn get_erc721_balance(
+        &self,
+        collection: FieldElement,
+        owner: FieldElement,
+        block_id: &BlockId,
+    ) -> Result<(u128, u128)> {
+        self.call_u256_view(collection, "balanceOf", vec![owner], block_id)
+            .await
+    }
+
+    /// The balance of the account in an ERC1155 token.
+    pub async fn get_erc1155_balance(
+        &self,
+        contract: FieldElement,
+        account: FieldElement,
+        (low, high): (u128, u128),
         block_id: &BlockId,
-    ) -> Result<String> {
-        let res = self
-            .client
-            .get_class_hash_at(block_id, contract_address)
-            .await?;
-        Ok(format!("{res:#x}"))
+    ) -> Result<(u128, u128)> {
+        let mut calldata = vec![account];
+        calldata.extend(u256_felts(low, high)?);
+        self.call_u256_view(contract, "balanceOf", calldata, block_id)
+            .await
     }
 
-    pub async fn get_events(
+    /// The balances of several accounts in several ERC1155 tokens, in a single `balanceOfBatch`
+    /// call. The balances are returned in the order of the queries.
+    pub async fn get_erc1155_balances(
         &self,
-        filter: EventFilter,
-        chunk_size: u64,
-        continuation_token: Option<String>,
-    ) -> Result<String> {
+        contract: FieldElement,
+        queries: &[(FieldElement, (u128, u128))],
+        block_id: &BlockId,
+    ) -> Result<Vec<(u128, u128)>> {
         let res = self
-            .client
-            .get_events(filter, continuation_token, chunk_size)
+            .call_view(
+                contract,
+                "balanceOfBatch",
+                balance_of_batch_calldata(queries)?,
+                block_id,
+            )
             .await?;
-        let value = serde_json::to_value(res)?;
-        Ok(serde_json::to_string_pretty(&value)?)
+
+        let balances = decode_u256_array(&res)?;
+        if balances.len() != queries.len() {
+            return Err(eyre!(
+                "{contract:#x} returned {} balances for {} queries",
+                balances.len(),
+                queries.len()
+            ));
+        }
+        Ok(balances)
     }
 
-    pub async fn get_eth_balance(
+    async fn call_view(
         &self,
-        account: FieldElement,
-        block_id: BlockId,
-    ) -> Result<String> {
-        // value is a Uint256(low,high)
+        contract_address: FieldElement,
+        function: &str,
+        calldata: Vec<FieldElement>,
+        block_id: &BlockId,
+    ) -> Result<Vec<FieldElement>> {
         let res = self
             .client
             .call(
                 &FunctionCall {
-                    calldata: vec![account],
-                    // ETH contract address on mainnet, testnet, testnet2
-                    contract_address: FieldElement::from_mont([
-                        4380532846569209554u64,
-                        17839402928228694863u64,
-                        17240401758547432026u64,
-                        418961398025637529u64,
-                    ]),
-                    // keccak hash of the string 'balanceOf'
-                    entry_point_selector: FieldElement::from_mont([
-                        8914400797191611589u64,
-                        3817639149632004388u64,
-                        9799122768618501063u64,
-                        186492163330788704u64,
-                    ]),
+                    calldata,
+                    contract_address,
+                    entry_point_selector: get_selector_from_name(function)?,
                 },
-                &block_id,
+                block_id,
             )
             .await?;
-        Ok(format!("{:#x}{:x}", res[1], res[0]))
+        Ok(res)
     }
 }
 
+/// The representations of a felt, for debugging.
+#[derive(Debug, Serialize)]
+pub struct FeltInspection {
+    pub hex: String,
+    pub decimal: String,
+    /// The felt read as a signed integer, as in Cairo.
+    pub signed: String,
+    pub bit_length: usize,
+    /// The big-endian bytes of the felt, without the leading zeros.
+    pub bytes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short_string: Option<String>,
+    /// Whether the felt is the x coordinate of a point of the Stark curve.
+    pub curve_x_coordinate: bool,
+}
+
 pub struct SimpleProbe;
 
 impl SimpleProbe {
@@ -304,6 +1209,60 @@ impl SimpleProbe {
         hex.to_string()
     }
 
+    pub fn selector(name: &str) -> Result<String> {
+        Ok(format!("{:#x}", get_selector_from_name(name)?))
+    }
+
+    /// Resolves a selector to the name it was computed from, after adding the names declared in
+    /// the `abis` to the selector database.
+    pub fn selector_lookup(selector: FieldElement, abis: &[PathBuf]) -> Result<String> {
+        let database = SelectorDatabase::default();
+        for abi in abis {
+            database.add(Abi::from_file(abi)?.selector_names())?;
+        }
+
+        database
+            .lookup(selector)?
+            .ok_or_else(|| eyre!("no known name for selector {selector:#x}"))
+    }
+
+    /// Computes the keys that an event starts with. Without an abi, each segment of the `::`
+    /// separated path is a nested variant that adds a key, unless it is listed in `flat`.
+    pub fn event_selector(path: &str, flat: &[String], abi: &Option<PathBuf>) -> Result<String> {
+        let keys = match abi {
+            Some(abi) => Abi::from_file(abi)?.event_keys(path)?,
+            None => path
+                .split("::")
+                .filter(|segment| !flat.iter().any(|flat| flat == segment))
+                .map(get_selector_from_name)
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+
+        Ok(keys
+            .iter()
+            .map(|key| format!("{key:#x}"))
+            .collect::<Vec<_>>()
+            .join(","))
+    }
+
+    /// Encodes the inputs of a function, read from a JSON file of the inputs keyed by their name.
+    /// The calldata is rendered as a space separated list followed by a JSON array.
+    pub fn encode(abi: &Path, function: &str, args_file: &Path) -> Result<String> {
+        let args: serde_json::Value = serde_json::from_str(&fs::read_to_string(args_file)?)?;
+        let calldata = Abi::from_file(abi)?.encode_function_args(function, &args)?;
+
+        let calldata = calldata
+            .iter()
+            .map(|felt| format!("{felt:#x}"))
+            .collect::<Vec<_>>();
+
+        Ok(format!(
+            "{}\n{}",
+            calldata.join(" "),
+            serde_json::to_string(&calldata)?
+        ))
+    }
+
     pub fn keccak(data: &str) -> Result<String> {
         let hash = match data.as_bytes() {
             // 0x prefix => read as hex data
@@ -315,6 +1274,47 @@ impl SimpleProbe {
         Ok(format!("{hash:#x}"))
     }
 
+    /// The full keccak256 of the data as computed on Ethereum, of its bytes if it's prefixed
+    /// with `0x` or else of its text.
+    pub fn keccak256(data: &str) -> Result<String> {
+        let hash = match data.as_bytes() {
+            [b'0', b'x', rest @ ..] => Keccak256::digest(hex::decode(rest)?),
+            _ => Keccak256::digest(data.as_bytes()),
+        };
+
+        Ok(format!("0x{}", hex::encode(hash)))
+    }
+
+    /// Computes the hash the StarkNet core contract gives a message sent from L1 to L2, the
+    /// keccak256 of its fields packed as 32 bytes words.
+    pub fn compute_l1_to_l2_message_hash(
+        from_address: FieldElement,
+        to_address: FieldElement,
+        selector: FieldElement,
+        payload: &[FieldElement],
+        nonce: FieldElement,
+    ) -> String {
+        let header = [
+            from_address,
+            to_address,
+            nonce,
+            selector,
+            FieldElement::from(payload.len()),
+        ];
+        message_hash(&header, payload)
+    }
+
+    /// Computes the hash the StarkNet core contract gives a message sent from L2 to L1, which
+    /// is consumed on L1 with it.
+    pub fn compute_l2_to_l1_message_hash(
+        from_address: FieldElement,
+        to_address: FieldElement,
+        payload: &[FieldElement],
+    ) -> String {
+        let header = [from_address, to_address, FieldElement::from(payload.len())];
+        message_hash(&header, payload)
+    }
+
     pub fn pedersen(x: &str, y: &str) -> Result<String> {
         let x = utils::parse_hex_or_str_as_felt(x)?;
         let y = utils::parse_hex_or_str_as_felt(y)?;
@@ -323,6 +1323,20 @@ impl SimpleProbe {
         Ok(format!("{hash:#x}"))
     }
 
+    pub fn poseidon(x: &FieldElement, y: &FieldElement) -> String {
+        format!("{:#x}", utils::poseidon_hash(*x, *y))
+    }
+
+    pub fn poseidon_many(elements: &[FieldElement]) -> String {
+        format!("{:#x}", utils::poseidon_hash_many(elements))
+    }
+
+    /// The Pedersen hash chain of the elements followed by their number, as used by the legacy
+    /// transaction hashes.
+    pub fn hash_on_elements(elements: &[FieldElement]) -> String {
+        format!("{:#x}", compute_hash_on_elements(elements))
+    }
+
     pub fn max_felt() -> String {
         FieldElement::MAX.to_string()
     }
@@ -335,6 +1349,88 @@ impl SimpleProbe {
         utils::SIGNED_FELT_MIN
     }
 
+    /// The felt of an Ethereum address, as passed in the calldata of the functions sending a
+    /// message or tokens to L1.
+    pub fn eth_to_felt(address: &str) -> Result<String> {
+        let bytes = ethereum::parse_address(address)?;
+        Ok(format!("{:#x}", FieldElement::from_byte_slice_be(&bytes)?))
+    }
+
+    /// The EIP-55 checksummed Ethereum address held by a felt.
+    pub fn felt_to_eth(felt: &FieldElement) -> Result<String> {
+        let bytes = felt.to_bytes_be();
+        let (high, address) = bytes.split_at(12);
+        if high.iter().any(|byte| *byte != 0) {
+            return Err(eyre!(
+                "{felt:#x} doesn't fit in the 20 bytes of an Ethereum address"
+            ));
+        }
+
+        Ok(ethereum::checksum_address(address.try_into()?))
+    }
+
+    pub fn inspect_felt(felt: &FieldElement) -> Result<FeltInspection> {
+        let bytes = felt.to_bytes_be();
+        let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+        let bit_length = felt
+            .to_bits_le()
+            .iter()
+            .rposition(|bit| *bit)
+            .map_or(0, |i| i + 1);
+
+        // x is a coordinate of the curve y^2 = x^3 + x + b if the right-hand side has a root
+        let beta = FieldElement::from_bytes_be(&BETA.to_bytes_be())?;
+        let curve_x_coordinate = (*felt * *felt * *felt + *felt + beta).sqrt().is_some();
+
+        Ok(FeltInspection {
+            hex: format!("{felt:#x}"),
+            decimal: felt.to_string(),
+            signed: Self::to_signed(felt)?,
+            bit_length,
+            bytes: bytes[start..].iter().map(|b| format!("{b:#04x}")).collect(),
+            short_string: short_string(*felt),
+            curve_x_coordinate,
+        })
+    }
+
+    /// Felts drawn at random, one per line.
+    pub fn random_felts(count: usize, fmt: &ValueFormat) -> String {
+        (0..count)
+            .map(|_| fmt.felt(&utils::random_felt()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Reads a felt as a signed integer, the felts above the maximum signed felt being the
+    /// negative ones as in Cairo.
+    pub fn to_signed(felt: &FieldElement) -> Result<String> {
+        let max = FieldElement::from_dec_str(utils::SIGNED_FELT_MAX)?;
+
+        // the big-endian bytes of felts compare in the same order as their values
+        if felt.to_bytes_be() > max.to_bytes_be() {
+            Ok(format!("-{}", -*felt))
+        } else {
+            Ok(felt.to_string())
+        }
+    }
+
+    /// The felt representing a signed decimal integer, between the minimum and the maximum
+    /// signed felts.
+    pub fn from_signed(value: &str) -> Result<String> {
+        let (negative, magnitude) = match value.strip_prefix('-') {
+            Some(magnitude) => (true, magnitude),
+            None => (false, value),
+        };
+
+        let max = FieldElement::from_dec_str(utils::SIGNED_FELT_MAX)?;
+        let felt = FieldElement::from_dec_str(magnitude)
+            .ok()
+            .filter(|felt| felt.to_bytes_be() <= max.to_bytes_be())
+            .ok_or_else(|| eyre!("`{value}` is not a signed integer within the felt range"))?;
+
+        Ok(format!("{:#x}", if negative { -felt } else { felt }))
+    }
+
     pub fn str_to_felt(short_str: &str) -> Result<String> {
         let felt = cairo_short_string_to_felt(short_str)?;
         Ok(format!("{felt:#x}"))
@@ -344,6 +1440,22 @@ impl SimpleProbe {
         parse_cairo_short_string(felt).map_err(Report::new)
     }
 
+    pub fn str_to_byte_array(string: &str) -> String {
+        byte_array_to_felts(string)
+            .iter()
+            .map(|felt| format!("{felt:#x}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn byte_array_to_str(felts: &[FieldElement]) -> Result<String> {
+        let (string, len) = byte_array_from_felts(felts)?;
+        if len < felts.len() {
+            return Err(eyre!("expected {len} felt(s) but got {}", felts.len()));
+        }
+        Ok(string)
+    }
+
     pub fn ecdsa_sign(
         private_key: &FieldElement,
         message_hash: &FieldElement,
@@ -351,6 +1463,17 @@ impl SimpleProbe {
         ecdsa_sign(private_key, message_hash).map_err(Report::new)
     }
 
+    /// Derives the public key of a private key, which is the x coordinate of the point the key
+    /// multiplies the generator into. The y coordinate is only printed when `full` is set.
+    pub fn private_to_public(private_key: &FieldElement, full: bool) -> Result<String> {
+        let (x, y) = public_key_point(private_key)?;
+        if full {
+            Ok(format!("{x:#x} {y:#x}"))
+        } else {
+            Ok(format!("{x:#x}"))
+        }
+    }
+
     pub fn ecdsa_verify(
         public_key: &FieldElement,
         message_hash: &FieldElement,
@@ -391,6 +1514,114 @@ impl SimpleProbe {
         format!("{address:#x}")
     }
 
+    /// Computes the hash of an invoke transaction from the account's `__execute__` calldata.
+    pub fn compute_invoke_tx_hash(
+        sender_address: FieldElement,
+        calldata: &[FieldElement],
+        max_fee: FieldElement,
+        chain_id: FieldElement,
+        nonce: FieldElement,
+        version: FieldElement,
+    ) -> FieldElement {
+        compute_hash_on_elements(&[
+            PREFIX_INVOKE,
+            version,
+            sender_address,
+            FieldElement::ZERO, // entry_point_selector
+            compute_hash_on_elements(calldata),
+            max_fee,
+            chain_id,
+            nonce,
+        ])
+    }
+
+    pub fn compute_declare_tx_hash(
+        sender_address: FieldElement,
+        class_hash: FieldElement,
+        max_fee: FieldElement,
+        chain_id: FieldElement,
+        nonce: FieldElement,
+        version: FieldElement,
+    ) -> FieldElement {
+        compute_hash_on_elements(&[
+            PREFIX_DECLARE,
+            version,
+            sender_address,
+            FieldElement::ZERO, // entry_point_selector
+            compute_hash_on_elements(&[class_hash]),
+            max_fee,
+            chain_id,
+            nonce,
+        ])
+    }
+
+    /// The sender of a deploy account transaction is the account being deployed, whose address is
+    /// derived from the class hash, salt and constructor calldata.
+    pub fn compute_deploy_account_tx_hash(
+        class_hash: FieldElement,
+        salt: FieldElement,
+        constructor_calldata: &[FieldElement],
+        max_fee: FieldElement,
+        chain_id: FieldElement,
+        nonce: FieldElement,
+        version: FieldElement,
+    ) -> FieldElement {
+        let contract_address =
+            get_contract_address(salt, class_hash, constructor_calldata, FieldElement::ZERO);
+
+        let mut elements = vec![class_hash, salt];
+        elements.extend_from_slice(constructor_calldata);
+
+        compute_hash_on_elements(&[
+            PREFIX_DEPLOY_ACCOUNT,
+            version,
+            contract_address,
+            FieldElement::ZERO, // entry_point_selector
+            compute_hash_on_elements(&elements),
+            max_fee,
+            chain_id,
+            nonce,
+        ])
+    }
+
+    /// Computes the hash of the `l1_handler` transaction of a message sent from L1, whose
+    /// calldata is the L1 sender followed by the payload. The nonce is the one the core contract
+    /// assigned to the message.
+    pub fn compute_l1_handler_tx_hash(
+        from_address: FieldElement,
+        contract_address: FieldElement,
+        selector: FieldElement,
+        payload: &[FieldElement],
+        nonce: FieldElement,
+        chain_id: FieldElement,
+    ) -> FieldElement {
+        let mut calldata = vec![from_address];
+        calldata.extend_from_slice(payload);
+
+        compute_hash_on_elements(&[
+            PREFIX_L1_HANDLER,
+            FieldElement::ZERO, // version
+            contract_address,
+            selector,
+            compute_hash_on_elements(&calldata),
+            FieldElement::ZERO, // max_fee
+            chain_id,
+            nonce,
+        ])
+    }
+
+    /// Converts a human amount of a token, e.g. `1.5`, into its raw integer amount, as the low
+    /// and high parts of a u256.
+    pub fn to_unit(amount: &str, decimals: u32) -> Result<(u128, u128)> {
+        parse_units(amount, decimals)
+    }
+
+    /// Converts a raw integer amount of a token, in decimal or in hex, into a human amount.
+    pub fn from_unit(amount: &str, decimals: u32) -> Result<String> {
+        let (low, high) = parse_u256(amount)?;
+        Ok(format_u256_units(low, high, decimals))
+    }
+
     pub fn split_u256(hex: &str) -> Result<(String, String)> {
         let hex = hex.trim_start_matches("0x");
         let hex_chars_len = hex.len();
@@ -413,11 +1644,30 @@ impl SimpleProbe {
         Ok((format!("{high:#x}"), format!("{low:#x}")))
     }
 
+    /// Reassembles a u256 from its low and high 128 bits, each in decimal or in hex.
+    pub fn combine_u256(low: &str, high: &str, fmt: &ValueFormat) -> Result<String> {
+        let half = |value: &str| match parse_u256(value)? {
+            (low, 0) => Ok(low),
+            _ => Err(eyre!("`{value}` doesn't fit in 128 bits")),
+        };
+
+        Ok(fmt.u256(half(low)?, half(high)?))
+    }
+
     pub fn generate_multicall_calldata(args: &str) -> Result<Vec<FieldElement>> {
+        let calls = Self::parse_calls(args)?;
+        let calldata = Self::generate_calldata_for_multicall_account(&calls);
+        Ok(calldata)
+    }
+
+    /// Parses a list of calls seperated with a hyphen, -
+    ///
+    /// e.g. `<contract address> <function name> [<calldata> ...] - ...`
+    pub fn parse_calls(args: &str) -> Result<Vec<Call>> {
         let mut calls = Vec::new();
 
         for (idx, call_str) in args.split('-').enumerate() {
-            let mut data = call_str.trim().split(' ');
+            let mut data = call_str.split_whitespace();
 
             let to = data
                 .next()
@@ -448,9 +1698,7 @@ impl SimpleProbe {
             calls.push(call);
         }
 
-        let calldata = Self::generate_calldata_for_multicall_account(&calls);
-
-        Ok(calldata)
+        Ok(calls)
     }
 
     pub fn generate_calldata_for_multicall_account(calls: &[Call]) -> Vec<FieldElement> {
@@ -473,11 +1721,416 @@ impl SimpleProbe {
 
         execute_calldata
     }
+
+    /// Generates the calldata of the `__execute__` function of Cairo 1 accounts, where the calls
+    /// are given one after the other with their calldata inlined.
+    pub fn generate_calldata_for_cairo_1_account(calls: &[Call]) -> Vec<FieldElement> {
+        let mut execute_calldata: Vec<FieldElement> = vec![calls.len().into()];
+        for call in calls.iter() {
+            execute_calldata.push(call.to);
+            execute_calldata.push(call.selector);
+            execute_calldata.push(call.calldata.len().into());
+            execute_calldata.extend_from_slice(&call.calldata);
+        }
+
+        execute_calldata
+    }
+}
+
+/// Multiplies the generator of the Stark curve by the private key.
+fn public_key_point(private_key: &FieldElement) -> Result<(FieldElement, FieldElement)> {
+    let scalar = starknet_crypto::FieldElement::from_bytes_be(&private_key.to_bytes_be())?;
+    let point = &GENERATOR * &scalar.to_bits_le()[..];
+
+    if point.infinity {
+        return Err(eyre!("the private key cannot be zero"));
+    }
+
+    Ok((
+        FieldElement::from_bytes_be(&point.x.to_bytes_be())?,
+        FieldElement::from_bytes_be(&point.y.to_bytes_be())?,
+    ))
+}
+
+/// Adds the name of the event and its decoded fields to its JSON.
+fn add_decoded_event(event: &mut serde_json::Value, decoded: &DecodedEvent) {
+    event["event"] = json!(decoded.name);
+    event["decoded"] = decoded
+        .params
+        .iter()
+        .map(|param| (param.name.clone(), json!(param.value.to_string())))
+        .collect();
+}
+
+/// Decodes the string returned by a view, which is a short string or an array of short strings
+/// (a long string) in older contracts, and a byte array in newer ones.
+fn decode_string(felts: &[FieldElement]) -> Result<String> {
+    match felts {
+        [felt] => Ok(parse_cairo_short_string(felt)?),
+        // a byte array has two more felts after its words than a long string
+        [len, parts @ ..] if felt_to_u128(*len).ok() == Some(parts.len() as u128) => parts
+            .iter()
+            .map(|part| Ok(parse_cairo_short_string(part)?))
+            .collect(),
+        _ => Ok(byte_array_from_felts(felts)?.0),
+    }
+}
+
+/// The low and high parts of a u256, as the two felts it is passed as.
+fn u256_felts(low: u128, high: u128) -> Result<Vec<FieldElement>> {
+    Ok(vec![
+        FieldElement::from_dec_str(&low.to_string())?,
+        FieldElement::from_dec_str(&high.to_string())?,
+    ])
+}
+
+/// The calldata of `balanceOfBatch`: the array of the accounts, then the array of the token ids.
+fn balance_of_batch_calldata(
+    queries: &[(FieldElement, (u128, u128))],
+) -> Result<Vec<FieldElement>> {
+    let len = FieldElement::from(queries.len() as u64);
+
+    let mut calldata = vec![len];
+    calldata.extend(queries.iter().map(|(account, _)| *account));
+    calldata.push(len);
+    for (_, (low, high)) in queries {
+        calldata.extend(u256_felts(*low, *high)?);
+    }
+    Ok(calldata)
+}
+
+/// Decodes an array of u256, given as its length followed by the low and high parts of each.
+fn decode_u256_array(felts: &[FieldElement]) -> Result<Vec<(u128, u128)>> {
+    let (len, values) = felts
+        .split_first()
+        .ok_or_else(|| eyre!("expected an array of u256, got nothing"))?;
+
+    if felt_to_u128(*len)? != values.len() as u128 / 2 || values.len() % 2 != 0 {
+        return Err(eyre!(
+            "expected an array of {len} u256, got {} felts",
+            values.len()
+        ));
+    }
+
+    values
+        .chunks_exact(2)
+        .map(|value| Ok((felt_to_u128(value[0])?, felt_to_u128(value[1])?)))
+        .collect()
+}
+
+fn decode_decimals(token: FieldElement, felts: &[FieldElement]) -> Result<u32> {
+    let decimals = felts
+        .first()
+        .ok_or_else(|| eyre!("{token:#x} returned no decimals"))?;
+    Ok(decimals.to_string().parse()?)
+}
+
+/// The keccak256 of the header of a message followed by its payload, each felt as a 32 bytes
+/// big-endian word.
+fn message_hash(header: &[FieldElement], payload: &[FieldElement]) -> String {
+    let mut hasher = Keccak256::new();
+    for word in header.iter().chain(payload) {
+        hasher.update(word.to_bytes_be());
+    }
+    format!("0x{}", hex::encode(hasher.finalize()))
+}
+
+/// Prunes the invocations of the trace down to the frames calling `contract` and their callers.
+/// Returns whether any frame is left.
+fn retain_frames_touching(trace: &mut serde_json::Value, contract: FieldElement) -> bool {
+    let mut touched = false;
+
+    if let Some(trace) = trace.as_object_mut() {
+        for (_, invocation) in trace
+            .iter_mut()
+            .filter(|(key, _)| key.ends_with("_invocation"))
+        {
+            if retain_calls_touching(invocation, contract) {
+                touched = true;
+            } else {
+                *invocation = serde_json::Value::Null;
+            }
+        }
+    }
+
+    touched
+}
+
+fn retain_calls_touching(invocation: &mut serde_json::Value, contract: FieldElement) -> bool {
+    let mut touched = invocation["contract_address"]
+        .as_str()
+        .and_then(|address| FieldElement::from_hex_be(address).ok())
+        == Some(contract);
+
+    if let Some(calls) = invocation
+        .get_mut("calls")
+        .and_then(|calls| calls.as_array_mut())
+    {
+        calls.retain_mut(|call| retain_calls_touching(call, contract));
+        touched |= !calls.is_empty();
+    }
+
+    touched
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use starknet::core::chain_id;
+    use starknet::signers::SigningKey;
+
+    #[test]
+    fn derive_public_key() {
+        let private_key = FieldElement::from_hex_be(
+            "0x139fe4d6f02e666e86a6f58e65060f115cd3c185bd9e98bd829636931458f79",
+        )
+        .unwrap();
+        let public_key = SigningKey::from_secret_scalar(private_key)
+            .verifying_key()
+            .scalar();
+
+        let (x, y) = public_key_point(&private_key).unwrap();
+        assert_eq!(x, public_key);
+        // the point lies on the curve y^2 = x^3 + x + b
+        let beta = FieldElement::from_bytes_be(&BETA.to_bytes_be()).unwrap();
+        assert_eq!(y * y, x * x * x + x + beta);
+
+        assert!(public_key_point(&FieldElement::ZERO).is_err());
+    }
+
+    #[test]
+    fn inspect_felts() {
+        let felt = FieldElement::from_hex_be("0x68656c6c6f").unwrap();
+        let inspection = SimpleProbe::inspect_felt(&felt).unwrap();
+        assert_eq!(inspection.decimal, "448378203247");
+        assert_eq!(inspection.bit_length, 39);
+        assert_eq!(inspection.bytes, ["0x68", "0x65", "0x6c", "0x6c", "0x6f"]);
+        assert_eq!(inspection.short_string.as_deref(), Some("hello"));
+
+        let (x, _) = public_key_point(&FieldElement::ONE).unwrap();
+        assert!(SimpleProbe::inspect_felt(&x).unwrap().curve_x_coordinate);
+
+        let zero = SimpleProbe::inspect_felt(&FieldElement::ZERO).unwrap();
+        assert_eq!(zero.bit_length, 0);
+        assert!(zero.bytes.is_empty());
+    }
+
+    #[test]
+    fn signed_felts() {
+        let felt = |value: &str| FieldElement::from_hex_be(value).unwrap();
+
+        assert_eq!(SimpleProbe::to_signed(&felt("0x5")).unwrap(), "5");
+        assert_eq!(SimpleProbe::to_signed(&-felt("0x5")).unwrap(), "-5");
+        assert_eq!(
+            SimpleProbe::from_signed("-5").unwrap(),
+            format!("{:#x}", -felt("0x5"))
+        );
+        assert_eq!(SimpleProbe::from_signed("0").unwrap(), "0x0");
+
+        for bound in [utils::SIGNED_FELT_MAX, utils::SIGNED_FELT_MIN] {
+            let felt = FieldElement::from_hex_be(&SimpleProbe::from_signed(bound).unwrap());
+            assert_eq!(SimpleProbe::to_signed(&felt.unwrap()).unwrap(), bound);
+        }
+        let above_max = format!(
+            "{}1",
+            &utils::SIGNED_FELT_MAX[..utils::SIGNED_FELT_MAX.len() - 1]
+        );
+        assert!(SimpleProbe::from_signed(&above_max).is_err());
+        assert!(SimpleProbe::from_signed("--5").is_err());
+    }
+
+    #[test]
+    fn ethereum_keccak256() {
+        let empty = "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
+        assert_eq!(SimpleProbe::keccak256("").unwrap(), empty);
+        assert_eq!(SimpleProbe::keccak256("0x").unwrap(), empty);
+        assert_eq!(
+            SimpleProbe::keccak256("0x68656c6c6f").unwrap(),
+            SimpleProbe::keccak256("hello").unwrap()
+        );
+    }
+
+    #[test]
+    fn transaction_hashes() {
+        // The expected hashes were computed apart from these functions, with starknet-crypto's
+        // pedersen hash over the fields of each transaction in the order the protocol hashes them.
+        let felt = |s| FieldElement::from_hex_be(s).unwrap();
+        let sender = felt("0x5b5e9f6f6fb7d2647d81a8b2c2b99cbc9cc9d03d705576d7061812324dca5c0");
+        let class_hash = felt("0x25ec026985a3bf9d0cc1fe17326b245dfdc3ff89b8fde106542a3ea56c5a918");
+        let max_fee = felt("0x2386f26fc10000");
+
+        // a `transfer` of 100 to itself through the account's `__execute__`
+        let calldata = [
+            felt("0x1"),
+            felt("0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"),
+            felt("0x83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e"),
+            felt("0x0"),
+            felt("0x3"),
+            felt("0x3"),
+            sender,
+            felt("0x64"),
+            felt("0x0"),
+        ];
+        assert_eq!(
+            SimpleProbe::compute_invoke_tx_hash(
+                sender,
+                &calldata,
+                max_fee,
+                chain_id::TESTNET,
+                felt("0x7"),
+                FieldElement::ONE,
+            ),
+            felt("0x9ac8c737efff438b34144fb6e2da6de9a489a9263c978f2d11d0d5944fd488")
+        );
+
+        assert_eq!(
+            SimpleProbe::compute_declare_tx_hash(
+                sender,
+                class_hash,
+                max_fee,
+                chain_id::TESTNET,
+                felt("0x7"),
+                FieldElement::ONE,
+            ),
+            felt("0x414241ee18f4299bd9023d0e0192cbd275f30a9293d1f2c216903c7a32bf3e4")
+        );
+
+        assert_eq!(
+            SimpleProbe::compute_deploy_account_tx_hash(
+                class_hash,
+                felt("0x2a"),
+                &[felt(
+                    "0x1f3d5c2a3bd9a8b7f2e4c6a1d0e9b8c7f6a5e4d3c2b1a0f9e8d7c6b5a4f3e2d"
+                )],
+                max_fee,
+                chain_id::TESTNET,
+                FieldElement::ZERO,
+                FieldElement::ONE,
+            ),
+            felt("0x2a244c5dd752c5c3e9cc39f8931eff8a38116724f06f7607b39b8fd31731c9f")
+        );
+    }
+
+    /// The deposit on the StarkGate ETH bridge consumed by the goerli transaction
+    /// 0x374286ae28f201e61ffbc5b022cc9701208640b405ea34ea9799f97d5d2d23c.
+    fn goerli_deposit() -> (
+        FieldElement,
+        FieldElement,
+        FieldElement,
+        Vec<FieldElement>,
+        FieldElement,
+    ) {
+        let felt = |s| FieldElement::from_hex_be(s).unwrap();
+        (
+            felt("0xc3511006C04EF1d78af4C8E0e74Ec18A6E64Ff9e"),
+            felt("0x73314940630fd6dcda0d772d4c972c4e0a9946bef9dabf4ef84eda8ef542b82"),
+            // handle_deposit
+            felt("0x2d757788a8d8d6f21d1cd40bce38a8222d70654214e96ff95d8086e684fbee5"),
+            vec![
+                felt("0x689ead7d814e51ed93644bc145f0754839b8dcb340027ce0c30953f38f55d7"),
+                felt("0x2c68af0bb140000"),
+                felt("0x0"),
+            ],
+            FieldElement::from(775628u64),
+        )
+    }
+
+    #[test]
+    fn l1_to_l2_message_hash() {
+        let (from, to, selector, payload, nonce) = goerli_deposit();
+        assert_eq!(
+            SimpleProbe::compute_l1_to_l2_message_hash(from, to, selector, &payload, nonce),
+            "0xc51a543ef9563ad2545342b390b67edfcddf9886aa36846cf70382362fc5fab3"
+        );
+    }
+
+    #[test]
+    fn l1_handler_tx_hash() {
+        let (from, to, selector, payload, nonce) = goerli_deposit();
+        assert_eq!(
+            SimpleProbe::compute_l1_handler_tx_hash(
+                from,
+                to,
+                selector,
+                &payload,
+                nonce,
+                chain_id::TESTNET
+            ),
+            FieldElement::from_hex_be(
+                "0x374286ae28f201e61ffbc5b022cc9701208640b405ea34ea9799f97d5d2d23c"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn l2_to_l1_message_hash() {
+        // A withdrawal of the bridge back to the depositor. The expected hash was computed apart
+        // from this function, as the keccak256 of from, to, the payload length and the payload.
+        let felt = |s| FieldElement::from_hex_be(s).unwrap();
+        let hash = SimpleProbe::compute_l2_to_l1_message_hash(
+            felt("0x73314940630fd6dcda0d772d4c972c4e0a9946bef9dabf4ef84eda8ef542b82"),
+            felt("0xc3511006C04EF1d78af4C8E0e74Ec18A6E64Ff9e"),
+            &[
+                felt("0x0"),
+                felt("0x689ead7d814e51ed93644bc145f0754839b8dcb3"),
+                felt("0x2c68af0bb140000"),
+                felt("0x0"),
+            ],
+        );
+        assert_eq!(
+            hash,
+            "0xa24a628672471177b11d862f15a78545aeef4ad162c3d87f5f4230157fb499fb"
+        );
+    }
+
+    #[test]
+    fn convert_token_units() {
+        assert_eq!(
+            SimpleProbe::to_unit("1.5", 18).unwrap(),
+            (1_500_000_000_000_000_000, 0)
+        );
+        assert_eq!(SimpleProbe::from_unit("1500000", 6).unwrap(), "1.5");
+        assert_eq!(SimpleProbe::from_unit("0x64", 2).unwrap(), "1");
+        assert!(SimpleProbe::from_unit("1.5", 18).is_err());
+    }
+
+    #[test]
+    fn erc1155_batch_balances() {
+        let felt = FieldElement::from;
+        let queries = [(felt(0xa1u64), (1, 0)), (felt(0xb2u64), (2, 3))];
+
+        assert_eq!(
+            balance_of_batch_calldata(&queries).unwrap(),
+            [2u64, 0xa1, 0xb2, 2, 1, 0, 2, 3].map(felt)
+        );
+
+        assert_eq!(
+            decode_u256_array(&[2u64, 10, 0, 20, 1].map(felt)).unwrap(),
+            vec![(10, 0), (20, 1)]
+        );
+        assert!(decode_u256_array(&[2u64, 10, 0].map(felt)).is_err());
+    }
+
+    #[test]
+    fn decode_token_strings() {
+        let short = cairo_short_string_to_felt("ETH").unwrap();
+        assert_eq!(decode_string(&[short]).unwrap(), "ETH");
+
+        let name = "A token whose name is longer than a short string";
+        assert_eq!(decode_string(&byte_array_to_felts(name)).unwrap(), name);
+
+        let uri = [
+            "ipfs://bafybeigdyrzt5sfp7udm7hu",
+            "76uh7y26nf3efuylqabf3oclgtqy55f",
+            "bzdi/1",
+        ]
+        .map(|part| cairo_short_string_to_felt(part).unwrap());
+        assert_eq!(
+            decode_string(&[FieldElement::THREE, uri[0], uri[1], uri[2]]).unwrap(),
+            "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi/1"
+        );
+    }
 
     #[test]
     fn parse_multicall_str() {
@@ -504,4 +2157,70 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn parse_calls_str() {
+        let arg = "0x123 transfer 0x1 0x2 -0xabc approve".to_string();
+        let calls = SimpleProbe::parse_calls(&arg).unwrap();
+
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].to, FieldElement::from_str("0x123").unwrap());
+        assert_eq!(
+            calls[0].selector,
+            get_selector_from_name("transfer").unwrap()
+        );
+        assert_eq!(
+            calls[0].calldata,
+            vec![FieldElement::ONE, FieldElement::TWO]
+        );
+        assert_eq!(calls[1].to, FieldElement::from_str("0xabc").unwrap());
+        assert!(calls[1].calldata.is_empty());
+    }
+
+    #[test]
+    fn filter_trace_frames_by_contract() {
+        let mut trace = json!({
+            "validate_invocation": {
+                "contract_address": "0x1",
+                "calls": []
+            },
+            "execute_invocation": {
+                "contract_address": "0x1",
+                "calls": [
+                    { "contract_address": "0x02", "calls": [] },
+                    { "contract_address": "0x3", "calls": [] }
+                ]
+            },
+            "state_diff": {}
+        });
+
+        assert!(retain_frames_touching(&mut trace, FieldElement::TWO));
+        assert!(trace["validate_invocation"].is_null());
+        assert_eq!(
+            trace["execute_invocation"]["calls"],
+            json!([{ "contract_address": "0x02", "calls": [] }])
+        );
+        assert!(!retain_frames_touching(&mut trace, FieldElement::THREE));
+    }
+
+    #[test]
+    fn transaction_state_from_json() {
+        let state = TransactionState::from_json(&json!({ "status": "PENDING" }));
+        assert_eq!(state.status, "RECEIVED");
+        assert_eq!(state.block_number, None);
+
+        let state = TransactionState::from_json(&json!({
+            "finality_status": "ACCEPTED_ON_L2",
+            "execution_status": "REVERTED",
+            "block_number": 42,
+        }));
+        assert_eq!(
+            state,
+            TransactionState {
+                status: "ACCEPTED_ON_L2".to_string(),
+                reverted: true,
+                block_number: Some(42),
+            }
+        );
+    }
 }
@@ -0,0 +1,249 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::FieldElement;
+use starknet::providers::jsonrpc::models::{
+    BlockId, BlockTag, ErrorCode, MaybePendingTransactionReceipt, TransactionReceipt,
+    TransactionStatus,
+};
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient, JsonRpcClientError, RpcError};
+
+pub const NONCE_FILE: &str = ".starknet/probe/nonces.json";
+
+/// How long a transaction that the node doesn't know of is still considered on its way, as a
+/// node may take a moment to know of a transaction that was just sent.
+const UNKNOWN_TRANSACTION_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+/// Last used nonces, keyed by chain id and then by account address.
+type Nonces = BTreeMap<String, BTreeMap<String, UsedNonce>>;
+
+/// The last nonce used by an account, along with the transaction that was sent with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct UsedNonce {
+    nonce: FieldElement,
+    transaction_hash: FieldElement,
+    /// When the transaction was sent, in seconds since the unix epoch.
+    sent_at: u64,
+}
+
+/// Picks the nonce of the next transaction sent from an account.
+///
+/// The nonce is fetched from the pending block so that transactions which are not yet included in
+/// a block are accounted for. When `persist` is set, the last nonce used by each account is also
+/// kept in a local file, which allows sending transactions faster than the node updates its
+/// pending state. The local nonce is only trusted while the transaction sent with it may still
+/// be included, so that a rejected or dropped transaction doesn't leave a gap in the nonces.
+pub struct NonceManager<'a> {
+    provider: &'a JsonRpcClient<HttpTransport>,
+    store: Option<NonceStore>,
+}
+
+impl<'a> NonceManager<'a> {
+    pub fn new(provider: &'a JsonRpcClient<HttpTransport>, persist: bool) -> Result<Self> {
+        let store = match persist {
+            true => Some(NonceStore::new(get_nonce_file()?)),
+            false => None,
+        };
+        Ok(Self { provider, store })
+    }
+
+    pub async fn next_nonce(&self, account: FieldElement, offset: u64) -> Result<FieldElement> {
+        let pending = self
+            .provider
+            .get_nonce(&BlockId::Tag(BlockTag::Pending), account)
+            .await?;
+
+        let nonce = match self.store {
+            Some(ref store) => {
+                let chain = self.provider.chain_id().await?;
+                match store.get(chain, account)? {
+                    // the pending state doesn't show the transaction sent with the last nonce yet
+                    Some(last) if pending <= last.nonce => {
+                        if self.is_nonce_taken(&last).await? {
+                            last.nonce + FieldElement::ONE
+                        } else {
+                            store.remove(chain, account)?;
+                            pending
+                        }
+                    }
+                    _ => pending,
+                }
+            }
+            None => pending,
+        };
+
+        Ok(nonce + FieldElement::from(offset))
+    }
+
+    /// Records `nonce` as used by `account` to send the transaction. Does nothing if the nonces
+    /// are not persisted.
+    pub async fn commit(
+        &self,
+        account: FieldElement,
+        nonce: FieldElement,
+        transaction_hash: FieldElement,
+    ) -> Result<()> {
+        if let Some(ref store) = self.store {
+            let chain = self.provider.chain_id().await?;
+            let used = UsedNonce {
+                nonce,
+                transaction_hash,
+                sent_at: now(),
+            };
+            store.set(chain, account, used)?;
+        }
+        Ok(())
+    }
+
+    /// Forgets the nonce kept for `account`, so that the next one is the pending nonce of the
+    /// node. Does nothing if the nonces are not persisted.
+    pub async fn reset(&self, account: FieldElement) -> Result<()> {
+        if let Some(ref store) = self.store {
+            let chain = self.provider.chain_id().await?;
+            store.remove(chain, account)?;
+        }
+        Ok(())
+    }
+
+    /// Whether the transaction sent with the last used nonce is either included or may still
+    /// be, in which case its nonce can't be used again.
+    async fn is_nonce_taken(&self, last: &UsedNonce) -> Result<bool> {
+        match self
+            .provider
+            .get_transaction_receipt(last.transaction_hash)
+            .await
+        {
+            Ok(MaybePendingTransactionReceipt::Receipt(receipt)) => {
+                let status = match receipt {
+                    TransactionReceipt::Invoke(r) => r.status,
+                    TransactionReceipt::Declare(r) => r.status,
+                    TransactionReceipt::Deploy(r) => r.status,
+                    TransactionReceipt::DeployAccount(r) => r.status,
+                    TransactionReceipt::L1Handler(r) => r.status,
+                };
+                Ok(status != TransactionStatus::Rejected)
+            }
+
+            Ok(MaybePendingTransactionReceipt::PendingReceipt(_)) => Ok(true),
+
+            Err(JsonRpcClientError::RpcError(RpcError::Code(
+                ErrorCode::TransactionHashNotFound,
+            ))) => Ok(now() < last.sent_at + UNKNOWN_TRANSACTION_GRACE_PERIOD.as_secs()),
+
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+fn get_nonce_file() -> Result<PathBuf> {
+    let home = home::home_dir().ok_or_else(|| eyre!("could not find the home directory"))?;
+    Ok(home.join(NONCE_FILE))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+struct NonceStore {
+    path: PathBuf,
+}
+
+impl NonceStore {
+    fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    fn get(&self, chain: FieldElement, account: FieldElement) -> Result<Option<UsedNonce>> {
+        let nonces = self.load()?;
+        Ok(nonces
+            .get(&format!("{chain:#x}"))
+            .and_then(|accounts| accounts.get(&format!("{account:#x}")))
+            .copied())
+    }
+
+    fn set(&self, chain: FieldElement, account: FieldElement, used: UsedNonce) -> Result<()> {
+        let mut nonces = self.load()?;
+        nonces
+            .entry(format!("{chain:#x}"))
+            .or_default()
+            .insert(format!("{account:#x}"), used);
+        self.save(&nonces)
+    }
+
+    fn remove(&self, chain: FieldElement, account: FieldElement) -> Result<()> {
+        let mut nonces = self.load()?;
+        let chain = format!("{chain:#x}");
+
+        if let Some(accounts) = nonces.get_mut(&chain) {
+            accounts.remove(&format!("{account:#x}"));
+            if accounts.is_empty() {
+                nonces.remove(&chain);
+            }
+            self.save(&nonces)?;
+        }
+
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Nonces> {
+        if !self.path.exists() {
+            return Ok(Nonces::new());
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn save(&self, nonces: &Nonces) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(nonces)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starknet::core::chain_id::{MAINNET, TESTNET};
+
+    #[test]
+    fn nonce_store_roundtrip() {
+        let path =
+            std::env::temp_dir().join(format!("probe-nonce-store-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let store = NonceStore::new(&path);
+        let account = FieldElement::from(0x1234_u64);
+        let used = |nonce: u64| UsedNonce {
+            nonce: FieldElement::from(nonce),
+            transaction_hash: FieldElement::from(0xabcd_u64),
+            sent_at: 1_700_000_000,
+        };
+
+        assert_eq!(store.get(TESTNET, account).unwrap(), None);
+
+        store.set(TESTNET, account, used(5)).unwrap();
+        store.set(MAINNET, account, used(9)).unwrap();
+
+        assert_eq!(store.get(TESTNET, account).unwrap(), Some(used(5)));
+        assert_eq!(store.get(MAINNET, account).unwrap(), Some(used(9)));
+
+        // forgetting the nonce of a chain leaves the other chains as they are
+        store.remove(TESTNET, account).unwrap();
+        assert_eq!(store.get(TESTNET, account).unwrap(), None);
+        assert_eq!(store.get(MAINNET, account).unwrap(), Some(used(9)));
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,137 @@
+//! ECDSA over the secp256k1 and secp256r1 curves, as used by Ethereum and by the Starknet accounts
+//! signing with Ethereum or passkey keys. Message hashes are signed as given, without hashing
+//! them again, and the nonces are derived deterministically as specified by RFC 6979.
+
+use clap::ValueEnum;
+use eyre::{eyre, Result};
+use k256::ecdsa::signature::hazmat::PrehashVerifier;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Curve {
+    /// The curve of Ethereum keys.
+    Secp256k1,
+    /// The NIST P-256 curve, of WebAuthn and passkey keys.
+    Secp256r1,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecpSignature {
+    pub r: [u8; 32],
+    pub s: [u8; 32],
+    /// The recovery id, the parity of the y coordinate of the point whose x coordinate is `r`,
+    /// from which the public key can be recovered.
+    pub v: u8,
+}
+
+/// Signs a message hash, with `s` normalized to the lower half of the curve order on
+/// secp256k1 as Ethereum requires.
+pub fn sign(
+    curve: Curve,
+    private_key: &[u8; 32],
+    message_hash: &[u8; 32],
+) -> Result<SecpSignature> {
+    let ((r, s), v) = match curve {
+        Curve::Secp256k1 => {
+            let key = k256::ecdsa::SigningKey::from_bytes(private_key.into())
+                .map_err(|_| eyre!("invalid secp256k1 private key"))?;
+            let (signature, recovery_id) = key.sign_prehash_recoverable(message_hash)?;
+            (signature.split_bytes(), recovery_id.to_byte())
+        }
+        Curve::Secp256r1 => {
+            let key = p256::ecdsa::SigningKey::from_bytes(private_key.into())
+                .map_err(|_| eyre!("invalid secp256r1 private key"))?;
+            let (signature, recovery_id) = key.sign_prehash_recoverable(message_hash)?;
+            (signature.split_bytes(), recovery_id.to_byte())
+        }
+    };
+
+    Ok(SecpSignature {
+        r: r.into(),
+        s: s.into(),
+        v,
+    })
+}
+
+/// Verifies the signature of a message hash against a public key in the SEC1 encoding, either
+/// compressed or not.
+pub fn verify(
+    curve: Curve,
+    public_key: &[u8],
+    message_hash: &[u8; 32],
+    r: &[u8; 32],
+    s: &[u8; 32],
+) -> Result<bool> {
+    match curve {
+        Curve::Secp256k1 => {
+            let key = k256::ecdsa::VerifyingKey::from_sec1_bytes(public_key)
+                .map_err(|_| eyre!("invalid secp256k1 public key"))?;
+            let Ok(signature) = k256::ecdsa::Signature::from_scalars(*r, *s) else {
+                return Ok(false);
+            };
+            Ok(key.verify_prehash(message_hash, &signature).is_ok())
+        }
+        Curve::Secp256r1 => {
+            let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(public_key)
+                .map_err(|_| eyre!("invalid secp256r1 public key"))?;
+            let Ok(signature) = p256::ecdsa::Signature::from_scalars(*r, *s) else {
+                return Ok(false);
+            };
+            Ok(key.verify_prehash(message_hash, &signature).is_ok())
+        }
+    }
+}
+
+/// The public key of a private key in the SEC1 encoding, `04` followed by its x and y
+/// coordinates unless `compressed` is set.
+pub fn public_key(curve: Curve, private_key: &[u8; 32], compressed: bool) -> Result<Vec<u8>> {
+    match curve {
+        Curve::Secp256k1 => {
+            let key = k256::ecdsa::SigningKey::from_bytes(private_key.into())
+                .map_err(|_| eyre!("invalid secp256k1 private key"))?;
+            Ok(key
+                .verifying_key()
+                .to_encoded_point(compressed)
+                .as_bytes()
+                .to_vec())
+        }
+        Curve::Secp256r1 => {
+            let key = p256::ecdsa::SigningKey::from_bytes(private_key.into())
+                .map_err(|_| eyre!("invalid secp256r1 private key"))?;
+            Ok(key
+                .verifying_key()
+                .to_encoded_point(compressed)
+                .as_bytes()
+                .to_vec())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha3::{Digest, Keccak256};
+
+    #[test]
+    fn sign_and_verify() {
+        let private_key = [0x42; 32];
+        let hash: [u8; 32] = Keccak256::digest(b"hello").into();
+
+        for curve in [Curve::Secp256k1, Curve::Secp256r1] {
+            let signature = sign(curve, &private_key, &hash).unwrap();
+            assert!(signature.v <= 1);
+
+            for compressed in [false, true] {
+                let key = public_key(curve, &private_key, compressed).unwrap();
+                assert_eq!(key.len(), if compressed { 33 } else { 65 });
+                assert!(verify(curve, &key, &hash, &signature.r, &signature.s).unwrap());
+            }
+
+            let key = public_key(curve, &private_key, false).unwrap();
+            let mut other = hash;
+            other[0] ^= 1;
+            assert!(!verify(curve, &key, &other, &signature.r, &signature.s).unwrap());
+        }
+
+        assert!(sign(Curve::Secp256k1, &[0; 32], &hash).is_err());
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eyre::Result;
+use reqwest::Url;
+use serde::Deserialize;
+use starknet::core::types::FieldElement;
+use starknet::core::utils::get_selector_from_name;
+
+pub const SELECTORS_FILE: &str = ".starknet/probe/selectors.json";
+
+/// Names of common entrypoints and events, which are resolved even if no abi declaring them has
+/// been recorded.
+const KNOWN_NAMES: [&str; 36] = [
+    "__execute__",
+    "__validate__",
+    "__validate_declare__",
+    "__validate_deploy__",
+    "__default__",
+    "__l1_default__",
+    "constructor",
+    "name",
+    "symbol",
+    "decimals",
+    "totalSupply",
+    "total_supply",
+    "balanceOf",
+    "balance_of",
+    "allowance",
+    "transfer",
+    "transferFrom",
+    "transfer_from",
+    "approve",
+    "increaseAllowance",
+    "decreaseAllowance",
+    "ownerOf",
+    "owner_of",
+    "safeTransferFrom",
+    "safe_transfer_from",
+    "setApprovalForAll",
+    "set_approval_for_all",
+    "owner",
+    "transferOwnership",
+    "transfer_ownership",
+    "upgrade",
+    "is_valid_signature",
+    "isValidSignature",
+    "deployContract",
+    "Transfer",
+    "Approval",
+];
+
+/// Known names, keyed by their selector.
+type Selectors = BTreeMap<String, String>;
+
+/// A local database of function and event names, used to resolve a selector back to the name it
+/// was computed from. Names are added to it from the abis fetched by the other commands.
+pub struct SelectorDatabase {
+    path: PathBuf,
+}
+
+impl SelectorDatabase {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn lookup(&self, selector: FieldElement) -> Result<Option<String>> {
+        let known = KNOWN_NAMES
+            .iter()
+            .find(|name| get_selector_from_name(name).ok() == Some(selector));
+
+        match known {
+            Some(name) => Ok(Some(name.to_string())),
+            None => Ok(self.load()?.get(&format!("{selector:#x}")).cloned()),
+        }
+    }
+
+    /// Adds the names to the database. Returns the number of names that were not known yet.
+    pub fn add<I, S>(&self, names: I) -> Result<usize>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut selectors = self.load()?;
+        let mut added = 0;
+
+        for name in names {
+            let name = name.as_ref();
+            let selector = format!("{:#x}", get_selector_from_name(name)?);
+            if selectors.insert(selector, name.to_string()).is_none() {
+                added += 1;
+            }
+        }
+
+        if added > 0 {
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&self.path, serde_json::to_string_pretty(&selectors)?)?;
+        }
+
+        Ok(added)
+    }
+
+    fn load(&self) -> Result<Selectors> {
+        if !self.path.exists() {
+            return Ok(Selectors::new());
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+/// Resolves selectors to names, from the local database first and then from a remote registry if
+/// one is set. The names found in the registry are added to the local database.
+pub struct SelectorResolver {
+    database: SelectorDatabase,
+    registry: Option<Url>,
+}
+
+#[derive(Deserialize)]
+struct RegistryEntry {
+    name: String,
+}
+
+impl SelectorResolver {
+    pub fn new(registry: Option<Url>) -> Self {
+        Self {
+            database: SelectorDatabase::default(),
+            registry,
+        }
+    }
+
+    /// Resolves the selectors, leaving out the ones that are not known. Naming selectors is only
+    /// there to help reading them, so failing to do so is not an error.
+    pub async fn resolve_all<I>(&self, selectors: I) -> HashMap<FieldElement, String>
+    where
+        I: IntoIterator<Item = FieldElement>,
+    {
+        let mut names = HashMap::new();
+
+        for selector in selectors {
+            if names.contains_key(&selector) {
+                continue;
+            }
+
+            let name = match self.database.lookup(selector).ok().flatten() {
+                Some(name) => Some(name),
+                None => self.query_registry(selector).await,
+            };
+
+            if let Some(name) = name {
+                names.insert(selector, name);
+            }
+        }
+
+        names
+    }
+
+    async fn query_registry(&self, selector: FieldElement) -> Option<String> {
+        let registry = self.registry.as_ref()?;
+        let url = Url::parse(&format!(
+            "{}/{selector:#x}",
+            registry.as_str().trim_end_matches('/')
+        ))
+        .ok()?;
+
+        let entry = reqwest::get(url)
+            .await
+            .ok()?
+            .json::<RegistryEntry>()
+            .await
+            .ok()?;
+
+        // the registry is not trusted, the name must hash to the selector
+        if get_selector_from_name(&entry.name).ok()? != selector {
+            return None;
+        }
+
+        let _ = self.database.add([&entry.name]);
+        Some(entry.name)
+    }
+}
+
+impl Default for SelectorDatabase {
+    fn default() -> Self {
+        Self::new(home::home_dir().unwrap().join(SELECTORS_FILE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_recorded_selectors() {
+        let path = std::env::temp_dir().join("probe-selectors-test.json");
+        let _ = fs::remove_file(&path);
+
+        let database = SelectorDatabase::new(&path);
+        let selector = |name| get_selector_from_name(name).unwrap();
+
+        assert_eq!(
+            database.lookup(selector("transfer")).unwrap().as_deref(),
+            Some("transfer")
+        );
+        assert_eq!(database.lookup(selector("swap_exact_in")).unwrap(), None);
+
+        assert_eq!(database.add(["swap_exact_in", "transfer"]).unwrap(), 2);
+        assert_eq!(database.add(["swap_exact_in"]).unwrap(), 0);
+        assert_eq!(
+            database
+                .lookup(selector("swap_exact_in"))
+                .unwrap()
+                .as_deref(),
+            Some("swap_exact_in")
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+}
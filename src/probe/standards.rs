@@ -0,0 +1,137 @@
+use super::abi::Abi;
+
+use serde::Serialize;
+use starknet::core::types::FieldElement;
+
+/// A standard interface, recognized from the SRC-5 interface ids the contract claims to support,
+/// or else from the functions declared in its ABI.
+pub struct Interface {
+    pub name: &'static str,
+    /// The SRC-5 id of the interface, then its ERC-165 id as used by the Cairo 0 contracts. ERC20
+    /// has none, as its contracts don't register an interface.
+    pub ids: &'static [&'static str],
+    /// The functions of the interface, each by its snake case name and its camel case name.
+    functions: &'static [&'static [&'static str]],
+}
+
+pub const INTERFACES: [Interface; 4] = [
+    Interface {
+        name: "ERC20",
+        ids: &[],
+        functions: &[
+            &["balance_of", "balanceOf"],
+            &["transfer"],
+            &["transfer_from", "transferFrom"],
+            &["approve"],
+            &["allowance"],
+            &["total_supply", "totalSupply"],
+        ],
+    },
+    Interface {
+        name: "ERC721",
+        ids: &[
+            "0x33eb2f84c309543403fd69f0d0f363781ef06ef6faeb0131ff16ea3175bd943",
+            "0x80ac58cd",
+        ],
+        functions: &[
+            &["balance_of", "balanceOf"],
+            &["owner_of", "ownerOf"],
+            &["transfer_from", "transferFrom"],
+            &["get_approved", "getApproved"],
+            &["set_approval_for_all", "setApprovalForAll"],
+        ],
+    },
+    Interface {
+        name: "ERC1155",
+        ids: &[
+            "0x6114a8f75559e1b39fcba08ce02961a1aa082d9256a158dd3e64964e4b1b52",
+            "0xd9b67a26",
+        ],
+        functions: &[
+            &["balance_of", "balanceOf"],
+            &["balance_of_batch", "balanceOfBatch"],
+            &["safe_transfer_from", "safeTransferFrom"],
+            &["safe_batch_transfer_from", "safeBatchTransferFrom"],
+        ],
+    },
+    Interface {
+        name: "ACCOUNT",
+        ids: &[
+            "0x2ceccef7f994940b3962a6c67e0ba4fcd37df7d131417c604f91e03caecc1cd",
+            "0xa66bd575",
+        ],
+        functions: &[
+            &["__execute__"],
+            &["__validate__"],
+            &["is_valid_signature", "isValidSignature"],
+        ],
+    },
+];
+
+impl Interface {
+    /// Whether the ABI declares every function of the interface, under either of its names.
+    pub fn is_declared_in(&self, abi: &Abi) -> bool {
+        self.functions
+            .iter()
+            .all(|names| names.iter().any(|name| abi.function(name).is_ok()))
+    }
+}
+
+/// The standards a contract implements.
+#[derive(Debug, Serialize)]
+pub struct TokenStandards {
+    pub address: FieldElement,
+    pub standards: Vec<DetectedStandard>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct DetectedStandard {
+    pub name: &'static str,
+    /// `SRC-5` when the contract claims to support the interface, or `ABI` when its ABI declares
+    /// the functions of the interface.
+    pub source: &'static str,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn interfaces_declared_in_abi() {
+        let function =
+            |name: &str| json!({ "type": "function", "name": name, "inputs": [], "outputs": [] });
+
+        let erc20 = Abi::parse(&json!([
+            function("balanceOf"),
+            function("transfer"),
+            function("transferFrom"),
+            function("approve"),
+            function("allowance"),
+            function("totalSupply"),
+        ]))
+        .unwrap();
+
+        // the functions of cairo 1 contracts are grouped by interface
+        let account = Abi::parse(&json!([{
+            "type": "interface",
+            "name": "ISRC6",
+            "items": [
+                function("__execute__"),
+                function("__validate__"),
+                function("is_valid_signature"),
+            ],
+        }]))
+        .unwrap();
+
+        let declared = |abi: &Abi| {
+            INTERFACES
+                .iter()
+                .filter(|interface| interface.is_declared_in(abi))
+                .map(|interface| interface.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(declared(&erc20), ["ERC20"]);
+        assert_eq!(declared(&account), ["ACCOUNT"]);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::probe::abi::{parse_u256, u256_to_dec};
+
+use eyre::{bail, eyre, Result};
+use serde::{Serialize, Serializer};
+use starknet::core::types::FieldElement;
+use starknet::core::utils::cairo_short_string_to_felt;
+
+/// The address of the ETH contract, which is the same on mainnet and on the testnets.
+pub const ETH_ADDRESS: &str = "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
+
+/// The address of the STRK contract, which is the same on mainnet and on the testnets.
+pub const STRK_ADDRESS: &str = "0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d";
+
+/// The fee tokens, deployed at the same address on every chain including the local ones.
+const FEE_TOKENS: [(&str, &str, &str, u8); 2] = [
+    ("ETH", "Ether", ETH_ADDRESS, 18),
+    ("STRK", "Starknet Token", STRK_ADDRESS, 18),
+];
+
+/// The other well-known tokens, by the short string of the chain id they are deployed on.
+const CHAIN_TOKENS: [(&str, &str, &str, &str, u8); 4] = [
+    (
+        "SN_MAIN",
+        "USDC",
+        "USD Coin",
+        "0x053c91253bc9682c04929ca02ed00b3e423f6710d2ee7e0d5ebb06f3ecf368a8",
+        6,
+    ),
+    (
+        "SN_MAIN",
+        "USDT",
+        "Tether USD",
+        "0x068f5c6a61780768455de69077e07e89787839bf8166decfbf92b645209c0fb8",
+        6,
+    ),
+    (
+        "SN_MAIN",
+        "DAI",
+        "Dai Stablecoin",
+        "0x00da114221cb83fa859dbdb4c44beeaa0bb37c7537ad5ae66fe5e0efd20e6eb3",
+        18,
+    ),
+    (
+        "SN_MAIN",
+        "WBTC",
+        "Wrapped BTC",
+        "0x03fe2b97c1fd336e750087d68b9b867997fd64a2661ff3ca5a7c771641e8e7ac",
+        8,
+    ),
+];
+
+/// The metadata of an ERC20 token, as read from its contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TokenMetadata {
+    pub address: FieldElement,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u32,
+    /// The low and high parts of the u256 supply, in the smallest unit of the token.
+    #[serde(serialize_with = "serialize_u256")]
+    pub total_supply: (u128, u128),
+}
+
+fn serialize_u256<S: Serializer>(
+    &(low, high): &(u128, u128),
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&u256_to_dec(low, high))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Token {
+    pub symbol: String,
+    pub name: String,
+    pub address: FieldElement,
+    pub decimals: u8,
+}
+
+impl Token {
+    fn new(symbol: &str, name: &str, address: &str, decimals: u8) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            name: name.to_string(),
+            address: FieldElement::from_hex_be(address).unwrap(),
+            decimals,
+        }
+    }
+}
+
+/// The well-known tokens of the chain.
+pub fn known_tokens(chain: FieldElement) -> Vec<Token> {
+    let fee_tokens = FEE_TOKENS
+        .iter()
+        .map(|(symbol, name, address, decimals)| Token::new(symbol, name, address, *decimals));
+
+    let chain_tokens = CHAIN_TOKENS
+        .iter()
+        .filter(|(id, ..)| cairo_short_string_to_felt(id).unwrap() == chain)
+        .map(|(_, symbol, name, address, decimals)| Token::new(symbol, name, address, *decimals));
+
+    fee_tokens.chain(chain_tokens).collect()
+}
+
+/// Finds a well-known token of the chain by its symbol, ignoring the case, or by its address.
+pub fn find_token(chain: FieldElement, token: &str) -> Option<Token> {
+    let address = FieldElement::from_hex_be(token).ok();
+
+    known_tokens(chain)
+        .into_iter()
+        .find(|known| known.symbol.eq_ignore_ascii_case(token) || Some(known.address) == address)
+}
+
+/// The address of the token given by symbol or by address, along with the token if it is a
+/// well-known one. Any address is accepted, but the units of the tokens outside of the registry
+/// aren't known.
+pub fn resolve_token(chain: FieldElement, token: &str) -> Result<(FieldElement, Option<Token>)> {
+    match find_token(chain, token) {
+        Some(known) => Ok((known.address, Some(known))),
+        None => match FieldElement::from_hex_be(token) {
+            Ok(address) => Ok((address, None)),
+            Err(_) => bail!("unknown token `{token}`, see `probe tokens ls`"),
+        },
+    }
+}
+
+/// Parses a human amount of a token with the given number of decimals, e.g. `1.5`, into its
+/// smallest unit, as the low and high parts of a u256.
+pub fn parse_units(amount: &str, decimals: u32) -> Result<(u128, u128)> {
+    let invalid = || eyre!("invalid amount `{amount}`");
+
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if whole.is_empty() && fraction.is_empty()
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals as usize {
+        bail!("the amount `{amount}` has more than the {decimals} decimals of the token")
+    }
+
+    // the leading zero keeps the digits of `.5` with no decimals from being empty
+    let digits = format!("0{whole}{fraction:0<width$}", width = decimals as usize);
+    parse_u256(&digits).map_err(|_| eyre!("the amount `{amount}` doesn't fit in a u256"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starknet::core::chain_id::{MAINNET, TESTNET};
+
+    #[test]
+    fn find_known_tokens() {
+        assert_eq!(find_token(MAINNET, "usdc").unwrap().decimals, 6);
+        assert_eq!(
+            find_token(TESTNET, ETH_ADDRESS).unwrap().symbol,
+            "ETH".to_string()
+        );
+        assert!(find_token(TESTNET, "USDC").is_none());
+        assert!(find_token(MAINNET, "0x1").is_none());
+        assert_eq!(known_tokens(MAINNET).len(), 6);
+    }
+
+    #[test]
+    fn parse_human_amounts() {
+        assert_eq!(
+            parse_units("1.5", 18).unwrap(),
+            (1_500_000_000_000_000_000, 0)
+        );
+        assert_eq!(parse_units("0.0001", 6).unwrap(), (100, 0));
+        assert_eq!(parse_units("42", 0).unwrap(), (42, 0));
+        assert_eq!(parse_units(".25", 2).unwrap(), (25, 0));
+        assert_eq!(parse_units("2.50", 1).unwrap(), (25, 0));
+        assert_eq!(
+            parse_units("340282366920938463463.374607431768211456", 18).unwrap(),
+            (0, 1)
+        );
+
+        assert!(parse_units("0.0000001", 6).is_err());
+        assert!(parse_units("1e18", 18).is_err());
+        assert!(parse_units("-1", 18).is_err());
+        assert!(parse_units(".", 18).is_err());
+        assert!(parse_units("", 18).is_err());
+    }
+}
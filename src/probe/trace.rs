@@ -0,0 +1,250 @@
+use std::collections::{BTreeMap, HashMap};
+
+use clap::ValueEnum;
+use serde_json::Value;
+use starknet::core::types::FieldElement;
+
+/// The top-level invocations of a transaction trace, in the order they are executed.
+const TOP_LEVEL_INVOCATIONS: [&str; 5] = [
+    "validate_invocation",
+    "constructor_invocation",
+    "execute_invocation",
+    "function_invocation",
+    "fee_transfer_invocation",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TraceFormat {
+    /// An indented tree of the calls, with their calldata and results.
+    Tree,
+    /// One line per call stack with the number of steps executed by its last call, as consumed
+    /// by flamegraph tools such as `inferno`.
+    Folded,
+}
+
+/// A call made during the execution of a transaction, along with the resources it used.
+#[derive(Debug)]
+pub struct TraceCall {
+    pub depth: usize,
+    pub contract_address: String,
+    pub selector: String,
+    /// Resources used by the call, including the ones of its inner calls.
+    pub resources: BTreeMap<String, u64>,
+    /// Resources used by the call itself, excluding the ones of its inner calls.
+    pub self_resources: BTreeMap<String, u64>,
+}
+
+impl TraceCall {
+    pub fn steps(&self) -> Option<u64> {
+        self.resources.get("steps").copied()
+    }
+}
+
+/// Flattens the call tree of the trace, with each call followed by its inner calls. Reverted
+/// invocations are skipped as they don't report the calls they made.
+pub fn flatten_calls(trace: &Value) -> Vec<TraceCall> {
+    let mut calls = Vec::new();
+    for key in TOP_LEVEL_INVOCATIONS {
+        flatten_invocation(&trace[key], 0, &mut calls);
+    }
+    calls
+}
+
+fn flatten_invocation(invocation: &Value, depth: usize, calls: &mut Vec<TraceCall>) {
+    let contract_address = match invocation["contract_address"].as_str() {
+        Some(address) => address.to_string(),
+        None => return,
+    };
+
+    let resources = numeric_fields(&invocation["execution_resources"]);
+    let index = calls.len();
+
+    calls.push(TraceCall {
+        depth,
+        contract_address,
+        selector: invocation["entry_point_selector"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        self_resources: resources.clone(),
+        resources,
+    });
+
+    for call in invocation["calls"].as_array().into_iter().flatten() {
+        let child = calls.len();
+        flatten_invocation(call, depth + 1, calls);
+
+        if child < calls.len() {
+            for (name, amount) in calls[child].resources.clone() {
+                if let Some(value) = calls[index].self_resources.get_mut(&name) {
+                    *value = value.saturating_sub(amount);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the selectors of the entrypoints called during the execution of the transaction.
+pub fn entry_point_selectors(trace: &Value) -> Vec<FieldElement> {
+    flatten_calls(trace)
+        .iter()
+        .filter_map(|call| FieldElement::from_hex_be(&call.selector).ok())
+        .collect()
+}
+
+/// Sets the `entry_point_name` of the invocations whose selector is named.
+pub fn name_entry_points(trace: &mut Value, names: &HashMap<FieldElement, String>) {
+    for key in TOP_LEVEL_INVOCATIONS {
+        name_invocation(&mut trace[key], names);
+    }
+}
+
+fn name_invocation(invocation: &mut Value, names: &HashMap<FieldElement, String>) {
+    let name = invocation["entry_point_selector"]
+        .as_str()
+        .and_then(|selector| FieldElement::from_hex_be(selector).ok())
+        .and_then(|selector| names.get(&selector));
+
+    if let (Some(name), Some(invocation)) = (name, invocation.as_object_mut()) {
+        invocation.insert("entry_point_name".to_string(), Value::from(name.as_str()));
+    }
+
+    if let Some(calls) = invocation["calls"].as_array_mut() {
+        for call in calls {
+            name_invocation(call, names);
+        }
+    }
+}
+
+/// Renders the calls in the folded stack format, where each stack is weighted by the number of
+/// steps executed by its last call. Each stack is prefixed with `root` if specified.
+pub fn folded_stacks(calls: &[TraceCall], root: Option<&str>) -> String {
+    let mut stack: Vec<String> = Vec::new();
+    let mut lines = Vec::new();
+
+    for call in calls {
+        stack.truncate(call.depth);
+        stack.push(format!("{}::{}", call.contract_address, call.selector));
+
+        let steps = call
+            .self_resources
+            .get("steps")
+            .copied()
+            .unwrap_or_default();
+        if steps > 0 {
+            let frames = root
+                .into_iter()
+                .map(String::from)
+                .chain(stack.iter().cloned());
+            lines.push(format!("{} {steps}", frames.collect::<Vec<_>>().join(";")));
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Returns the numeric fields of a resources object. Builtins, which are reported as
+/// `<name>_builtin_applications`, are keyed by their name only.
+pub fn numeric_fields(value: &Value) -> BTreeMap<String, u64> {
+    value
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, value)| {
+            let name = name.trim_end_matches("_builtin_applications").to_string();
+            value.as_u64().map(|value| (name, value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn flatten_trace_calls() {
+        let trace = json!({
+            "execute_invocation": {
+                "contract_address": "0x1",
+                "entry_point_selector": "0xa",
+                "execution_resources": { "steps": 300, "range_check_builtin_applications": 10 },
+                "calls": [
+                    {
+                        "contract_address": "0x2",
+                        "entry_point_selector": "0xb",
+                        "execution_resources": { "steps": 100, "range_check_builtin_applications": 4 },
+                        "calls": []
+                    },
+                    {
+                        "contract_address": "0x3",
+                        "entry_point_selector": "0xc",
+                        "execution_resources": { "steps": 50 },
+                        "calls": []
+                    }
+                ]
+            },
+            "fee_transfer_invocation": {
+                "contract_address": "0x4",
+                "entry_point_selector": "0xd",
+                "execution_resources": { "steps": 20 },
+                "calls": []
+            }
+        });
+
+        let calls = flatten_calls(&trace);
+
+        assert_eq!(
+            calls
+                .iter()
+                .map(|call| (call.depth, call.contract_address.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(0, "0x1"), (1, "0x2"), (1, "0x3"), (0, "0x4")]
+        );
+        assert_eq!(calls[0].steps(), Some(300));
+        assert_eq!(calls[0].self_resources["steps"], 150);
+        assert_eq!(calls[0].self_resources["range_check"], 6);
+        assert_eq!(calls[1].self_resources["steps"], 100);
+
+        assert_eq!(
+            folded_stacks(&calls, Some("tx")),
+            "tx;0x1::0xa 150
+tx;0x1::0xa;0x2::0xb 100
+tx;0x1::0xa;0x3::0xc 50
+tx;0x4::0xd 20"
+        );
+    }
+
+    #[test]
+    fn name_trace_entry_points() {
+        let mut trace = json!({
+            "execute_invocation": {
+                "contract_address": "0x1", "entry_point_selector": "0xa",
+                "calls": [
+                    { "contract_address": "0x2", "entry_point_selector": "0xb", "calls": [] },
+                    { "contract_address": "0x3", "entry_point_selector": "0xc", "calls": [] }
+                ]
+            }
+        });
+
+        assert_eq!(
+            entry_point_selectors(&trace),
+            vec![
+                FieldElement::from_hex_be("0xa").unwrap(),
+                FieldElement::from_hex_be("0xb").unwrap(),
+                FieldElement::from_hex_be("0xc").unwrap(),
+            ]
+        );
+
+        let names = HashMap::from([(
+            FieldElement::from_hex_be("0xb").unwrap(),
+            "transfer".to_string(),
+        )]);
+        name_entry_points(&mut trace, &names);
+
+        let invocation = &trace["execute_invocation"];
+        assert_eq!(invocation.get("entry_point_name"), None);
+        assert_eq!(invocation["calls"][0]["entry_point_name"], "transfer");
+        assert_eq!(invocation["calls"][1].get("entry_point_name"), None);
+    }
+}
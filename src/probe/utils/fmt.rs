@@ -1,26 +1,76 @@
+use crate::probe::abi::{
+    short_string, signature, u256_to_dec, Abi, AbiChange, DecodedEvent, DecodedParam,
+};
+use crate::probe::addressbook::Contacts;
+use crate::probe::chains::KnownChain;
+use crate::probe::merkle::MerkleOutput;
+use crate::probe::standards::TokenStandards;
+use crate::probe::tokens::{Token, TokenMetadata};
+use crate::probe::utils::decode_revert_reason;
+use crate::probe::{FeltInspection, MessageStatus};
+
+use std::fmt::Write;
+use std::time::{Duration, UNIX_EPOCH};
 use std::vec;
 
 use comfy_table::modifiers::UTF8_SOLID_INNER_BORDERS;
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::Table;
+use serde_json::{json, Value};
 use starknet::core::types::FieldElement;
 use starknet::providers::jsonrpc::models::{
-    Event, InvokeTransaction, MaybePendingBlockWithTxs, MaybePendingTransactionReceipt, MsgToL1,
-    Transaction, TransactionReceipt,
+    Event, FeeEstimate, InvokeTransaction, MaybePendingBlockWithTxs,
+    MaybePendingTransactionReceipt, MsgToL1, Transaction, TransactionReceipt,
 };
 
 pub trait Pretty {
-    fn prettify(&self) -> String;
+    fn prettify(&self, fmt: &ValueFormat) -> String;
+}
+
+/// How the values are rendered, as chosen with the display options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ValueFormat {
+    /// Felts and timestamps are printed as is, without the decoded short strings and dates.
+    pub raw: bool,
+    /// Felts are printed in decimal rather than hex.
+    pub decimal: bool,
+}
+
+impl ValueFormat {
+    /// Formats a felt in the radix chosen with `--dec` or `--hex`.
+    pub fn felt(&self, felt: &FieldElement) -> String {
+        if self.decimal {
+            felt.to_string()
+        } else {
+            format!("{felt:#x}")
+        }
+    }
+
+    /// Formats a u256, given as its low and high 128 bits, in the radix chosen with `--dec` or
+    /// `--hex`.
+    pub fn u256(&self, low: u128, high: u128) -> String {
+        if self.decimal {
+            u256_to_dec(low, high)
+        } else if high == 0 {
+            format!("{low:#x}")
+        } else {
+            format!("{high:#x}{low:032x}")
+        }
+    }
 }
 
 impl Pretty for FieldElement {
-    fn prettify(&self) -> String {
-        format!("{self:#x}")
+    /// Felts that hold a short string are followed by the decoded text, e.g. `0x4f4b (OK)`.
+    fn prettify(&self, fmt: &ValueFormat) -> String {
+        match short_string(*self) {
+            Some(text) if !fmt.raw => format!("{} ({text})", fmt.felt(self)),
+            _ => fmt.felt(self),
+        }
     }
 }
 
 impl Pretty for u64 {
-    fn prettify(&self) -> String {
+    fn prettify(&self, _: &ValueFormat) -> String {
         format!("{self}")
     }
 }
@@ -29,128 +79,166 @@ impl<T> Pretty for Vec<T>
 where
     T: Pretty,
 {
-    fn prettify(&self) -> String {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
         self.iter()
-            .map(|i| i.prettify())
+            .map(|i| i.prettify(fmt))
             .collect::<Vec<String>>()
             .join("\n")
     }
 }
 
 impl Pretty for Event {
-    fn prettify(&self) -> String {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
         let mut table = Table::new();
         table
             .load_preset(UTF8_FULL)
             .apply_modifier(UTF8_SOLID_INNER_BORDERS)
-            .add_row(vec!["FROM", &self.from_address.prettify()])
-            .add_row(vec!["KEYS", &self.keys.prettify()])
-            .add_row(vec!["DATA", &self.data.prettify()]);
+            .add_row(vec!["FROM", &self.from_address.prettify(fmt)])
+            .add_row(vec!["KEYS", &self.keys.prettify(fmt)])
+            .add_row(vec!["DATA", &self.data.prettify(fmt)]);
 
         format!("{table}")
     }
 }
 
 impl Pretty for MsgToL1 {
-    fn prettify(&self) -> String {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
         let mut table = Table::new();
         table
             .load_preset(UTF8_FULL)
             .apply_modifier(UTF8_SOLID_INNER_BORDERS)
-            .add_row(vec!["TO", &self.to_address.prettify()])
-            .add_row(vec!["PAYLOAD", &self.payload.prettify()]);
+            .add_row(vec!["TO", &self.to_address.prettify(fmt)])
+            .add_row(vec!["PAYLOAD", &self.payload.prettify(fmt)]);
 
         format!("{table}")
     }
 }
 
-impl Pretty for MaybePendingTransactionReceipt {
-    fn prettify(&self) -> String {
+impl Pretty for FeeEstimate {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
         let mut table = Table::new();
         table
             .load_preset(UTF8_FULL)
-            .apply_modifier(UTF8_SOLID_INNER_BORDERS);
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .add_row(vec!["OVERALL FEE", &self.overall_fee.prettify(fmt)])
+            .add_row(vec!["GAS CONSUMED", &self.gas_consumed.prettify(fmt)])
+            .add_row(vec!["GAS PRICE", &self.gas_price.prettify(fmt)]);
 
-        match self {
-            Self::Receipt(receipt) => match receipt {
-                TransactionReceipt::Invoke(_) => {
-                    table.add_row(vec!["TYPE", "INVOKE"]);
-                }
-                TransactionReceipt::Declare(_) => {
-                    table.add_row(vec!["TYPE", "DECLARE"]);
-                }
-                TransactionReceipt::Deploy(_) => {
-                    table.add_row(vec!["TYPE", "DEPLOY"]);
-                }
-                TransactionReceipt::DeployAccount(_) => {
-                    table.add_row(vec!["TYPE", "DEPLOY ACCOUNT"]);
-                }
-                TransactionReceipt::L1Handler(_) => {
-                    table.add_row(vec!["TYPE", "DEPLOY ACCOUNT"]);
-                }
-            },
+        format!("{table}")
+    }
+}
 
-            Self::PendingReceipt(pending) => return serde_json::to_string_pretty(pending).unwrap(),
-        }
+impl Pretty for MaybePendingTransactionReceipt {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
+        pretty_transaction_receipt(self, None, None, fmt)
+    }
+}
 
-        let mut value = serde_json::to_value(self).unwrap();
+/// Renders a transaction receipt. If `decoded_events` is specified, the events are rendered with
+/// their decoded fields, except for the ones that could not be decoded.
+pub fn pretty_transaction_receipt(
+    receipt: &MaybePendingTransactionReceipt,
+    revert_reason: Option<&str>,
+    decoded_events: Option<&[Option<DecodedEvent>]>,
+    fmt: &ValueFormat,
+) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_SOLID_INNER_BORDERS);
 
-        table
-            .add_row(vec![
-                "TRANSACTION HASH",
-                &serde_json::from_value::<FieldElement>(value["transaction_hash"].take())
-                    .unwrap()
-                    .prettify(),
-            ])
-            .add_row(vec![
-                "BLOCK HASH",
-                &serde_json::from_value::<FieldElement>(value["block_hash"].take())
-                    .unwrap()
-                    .prettify(),
-            ])
-            .add_row(vec![
-                "BLOCK NUMBER",
-                &serde_json::from_value::<u64>(value["block_number"].take())
-                    .unwrap()
-                    .prettify(),
-            ])
-            .add_row(vec![
-                "ACTUAL FEE",
-                &serde_json::from_value::<FieldElement>(value["actual_fee"].take())
-                    .unwrap()
-                    .prettify(),
-            ]);
+    match receipt {
+        MaybePendingTransactionReceipt::Receipt(receipt) => match receipt {
+            TransactionReceipt::Invoke(_) => {
+                table.add_row(vec!["TYPE", "INVOKE"]);
+            }
+            TransactionReceipt::Declare(_) => {
+                table.add_row(vec!["TYPE", "DECLARE"]);
+            }
+            TransactionReceipt::Deploy(_) => {
+                table.add_row(vec!["TYPE", "DEPLOY"]);
+            }
+            TransactionReceipt::DeployAccount(_) => {
+                table.add_row(vec!["TYPE", "DEPLOY ACCOUNT"]);
+            }
+            TransactionReceipt::L1Handler(_) => {
+                table.add_row(vec!["TYPE", "DEPLOY ACCOUNT"]);
+            }
+        },
 
-        if let Some(value) = value.get("contract_address") {
-            table.add_row(vec![
-                "CONTRACT\nADDRESS",
-                &serde_json::from_value::<FieldElement>(value.to_owned())
-                    .unwrap()
-                    .prettify(),
-            ]);
+        MaybePendingTransactionReceipt::PendingReceipt(pending) => {
+            return serde_json::to_string_pretty(pending).unwrap()
         }
+    }
 
-        table
-            .add_row(vec!["STATUS", value["status"].take().as_str().unwrap()])
-            .add_row(vec![
-                "EVENTS",
-                &serde_json::from_value::<Vec<Event>>(value["events"].take())
-                    .unwrap()
-                    .prettify(),
-            ])
-            .add_row(vec![
-                "MESSAGES SENT",
-                &serde_json::from_value::<Vec<MsgToL1>>(value["messages_sent"].take())
-                    .unwrap()
-                    .prettify(),
-            ]);
+    let mut value = serde_json::to_value(receipt).unwrap();
+    let events = serde_json::from_value::<Vec<Event>>(value["events"].take()).unwrap();
+    let events = match decoded_events {
+        Some(decoded_events) => events
+            .iter()
+            .zip(decoded_events)
+            .map(|(event, decoded)| match decoded {
+                Some(decoded) => pretty_decoded_event(event, decoded, fmt),
+                None => event.prettify(fmt),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        None => events.prettify(fmt),
+    };
 
-        format!("{table}")
+    table
+        .add_row(vec![
+            "TRANSACTION HASH",
+            &serde_json::from_value::<FieldElement>(value["transaction_hash"].take())
+                .unwrap()
+                .prettify(fmt),
+        ])
+        .add_row(vec![
+            "BLOCK HASH",
+            &serde_json::from_value::<FieldElement>(value["block_hash"].take())
+                .unwrap()
+                .prettify(fmt),
+        ])
+        .add_row(vec![
+            "BLOCK NUMBER",
+            &serde_json::from_value::<u64>(value["block_number"].take())
+                .unwrap()
+                .prettify(fmt),
+        ])
+        .add_row(vec![
+            "ACTUAL FEE",
+            &serde_json::from_value::<FieldElement>(value["actual_fee"].take())
+                .unwrap()
+                .prettify(fmt),
+        ]);
+
+    if let Some(value) = value.get("contract_address") {
+        table.add_row(vec![
+            "CONTRACT\nADDRESS",
+            &serde_json::from_value::<FieldElement>(value.to_owned())
+                .unwrap()
+                .prettify(fmt),
+        ]);
     }
+
+    table.add_row(vec!["STATUS", value["status"].take().as_str().unwrap()]);
+
+    if let Some(reason) = revert_reason {
+        table.add_row(vec!["REVERT REASON", &decode_revert_reason(reason)]);
+    }
+
+    table.add_row(vec!["EVENTS", &events]).add_row(vec![
+        "MESSAGES SENT",
+        &serde_json::from_value::<Vec<MsgToL1>>(value["messages_sent"].take())
+            .unwrap()
+            .prettify(fmt),
+    ]);
+
+    format!("{table}")
 }
 
 impl Pretty for Transaction {
-    fn prettify(&self) -> String {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
         let mut table = Table::new();
         table
             .load_preset(UTF8_FULL)
@@ -163,26 +251,26 @@ impl Pretty for Transaction {
                 match invoke {
                     InvokeTransaction::V1(tx) => {
                         table
-                            .add_row(vec!["TRANSACTION HASH", &tx.transaction_hash.prettify()])
-                            .add_row(vec!["SENDER ADDRESS", &tx.sender_address.prettify()])
-                            .add_row(vec!["SIGNATURE", &tx.signature.prettify()])
-                            .add_row(vec!["NONCE", &tx.nonce.prettify()])
-                            .add_row(vec!["MAX FEE", &tx.max_fee.prettify()])
-                            .add_row(vec!["CALLDATA", &tx.calldata.prettify()])
+                            .add_row(vec!["TRANSACTION HASH", &tx.transaction_hash.prettify(fmt)])
+                            .add_row(vec!["SENDER ADDRESS", &tx.sender_address.prettify(fmt)])
+                            .add_row(vec!["SIGNATURE", &tx.signature.prettify(fmt)])
+                            .add_row(vec!["NONCE", &tx.nonce.prettify(fmt)])
+                            .add_row(vec!["MAX FEE", &tx.max_fee.prettify(fmt)])
+                            .add_row(vec!["CALLDATA", &tx.calldata.prettify(fmt)])
                             .add_row(vec!["VERSION", "1"]);
                     }
                     InvokeTransaction::V0(tx) => {
                         table
-                            .add_row(vec!["TRANSACTION HASH", &tx.transaction_hash.prettify()])
-                            .add_row(vec!["CONTRACT ADDRESS", &tx.contract_address.prettify()])
+                            .add_row(vec!["TRANSACTION HASH", &tx.transaction_hash.prettify(fmt)])
+                            .add_row(vec!["CONTRACT ADDRESS", &tx.contract_address.prettify(fmt)])
                             .add_row(vec![
                                 "ENTRY POINT\nSELECTOR",
-                                &tx.entry_point_selector.prettify(),
+                                &tx.entry_point_selector.prettify(fmt),
                             ])
-                            .add_row(vec!["SIGNATURE", &tx.signature.prettify()])
-                            .add_row(vec!["NONCE", &tx.nonce.prettify()])
-                            .add_row(vec!["MAX FEE", &tx.max_fee.prettify()])
-                            .add_row(vec!["CALLDATA", &tx.calldata.prettify()])
+                            .add_row(vec!["SIGNATURE", &tx.signature.prettify(fmt)])
+                            .add_row(vec!["NONCE", &tx.nonce.prettify(fmt)])
+                            .add_row(vec!["MAX FEE", &tx.max_fee.prettify(fmt)])
+                            .add_row(vec!["CALLDATA", &tx.calldata.prettify(fmt)])
                             .add_row(vec!["VERSION", "0"]);
                     }
                 }
@@ -191,62 +279,62 @@ impl Pretty for Transaction {
             Self::Declare(tx) => {
                 table
                     .add_row(vec!["TYPE", "DECLARE"])
-                    .add_row(vec!["TRANSACTION HASH", &tx.transaction_hash.prettify()])
-                    .add_row(vec!["SENDER ADDRESS", &tx.sender_address.prettify()])
-                    .add_row(vec!["CLASS HASH", &tx.class_hash.prettify()])
-                    .add_row(vec!["SIGNATURE", &tx.signature.prettify()])
-                    .add_row(vec!["NONCE", &tx.nonce.prettify()])
-                    .add_row(vec!["MAX FEE", &tx.max_fee.prettify()])
-                    .add_row(vec!["VERSION", &tx.version.prettify()]);
+                    .add_row(vec!["TRANSACTION HASH", &tx.transaction_hash.prettify(fmt)])
+                    .add_row(vec!["SENDER ADDRESS", &tx.sender_address.prettify(fmt)])
+                    .add_row(vec!["CLASS HASH", &tx.class_hash.prettify(fmt)])
+                    .add_row(vec!["SIGNATURE", &tx.signature.prettify(fmt)])
+                    .add_row(vec!["NONCE", &tx.nonce.prettify(fmt)])
+                    .add_row(vec!["MAX FEE", &tx.max_fee.prettify(fmt)])
+                    .add_row(vec!["VERSION", &tx.version.prettify(fmt)]);
             }
 
             Self::Deploy(tx) => {
                 table
                     .add_row(vec!["TYPE", "DEPLOY"])
-                    .add_row(vec!["TRANSACTION HASH", &tx.transaction_hash.prettify()])
-                    .add_row(vec!["CLASS HASH", &tx.class_hash.prettify()])
+                    .add_row(vec!["TRANSACTION HASH", &tx.transaction_hash.prettify(fmt)])
+                    .add_row(vec!["CLASS HASH", &tx.class_hash.prettify(fmt)])
                     .add_row(vec![
                         "CONTRACT ADDRESS\nSALT",
-                        &tx.contract_address_salt.prettify(),
+                        &tx.contract_address_salt.prettify(fmt),
                     ])
                     .add_row(vec![
                         "CONSTRUCTOR\nCALLDATA",
-                        &tx.constructor_calldata.prettify(),
+                        &tx.constructor_calldata.prettify(fmt),
                     ])
-                    .add_row(vec!["VERSION", &tx.version.prettify()]);
+                    .add_row(vec!["VERSION", &tx.version.prettify(fmt)]);
             }
 
             Self::L1Handler(tx) => {
                 table
                     .add_row(vec!["TYPE", "L1_HANDLER"])
-                    .add_row(vec!["TRANSACTION HASH", &tx.transaction_hash.prettify()])
-                    .add_row(vec!["CONTRACT ADDRESS", &tx.contract_address.prettify()])
+                    .add_row(vec!["TRANSACTION HASH", &tx.transaction_hash.prettify(fmt)])
+                    .add_row(vec!["CONTRACT ADDRESS", &tx.contract_address.prettify(fmt)])
                     .add_row(vec![
                         "ENTRY POINT\nSELECTOR",
-                        &tx.entry_point_selector.prettify(),
+                        &tx.entry_point_selector.prettify(fmt),
                     ])
-                    .add_row(vec!["CALLDATA", &tx.calldata.prettify()])
-                    .add_row(vec!["NONCE", &tx.nonce.prettify()])
-                    .add_row(vec!["VERSION", &tx.version.prettify()]);
+                    .add_row(vec!["CALLDATA", &tx.calldata.prettify(fmt)])
+                    .add_row(vec!["NONCE", &tx.nonce.prettify(fmt)])
+                    .add_row(vec!["VERSION", &tx.version.prettify(fmt)]);
             }
 
             Self::DeployAccount(tx) => {
                 table
                     .add_row(vec!["TYPE", "DEPLOY_ACCOUNT"])
-                    .add_row(vec!["TRANSACTION HASH", &tx.transaction_hash.prettify()])
-                    .add_row(vec!["CLASS HASH", &tx.class_hash.prettify()])
+                    .add_row(vec!["TRANSACTION HASH", &tx.transaction_hash.prettify(fmt)])
+                    .add_row(vec!["CLASS HASH", &tx.class_hash.prettify(fmt)])
                     .add_row(vec![
                         "CONTRACT ADDRESS\nSALT",
-                        &tx.contract_address_salt.prettify(),
+                        &tx.contract_address_salt.prettify(fmt),
                     ])
                     .add_row(vec![
                         "CONSTRUCTOR\nCALLDATA",
-                        &tx.constructor_calldata.prettify(),
+                        &tx.constructor_calldata.prettify(fmt),
                     ])
-                    .add_row(vec!["SIGNATURE", &tx.signature.prettify()])
-                    .add_row(vec!["MAX FEE", &tx.max_fee.prettify()])
-                    .add_row(vec!["NONCE", &tx.nonce.prettify()])
-                    .add_row(vec!["VERSION", &tx.version.prettify()]);
+                    .add_row(vec!["SIGNATURE", &tx.signature.prettify(fmt)])
+                    .add_row(vec!["MAX FEE", &tx.max_fee.prettify(fmt)])
+                    .add_row(vec!["NONCE", &tx.nonce.prettify(fmt)])
+                    .add_row(vec!["VERSION", &tx.version.prettify(fmt)]);
             }
         }
 
@@ -255,7 +343,7 @@ impl Pretty for Transaction {
 }
 
 impl Pretty for MaybePendingBlockWithTxs {
-    fn prettify(&self) -> String {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
         let mut table = Table::new();
         table
             .load_preset(UTF8_FULL)
@@ -264,34 +352,34 @@ impl Pretty for MaybePendingBlockWithTxs {
         match self {
             Self::Block(block) => {
                 table
-                    .add_row(vec!["BLOCK HASH", &block.block_hash.prettify()])
-                    .add_row(vec!["PARENT HASH", &block.parent_hash.prettify()])
-                    .add_row(vec!["BLOCK NUMBER", &block.block_number.prettify()])
-                    .add_row(vec!["NEW ROOT", &block.new_root.prettify()])
-                    .add_row(vec!["TIMESTAMP", &block.timestamp.prettify()])
+                    .add_row(vec!["BLOCK HASH", &block.block_hash.prettify(fmt)])
+                    .add_row(vec!["PARENT HASH", &block.parent_hash.prettify(fmt)])
+                    .add_row(vec!["BLOCK NUMBER", &block.block_number.prettify(fmt)])
+                    .add_row(vec!["NEW ROOT", &block.new_root.prettify(fmt)])
+                    .add_row(vec!["TIMESTAMP", &pretty_timestamp(block.timestamp, fmt)])
                     .add_row(vec![
                         "SEQUENCER ADDRESS",
-                        &block.sequencer_address.prettify(),
+                        &block.sequencer_address.prettify(fmt),
                     ])
                     .add_row(vec![
                         "STATUS",
-                        serde_json::to_value(&block.status)
+                        serde_json::to_value(block.status)
                             .unwrap_or_default()
                             .as_str()
                             .unwrap_or_default(),
                     ])
-                    .add_row(vec!["TRANSACTIONS", &block.transactions.prettify()]);
+                    .add_row(vec!["TRANSACTIONS", &block.transactions.prettify(fmt)]);
             }
 
             Self::PendingBlock(block) => {
                 table
-                    .add_row(vec!["PARENT HASH", &block.parent_hash.prettify()])
-                    .add_row(vec!["TIMESTAMP", &block.timestamp.prettify()])
+                    .add_row(vec!["PARENT HASH", &block.parent_hash.prettify(fmt)])
+                    .add_row(vec!["TIMESTAMP", &pretty_timestamp(block.timestamp, fmt)])
                     .add_row(vec![
                         "SEQUENCER ADDRESS",
-                        &block.sequencer_address.prettify(),
+                        &block.sequencer_address.prettify(fmt),
                     ])
-                    .add_row(vec!["TRANSACTIONS", &block.transactions.prettify()]);
+                    .add_row(vec!["TRANSACTIONS", &block.transactions.prettify(fmt)]);
             }
         }
 
@@ -299,7 +387,7 @@ impl Pretty for MaybePendingBlockWithTxs {
     }
 }
 
-pub fn pretty_block_without_txs(block: &MaybePendingBlockWithTxs) -> String {
+pub fn pretty_block_without_txs(block: &MaybePendingBlockWithTxs, fmt: &ValueFormat) -> String {
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
@@ -308,18 +396,18 @@ pub fn pretty_block_without_txs(block: &MaybePendingBlockWithTxs) -> String {
     match block {
         MaybePendingBlockWithTxs::Block(block) => {
             table
-                .add_row(vec!["BLOCK HASH", &block.block_hash.prettify()])
-                .add_row(vec!["PARENT HASH", &block.parent_hash.prettify()])
-                .add_row(vec!["BLOCK NUMBER", &block.block_number.prettify()])
-                .add_row(vec!["NEW ROOT", &block.new_root.prettify()])
-                .add_row(vec!["TIMESTAMP", &block.timestamp.prettify()])
+                .add_row(vec!["BLOCK HASH", &block.block_hash.prettify(fmt)])
+                .add_row(vec!["PARENT HASH", &block.parent_hash.prettify(fmt)])
+                .add_row(vec!["BLOCK NUMBER", &block.block_number.prettify(fmt)])
+                .add_row(vec!["NEW ROOT", &block.new_root.prettify(fmt)])
+                .add_row(vec!["TIMESTAMP", &pretty_timestamp(block.timestamp, fmt)])
                 .add_row(vec![
                     "SEQUENCER ADDRESS",
-                    &block.sequencer_address.prettify(),
+                    &block.sequencer_address.prettify(fmt),
                 ])
                 .add_row(vec![
                     "STATUS",
-                    serde_json::to_value(&block.status)
+                    serde_json::to_value(block.status)
                         .unwrap_or_default()
                         .as_str()
                         .unwrap_or_default(),
@@ -327,14 +415,960 @@ pub fn pretty_block_without_txs(block: &MaybePendingBlockWithTxs) -> String {
         }
         MaybePendingBlockWithTxs::PendingBlock(block) => {
             table
-                .add_row(vec!["PARENT HASH", &block.parent_hash.prettify()])
-                .add_row(vec!["TIMESTAMP", &block.timestamp.prettify()])
+                .add_row(vec!["PARENT HASH", &block.parent_hash.prettify(fmt)])
+                .add_row(vec!["TIMESTAMP", &pretty_timestamp(block.timestamp, fmt)])
                 .add_row(vec![
                     "SEQUENCER ADDRESS",
-                    &block.sequencer_address.prettify(),
+                    &block.sequencer_address.prettify(fmt),
                 ]);
         }
     }
 
     format!("{table}")
 }
+
+/// Renders the saved addresses as a table, one row per name on each chain.
+pub fn pretty_contacts(contacts: &Contacts, fmt: &ValueFormat) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+        .set_header(vec!["CHAIN", "NAME", "ADDRESS"]);
+
+    for (chain, names) in contacts {
+        let chain = FieldElement::from_hex_be(chain)
+            .map(|chain| chain.prettify(fmt))
+            .unwrap_or_else(|_| chain.clone());
+
+        for (name, address) in names {
+            table.add_row(vec![chain.clone(), name.clone(), address.prettify(fmt)]);
+        }
+    }
+
+    format!("{table}")
+}
+
+/// Renders the known chains as a table.
+/// Renders the balances of several addresses, already formatted, as a table.
+pub fn pretty_balances(
+    addresses: &[FieldElement],
+    balances: &[String],
+    fmt: &ValueFormat,
+) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+        .set_header(vec!["ADDRESS", "BALANCE"]);
+
+    for (address, balance) in addresses.iter().zip(balances) {
+        table.add_row(vec![fmt.felt(address), balance.clone()]);
+    }
+
+    format!("{table}")
+}
+
+pub fn pretty_chains(chains: &[KnownChain], fmt: &ValueFormat) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+        .set_header(vec!["ALIAS", "NAME", "CHAIN ID"]);
+
+    for chain in chains {
+        table.add_row(vec![
+            chain.alias.to_string(),
+            chain.name.to_string(),
+            fmt.felt(&chain.id),
+        ]);
+    }
+
+    format!("{table}")
+}
+
+/// Renders the L2 transactions of the messages sent by an L1 transaction as a table.
+pub fn pretty_messages_status(messages: &[MessageStatus]) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+        .set_header(vec![
+            "L1 HANDLER TRANSACTION",
+            "FINALITY STATUS",
+            "EXECUTION STATUS",
+        ]);
+
+    for message in messages {
+        let mut execution = message.execution_status.clone().unwrap_or_default();
+        if let Some(reason) = &message.failure_reason {
+            execution = format!("{execution}\n{reason}").trim().to_string();
+        }
+
+        table.add_row(vec![
+            message.transaction_hash.clone(),
+            message.finality_status.clone(),
+            execution,
+        ]);
+    }
+
+    format!("{table}")
+}
+
+impl Pretty for TokenMetadata {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
+        let (low, high) = self.total_supply;
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .add_row(vec!["ADDRESS".to_string(), fmt.felt(&self.address)])
+            .add_row(vec!["NAME".to_string(), self.name.clone()])
+            .add_row(vec!["SYMBOL".to_string(), self.symbol.clone()])
+            .add_row(vec!["DECIMALS".to_string(), self.decimals.to_string()])
+            .add_row(vec![
+                "TOTAL SUPPLY".to_string(),
+                format!(
+                    "{} {}",
+                    format_u256_units(low, high, self.decimals),
+                    self.symbol
+                ),
+            ]);
+
+        format!("{table}")
+    }
+}
+
+impl Pretty for TokenStandards {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
+        if self.standards.is_empty() {
+            return format!(
+                "{} implements none of the known standards",
+                fmt.felt(&self.address)
+            );
+        }
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .set_header(vec!["STANDARD", "DETECTED FROM"]);
+
+        for standard in &self.standards {
+            table.add_row(vec![standard.name, standard.source]);
+        }
+
+        format!("{table}")
+    }
+}
+
+impl Pretty for FeltInspection {
+    fn prettify(&self, _: &ValueFormat) -> String {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .add_row(vec!["HEX".to_string(), self.hex.clone()])
+            .add_row(vec!["DECIMAL".to_string(), self.decimal.clone()])
+            .add_row(vec!["SIGNED".to_string(), self.signed.clone()])
+            .add_row(vec!["BIT LENGTH".to_string(), self.bit_length.to_string()])
+            .add_row(vec!["BYTES".to_string(), self.bytes.join(" ")]);
+
+        if let Some(short_string) = &self.short_string {
+            table.add_row(vec!["SHORT STRING".to_string(), short_string.clone()]);
+        }
+
+        table.add_row(vec![
+            "CURVE X COORDINATE".to_string(),
+            self.curve_x_coordinate.to_string(),
+        ]);
+
+        format!("{table}")
+    }
+}
+
+impl Pretty for MerkleOutput {
+    fn prettify(&self, fmt: &ValueFormat) -> String {
+        if self.proofs.is_empty() {
+            return fmt.felt(&self.root);
+        }
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .set_header(vec!["LEAF", "INDEX", "PROOF"]);
+
+        for proof in &self.proofs {
+            table.add_row(vec![
+                fmt.felt(&proof.leaf),
+                proof.index.to_string(),
+                proof
+                    .proof
+                    .iter()
+                    .map(|felt| fmt.felt(felt))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ]);
+        }
+
+        format!("Root : {}\n\n{table}", fmt.felt(&self.root))
+    }
+}
+
+/// Renders the known tokens as a table.
+pub fn pretty_tokens(tokens: &[Token]) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+        .set_header(vec!["SYMBOL", "NAME", "ADDRESS", "DECIMALS"]);
+
+    for token in tokens {
+        table.add_row(vec![
+            token.symbol.clone(),
+            token.name.clone(),
+            format!("{:#x}", token.address),
+            token.decimals.to_string(),
+        ]);
+    }
+
+    format!("{table}")
+}
+
+/// Renders the parameters decoded from calldata as a table.
+pub fn pretty_decoded_params(params: &[DecodedParam]) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+        .set_header(vec!["NAME", "TYPE", "VALUE"]);
+
+    for param in params {
+        table.add_row(vec![
+            param.name.clone(),
+            param.r#type.clone(),
+            param.value.to_string(),
+        ]);
+    }
+
+    format!("{table}")
+}
+
+/// Renders an event along with its name and decoded fields.
+fn pretty_decoded_event(event: &Event, decoded: &DecodedEvent, fmt: &ValueFormat) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+        .add_row(vec!["FROM".to_string(), event.from_address.prettify(fmt)])
+        .add_row(vec!["EVENT".to_string(), decoded.name.clone()]);
+
+    for param in &decoded.params {
+        table.add_row(vec![param.name.to_uppercase(), param.value.to_string()]);
+    }
+
+    format!("{table}")
+}
+
+/// Renders the differences between two ABIs, one line per added (`+`) or removed (`-`) entry.
+/// Changed entries are followed by their signature before and after the change.
+pub fn pretty_abi_changes(changes: &[AbiChange]) -> String {
+    if changes.is_empty() {
+        return "The ABIs are identical.".to_string();
+    }
+
+    let mut out = String::new();
+    for change in changes {
+        match change {
+            AbiChange::Added(after) => writeln!(out, "+ {after}"),
+            AbiChange::Removed(before) => writeln!(out, "- {before}"),
+            AbiChange::Changed {
+                name,
+                before,
+                after,
+            } => writeln!(out, "~ {name}\n    - {before}\n    + {after}"),
+        }
+        .unwrap();
+    }
+
+    out.trim_end().to_string()
+}
+
+/// Renders the result of a simulated transaction: its fee estimation followed by its trace and,
+/// if the node returns it, the state diff.
+pub fn pretty_simulated_transaction(simulation: &Value, fmt: &ValueFormat) -> String {
+    let trace = &simulation["transaction_trace"];
+    let mut out = format!(
+        "{}\n\n{}",
+        pretty_fee_estimation(&simulation["fee_estimation"]),
+        pretty_transaction_trace(trace, fmt)
+    );
+
+    if !trace["state_diff"].is_null() {
+        let _ = write!(out, "\n\n{}", pretty_state_diff(&trace["state_diff"]));
+    }
+
+    out
+}
+
+/// Renders a fee estimation as returned by the node, where the amounts are hex strings.
+pub fn pretty_fee_estimation(fee: &Value) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+        .add_row(vec![
+            "OVERALL FEE",
+            fee["overall_fee"].as_str().unwrap_or_default(),
+        ])
+        .add_row(vec![
+            "GAS CONSUMED",
+            fee["gas_consumed"].as_str().unwrap_or_default(),
+        ])
+        .add_row(vec![
+            "GAS PRICE",
+            fee["gas_price"].as_str().unwrap_or_default(),
+        ]);
+
+    // only returned by the more recent versions of the node
+    for (key, title) in [
+        ("data_gas_consumed", "DATA GAS CONSUMED"),
+        ("data_gas_price", "DATA GAS PRICE"),
+        ("unit", "UNIT"),
+    ] {
+        if let Some(value) = fee[key].as_str() {
+            table.add_row(vec![title, value]);
+        }
+    }
+
+    format!("{table}")
+}
+
+/// Renders a page of events, one table per event, followed by the token to query the next page
+/// with if there is one.
+pub fn pretty_events(page: &Value, fmt: &ValueFormat) -> String {
+    let mut out = String::new();
+
+    for event in page["events"].as_array().into_iter().flatten() {
+        let _ = writeln!(out, "{}", pretty_event(event, fmt));
+    }
+
+    match page["continuation_token"].as_str() {
+        Some(token) => {
+            let _ = writeln!(out, "CONTINUATION TOKEN : {token}");
+        }
+        None if out.is_empty() => out.push_str("No events found."),
+        None => {}
+    }
+
+    out.trim_end().to_string()
+}
+
+/// Renders the events along with their name and decoded fields, or as is for the ones that
+/// could not be decoded.
+pub fn pretty_decoded_events(
+    events: &[(Value, Option<DecodedEvent>)],
+    continuation_token: Option<&str>,
+    fmt: &ValueFormat,
+) -> String {
+    let mut out = String::new();
+
+    for (event, decoded) in events {
+        let Some(decoded) = decoded else {
+            let _ = writeln!(out, "{}", pretty_event(event, fmt));
+            continue;
+        };
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+            .add_row(vec![
+                "BLOCK NUMBER".to_string(),
+                event["block_number"].to_string(),
+            ])
+            .add_row(vec![
+                "TRANSACTION HASH".to_string(),
+                felt_str(&event["transaction_hash"]),
+            ])
+            .add_row(vec!["FROM".to_string(), felt_str(&event["from_address"])])
+            .add_row(vec!["EVENT".to_string(), decoded.name.clone()]);
+
+        for param in &decoded.params {
+            table.add_row(vec![param.name.to_uppercase(), param.value.to_string()]);
+        }
+        let _ = writeln!(out, "{table}");
+    }
+
+    match continuation_token {
+        Some(token) => {
+            let _ = writeln!(out, "CONTINUATION TOKEN : {token}");
+        }
+        None if out.is_empty() => out.push_str("No events found."),
+        None => {}
+    }
+
+    out.trim_end().to_string()
+}
+
+pub fn pretty_event(event: &Value, fmt: &ValueFormat) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+        .add_row(vec![
+            "BLOCK NUMBER".to_string(),
+            event["block_number"].to_string(),
+        ])
+        .add_row(vec![
+            "TRANSACTION HASH".to_string(),
+            felt_str(&event["transaction_hash"]),
+        ])
+        .add_row(vec!["FROM".to_string(), felt_str(&event["from_address"])])
+        .add_row(vec!["KEYS".to_string(), felt_lines(&event["keys"], fmt)])
+        .add_row(vec!["DATA".to_string(), felt_lines(&event["data"], fmt)]);
+
+    format!("{table}")
+}
+
+pub const EVENT_CSV_HEADER: &str = "block_number,transaction_hash,from_address,keys,data";
+
+/// Flattens an event into a CSV row, with its keys and data separated by spaces.
+pub fn event_csv_row(event: &Value) -> String {
+    format!(
+        "{},{},{},{},{}",
+        event["block_number"],
+        felt_str(&event["transaction_hash"]),
+        felt_str(&event["from_address"]),
+        join_felts(&event["keys"], " "),
+        join_felts(&event["data"], " ")
+    )
+}
+
+/// Flattens an event into a single line of JSON.
+pub fn event_ndjson_line(event: &Value) -> String {
+    json!({
+        "block_number": event["block_number"],
+        "transaction_hash": event["transaction_hash"],
+        "from_address": event["from_address"],
+        "keys": event["keys"],
+        "data": event["data"],
+    })
+    .to_string()
+}
+
+/// Renders a state update: the roots of the state before and after the block, followed by the
+/// changes it made.
+pub fn pretty_state_update(update: &Value) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_SOLID_INNER_BORDERS);
+
+    // a pending update is not part of a block yet
+    if !update["block_hash"].is_null() {
+        table.add_row(vec!["BLOCK HASH", &felt_str(&update["block_hash"])]);
+        table.add_row(vec!["NEW ROOT", &felt_str(&update["new_root"])]);
+    }
+    table.add_row(vec!["OLD ROOT", &felt_str(&update["old_root"])]);
+
+    format!("{table}\n{}", pretty_state_diff(&update["state_diff"]))
+}
+
+/// Renders a summary of a contract class: its kind, its entry points and the functions and events
+/// declared in its ABI. The program itself is left out.
+pub fn pretty_contract_class(class: &Value) -> String {
+    let kind = match class["contract_class_version"].as_str() {
+        Some(version) => format!("Sierra (version {version})"),
+        None => "Cairo 0".to_string(),
+    };
+    let entry_points = |ty: &str| {
+        class["entry_points_by_type"][ty]
+            .as_array()
+            .map_or(0, Vec::len)
+            .to_string()
+    };
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+        .add_row(vec!["CLASS".to_string(), kind])
+        .add_row(vec!["EXTERNAL".to_string(), entry_points("EXTERNAL")])
+        .add_row(vec!["L1 HANDLER".to_string(), entry_points("L1_HANDLER")])
+        .add_row(vec!["CONSTRUCTOR".to_string(), entry_points("CONSTRUCTOR")]);
+
+    if let Ok(abi) = Abi::parse(class) {
+        let declarations = abi
+            .declarations()
+            .into_iter()
+            .map(signature)
+            .collect::<Vec<_>>();
+        table.add_row(vec!["ABI".to_string(), declarations.join("\n")]);
+    }
+
+    format!("{table}")
+}
+
+/// Renders the trace of a transaction as a tree of its function invocations.
+pub fn pretty_transaction_trace(trace: &Value, fmt: &ValueFormat) -> String {
+    let mut out = String::new();
+
+    for (key, title) in [
+        ("validate_invocation", "VALIDATE"),
+        ("execute_invocation", "EXECUTE"),
+        ("constructor_invocation", "CONSTRUCTOR"),
+        ("function_invocation", "FUNCTION"),
+        ("fee_transfer_invocation", "FEE TRANSFER"),
+    ] {
+        let invocation = &trace[key];
+        if invocation.is_null() {
+            continue;
+        }
+
+        out.push_str(title);
+        out.push('\n');
+
+        match invocation.get("revert_reason") {
+            Some(reason) => {
+                let reason = decode_revert_reason(reason.as_str().unwrap_or_default());
+                let _ = writeln!(out, "└─ REVERTED: {reason}");
+            }
+            None => pretty_function_invocation(&mut out, invocation, "", true, fmt),
+        }
+    }
+
+    out.trim_end().to_string()
+}
+
+/// Renders the state diff of a transaction as a table of the changes made to each contract.
+pub fn pretty_state_diff(diff: &Value) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_SOLID_INNER_BORDERS)
+        .set_header(vec!["CHANGE", "CONTRACT", "KEY", "VALUE"]);
+
+    let entries = |key: &str| diff[key].as_array().cloned().unwrap_or_default();
+    let felt = |value: &Value| value.as_str().unwrap_or_default().to_string();
+
+    for storage in entries("storage_diffs") {
+        for entry in storage["storage_entries"].as_array().into_iter().flatten() {
+            table.add_row(vec![
+                "STORAGE".to_string(),
+                felt(&storage["address"]),
+                felt(&entry["key"]),
+                felt(&entry["value"]),
+            ]);
+        }
+    }
+
+    for nonce in entries("nonces") {
+        table.add_row(vec![
+            "NONCE".to_string(),
+            felt(&nonce["contract_address"]),
+            String::new(),
+            felt(&nonce["nonce"]),
+        ]);
+    }
+
+    for contract in entries("deployed_contracts") {
+        table.add_row(vec![
+            "DEPLOYED".to_string(),
+            felt(&contract["address"]),
+            "class_hash".to_string(),
+            felt(&contract["class_hash"]),
+        ]);
+    }
+
+    for contract in entries("replaced_classes") {
+        table.add_row(vec![
+            "REPLACED".to_string(),
+            felt(&contract["contract_address"]),
+            "class_hash".to_string(),
+            felt(&contract["class_hash"]),
+        ]);
+    }
+
+    for class in entries("declared_classes") {
+        table.add_row(vec![
+            "DECLARED".to_string(),
+            String::new(),
+            felt(&class["class_hash"]),
+            felt(&class["compiled_class_hash"]),
+        ]);
+    }
+
+    for class_hash in entries("deprecated_declared_classes") {
+        table.add_row(vec![
+            "DECLARED".to_string(),
+            String::new(),
+            felt(&class_hash),
+            String::new(),
+        ]);
+    }
+
+    format!("{table}")
+}
+
+fn pretty_function_invocation(
+    out: &mut String,
+    invocation: &Value,
+    prefix: &str,
+    last: bool,
+    fmt: &ValueFormat,
+) {
+    let (branch, indent) = if last {
+        ("└─ ", "   ")
+    } else {
+        ("├─ ", "│  ")
+    };
+
+    let _ = write!(
+        out,
+        "{prefix}{branch}{} :: {}",
+        invocation["contract_address"].as_str().unwrap_or_default(),
+        invocation["entry_point_selector"]
+            .as_str()
+            .unwrap_or_default(),
+    );
+    match invocation["entry_point_name"].as_str() {
+        Some(name) => {
+            let _ = writeln!(out, " ({name})");
+        }
+        None => out.push('\n'),
+    }
+
+    let prefix = format!("{prefix}{indent}");
+    let calls = invocation["calls"].as_array().cloned().unwrap_or_default();
+    let pipe = if calls.is_empty() { "  " } else { "│ " };
+
+    let _ = writeln!(
+        out,
+        "{prefix}{pipe}calldata : [{}]",
+        pretty_felts(&invocation["calldata"], ", ", fmt)
+    );
+    let _ = writeln!(
+        out,
+        "{prefix}{pipe}result   : [{}]",
+        pretty_felts(&invocation["result"], ", ", fmt)
+    );
+
+    for (i, call) in calls.iter().enumerate() {
+        pretty_function_invocation(out, call, &prefix, i == calls.len() - 1, fmt);
+    }
+}
+
+const DIM: &str = "\x1b[2m";
+const CYAN: &str = "\x1b[36m";
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// Adds colors to rendered pretty output: the keys in the first column of the tables are dimmed,
+/// hashes and addresses are cyan, and statuses are green or red whether the transaction succeeded
+/// or not. The colors are inserted around the existing text so the tables stay aligned.
+pub fn colorize(output: &str) -> String {
+    output
+        .lines()
+        .map(colorize_line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn colorize_line(line: &str) -> String {
+    // a row of a table with at least two columns, e.g. `│ KEY │ value │`
+    let key = line.strip_prefix('│').and_then(|rest| {
+        let end = rest.find('│')?;
+        rest[end + '│'.len_utf8()..].contains('│').then_some(end)
+    });
+
+    match key {
+        Some(end) => {
+            let (key, rest) = line[3..].split_at(end);
+            let trimmed = key.trim();
+            let key = if trimmed.is_empty() {
+                key.to_string()
+            } else {
+                key.replacen(trimmed, &format!("{DIM}{trimmed}{RESET}"), 1)
+            };
+            format!("│{key}{}", colorize_values(rest))
+        }
+        None => colorize_values(line),
+    }
+}
+
+fn colorize_values(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    // hex values long enough to be hashes or addresses rather than amounts
+    while let Some(start) = rest.find("0x") {
+        let digits = rest[start + 2..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(rest.len() - start - 2);
+        let end = start + 2 + digits;
+
+        out.push_str(&rest[..start]);
+        if digits >= 40 {
+            out.push_str(&format!("{CYAN}{}{RESET}", &rest[start..end]));
+        } else {
+            out.push_str(&rest[start..end]);
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+
+    [
+        ("ACCEPTED_ON_L1", GREEN),
+        ("ACCEPTED_ON_L2", GREEN),
+        ("SUCCEEDED", GREEN),
+        ("REVERTED", RED),
+        ("REJECTED", RED),
+    ]
+    .iter()
+    .fold(out, |out, (status, color)| {
+        out.replace(status, &format!("{color}{status}{RESET}"))
+    })
+}
+
+/// Formats an amount in the smallest unit of a token with the given number of decimals.
+pub fn format_units(amount: u128, decimals: u32) -> String {
+    let unit = 10u128.pow(decimals);
+    let fraction = format!("{:0width$}", amount % unit, width = decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        format!("{}", amount / unit)
+    } else {
+        format!("{}.{fraction}", amount / unit)
+    }
+}
+
+/// Formats a u256 amount, given as its low and high 128 bits, like `format_units`.
+pub fn format_u256_units(low: u128, high: u128, decimals: u32) -> String {
+    // 10^38 is the largest power of ten that fits in a u128
+    if high == 0 && decimals <= 38 {
+        return format_units(low, decimals);
+    }
+
+    // padded so that there is at least one digit before the decimal point
+    let digits = format!(
+        "{:0>width$}",
+        u256_to_dec(low, high),
+        width = decimals as usize + 1
+    );
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
+    }
+}
+
+fn felt_str(value: &Value) -> String {
+    value.as_str().unwrap_or_default().to_string()
+}
+
+fn felt_lines(values: &Value, fmt: &ValueFormat) -> String {
+    pretty_felts(values, "\n", fmt)
+}
+
+/// Joins the felts, with the text of the ones that hold a short string.
+fn pretty_felts(values: &Value, separator: &str, fmt: &ValueFormat) -> String {
+    values
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(|felt| match FieldElement::from_hex_be(felt) {
+                    Ok(value) => value.prettify(fmt),
+                    Err(_) => felt.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(separator)
+        })
+        .unwrap_or_default()
+}
+
+/// Renders a unix timestamp along with its date, e.g. `1680000000 (2023-03-28T10:40:00Z)`.
+pub fn pretty_timestamp(timestamp: u64, fmt: &ValueFormat) -> String {
+    if fmt.raw {
+        return timestamp.to_string();
+    }
+
+    let date = UNIX_EPOCH + Duration::from_secs(timestamp);
+    format!("{timestamp} ({})", humantime::format_rfc3339_seconds(date))
+}
+
+fn join_felts(values: &Value, separator: &str) -> String {
+    values
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join(separator)
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_token_units() {
+        assert_eq!(format_units(0, 18), "0");
+        assert_eq!(format_units(1_000_000_000_000_000_000, 18), "1");
+        assert_eq!(format_units(1_500_000_000_000_000_000, 18), "1.5");
+        assert_eq!(format_units(123_000_000_000_000, 18), "0.000123");
+        assert_eq!(format_u256_units(1_520_000_000_000_000_000, 0, 18), "1.52");
+        assert_eq!(
+            format_u256_units(0, 1, 18),
+            "340282366920938463463.374607431768211456"
+        );
+        assert_eq!(
+            format_u256_units(5, 0, 40),
+            "0.0000000000000000000000000000000000000005"
+        );
+    }
+
+    #[test]
+    fn render_transaction_trace() {
+        let trace = json!({
+            "execute_invocation": {
+                "contract_address": "0x1",
+                "entry_point_selector": "0xa",
+                "calldata": ["0x2"],
+                "result": [],
+                "calls": [
+                    {
+                        "contract_address": "0x2",
+                        "entry_point_selector": "0xb",
+                        "calldata": [],
+                        "result": ["0x1"],
+                        "calls": []
+                    }
+                ]
+            },
+            "fee_transfer_invocation": {
+                "revert_reason": "insufficient balance"
+            }
+        });
+
+        assert_eq!(
+            pretty_transaction_trace(&trace, &ValueFormat::default()),
+            "EXECUTE
+└─ 0x1 :: 0xa
+   │ calldata : [0x2]
+   │ result   : []
+   └─ 0x2 :: 0xb
+        calldata : []
+        result   : [0x1]
+FEE TRANSFER
+└─ REVERTED: insufficient balance"
+        );
+    }
+
+    #[test]
+    fn decode_pretty_values() {
+        let felt = |hex| FieldElement::from_hex_be(hex).unwrap();
+        let fmt = &ValueFormat::default();
+
+        assert_eq!(felt("0x4f4b").prettify(fmt), "0x4f4b (OK)");
+        assert_eq!(felt("0x4f").prettify(fmt), "0x4f");
+        assert_eq!(felt("0x4f00").prettify(fmt), "0x4f00");
+        assert_eq!(
+            pretty_felts(&json!(["0x1", "0x6f776e6572"]), ", ", fmt),
+            "0x1, 0x6f776e6572 (owner)"
+        );
+        assert_eq!(
+            pretty_timestamp(1680000000, fmt),
+            "1680000000 (2023-03-28T10:40:00Z)"
+        );
+
+        let raw = &ValueFormat {
+            raw: true,
+            decimal: false,
+        };
+        assert_eq!(felt("0x4f4b").prettify(raw), "0x4f4b");
+        assert_eq!(pretty_timestamp(1680000000, raw), "1680000000");
+    }
+
+    #[test]
+    fn format_u256_in_hex() {
+        let fmt = ValueFormat::default();
+        assert_eq!(fmt.u256(0x10, 0), "0x10");
+        assert_eq!(fmt.u256(0x10, 0x2), "0x200000000000000000000000000000010");
+    }
+
+    #[test]
+    fn colorize_tables() {
+        let hash = format!("0x{}", "ab".repeat(31));
+        let output = format!(
+            "┌────────┬──────┐\n│ STATUS │ REVERTED │\n│ HASH   │ {hash} │\n│ NONCE  │ 0x1 │"
+        );
+
+        assert_eq!(
+            colorize(&output),
+            format!(
+                "┌────────┬──────┐\n│ {DIM}STATUS{RESET} │ {RED}REVERTED{RESET} │\n│ {DIM}HASH{RESET}   │ {CYAN}{hash}{RESET} │\n│ {DIM}NONCE{RESET}  │ 0x1 │"
+            )
+        );
+    }
+
+    #[test]
+    fn flatten_events() {
+        let event = json!({
+            "block_hash": "0x9",
+            "block_number": 12,
+            "transaction_hash": "0x1",
+            "from_address": "0x2",
+            "keys": ["0x3", "0x4"],
+            "data": ["0x5"]
+        });
+
+        assert_eq!(event_csv_row(&event), "12,0x1,0x2,0x3 0x4,0x5");
+        assert_eq!(
+            event_ndjson_line(&event),
+            r#"{"block_number":12,"data":["0x5"],"from_address":"0x2","keys":["0x3","0x4"],"transaction_hash":"0x1"}"#
+        );
+    }
+
+    #[test]
+    fn render_contract_class() {
+        let class = json!({
+            "contract_class_version": "0.1.0",
+            "entry_points_by_type": {
+                "EXTERNAL": [{ "selector": "0x1", "function_idx": 0 }],
+                "L1_HANDLER": [],
+                "CONSTRUCTOR": []
+            },
+            "abi": json!([
+                { "type": "event", "name": "Transfer", "kind": "struct", "members": [] },
+                { "type": "function", "name": "transfer", "inputs": [], "outputs": [] }
+            ])
+            .to_string()
+        });
+
+        let rendered = pretty_contract_class(&class);
+        assert!(rendered.contains("Sierra (version 0.1.0)"));
+        assert!(rendered.contains("function transfer()"));
+
+        let abi = rendered.find("ABI").unwrap();
+        assert!(
+            rendered[abi..].find("transfer").unwrap() < rendered[abi..].find("Transfer").unwrap()
+        );
+
+        assert_eq!(
+            pretty_events(&json!({ "events": [] }), &ValueFormat::default()),
+            "No events found."
+        );
+    }
+}
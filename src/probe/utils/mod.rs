@@ -1,14 +1,24 @@
 pub mod fmt;
 
+use super::abi::short_string;
+
 use std::fs;
+use std::io::Write;
 use std::path::Path;
-use std::{collections::HashMap, path::PathBuf};
+use std::path::PathBuf;
 
 use eyre::{eyre, Result};
+use flate2::{write::GzEncoder, Compression};
+use rand::RngCore;
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
 use starknet::core::{
-    types::{AbiEntry, FieldElement},
+    types::{ContractArtifact, FieldElement},
     utils::cairo_short_string_to_felt,
 };
+use starknet::providers::jsonrpc::models::ContractClass;
+use starknet_curve::curve_params::EC_ORDER;
 
 // const STARKNET_ACCOUNT_FILEPATH: &'static str = "~/.starknet_accounts";
 pub const SIGNED_FELT_MIN: &str =
@@ -31,53 +41,274 @@ pub fn parse_hex_or_str_as_felt(data: &str) -> Result<FieldElement> {
     Ok(felt)
 }
 
-/// `file` path to the abi file
-pub fn count_function_inputs<P>(abi_file: P, function_name: &str) -> Result<u64>
-where
-    P: AsRef<Path>,
-{
-    let abi = fs::read_to_string(abi_file)?;
-    let abi = parse_abi_into_map(&abi)?;
+/// The Poseidon hash of two felts.
+pub fn poseidon_hash(x: FieldElement, y: FieldElement) -> FieldElement {
+    from_crypto_felt(starknet_crypto::poseidon_hash(
+        to_crypto_felt(x),
+        to_crypto_felt(y),
+    ))
+}
+
+/// The Poseidon hash of an array of felts, as used by the v3 transaction hashes.
+pub fn poseidon_hash_many(elements: &[FieldElement]) -> FieldElement {
+    let elements = elements
+        .iter()
+        .map(|e| to_crypto_felt(*e))
+        .collect::<Vec<_>>();
 
-    if let Some(AbiEntry::Function(function)) = abi.get(function_name) {
-        let mut count = 0;
+    from_crypto_felt(starknet_crypto::poseidon_hash_many(&elements))
+}
 
-        for input in function.inputs.iter() {
-            if input.r#type.eq("felt") {
-                count += 1;
+/// Raises a felt to a power modulo the Stark prime, by square-and-multiply over the bits of the
+/// exponent.
+pub fn felt_pow(base: FieldElement, exponent: FieldElement) -> FieldElement {
+    exponent
+        .to_bits_le()
+        .iter()
+        .rev()
+        .fold(FieldElement::ONE, |acc, bit| {
+            let acc = acc * acc;
+            if *bit {
+                acc * base
             } else {
-                match abi.get(input.r#type.as_str()) {
-                    Some(AbiEntry::Struct(s)) => count += s.size,
-                    _ => return Err(eyre::eyre!("no type `{}` found in the abi", input.r#type)),
-                }
+                acc
+            }
+        })
+}
+
+/// A felt drawn uniformly at random from a CSPRNG.
+pub fn random_felt() -> FieldElement {
+    let bytes = random_252_bits(|bytes| FieldElement::from_bytes_be(bytes).is_ok());
+    FieldElement::from_bytes_be(&bytes).unwrap()
+}
+
+/// A Stark private key drawn uniformly at random from a CSPRNG, between 1 and the order of the
+/// curve.
+pub fn random_private_key() -> FieldElement {
+    let order = EC_ORDER.to_bytes_be();
+    let bytes = random_252_bits(|bytes| *bytes < order && *bytes != [0; 32]);
+    FieldElement::from_bytes_be(&bytes).unwrap()
+}
+
+/// Draws 252 bits until they are accepted. The prime and the curve order are both just above
+/// 2^251, so about half of the draws are.
+fn random_252_bits(accept: impl Fn(&[u8; 32]) -> bool) -> [u8; 32] {
+    let mut rng = rand::thread_rng();
+    loop {
+        let mut bytes = [0u8; 32];
+        rng.fill_bytes(&mut bytes);
+        bytes[0] &= 0x0f;
+
+        if accept(&bytes) {
+            return bytes;
+        }
+    }
+}
+
+// the felts of starknet-crypto are a distinct type, of the same 32 bytes
+fn to_crypto_felt(felt: FieldElement) -> starknet_crypto::FieldElement {
+    starknet_crypto::FieldElement::from_bytes_be(&felt.to_bytes_be()).unwrap()
+}
+
+fn from_crypto_felt(felt: starknet_crypto::FieldElement) -> FieldElement {
+    FieldElement::from_bytes_be(&felt.to_bytes_be()).unwrap()
+}
+
+/// Makes the revert reasons and panic data found in an error message readable, by following each
+/// felt that holds a short string with its text, e.g. `0x4e6f206d6f6e6579 ('No money')`. Felts
+/// that are already followed by their text are left as is.
+pub fn decode_revert_reason(message: &str) -> String {
+    let mut decoded = String::with_capacity(message.len());
+    let mut rest = message;
+
+    while let Some(start) = rest.find("0x") {
+        let len = rest[start + 2..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(rest.len() - start - 2);
+        let (token, tail) = rest[start..].split_at(len + 2);
+
+        decoded.push_str(&rest[..start]);
+        decoded.push_str(token);
+
+        let text = FieldElement::from_hex_be(token).ok().and_then(short_string);
+        if let Some(text) = text {
+            if !tail.starts_with(" ('") {
+                decoded.push_str(&format!(" ('{text}')"));
             }
         }
 
-        Ok(count)
-    } else {
-        Err(eyre!("no function `{}` found in the abi", function_name))
+        rest = tail;
     }
+
+    decoded.push_str(rest);
+    decoded
 }
 
-pub fn parse_abi_into_map(abi_str: &str) -> Result<HashMap<String, AbiEntry>> {
-    let abi: Vec<AbiEntry> = serde_json::from_str(abi_str)?;
-    let mut map = HashMap::new();
+/// Reads a compiled contract artifact and returns its class hash together with the
+/// contract class in the format expected by the RPC, i.e. with a gzipped program.
+pub fn parse_contract_artifact<P>(contract_file: P) -> Result<(FieldElement, ContractClass)>
+where
+    P: AsRef<Path>,
+{
+    let content = fs::read_to_string(contract_file)?;
 
-    for elem in abi.into_iter() {
-        let key = match &elem {
-            AbiEntry::Event(v) => v.name.to_owned(),
-            AbiEntry::Struct(v) => v.name.to_owned(),
-            AbiEntry::Function(v) => v.name.to_owned(),
-            AbiEntry::L1Handler(v) => v.name.to_owned(),
-            AbiEntry::Constructor(v) => v.name.to_owned(),
-        };
-        map.insert(key, elem);
+    let class_hash = serde_json::from_str::<ContractArtifact>(&content)?.class_hash()?;
+
+    let mut artifact: serde_json::Value = serde_json::from_str(&content)?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(serde_json::to_string(&artifact["program"])?.as_bytes())?;
+
+    let contract_class = ContractClass {
+        program: encoder.finish()?,
+        entry_points_by_type: serde_json::from_value(artifact["entry_points_by_type"].take())?,
+        abi: serde_json::from_value(artifact["abi"].take())?,
+    };
+
+    Ok((class_hash, contract_class))
+}
+
+/// The error object of a JSON-RPC response, which callers can downcast to in order to look at the
+/// code.
+#[derive(Debug, thiserror::Error)]
+#[error("{message} (code {code})")]
+pub struct RpcErrorResponse {
+    pub code: i64,
+    pub message: String,
+}
+
+/// Sends a JSON-RPC request for methods which are not supported by the provider, returning the
+/// `result` of the response.
+pub async fn send_rpc_request<T>(url: &Url, method: &str, params: serde_json::Value) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let mut res = reqwest::Client::new()
+        .post(url.clone())
+        .json(&json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params
+        }))
+        .send()
+        .await?
+        .json::<serde_json::Value>()
+        .await?;
+
+    if let Some(error) = res.get("error") {
+        return Err(RpcErrorResponse {
+            code: error["code"].as_i64().unwrap_or_default(),
+            message: error["message"].as_str().unwrap_or_default().to_string(),
+        }
+        .into());
     }
 
-    Ok(map)
+    Ok(serde_json::from_value(res["result"].take())?)
+}
+
+/// Selects the fields of a JSON value. A field is a path of object keys and array indices joined
+/// with dots, e.g. `transactions.0.sender_address`. A single field is returned as is, while
+/// multiple fields are returned as an object keyed by their path. `kind` names the value in the
+/// error returned for an invalid path.
+pub fn select_fields(value: Value, fields: &[String], kind: &str) -> Result<Value> {
+    let select = |path: &str| {
+        path.split('.')
+            .try_fold(&value, |value, segment| match value {
+                Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+                value => value.get(segment),
+            })
+            .cloned()
+            .ok_or_else(|| eyre!("`{path}` is not a valid {kind} field."))
+    };
+
+    match fields {
+        [field] => select(field),
+        fields => fields
+            .iter()
+            .map(|field| Ok((field.clone(), select(field)?)))
+            .collect::<Result<Map<_, _>>>()
+            .map(Value::Object),
+    }
 }
 
 pub fn canonicalize_path(path: impl AsRef<str>) -> Result<PathBuf> {
     let path = shellexpand::tilde(path.as_ref());
     Ok(dunce::canonicalize(path.to_string().as_str())?)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn felt_power() {
+        let felt = FieldElement::from;
+        assert_eq!(felt_pow(felt(3u64), felt(5u64)), felt(243u64));
+        assert_eq!(felt_pow(felt(7u64), FieldElement::ZERO), FieldElement::ONE);
+        // a^(p-1) = 1 and a^(p-2) = 1/a for any non-zero a
+        let p_minus_one = -FieldElement::ONE;
+        assert_eq!(felt_pow(felt(7u64), p_minus_one), FieldElement::ONE);
+        assert_eq!(
+            felt_pow(felt(7u64), p_minus_one - FieldElement::ONE),
+            felt(7u64).invert().unwrap()
+        );
+    }
+
+    #[test]
+    fn random_keys_below_curve_order() {
+        let order = EC_ORDER.to_bytes_be();
+        for _ in 0..100 {
+            let key = random_private_key();
+            assert!(key != FieldElement::ZERO && key.to_bytes_be() < order);
+        }
+        assert_ne!(random_felt(), random_felt());
+    }
+
+    #[test]
+    fn decode_felt_revert_reasons() {
+        assert_eq!(
+            decode_revert_reason("Execution failed. Failure reason: 0x4e6f206d6f6e6579."),
+            "Execution failed. Failure reason: 0x4e6f206d6f6e6579 ('No money')."
+        );
+        assert_eq!(
+            decode_revert_reason("panic data: [0x753235365f737562204f766572666c6f77, 0x1]"),
+            "panic data: [0x753235365f737562204f766572666c6f77 ('u256_sub Overflow'), 0x1]"
+        );
+        // already decoded by the node
+        assert_eq!(
+            decode_revert_reason("0x4e6f206d6f6e6579 ('No money')"),
+            "0x4e6f206d6f6e6579 ('No money')"
+        );
+        assert_eq!(decode_revert_reason("at pc=0:12, 0x"), "at pc=0:12, 0x");
+    }
+
+    #[test]
+    fn select_nested_fields() {
+        let block = json!({
+            "block_number": 1,
+            "transactions": [{ "sender_address": "0x1", "calldata": ["0x2", "0x3"] }]
+        });
+        let fields = |fields: &[&str]| fields.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            select_fields(
+                block.clone(),
+                &fields(&["transactions.0.calldata.1"]),
+                "block"
+            )
+            .unwrap(),
+            json!("0x3")
+        );
+        assert_eq!(
+            select_fields(
+                block.clone(),
+                &fields(&["block_number", "transactions.0.sender_address"]),
+                "block"
+            )
+            .unwrap(),
+            json!({ "block_number": 1, "transactions.0.sender_address": "0x1" })
+        );
+        assert!(select_fields(block.clone(), &fields(&["transactions.1"]), "block").is_err());
+        assert!(select_fields(block, &fields(&["transactions.first"]), "block").is_err());
+    }
+}
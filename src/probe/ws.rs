@@ -0,0 +1,364 @@
+use std::collections::VecDeque;
+
+use eyre::{bail, eyre, Result};
+use rand::Rng;
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use sha1::{Digest, Sha1};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_native_tls::{native_tls, TlsConnector};
+
+use super::utils::RpcErrorResponse;
+
+/// The GUID the server appends to the key of the handshake, as defined by RFC 6455.
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_BINARY: u8 = 0x2;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xa;
+
+/// The largest payload accepted in a single frame, so that a misbehaving node can't make the
+/// client allocate an arbitrary amount of memory from the length of a frame header.
+const MAX_FRAME_SIZE: u64 = 16 << 20;
+/// The largest message accepted once its fragments are put together.
+const MAX_MESSAGE_SIZE: usize = 64 << 20;
+/// The largest payload of a control frame, as defined by RFC 6455.
+const MAX_CONTROL_FRAME_SIZE: u64 = 125;
+
+/// Whether the endpoint is reached over WebSocket rather than HTTP.
+pub fn is_websocket(url: &Url) -> bool {
+    matches!(url.scheme(), "ws" | "wss")
+}
+
+trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Stream for T {}
+
+/// A notification pushed by the node for one of the subscriptions of the connection.
+#[derive(Debug, Clone)]
+pub struct Notification {
+    /// e.g. `starknet_subscriptionNewHeads`.
+    pub method: String,
+    pub result: Value,
+}
+
+/// A JSON-RPC client over a WebSocket connection, through which the node can push the updates of
+/// the `starknet_subscribe*` methods instead of being polled.
+pub struct WsClient {
+    stream: Box<dyn Stream>,
+    next_id: u64,
+    /// The notifications received while waiting for the response to a request.
+    notifications: VecDeque<Notification>,
+}
+
+impl WsClient {
+    pub async fn connect(url: &Url) -> Result<Self> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| eyre!("{url} has no host"))?
+            .to_string();
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| eyre!("{url} has no port"))?;
+
+        let tcp = TcpStream::connect((host.as_str(), port)).await?;
+        let stream: Box<dyn Stream> = match url.scheme() {
+            "ws" => Box::new(tcp),
+            "wss" => {
+                let connector = TlsConnector::from(native_tls::TlsConnector::new()?);
+                Box::new(connector.connect(&host, tcp).await?)
+            }
+            scheme => bail!("`{scheme}` is not a websocket scheme, use ws:// or wss://"),
+        };
+
+        let mut client = Self::from_stream(stream);
+        client.handshake(url).await?;
+        Ok(client)
+    }
+
+    fn from_stream(stream: Box<dyn Stream>) -> Self {
+        Self {
+            stream,
+            next_id: 1,
+            notifications: VecDeque::new(),
+        }
+    }
+
+    /// Sends a JSON-RPC request, returning the `result` of its response.
+    pub async fn request<T: DeserializeOwned>(&mut self, method: &str, params: Value) -> Result<T> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = json!({
+            "id": id,
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params
+        });
+        self.write_frame(OPCODE_TEXT, request.to_string().as_bytes())
+            .await?;
+
+        loop {
+            let mut message = self.read_message().await?;
+
+            if message["id"].as_u64() == Some(id) {
+                if let Some(error) = message.get("error") {
+                    return Err(RpcErrorResponse {
+                        code: error["code"].as_i64().unwrap_or_default(),
+                        message: error["message"].as_str().unwrap_or_default().to_string(),
+                    }
+                    .into());
+                }
+                return Ok(serde_json::from_value(message["result"].take())?);
+            }
+
+            if let Some(notification) = parse_notification(message) {
+                self.notifications.push_back(notification);
+            }
+        }
+    }
+
+    /// Calls one of the `starknet_subscribe*` methods, returning the id of the subscription.
+    pub async fn subscribe(&mut self, method: &str, params: Value) -> Result<Value> {
+        self.request(method, params).await
+    }
+
+    /// Waits for the next notification of any subscription of the connection.
+    pub async fn next_notification(&mut self) -> Result<Notification> {
+        if let Some(notification) = self.notifications.pop_front() {
+            return Ok(notification);
+        }
+
+        loop {
+            if let Some(notification) = parse_notification(self.read_message().await?) {
+                return Ok(notification);
+            }
+        }
+    }
+
+    async fn handshake(&mut self, url: &Url) -> Result<()> {
+        let key = base64::encode(rand::thread_rng().gen::<[u8; 16]>());
+
+        let mut target = url.path().to_string();
+        if let Some(query) = url.query() {
+            target.push('?');
+            target.push_str(query);
+        }
+        let host = match url.port() {
+            Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+
+        let request = format!(
+            "GET {target} HTTP/1.1\r\nHost: {host}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: {key}\r\nSec-WebSocket-Version: 13\r\n\r\n"
+        );
+        self.stream.write_all(request.as_bytes()).await?;
+
+        // the response is read a byte at a time so that nothing past the headers is consumed
+        let mut response = Vec::new();
+        while !response.ends_with(b"\r\n\r\n") {
+            response.push(self.stream.read_u8().await?);
+        }
+        let response = String::from_utf8_lossy(&response);
+
+        let status = response.lines().next().unwrap_or_default();
+        if status.split_whitespace().nth(1) != Some("101") {
+            bail!("{url} did not accept the websocket connection: {status}")
+        }
+
+        let accept = response.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.trim()
+                .eq_ignore_ascii_case("sec-websocket-accept")
+                .then(|| value.trim().to_string())
+        });
+        if accept.as_deref() != Some(accept_key(&key).as_str()) {
+            bail!("{url} answered the websocket handshake with an invalid key")
+        }
+
+        Ok(())
+    }
+
+    /// Reads the next text or binary message, answering pings and joining fragmented frames.
+    async fn read_message(&mut self) -> Result<Value> {
+        let mut payload = Vec::new();
+
+        loop {
+            let (fin, opcode, data) = self.read_frame().await?;
+
+            match opcode {
+                OPCODE_PING => self.write_frame(OPCODE_PONG, &data).await?,
+                OPCODE_PONG => {}
+                OPCODE_CLOSE => bail!("the node closed the websocket connection"),
+                OPCODE_TEXT | OPCODE_BINARY | OPCODE_CONTINUATION => {
+                    if payload.len() + data.len() > MAX_MESSAGE_SIZE {
+                        bail!("websocket message larger than {MAX_MESSAGE_SIZE} bytes");
+                    }
+                    payload.extend_from_slice(&data);
+                    if fin {
+                        return Ok(serde_json::from_slice(&payload)?);
+                    }
+                }
+                opcode => bail!("unexpected websocket opcode {opcode:#x}"),
+            }
+        }
+    }
+
+    async fn read_frame(&mut self) -> Result<(bool, u8, Vec<u8>)> {
+        let mut header = [0u8; 2];
+        self.stream.read_exact(&mut header).await?;
+
+        let fin = header[0] & 0x80 != 0;
+        let opcode = header[0] & 0x0f;
+        let masked = header[1] & 0x80 != 0;
+
+        let len = match header[1] & 0x7f {
+            126 => self.stream.read_u16().await? as u64,
+            127 => self.stream.read_u64().await?,
+            len => len as u64,
+        };
+
+        // control frames have their opcode's most significant bit set
+        if opcode & 0x8 != 0 {
+            if !fin {
+                bail!("fragmented websocket control frame");
+            }
+            if len > MAX_CONTROL_FRAME_SIZE {
+                bail!(
+                    "websocket control frame of {len} bytes, larger than {MAX_CONTROL_FRAME_SIZE}"
+                );
+            }
+        } else if len > MAX_FRAME_SIZE {
+            bail!("websocket frame of {len} bytes, larger than {MAX_FRAME_SIZE}");
+        }
+
+        let mut mask = [0u8; 4];
+        if masked {
+            self.stream.read_exact(&mut mask).await?;
+        }
+
+        let mut data = vec![0u8; len as usize];
+        self.stream.read_exact(&mut data).await?;
+        if masked {
+            apply_mask(&mut data, mask);
+        }
+
+        Ok((fin, opcode, data))
+    }
+
+    /// Writes a single frame. The frames of a client must always be masked.
+    async fn write_frame(&mut self, opcode: u8, data: &[u8]) -> Result<()> {
+        let mut frame = vec![0x80 | opcode];
+
+        match data.len() {
+            len @ 0..=125 => frame.push(0x80 | len as u8),
+            len @ 126..=0xffff => {
+                frame.push(0x80 | 126);
+                frame.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            len => {
+                frame.push(0x80 | 127);
+                frame.extend_from_slice(&(len as u64).to_be_bytes());
+            }
+        }
+
+        let mask = rand::thread_rng().gen::<[u8; 4]>();
+        frame.extend_from_slice(&mask);
+
+        let mut data = data.to_vec();
+        apply_mask(&mut data, mask);
+        frame.extend_from_slice(&data);
+
+        self.stream.write_all(&frame).await?;
+        self.stream.flush().await?;
+        Ok(())
+    }
+}
+
+fn parse_notification(mut message: Value) -> Option<Notification> {
+    let method = message["method"].as_str()?.to_string();
+    let mut params = message["params"].take();
+
+    Some(Notification {
+        method,
+        result: params["result"].take(),
+    })
+}
+
+/// The key the server must answer the handshake with.
+fn accept_key(key: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(key.as_bytes());
+    hasher.update(WEBSOCKET_GUID.as_bytes());
+    base64::encode(hasher.finalize())
+}
+
+fn apply_mask(data: &mut [u8], mask: [u8; 4]) {
+    for (i, byte) in data.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handshake_accept_key() {
+        // the example of RFC 6455
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[tokio::test]
+    async fn notifications_received_during_a_request() {
+        let (client, mut server) = tokio::io::duplex(1024);
+        let mut client = WsClient::from_stream(Box::new(client));
+
+        let messages = [
+            json!({ "jsonrpc": "2.0", "method": "starknet_subscriptionNewHeads", "params": { "subscription_id": "0x1", "result": { "block_number": 7 } } }),
+            json!({ "jsonrpc": "2.0", "id": 1, "result": "0x1" }),
+        ];
+        for message in messages {
+            let data = message.to_string();
+            let mut frame = vec![0x80 | OPCODE_TEXT, data.len() as u8];
+            frame.extend_from_slice(data.as_bytes());
+            server.write_all(&frame).await.unwrap();
+        }
+
+        let id: Value = client
+            .subscribe("starknet_subscribeNewHeads", json!({}))
+            .await
+            .unwrap();
+        assert_eq!(id, json!("0x1"));
+
+        let notification = client.next_notification().await.unwrap();
+        assert_eq!(notification.method, "starknet_subscriptionNewHeads");
+        assert_eq!(notification.result["block_number"], 7);
+    }
+
+    #[tokio::test]
+    async fn oversized_frames_are_rejected() {
+        let (client, mut server) = tokio::io::duplex(1024);
+        let mut client = WsClient::from_stream(Box::new(client));
+
+        // a frame announcing a payload of 2^63 bytes must fail before anything is allocated
+        let mut frame = vec![0x80 | OPCODE_TEXT, 127];
+        frame.extend_from_slice(&(1u64 << 63).to_be_bytes());
+        server.write_all(&frame).await.unwrap();
+        assert!(client.read_frame().await.is_err());
+
+        // control frames can't carry more than 125 bytes
+        let mut frame = vec![0x80 | OPCODE_PING, 126];
+        frame.extend_from_slice(&126u16.to_be_bytes());
+        server.write_all(&frame).await.unwrap();
+        assert!(client.read_frame().await.is_err());
+    }
+}